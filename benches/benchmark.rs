@@ -47,7 +47,7 @@ fn bench_aggregate_sign(c: &mut Criterion) {
             &(&sks, &pks, msg, t),
             |b, (sks, pks, msg, t)| {
                 b.iter(|| {
-                    let (sigs, proofs) = aggregate_sign(sks, pks, *msg, *t);
+                    let (sigs, proofs) = aggregate_sign(sks, pks, *msg, *t).unwrap();
                     black_box((sigs, proofs))
                 });
             },
@@ -70,7 +70,7 @@ fn bench_aggregate_proofs(c: &mut Criterion) {
     for (n, t, name) in configs {
         let (sks, pks, pk_root) = setup(n);
         let msg = b"benchmark message for aggregation";
-        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, t);
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, t).unwrap();
 
         group.throughput(Throughput::Elements(t as u64));
         group.bench_with_input(
@@ -107,7 +107,7 @@ fn bench_verify(c: &mut Criterion) {
     for (n, t, name) in configs {
         let (sks, pks, pk_root) = setup(n);
         let msg = b"benchmark message for verification";
-        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, t);
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, t).unwrap();
         let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
 
         group.bench_with_input(
@@ -150,7 +150,7 @@ fn bench_full_flow(c: &mut Criterion) {
                         let msg = format!("transaction {}", i);
                         let msg_bytes = msg.as_bytes();
 
-                        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg_bytes, t);
+                        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg_bytes, t).unwrap();
                         let proof = aggregate_proofs(sigs, proofs, pk_root, msg_bytes, &pks).unwrap();
                         let valid = verify(pk_root, msg_bytes, &proof);
 
@@ -178,7 +178,7 @@ fn bench_proof_size(c: &mut Criterion) {
 
         let (sks, pks, pk_root) = setup(n);
         let msg = b"size test message";
-        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, *t);
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, *t).unwrap();
         let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
 
         println!("Threshold {}: proof size = {} bytes", t, proof.size());
@@ -190,7 +190,7 @@ fn bench_proof_size(c: &mut Criterion) {
                 b.iter(|| {
                     // Just measure the aggregation which produces the proof
                     let (sks, pks, pk_root) = setup(n);
-                    let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, *t);
+                    let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, *t).unwrap();
                     let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
                     black_box(proof.size())
                 });
@@ -214,9 +214,8 @@ criterion_group!(
 #[cfg(feature = "nova")]
 mod nova_benches {
     use super::*;
-    use pq_aggregate::nova::{params::gen_params, prover::{prove_batch, setup_keys, verify_proof}};
-    use pasta_curves::pallas;
-    use ff::Field;
+    use pq_aggregate::{aggregate_proofs_nova, MessageMode};
+    use pq_aggregate::nova::params::aggregation_keys;
 
     /// Benchmark Nova O(1) verification.
     pub fn bench_nova_verify(c: &mut Criterion) {
@@ -224,27 +223,20 @@ mod nova_benches {
 
         // Generate params once (expensive)
         println!("Generating Nova public parameters...");
-        let params = gen_params();
-        let (pk, vk) = setup_keys(&params).expect("Key setup failed");
+        aggregation_keys();
 
-        // Pre-generate proof for different step counts
+        let (sks, pks, pk_root) = setup(10);
+        let msg = b"benchmark message";
+
+        // Pre-generate proof for different signer counts
         for steps in [1, 3, 5, 10].iter() {
-            println!("Generating proof for {} steps...", steps);
-            let proof = prove_batch(&params, *steps, &pk).expect("Proving failed");
-
-            let z0 = vec![pallas::Scalar::ZERO; 2];
-            let zn = z0.clone();
-
-            group.bench_with_input(
-                BenchmarkId::new("steps", steps),
-                &(&vk, &proof, *steps, &z0, &zn),
-                |b, (vk, proof, steps, z0, zn)| {
-                    b.iter(|| {
-                        let valid = verify_proof(vk, proof, *steps, z0, zn);
-                        black_box(valid)
-                    });
-                },
-            );
+            println!("Generating proof for {} signers...", steps);
+            let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, *steps).unwrap();
+            let proof = aggregate_proofs_nova(sigs, proofs, pk_root, msg, MessageMode::Pure, &pks).expect("Proving failed");
+
+            group.bench_with_input(BenchmarkId::new("steps", steps), &proof, |b, proof| {
+                b.iter(|| black_box(verify(pk_root, msg, proof)));
+            });
         }
 
         group.finish();
@@ -45,7 +45,8 @@ fn main() {
     println!("   Message: \"{}\"", String::from_utf8_lossy(message));
 
     let start = std::time::Instant::now();
-    let (signatures, merkle_proofs) = aggregate_sign(&secret_keys, &public_keys, message, t);
+    let (signatures, merkle_proofs) = aggregate_sign(&secret_keys, &public_keys, message, t)
+        .expect("Signing should succeed");
     let sign_time = start.elapsed();
 
     println!("   ✓ Collected {} signatures", signatures.len());
@@ -76,7 +76,12 @@ impl EthereumAdapter {
         recipient: Address,
     ) -> Result<String> {
         // 1. Serialization for Solidity:
-        // verifyAndMint(bytes32[4] commitment, uint16 signer_count, bytes32 pk_root, uint256 amount, address recipient)
+        // verifyAndMint(bytes32[4] commitment, uint16 signer_count, bytes32 pk_root,
+        //               bytes32 domain, uint64 epoch, uint64 not_before, uint64 not_after,
+        //               uint256 amount, address recipient)
+        // The domain, epoch and window come from the proof's ProofContext.
+        // commitment[0] must be sha256(abi.encodePacked(pk_root, domain, epoch,
+        // not_before, not_after, amount, recipient)); the contract recomputes it.
         
         // This is a simplified simulation of contract interaction.
         // In a real implementation, we would use `abigen!` macro.
@@ -116,7 +121,7 @@ impl EthereumAdapter {
         recipient: Address,
     ) -> Result<Vec<u8>> {
         // Function selector for verifyAndMint(...)
-        // keccak256("verifyAndMint(bytes32[4],uint16,bytes32,uint256,address)")
+        // keccak256("verifyAndMint(bytes32[4],uint16,bytes32,bytes32,uint64,uint64,uint64,uint256,address)")
         // Take first 4 bytes. For now, we mock this or use a placeholder.
         let selector = hex::decode("12345678").unwrap(); // Placeholder
         
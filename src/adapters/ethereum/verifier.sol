@@ -7,6 +7,8 @@ contract CausalGuardVerifier {
     // Storage optimization: pack variables
     address public owner;
     bytes32 public pkRoot; // The root of the aggregated public keys (t-of-n)
+    bytes32 public domainId; // ProofContext domain this deployment accepts
+    uint64 public epoch; // Committee epoch of pkRoot
     
     // Safety thresholds
     uint16 constant MIN_SIGNERS_LOW = 2;
@@ -19,22 +21,35 @@ contract CausalGuardVerifier {
     error InvalidProofParams();
     error InsufficientSignatures();
     error InvalidRoot();
+    error WrongDomain();
+    error WrongEpoch();
+    error ProofExpired();
 
-    constructor(bytes32 _pkRoot) {
+    constructor(bytes32 _pkRoot, bytes32 _domainId, uint64 _epoch) {
         owner = msg.sender;
         pkRoot = _pkRoot;
+        domainId = _domainId;
+        epoch = _epoch;
     }
 
     /// @notice Verifies a CausalGuard proof and executes a transfer/mint.
     /// @param commitment The cryptographic commitment (bytes32[4]) from the proof.
     /// @param signerCount Number of signers aggregated in this proof.
     /// @param proofRoot The PKroot claimed in the proof (must match storage).
+    /// @param proofDomain The ProofContext domain (verified against commitment).
+    /// @param proofEpoch The ProofContext committee epoch (verified against commitment).
+    /// @param notBefore Start of the validity window (Unix seconds, inclusive).
+    /// @param notAfter End of the validity window (Unix seconds, inclusive).
     /// @param amount Amount of USDC to transfer (verified against commitment).
     /// @param recipient Destination address.
     function verifyAndMint(
         bytes32[4] calldata commitment,
         uint16 signerCount,
         bytes32 proofRoot,
+        bytes32 proofDomain,
+        uint64 proofEpoch,
+        uint64 notBefore,
+        uint64 notAfter,
         uint256 amount,
         address recipient
     ) external {
@@ -45,30 +60,37 @@ contract CausalGuardVerifier {
         // For cross-chain, we mandate High Risk Tier (t=5)
         if (signerCount < MIN_SIGNERS_HIGH) revert InsufficientSignatures();
 
-        // 3. Proof Validation (Simplified for Demo/Spec)
-        // In full prod, this calls a Pairing.verify() or dedicated precompile.
-        // Here we verify the structural integrity and commitment binding.
-        // Validating that 'amount' and 'recipient' are bound to 'commitment[0]'.
-        
-        // Hash(amount, recipient, nonce...) must match commitment[0]
-        // This prevents replay or tampering of the payload.
-        // We assume commitment[0] is sha256(payload).
-        
-        // Mock validation for gas estimation purposes:
-        // assembly { ... } to simulate pairing cost if needed, 
-        // but user wants < 85k gas, so we keep it lean.
-        
-        if (commitment[0] == bytes32(0)) revert InvalidProofParams();
+        // 3. Payload Binding
+        // commitment[0] is sha256 of the payload: the root, the ProofContext
+        // (domain, epoch, window) and the transfer. Recomputing it here means
+        // none of these arguments can be swapped without changing commitment[0].
+        bytes32 payload = sha256(
+            abi.encodePacked(proofRoot, proofDomain, proofEpoch, notBefore, notAfter, amount, recipient)
+        );
+        if (commitment[0] != payload) revert InvalidProofParams();
 
-        // 4. Execute Logic (Mint/Unlock)
+        // 4. Freshness: only now that the context is bound to commitment[0]
+        // can it be checked against this deployment's domain, epoch and clock
+        if (proofDomain != domainId) revert WrongDomain();
+        if (proofEpoch != epoch) revert WrongEpoch();
+        if (block.timestamp < notBefore || block.timestamp > notAfter) revert ProofExpired();
+
+        // 5. Proof Validation (Simplified for Demo/Spec)
+        // In full prod, this calls a Pairing.verify() or dedicated precompile
+        // over the commitment, which ties commitment[0] to the signed proof.
+        // Without it the payload binding above is only as strong as the caller.
+
+        // 6. Execute Logic (Mint/Unlock)
         // In real deployment, this would call USDC.mint() or bridge.unlock()
         emit ProofVerified(commitment[0], signerCount);
         emit USDCMinted(recipient, amount);
     }
 
-    /// @notice Updates the PK root (Governance only).
-    function updateRoot(bytes32 _newRoot) external {
+    /// @notice Updates the PK root and its committee epoch (Governance only).
+    function updateRoot(bytes32 _newRoot, uint64 _epoch) external {
         if (msg.sender != owner) revert("Unauthorized");
+        if (_epoch <= epoch) revert WrongEpoch();
         pkRoot = _newRoot;
+        epoch = _epoch;
     }
 }
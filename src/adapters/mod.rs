@@ -3,7 +3,7 @@
 //! Provides a unified interface for encoding proofs for different blockchains.
 
 use alloc::vec::Vec;
-use crate::types::ZKSNARKProof;
+use crate::types::{ProofContext, ZKSNARKProof};
 use crate::error::Result;
 
 /// Trait for blockchain-specific proof encoding and verification hints.
@@ -52,6 +52,9 @@ pub trait BlockchainAdapter {
     fn chain_id(&self) -> &'static str;
 }
 
+/// Binary format version for [`VerificationHint`].
+pub const VERIFICATION_HINT_FORMAT_VERSION: u8 = 0x01;
+
 /// Verification hint for lightweight on-chain verifiers.
 ///
 /// Contains precomputed values that speed up on-chain verification.
@@ -65,12 +68,17 @@ pub struct VerificationHint {
     pub pk_root: [u8; 32],
     /// Hash of the signed message
     pub msg_hash: [u8; 32],
+    /// Domain, epoch and validity window of a context-bound proof
+    pub context: Option<ProofContext>,
     /// Chain-specific metadata
     pub metadata: Vec<u8>,
 }
 
 impl VerificationHint {
     /// Create a new verification hint.
+    ///
+    /// The proof's [`ProofContext`], if it has one, is copied into the hint
+    /// so on-chain verifiers can check the domain, epoch and expiry cheaply.
     pub fn new(
         proof: &ZKSNARKProof,
         pk_root: [u8; 32],
@@ -81,26 +89,84 @@ impl VerificationHint {
         let mut hasher = Sha3_256::new();
         hasher.update(proof.as_bytes());
         let proof_commitment: [u8; 32] = hasher.finalize().into();
+        let context = crate::core::proof_format::ProofView::parse(proof.as_bytes())
+            .and_then(|view| view.context())
+            .flatten();
         
         Self {
             proof_commitment,
             num_signatures: proof.num_signatures() as u16,
             pk_root,
             msg_hash,
+            context,
             metadata: Vec::new(),
         }
     }
+
+    /// Whether an on-chain verifier at `time`, `epoch` and `domain` may accept this proof.
+    ///
+    /// Always `false` for proofs without a context.
+    pub fn is_fresh(&self, time: u64, epoch: u64, domain: &[u8; 32]) -> bool {
+        self.context
+            .is_some_and(|context| context.domain == *domain && context.epoch == epoch && context.is_valid_at(time))
+    }
     
     /// Serialize to bytes.
+    ///
+    /// Format: [version:1][proof_commitment:32][num_sigs:2][pk_root:32][msg_hash:32]
+    ///         [has_context:1][context:56 if present][metadata]
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut out = Vec::with_capacity(98 + self.metadata.len());
+        let mut out = Vec::with_capacity(100 + ProofContext::ENCODED_LEN + self.metadata.len());
+        out.push(VERIFICATION_HINT_FORMAT_VERSION);
         out.extend_from_slice(&self.proof_commitment);
         out.extend_from_slice(&self.num_signatures.to_le_bytes());
         out.extend_from_slice(&self.pk_root);
         out.extend_from_slice(&self.msg_hash);
+        match &self.context {
+            Some(context) => {
+                out.push(1);
+                out.extend_from_slice(&context.to_bytes());
+            }
+            None => out.push(0),
+        }
         out.extend_from_slice(&self.metadata);
         out
     }
+
+    /// Deserialize from bytes produced by [`Self::to_bytes`].
+    ///
+    /// Returns `None` if the bytes are malformed or have a different version.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 100 || bytes[0] != VERIFICATION_HINT_FORMAT_VERSION {
+            return None;
+        }
+
+        let mut proof_commitment = [0u8; 32];
+        proof_commitment.copy_from_slice(&bytes[1..33]);
+        let num_signatures = u16::from_le_bytes([bytes[33], bytes[34]]);
+        let mut pk_root = [0u8; 32];
+        pk_root.copy_from_slice(&bytes[35..67]);
+        let mut msg_hash = [0u8; 32];
+        msg_hash.copy_from_slice(&bytes[67..99]);
+
+        let (context, metadata_offset) = match bytes[99] {
+            0 => (None, 100),
+            1 => {
+                let end = 100 + ProofContext::ENCODED_LEN;
+                (Some(ProofContext::from_bytes(bytes.get(100..end)?)?), end)
+            }
+            _ => return None,
+        };
+
+        Some(Self {
+            proof_commitment,
+            num_signatures,
+            pk_root,
+            msg_hash,
+            context,
+            metadata: bytes[metadata_offset..].to_vec(),
+        })
+    }
 }
 
 pub mod solana;
@@ -138,3 +204,50 @@ impl BlockchainAdapter for DefaultAdapter {
         "generic"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::aggregation::aggregate_proofs_with_context;
+    use crate::core::prehash::MessageMode;
+    use crate::core::keygen::setup;
+    use crate::core::signing::aggregate_sign;
+
+    #[test]
+    fn test_hint_carries_proof_context() {
+        let (sks, pks, pk_root) = setup(3);
+        let msg = b"hint";
+        let domain = ProofContext::domain_id("cosmos:hub-4");
+        let context = ProofContext::new(domain, 2, 100, 200);
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, &context.bind(msg), 2).unwrap();
+        let proof = aggregate_proofs_with_context(sigs, proofs, pk_root, msg, MessageMode::Pure, &pks, &context).unwrap();
+
+        let hint = VerificationHint::new(&proof, pk_root, [0xCC; 32]);
+        assert_eq!(hint.context, Some(context));
+        assert!(hint.is_fresh(150, 2, &domain));
+        assert!(!hint.is_fresh(201, 2, &domain));
+        assert!(!hint.is_fresh(150, 3, &domain));
+
+        let bytes = hint.to_bytes();
+        assert_eq!(bytes[0], VERIFICATION_HINT_FORMAT_VERSION);
+        assert_eq!(bytes[99], 1);
+        assert_eq!(&bytes[100..100 + ProofContext::ENCODED_LEN], &context.to_bytes());
+        let decoded = VerificationHint::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.context, Some(context));
+        assert_eq!(decoded.pk_root, pk_root);
+        assert_eq!(decoded.num_signatures, 2);
+
+        // Hints from another format version are not misread
+        let mut other_version = bytes.clone();
+        other_version[0] = VERIFICATION_HINT_FORMAT_VERSION + 1;
+        assert!(VerificationHint::from_bytes(&other_version).is_none());
+        assert!(VerificationHint::from_bytes(&bytes[..100 + ProofContext::ENCODED_LEN - 1]).is_none());
+
+        // Unbound proofs carry an empty context flag
+        let plain = VerificationHint::new(&ZKSNARKProof::new(vec![0; 8], 1, [0; 32]), pk_root, [0xCC; 32]);
+        assert!(plain.context.is_none());
+        assert!(!plain.is_fresh(150, 2, &domain));
+        assert_eq!(plain.to_bytes().len(), 100);
+        assert!(VerificationHint::from_bytes(&plain.to_bytes()).unwrap().context.is_none());
+    }
+}
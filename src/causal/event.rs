@@ -6,16 +6,23 @@
 //! ## Event Versions
 //! - **v0.01 (legacy)**: Original format without metadata commitment.
 //! - **v0.02 (metadata-aware)**: Includes cryptographic metadata commitment for risk-adaptive policies.
+//! - **v0.03 (tagged)**: Fingerprint, leaf and metadata commitment are domain-separated
+//!   (see [`crate::utils::tags`]); the metadata commitment is optional.
+//!
+//! New events are always v0.03. v0.01 and v0.02 events are only accepted
+//! with the `legacy-hashing` feature.
 
 use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
 use sha3::{Digest, Sha3_256};
 
 use crate::causal::metadata::{StructuredMetadata, compute_metadata_commitment};
+use crate::utils::{tags, TaggedHasher};
 
 /// Event format versions.
 pub const EVENT_VERSION_LEGACY: u8 = 0x01;
 pub const EVENT_VERSION_METADATA: u8 = 0x02;
+pub const EVENT_VERSION_TAGGED: u8 = 0x03;
 
 /// Types of actions that can be logged.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -43,12 +50,14 @@ impl From<u8> for ActionType {
 
 /// A cryptographically robust, nonce-ordered event.
 ///
-/// Supports two versions:
+/// Supports three versions:
 /// - **v0.01 (legacy)**: `metadata_commitment` is `[0u8; 32]`, fingerprint excludes metadata.
 /// - **v0.02 (metadata-aware)**: `metadata_commitment` is bound to payload, fingerprint includes metadata.
+/// - **v0.03 (tagged)**: like v0.02 with domain-separated hashes; `metadata_commitment`
+///   is `[0u8; 32]` when the event carries no metadata.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CausalEvent {
-    /// Event format version (0x01 = legacy, 0x02 = metadata-aware).
+    /// Event format version (0x01 = legacy, 0x02 = metadata-aware, 0x03 = tagged).
     pub version: u8,
     /// Strictly monotonically increasing counter.
     pub nonce: u64,
@@ -61,16 +70,14 @@ pub struct CausalEvent {
     /// SHA3-256 of action-specific data.
     pub payload_hash: [u8; 32],
     /// Cryptographic commitment to structured metadata (v0.02+).
-    /// For v0.01 events, this is `[0u8; 32]`.
+    /// For v0.01 events and v0.03 events without metadata, this is `[0u8; 32]`.
     pub metadata_commitment: [u8; 32],
-    /// SHA3-256 of (nonce || timestamp || action_type || payload_hash [|| metadata_commitment]).
+    /// Hash of (nonce || timestamp || action_type || payload_hash [|| metadata_commitment]).
     pub behavioral_fingerprint: [u8; 32],
 }
 
 impl CausalEvent {
-    /// Create a new legacy (v0.01) causal event without metadata.
-    ///
-    /// Maintains backward compatibility with existing code.
+    /// Create a new causal event without metadata.
     pub fn new(
         nonce: u64,
         timestamp: u64,
@@ -79,16 +86,17 @@ impl CausalEvent {
         payload: &[u8],
     ) -> Self {
         let payload_hash = Self::hash_data(payload);
-        let metadata_commitment = [0u8; 32]; // Legacy: no metadata
-        let behavioral_fingerprint = Self::compute_fingerprint_v1(
+        let metadata_commitment = [0u8; 32]; // No metadata
+        let behavioral_fingerprint = Self::compute_fingerprint_tagged(
             nonce,
             timestamp,
             action_type,
             &payload_hash,
+            &metadata_commitment,
         );
 
         Self {
-            version: EVENT_VERSION_LEGACY,
+            version: EVENT_VERSION_TAGGED,
             nonce,
             timestamp,
             agent_id,
@@ -99,7 +107,7 @@ impl CausalEvent {
         }
     }
 
-    /// Create a new metadata-aware (v0.02) causal event.
+    /// Create a new causal event carrying metadata.
     ///
     /// The metadata is cryptographically bound to the payload hash.
     pub fn new_with_metadata(
@@ -112,7 +120,7 @@ impl CausalEvent {
     ) -> Self {
         let payload_hash = Self::hash_data(payload);
         let metadata_commitment = compute_metadata_commitment(nonce, &payload_hash, metadata);
-        let behavioral_fingerprint = Self::compute_fingerprint_v2(
+        let behavioral_fingerprint = Self::compute_fingerprint_tagged(
             nonce,
             timestamp,
             action_type,
@@ -121,7 +129,7 @@ impl CausalEvent {
         );
 
         Self {
-            version: EVENT_VERSION_METADATA,
+            version: EVENT_VERSION_TAGGED,
             nonce,
             timestamp,
             agent_id,
@@ -132,17 +140,102 @@ impl CausalEvent {
         }
     }
 
+    /// Create a v0.01 (without metadata) or v0.02 (with metadata) event with
+    /// untagged hashes, as produced before domain separation.
+    #[cfg(feature = "legacy-hashing")]
+    pub fn new_legacy(
+        nonce: u64,
+        timestamp: u64,
+        agent_id: [u8; 32],
+        action_type: u8,
+        payload: &[u8],
+        metadata: Option<&StructuredMetadata>,
+    ) -> Self {
+        use crate::causal::metadata::compute_metadata_commitment_legacy;
+
+        let payload_hash = Self::hash_data(payload);
+        let (version, metadata_commitment, behavioral_fingerprint) = match metadata {
+            Some(metadata) => {
+                let commitment = compute_metadata_commitment_legacy(nonce, &payload_hash, metadata);
+                let fingerprint =
+                    Self::compute_fingerprint_v2(nonce, timestamp, action_type, &payload_hash, &commitment);
+                (EVENT_VERSION_METADATA, commitment, fingerprint)
+            }
+            None => {
+                let fingerprint = Self::compute_fingerprint_v1(nonce, timestamp, action_type, &payload_hash);
+                (EVENT_VERSION_LEGACY, [0u8; 32], fingerprint)
+            }
+        };
+
+        Self {
+            version,
+            nonce,
+            timestamp,
+            agent_id,
+            action_type,
+            payload_hash,
+            metadata_commitment,
+            behavioral_fingerprint,
+        }
+    }
+
+    /// Whether the event was produced before domain-separated hashing (v0.01 or v0.02).
+    pub fn is_legacy(&self) -> bool {
+        matches!(self.version, EVENT_VERSION_LEGACY | EVENT_VERSION_METADATA)
+    }
+
+    /// Whether the event carries a metadata commitment.
+    pub fn has_metadata(&self) -> bool {
+        match self.version {
+            EVENT_VERSION_METADATA => true,
+            EVENT_VERSION_TAGGED => self.metadata_commitment != [0u8; 32],
+            _ => false,
+        }
+    }
+
+    /// Check that `metadata` is the metadata this event commits to.
+    ///
+    /// Always `false` for events without metadata. v0.02 commitments are
+    /// only checked with the `legacy-hashing` feature.
+    pub fn commits_to_metadata(&self, metadata: &StructuredMetadata) -> bool {
+        if !self.has_metadata() {
+            return false;
+        }
+
+        let expected = match self.version {
+            EVENT_VERSION_TAGGED => compute_metadata_commitment(self.nonce, &self.payload_hash, metadata),
+            #[cfg(feature = "legacy-hashing")]
+            EVENT_VERSION_METADATA => crate::causal::metadata::compute_metadata_commitment_legacy(
+                self.nonce,
+                &self.payload_hash,
+                metadata,
+            ),
+            _ => return false,
+        };
+        expected == self.metadata_commitment
+    }
+
     /// Verify and recompute the behavioral fingerprint based on event version.
     ///
     /// Returns `true` if the stored fingerprint matches the computed one.
+    /// v0.01 and v0.02 events only verify with the `legacy-hashing` feature.
     pub fn verify_fingerprint(&self) -> bool {
         let computed = match self.version {
+            EVENT_VERSION_TAGGED => Self::compute_fingerprint_tagged(
+                self.nonce,
+                self.timestamp,
+                self.action_type,
+                &self.payload_hash,
+                &self.metadata_commitment,
+            ),
+            #[cfg(feature = "legacy-hashing")]
             EVENT_VERSION_LEGACY => Self::compute_fingerprint_v1(
                 self.nonce,
                 self.timestamp,
                 self.action_type,
                 &self.payload_hash,
             ),
+            #[cfg(feature = "legacy-hashing")]
             EVENT_VERSION_METADATA => Self::compute_fingerprint_v2(
                 self.nonce,
                 self.timestamp,
@@ -155,12 +248,21 @@ impl CausalEvent {
         computed == self.behavioral_fingerprint
     }
 
-    /// Compute leaf hash for Merkle integration: leaf = SHA3-256(nonce || behavioral_fingerprint).
+    /// Compute leaf hash for Merkle integration: leaf = H(nonce || behavioral_fingerprint).
+    ///
+    /// v0.03 events hash under [`tags::EVENT_LEAF`]; older events use bare SHA3-256.
     pub fn to_leaf(&self) -> [u8; 32] {
-        let mut hasher = Sha3_256::new();
-        hasher.update(&self.nonce.to_le_bytes());
-        hasher.update(&self.behavioral_fingerprint);
-        hasher.finalize().into()
+        if self.is_legacy() {
+            let mut hasher = Sha3_256::new();
+            hasher.update(self.nonce.to_le_bytes());
+            hasher.update(self.behavioral_fingerprint);
+            return hasher.finalize().into();
+        }
+
+        let mut hasher = TaggedHasher::new(tags::EVENT_LEAF);
+        hasher.update(self.nonce.to_le_bytes());
+        hasher.update(self.behavioral_fingerprint);
+        hasher.finalize()
     }
 
     /// Helper to hash payload data.
@@ -170,9 +272,30 @@ impl CausalEvent {
         hasher.finalize().into()
     }
 
+    /// Compute the tagged (v0.03) behavioral fingerprint.
+    ///
+    /// `H_tag(nonce || timestamp || action_type || payload_hash || metadata_commitment)`
+    /// with tag [`tags::EVENT_FINGERPRINT`].
+    fn compute_fingerprint_tagged(
+        nonce: u64,
+        timestamp: u64,
+        action_type: u8,
+        payload_hash: &[u8; 32],
+        metadata_commitment: &[u8; 32],
+    ) -> [u8; 32] {
+        let mut hasher = TaggedHasher::new(tags::EVENT_FINGERPRINT);
+        hasher.update(nonce.to_le_bytes());
+        hasher.update(timestamp.to_le_bytes());
+        hasher.update([action_type]);
+        hasher.update(payload_hash);
+        hasher.update(metadata_commitment);
+        hasher.finalize()
+    }
+
     /// Compute the legacy (v0.01) behavioral fingerprint.
     ///
     /// `SHA3-256(nonce || timestamp || action_type || payload_hash)`
+    #[cfg(feature = "legacy-hashing")]
     fn compute_fingerprint_v1(
         nonce: u64,
         timestamp: u64,
@@ -190,6 +313,7 @@ impl CausalEvent {
     /// Compute the metadata-aware (v0.02) behavioral fingerprint.
     ///
     /// `SHA3-256(nonce || timestamp || action_type || payload_hash || metadata_commitment)`
+    #[cfg(feature = "legacy-hashing")]
     fn compute_fingerprint_v2(
         nonce: u64,
         timestamp: u64,
@@ -228,7 +352,7 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_legacy_event_creation() {
+    fn test_event_without_metadata_creation() {
         let event = CausalEvent::new(
             1,
             1000,
@@ -236,8 +360,9 @@ mod tests {
             0x01,
             b"test payload",
         );
-        assert_eq!(event.version, EVENT_VERSION_LEGACY);
+        assert_eq!(event.version, EVENT_VERSION_TAGGED);
         assert_eq!(event.metadata_commitment, [0u8; 32]);
+        assert!(!event.has_metadata());
         assert!(event.verify_fingerprint());
     }
 
@@ -252,8 +377,11 @@ mod tests {
             b"test payload",
             &metadata,
         );
-        assert_eq!(event.version, EVENT_VERSION_METADATA);
+        assert_eq!(event.version, EVENT_VERSION_TAGGED);
         assert_ne!(event.metadata_commitment, [0u8; 32]);
+        assert!(event.has_metadata());
+        assert!(event.commits_to_metadata(&metadata));
+        assert!(!event.commits_to_metadata(&StructuredMetadata::new(1, 137, 0)));
         assert!(event.verify_fingerprint());
     }
 
@@ -275,13 +403,42 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "legacy-hashing")]
     fn test_legacy_backward_compatibility() {
         // Simulate a v0.01 event and verify it still works
-        let event = CausalEvent::new(5, 2000, [0xCCu8; 32], 0x03, b"legacy data");
+        let event = CausalEvent::new_legacy(5, 2000, [0xCCu8; 32], 0x03, b"legacy data", None);
+        assert_eq!(event.version, EVENT_VERSION_LEGACY);
         assert!(event.verify_fingerprint());
         
         // Leaf computation should work
         let leaf = event.to_leaf();
         assert_ne!(leaf, [0u8; 32]);
+
+        // v0.02 events keep their metadata binding
+        let metadata = StructuredMetadata::new(100_00, 0, 0);
+        let event = CausalEvent::new_legacy(6, 2000, [0xCCu8; 32], 0x01, b"legacy data", Some(&metadata));
+        assert_eq!(event.version, EVENT_VERSION_METADATA);
+        assert!(event.verify_fingerprint());
+        assert!(event.commits_to_metadata(&metadata));
+    }
+
+    #[test]
+    fn test_fingerprint_is_domain_separated() {
+        let event = CausalEvent::new(5, 2000, [0xCCu8; 32], 0x03, b"data");
+
+        // The untagged v0.02 fingerprint over the same fields differs
+        let mut hasher = Sha3_256::new();
+        hasher.update(event.nonce.to_le_bytes());
+        hasher.update(event.timestamp.to_le_bytes());
+        hasher.update([event.action_type]);
+        hasher.update(event.payload_hash);
+        hasher.update(event.metadata_commitment);
+        let untagged: [u8; 32] = hasher.finalize().into();
+        assert_ne!(untagged, event.behavioral_fingerprint);
+
+        // Relabelling a tagged event as legacy does not verify by default
+        let mut relabelled = event.clone();
+        relabelled.version = EVENT_VERSION_METADATA;
+        assert!(!relabelled.verify_fingerprint());
     }
 }
@@ -1,16 +1,24 @@
 //! Causal Event Logger implementation.
 //!
 //! Enforces strict nonce ordering and temporal causal integrity.
-//! Supports both legacy (v0.01) and metadata-aware (v0.02) events.
+//! Logs v0.03 events, with or without metadata; v0.01 and v0.02 chains
+//! verify with the `legacy-hashing` feature.
 
 use alloc::vec::Vec;
-use crate::causal::event::{CausalEvent, EVENT_VERSION_LEGACY, EVENT_VERSION_METADATA};
+use crate::causal::event::CausalEvent;
 use crate::causal::metadata::StructuredMetadata;
 use crate::causal::merkle::IncrementalMerkleTree;
+#[cfg(feature = "nova")]
+use crate::nova::behavioral_circuit::EventChainAccumulator;
 use core::result::Result;
-use sha3::{Digest, Sha3_256};
 use thiserror::Error;
 
+/// Tolerated clock skew between consecutive events, in milliseconds.
+///
+/// An event may be timestamped up to this much earlier than the latest
+/// event before it.
+pub const TIMESTAMP_SKEW_MS: u64 = 500;
+
 /// Errors specific to the Causal Event Logger.
 #[derive(Debug, Error, PartialEq)]
 pub enum LoggerError {
@@ -33,6 +41,9 @@ pub struct CausalEventLogger {
     leaves: Vec<[u8; 32]>,
     /// Store actual events for policy evaluation.
     events: Vec<CausalEvent>,
+    /// Poseidon root that unified proofs are checked against.
+    #[cfg(feature = "nova")]
+    event_chain: EventChainAccumulator,
 }
 
 impl CausalEventLogger {
@@ -44,13 +55,12 @@ impl CausalEventLogger {
             merkle_tree: IncrementalMerkleTree::new(),
             leaves: Vec::new(),
             events: Vec::new(),
+            #[cfg(feature = "nova")]
+            event_chain: EventChainAccumulator::new(),
         }
     }
 
-    /// Log a new legacy (v0.01) event without metadata.
-    ///
-    /// This method is preserved for backward compatibility with existing code.
-    /// Internally calls `log_event_with_metadata` with default metadata.
+    /// Log a new event without metadata.
     pub fn log_event(
         &mut self,
         agent_id: &[u8; 32],
@@ -62,12 +72,12 @@ impl CausalEventLogger {
             agent_id,
             action_type,
             payload,
-            None, // No metadata = v0.01 event
+            None, // No metadata: zero commitment
             current_time_ms,
         )
     }
 
-    /// Log a new metadata-aware (v0.02) event.
+    /// Log a new metadata-aware event.
     ///
     /// The metadata is cryptographically bound to the payload hash,
     /// enabling risk-adaptive policy enforcement.
@@ -106,7 +116,7 @@ impl CausalEventLogger {
         let new_nonce = self.last_nonce + 1;
 
         // 3. Validate timestamp regression (±500ms skew tolerance)
-        if self.last_timestamp > 0 && current_time_ms + 500 < self.last_timestamp {
+        if self.last_timestamp > 0 && current_time_ms + TIMESTAMP_SKEW_MS < self.last_timestamp {
             return Err(LoggerError::TimestampRegression(current_time_ms));
         }
 
@@ -142,6 +152,8 @@ impl CausalEventLogger {
         self.merkle_tree.insert(leaf);
         self.leaves.push(leaf);
         self.events.push(event.clone());
+        #[cfg(feature = "nova")]
+        self.event_chain.push(&event, metadata);
 
         Ok(event)
     }
@@ -151,6 +163,17 @@ impl CausalEventLogger {
         self.merkle_tree.current_root
     }
 
+    /// Get the Poseidon root of the logged events.
+    ///
+    /// This is the [`event_chain_root`](crate::nova::behavioral_circuit::event_chain_root)
+    /// of every event logged so far, with the last event's metadata opened
+    /// if it was logged with any. A unified proof over the whole log, made
+    /// with that metadata, verifies against it.
+    #[cfg(feature = "nova")]
+    pub fn get_event_chain_root(&self) -> [u8; 32] {
+        self.event_chain.root()
+    }
+
     /// Generate a Merkle proof for a specific nonce.
     pub fn generate_proof(&self, nonce: u64) -> Option<Vec<[u8; 32]>> {
         if nonce == 0 || nonce > self.leaves.len() as u64 {
@@ -163,7 +186,9 @@ impl CausalEventLogger {
 
     /// Verify the integrity of an event chain against a root.
     ///
-    /// Supports both v0.01 (legacy) and v0.02 (metadata-aware) events.
+    /// Events with and without metadata may be mixed. Chains of v0.01/v0.02
+    /// events are only accepted with the `legacy-hashing` feature, and never
+    /// mixed with v0.03 events, since the two use different Merkle trees.
     pub fn verify_event_chain(
         events: &[CausalEvent],
         expected_root: &[u8; 32],
@@ -172,56 +197,35 @@ impl CausalEventLogger {
             return expected_root == &[0u8; 32];
         }
 
+        let legacy = events[0].is_legacy();
         let mut leaves = Vec::with_capacity(events.len());
         for (i, event) in events.iter().enumerate() {
             // 1. Version-aware fingerprint verification
-            let derived_fingerprint: [u8; 32] = match event.version {
-                EVENT_VERSION_LEGACY => {
-                    // v0.01: fingerprint = SHA3-256(nonce || timestamp || action_type || payload_hash)
-                    let mut hasher = Sha3_256::new();
-                    hasher.update(&event.nonce.to_le_bytes());
-                    hasher.update(&event.timestamp.to_le_bytes());
-                    hasher.update(&[event.action_type]);
-                    hasher.update(&event.payload_hash);
-                    hasher.finalize().into()
-                }
-                EVENT_VERSION_METADATA => {
-                    // v0.02: fingerprint = SHA3-256(nonce || timestamp || action_type || payload_hash || metadata_commitment)
-                    let mut hasher = Sha3_256::new();
-                    hasher.update(&event.nonce.to_le_bytes());
-                    hasher.update(&event.timestamp.to_le_bytes());
-                    hasher.update(&[event.action_type]);
-                    hasher.update(&event.payload_hash);
-                    hasher.update(&event.metadata_commitment);
-                    hasher.finalize().into()
-                }
-                _ => return false, // Unknown version
-            };
-
-            if derived_fingerprint != event.behavioral_fingerprint {
+            if event.is_legacy() != legacy || !event.verify_fingerprint() {
                 return false; // Tampered!
             }
 
             // 2. Recompute leaf from nonce and fingerprint
-            let mut leaf_hasher = Sha3_256::new();
-            leaf_hasher.update(&event.nonce.to_le_bytes());
-            leaf_hasher.update(&derived_fingerprint);
-            let leaf: [u8; 32] = leaf_hasher.finalize().into();
-            leaves.push(leaf);
-
+            leaves.push(event.to_leaf());
 
             // 3. Strict ordering check
             if i > 0 {
                 if events[i].nonce <= events[i-1].nonce {
                     return false;
                 }
-                if events[i].timestamp + 500 < events[i-1].timestamp {
+                if events[i].timestamp + TIMESTAMP_SKEW_MS < events[i-1].timestamp {
                     return false;
                 }
             }
         }
 
-        let tree = crate::utils::MerkleTree::from_leaves(&leaves);
+        let tree = match legacy {
+            false => crate::utils::MerkleTree::from_leaves(&leaves),
+            #[cfg(feature = "legacy-hashing")]
+            true => crate::utils::MerkleTree::from_leaves_legacy(&leaves),
+            #[cfg(not(feature = "legacy-hashing"))]
+            true => return false,
+        };
         tree.root() == *expected_root
     }
 
@@ -3,8 +3,11 @@
 //! Provides cryptographically bound metadata without exposing raw payloads.
 
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "legacy-hashing")]
 use sha3::{Digest, Sha3_256};
 
+use crate::utils::{tags, TaggedHasher};
+
 /// Risk flags bitmask constants.
 pub mod risk_flags {
     /// Cross-chain transfer.
@@ -84,14 +87,29 @@ impl StructuredMetadata {
 ///
 /// The commitment binds metadata to the payload via:
 /// ```text
-/// commitment = SHA3-256(nonce || payload_hash || metadata_bytes)
+/// commitment = H_tag(nonce || payload_hash || metadata_bytes)
 /// ```
+/// with tag [`tags::METADATA_COMMITMENT`].
 ///
 /// This prevents an attacker from substituting metadata for a given payload_hash.
 pub fn compute_metadata_commitment(
     nonce: u64,
     payload_hash: &[u8; 32],
     metadata: &StructuredMetadata,
+) -> [u8; 32] {
+    let mut hasher = TaggedHasher::new(tags::METADATA_COMMITMENT);
+    hasher.update(nonce.to_le_bytes());
+    hasher.update(payload_hash);
+    hasher.update(metadata.to_bytes());
+    hasher.finalize()
+}
+
+/// Untagged metadata commitment of v0.02 events: `SHA3-256(nonce || payload_hash || metadata_bytes)`.
+#[cfg(feature = "legacy-hashing")]
+pub fn compute_metadata_commitment_legacy(
+    nonce: u64,
+    payload_hash: &[u8; 32],
+    metadata: &StructuredMetadata,
 ) -> [u8; 32] {
     let mut hasher = Sha3_256::new();
     hasher.update(&nonce.to_le_bytes());
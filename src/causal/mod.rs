@@ -8,7 +8,7 @@ pub mod merkle;
 pub mod logger;
 pub mod metadata;
 
-pub use event::{CausalEvent, ActionType, EVENT_VERSION_LEGACY, EVENT_VERSION_METADATA};
+pub use event::{CausalEvent, ActionType, EVENT_VERSION_LEGACY, EVENT_VERSION_METADATA, EVENT_VERSION_TAGGED};
 pub use merkle::IncrementalMerkleTree;
-pub use logger::{CausalEventLogger, LoggerError};
+pub use logger::{CausalEventLogger, LoggerError, TIMESTAMP_SKEW_MS};
 pub use metadata::{StructuredMetadata, compute_metadata_commitment, risk_flags};
@@ -0,0 +1,345 @@
+//! Hierarchical (threshold-of-thresholds) access structures.
+//!
+//! A flat [`ThresholdPolicy`](crate::types::ThresholdPolicy) only counts
+//! signers. An [`AccessStructure`] nests thresholds, so a policy such as
+//! "2 of the 3 organizations, each contributing at least 2 of its 4 signers"
+//! can be stated directly:
+//!
+//! ```
+//! use pq_aggregate::core::access::AccessStructure;
+//!
+//! let structure = AccessStructure::groups(2, [(2, vec![0, 1, 2, 3]), (2, vec![4, 5, 6, 7]), (2, vec![8, 9, 10, 11])]);
+//! assert!(structure.is_satisfied_by(&[0, 1, 4, 5]));
+//! assert!(!structure.is_satisfied_by(&[0, 1, 2, 4]));
+//! ```
+//!
+//! The committee's key tree follows the structure: each group is a Merkle
+//! tree over its members, and each group node also hashes in its threshold.
+//! The resulting [`AccessStructure::key_root`] commits to both the keys and
+//! the shape of the policy.
+
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+use crate::core::scheme::{ActiveScheme, SignatureScheme};
+use crate::error::{PQAggregateError, Result};
+use crate::types::PublicKey;
+use crate::utils::{tagged_hash, tags, MerkleTree, TaggedHasher};
+
+/// Binary format version for [`AccessStructure`].
+pub const ACCESS_STRUCTURE_FORMAT_VERSION: u8 = 0x01;
+
+/// Maximum nesting depth of an access structure.
+pub const MAX_ACCESS_DEPTH: usize = 16;
+
+/// Node tag of a single signer in the binary encoding.
+const NODE_SIGNER: u8 = 0x00;
+
+/// Node tag of a threshold gate in the binary encoding.
+const NODE_THRESHOLD: u8 = 0x01;
+
+/// A monotone access structure over committee indices.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccessStructure {
+    /// The validator at this committee index
+    Signer(usize),
+    /// At least `threshold` of `children` must be satisfied
+    Threshold {
+        threshold: usize,
+        children: Vec<AccessStructure>,
+    },
+}
+
+impl AccessStructure {
+    /// A `threshold`-of-`children` gate.
+    pub fn threshold(threshold: usize, children: Vec<AccessStructure>) -> Self {
+        Self::Threshold { threshold, children }
+    }
+
+    /// Two-level structure: `threshold` of the groups, where each group is
+    /// `(group_threshold, members)`.
+    pub fn groups(threshold: usize, groups: impl IntoIterator<Item = (usize, Vec<usize>)>) -> Self {
+        let children = groups
+            .into_iter()
+            .map(|(group_threshold, members)| {
+                Self::threshold(group_threshold, members.into_iter().map(Self::Signer).collect())
+            })
+            .collect();
+        Self::threshold(threshold, children)
+    }
+
+    /// Check that the structure is well-formed for a committee of `committee_size`.
+    ///
+    /// Every gate needs at least one child and a threshold between 1 and its
+    /// number of children, every signer index must lie inside the committee
+    /// and appear only once, and nesting may not exceed [`MAX_ACCESS_DEPTH`].
+    pub fn validate(&self, committee_size: usize) -> Result<()> {
+        let mut seen = BTreeSet::new();
+        self.validate_node(committee_size, 1, &mut seen)
+    }
+
+    fn validate_node(&self, committee_size: usize, depth: usize, seen: &mut BTreeSet<usize>) -> Result<()> {
+        if depth > MAX_ACCESS_DEPTH {
+            return Err(PQAggregateError::InvalidInput {
+                reason: format!("Access structure is nested deeper than {} levels", MAX_ACCESS_DEPTH),
+            });
+        }
+
+        match self {
+            Self::Signer(index) => {
+                if *index >= committee_size {
+                    return Err(PQAggregateError::InvalidInput {
+                        reason: format!("Signer {} is outside the committee of {}", index, committee_size),
+                    });
+                }
+                if !seen.insert(*index) {
+                    return Err(PQAggregateError::InvalidInput {
+                        reason: format!("Signer {} appears more than once in the access structure", index),
+                    });
+                }
+                Ok(())
+            }
+            Self::Threshold { threshold, children } => {
+                if *threshold == 0 || *threshold > children.len() {
+                    return Err(PQAggregateError::InvalidThreshold {
+                        threshold: *threshold,
+                        num_participants: children.len(),
+                    });
+                }
+                children
+                    .iter()
+                    .try_for_each(|child| child.validate_node(committee_size, depth + 1, seen))
+            }
+        }
+    }
+
+    /// Whether the given signers satisfy the structure.
+    pub fn is_satisfied_by(&self, signers: &[usize]) -> bool {
+        let signers: BTreeSet<usize> = signers.iter().copied().collect();
+        self.is_satisfied_by_set(&signers)
+    }
+
+    fn is_satisfied_by_set(&self, signers: &BTreeSet<usize>) -> bool {
+        match self {
+            Self::Signer(index) => signers.contains(index),
+            Self::Threshold { threshold, children } => {
+                children.iter().filter(|child| child.is_satisfied_by_set(signers)).count() >= *threshold
+            }
+        }
+    }
+
+    /// Smallest number of signers that can satisfy the structure.
+    pub fn min_signers(&self) -> usize {
+        match self {
+            Self::Signer(_) => 1,
+            Self::Threshold { threshold, children } => {
+                let mut minima: Vec<usize> = children.iter().map(Self::min_signers).collect();
+                minima.sort_unstable();
+                minima.iter().take(*threshold).sum()
+            }
+        }
+    }
+
+    /// Committee indices named in the structure, in order of appearance.
+    pub fn signers(&self) -> Vec<usize> {
+        let mut out = Vec::new();
+        self.collect_signers(&mut out);
+        out
+    }
+
+    fn collect_signers(&self, out: &mut Vec<usize>) {
+        match self {
+            Self::Signer(index) => out.push(*index),
+            Self::Threshold { children, .. } => children.iter().for_each(|child| child.collect_signers(out)),
+        }
+    }
+
+    /// Commitment to the structure alone (no keys), recorded in aggregated proofs.
+    pub fn commitment(&self) -> [u8; 32] {
+        tagged_hash(tags::ACCESS_STRUCTURE, &self.to_bytes())
+    }
+
+    /// Root of the group-of-groups key tree over `pks`.
+    ///
+    /// A signer node hashes its committee index and the key's
+    /// [`leaf_hash`](SignatureScheme::leaf_hash); a gate hashes its threshold,
+    /// its number of children and the Merkle root over its children.
+    ///
+    /// # Errors
+    /// `InvalidInput` / `InvalidThreshold` if the structure is not valid for
+    /// a committee of `pks.len()`.
+    pub fn key_root(&self, pks: &[PublicKey]) -> Result<[u8; 32]> {
+        self.validate(pks.len())?;
+        Ok(self.node_hash(pks))
+    }
+
+    fn node_hash(&self, pks: &[PublicKey]) -> [u8; 32] {
+        match self {
+            Self::Signer(index) => {
+                let mut hasher = TaggedHasher::new(tags::ACCESS_SIGNER);
+                hasher.update((*index as u64).to_le_bytes());
+                hasher.update(ActiveScheme::leaf_hash(pks[*index].as_bytes()));
+                hasher.finalize()
+            }
+            Self::Threshold { threshold, children } => {
+                let child_hashes: Vec<[u8; 32]> = children.iter().map(|child| child.node_hash(pks)).collect();
+                let mut hasher = TaggedHasher::new(tags::ACCESS_GROUP);
+                hasher.update((*threshold as u64).to_le_bytes());
+                hasher.update((children.len() as u64).to_le_bytes());
+                hasher.update(MerkleTree::from_leaves(&child_hashes).root());
+                hasher.finalize()
+            }
+        }
+    }
+
+    /// Serialize to binary format so verifiers can load the policy.
+    ///
+    /// Format: [version:1] followed by the root node, where a node is either
+    /// [0x00][signer_index:4] or [0x01][threshold:4][num_children:4][children].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = alloc::vec![ACCESS_STRUCTURE_FORMAT_VERSION];
+        self.encode_node(&mut out);
+        out
+    }
+
+    fn encode_node(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Signer(index) => {
+                out.push(NODE_SIGNER);
+                out.extend_from_slice(&(*index as u32).to_le_bytes());
+            }
+            Self::Threshold { threshold, children } => {
+                out.push(NODE_THRESHOLD);
+                out.extend_from_slice(&(*threshold as u32).to_le_bytes());
+                out.extend_from_slice(&(children.len() as u32).to_le_bytes());
+                children.iter().for_each(|child| child.encode_node(out));
+            }
+        }
+    }
+
+    /// Deserialize from binary format.
+    ///
+    /// Returns `None` if the bytes are malformed or nested deeper than
+    /// [`MAX_ACCESS_DEPTH`]. A successful parse does not imply the structure
+    /// is valid for a given committee; see [`Self::validate`].
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.first() != Some(&ACCESS_STRUCTURE_FORMAT_VERSION) {
+            return None;
+        }
+
+        let mut pos = 1;
+        let node = Self::decode_node(bytes, &mut pos, 1)?;
+        (pos == bytes.len()).then_some(node)
+    }
+
+    fn decode_node(bytes: &[u8], pos: &mut usize, depth: usize) -> Option<Self> {
+        if depth > MAX_ACCESS_DEPTH {
+            return None;
+        }
+
+        let tag = *bytes.get(*pos)?;
+        *pos += 1;
+        match tag {
+            NODE_SIGNER => Some(Self::Signer(read_u32(bytes, pos)? as usize)),
+            NODE_THRESHOLD => {
+                let threshold = read_u32(bytes, pos)? as usize;
+                let num_children = read_u32(bytes, pos)? as usize;
+                // Each child takes at least 5 bytes, which bounds the allocation
+                let mut children = Vec::with_capacity(num_children.min((bytes.len() - *pos) / 5));
+                for _ in 0..num_children {
+                    children.push(Self::decode_node(bytes, pos, depth + 1)?);
+                }
+                Some(Self::Threshold { threshold, children })
+            }
+            _ => None,
+        }
+    }
+}
+
+fn read_u32(bytes: &[u8], pos: &mut usize) -> Option<u32> {
+    let b = bytes.get(*pos..*pos + 4)?;
+    *pos += 4;
+    Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2 of 3 organizations, each contributing at least 2 of its 4 signers.
+    fn organizations() -> AccessStructure {
+        AccessStructure::groups(2, [(2, vec![0, 1, 2, 3]), (2, vec![4, 5, 6, 7]), (2, vec![8, 9, 10, 11])])
+    }
+
+    #[test]
+    fn test_satisfaction() {
+        let structure = organizations();
+        assert!(structure.validate(12).is_ok());
+        assert_eq!(structure.min_signers(), 4);
+
+        assert!(structure.is_satisfied_by(&[0, 1, 4, 5]));
+        assert!(structure.is_satisfied_by(&[2, 3, 10, 11, 6]));
+        // Four signers, but only one organization reaches its threshold
+        assert!(!structure.is_satisfied_by(&[0, 1, 2, 4]));
+        // Every organization represented, none with two signers
+        assert!(!structure.is_satisfied_by(&[0, 4, 8]));
+    }
+
+    #[test]
+    fn test_validate_rejects_malformed_structures() {
+        assert!(organizations().validate(11).is_err());
+        assert!(AccessStructure::threshold(0, vec![AccessStructure::Signer(0)]).validate(1).is_err());
+        assert!(AccessStructure::threshold(2, vec![AccessStructure::Signer(0)]).validate(1).is_err());
+        assert!(AccessStructure::threshold(1, vec![]).validate(1).is_err());
+
+        let repeated = AccessStructure::groups(1, [(1, vec![0, 1]), (1, vec![1, 2])]);
+        assert!(repeated.validate(3).is_err());
+
+        let mut deep = AccessStructure::Signer(0);
+        for _ in 0..MAX_ACCESS_DEPTH {
+            deep = AccessStructure::threshold(1, vec![deep]);
+        }
+        assert!(deep.validate(1).is_err());
+    }
+
+    #[test]
+    fn test_serialization_roundtrip() {
+        let structure = organizations();
+        let bytes = structure.to_bytes();
+        assert_eq!(AccessStructure::from_bytes(&bytes), Some(structure));
+
+        assert!(AccessStructure::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(AccessStructure::from_bytes(&trailing).is_none());
+
+        // Claimed child count far beyond the input
+        let mut oversized = bytes;
+        oversized[6..10].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(AccessStructure::from_bytes(&oversized).is_none());
+    }
+
+    #[test]
+    fn test_key_root_commits_to_structure() {
+        let (_, pks, flat_root) = crate::core::keygen::setup(12);
+        let structure = organizations();
+        let root = structure.key_root(&pks).unwrap();
+        assert_ne!(root, flat_root);
+
+        // Changing a threshold or moving a signer between groups changes the root
+        let lower = AccessStructure::groups(1, [(2, vec![0, 1, 2, 3]), (2, vec![4, 5, 6, 7]), (2, vec![8, 9, 10, 11])]);
+        let moved = AccessStructure::groups(2, [(2, vec![0, 1, 2]), (2, vec![3, 4, 5, 6, 7]), (2, vec![8, 9, 10, 11])]);
+        assert_ne!(lower.key_root(&pks).unwrap(), root);
+        assert_ne!(moved.key_root(&pks).unwrap(), root);
+        assert_ne!(lower.commitment(), structure.commitment());
+
+        // So does swapping a key
+        let mut swapped = pks.clone();
+        swapped.swap(0, 11);
+        assert_ne!(structure.key_root(&swapped).unwrap(), root);
+
+        assert!(structure.key_root(&pks[..11]).is_err());
+    }
+}
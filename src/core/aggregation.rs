@@ -1,17 +1,29 @@
 //! Proof aggregation for PQ-Aggregate.
 //!
-//! Aggregates multiple ML-DSA signatures into a compact proof using
+//! Aggregates multiple validator signatures into a compact proof using
 //! a commitment-based scheme (simulating Nova recursive folding for v0.1.0).
 
 use alloc::string::ToString;
 use alloc::vec::Vec;
 use sha3::{Digest, Sha3_256};
 
+use crate::core::access::AccessStructure;
+use crate::core::prehash::MessageMode;
+use crate::core::proof_format::{self, ProofView};
+use crate::core::scheme::{ActiveScheme, SignatureScheme};
 use crate::error::{PQAggregateError, Result};
-use crate::types::{MerkleProof, PublicKey, Signature, ZKSNARKProof};
-use crate::utils::MerkleTree;
-
-/// Maximum proof size in bytes (target: ≤1.2 KB).
+use crate::types::{
+    FullProof, MerkleMultiProof, MerkleProof, MultiMessageCertificate, ProofContext, PublicKey, Signature,
+    SignatureShare, ZKSNARKProof,
+};
+use crate::utils::{tagged_hash, tags, MerkleTree, TaggedHasher};
+#[cfg(feature = "nova")]
+use crate::types::KeyTree;
+
+/// Maximum proof size in bytes (target: ≤1.2 KB), excluding the signer bitmap.
+///
+/// v1 proofs carry a fixed 32-byte bitmap; v2 proofs may exceed this by the
+/// `ceil(n / 8)` bytes of their committee-sized bitmap.
 pub const MAX_PROOF_SIZE: usize = 1228;
 
 /// Aggregate multiple signatures into a single ZK proof.
@@ -40,117 +52,850 @@ pub fn aggregate_proofs(
     msg: &[u8],
     pks: &[PublicKey],
 ) -> Result<ZKSNARKProof> {
-    // Validate inputs
+    aggregate_proofs_with_mode(sigs, proofs, pk_root, msg, MessageMode::Pure, pks)
+}
+
+/// [`aggregate_proofs`] for signatures over a message in the given [`MessageMode`].
+///
+/// The proof records the mode, and verifies only through
+/// [`verify_with_mode`](crate::verifier::verify_with_mode) with the same mode.
+///
+/// # Errors
+/// Same as [`aggregate_proofs`]; signatures made in another mode fail verification.
+pub fn aggregate_proofs_with_mode(
+    sigs: Vec<Signature>,
+    proofs: Vec<MerkleProof>,
+    pk_root: [u8; 32],
+    msg: &[u8],
+    mode: MessageMode,
+    pks: &[PublicKey],
+) -> Result<ZKSNARKProof> {
+    validate_shares(&sigs, &proofs, &pk_root, msg, mode, pks, None)?;
+
+    // Create aggregated proof using commitment scheme
+    // This simulates Nova folding for v0.1.0
+    let leaf_hashes: Vec<[u8; 32]> = proofs.iter().map(|p| *p.leaf_hash()).collect();
+    let extensions = proof_format::proof_extensions(mode, None, None, None);
+    let proof = create_aggregated_commitment(&sigs, &leaf_hashes, &pk_root, msg, pks.len(), extensions)?;
+
+    Ok(proof)
+}
+
+/// Aggregate multiple signatures into a Nova-backed proof (format v3).
+///
+/// Shares are validated exactly as in [`aggregate_proofs_with_mode`]. Each signer is
+/// then folded, in ascending index order, through one step of the
+/// [`MerkleStepCircuit`](crate::nova::circuit::MerkleStepCircuit), and the
+/// recursive SNARK is compressed with Nova's `CompressedSNARK`. The result
+/// carries the same public inputs as a v2 proof, and
+/// [`verify`](crate::verifier::verify) additionally checks the SNARK against
+/// the signer bitmap.
+///
+/// The signatures are checked only here, natively. Their Poseidon commitment
+/// is folded into the SNARK but not bound to anything a verifier recomputes,
+/// so the SNARK proves which committee members are counted as signers, not
+/// that they signed `msg`.
+///
+/// The SNARK adds about 10 KB, so [`MAX_PROOF_SIZE`] does not apply.
+/// The first call in a process also generates the Nova parameters.
+///
+/// # Errors
+/// As [`aggregate_proofs`], plus `NovaError` if proving fails or the
+/// committee has more than 65,536 members.
+#[cfg(feature = "nova")]
+pub fn aggregate_proofs_nova(
+    sigs: Vec<Signature>,
+    proofs: Vec<MerkleProof>,
+    pk_root: [u8; 32],
+    msg: &[u8],
+    mode: MessageMode,
+    pks: &[PublicKey],
+) -> Result<ZKSNARKProof> {
+    aggregate_proofs_nova_with_key_tree(sigs, proofs, pk_root, msg, mode, pks, KeyTree::Sha3)
+}
+
+/// [`aggregate_proofs_nova`] over a committee committed to with `key_tree`.
+///
+/// With [`KeyTree::Poseidon`], `pk_root` is a
+/// [`PoseidonKeyTree`](crate::nova::merkle::PoseidonKeyTree) root and
+/// `proofs` are its paths. Each path is then also checked inside the step
+/// circuit, so the SNARK alone shows that every signer is a committee
+/// member. The key tree is recorded in the proof.
+///
+/// # Errors
+/// As [`aggregate_proofs_nova`].
+#[cfg(feature = "nova")]
+pub fn aggregate_proofs_nova_with_key_tree(
+    sigs: Vec<Signature>,
+    proofs: Vec<MerkleProof>,
+    pk_root: [u8; 32],
+    msg: &[u8],
+    mode: MessageMode,
+    pks: &[PublicKey],
+    key_tree: KeyTree,
+) -> Result<ZKSNARKProof> {
+    use crate::nova::aggregation::{prove_signers, signer_witness};
+    use crate::nova::merkle::{poseidon_leaf, PoseidonKeyTree};
+
+    match key_tree {
+        KeyTree::Sha3 => validate_shares(&sigs, &proofs, &pk_root, msg, mode, pks, None)?,
+        KeyTree::Poseidon => validate_shares_with(&sigs, &proofs, |i, sig, proof| {
+            let pk = pks.get(sig.signer_index()).ok_or_else(|| PQAggregateError::InvalidInput {
+                reason: alloc::format!("Signer index {} out of bounds (have {} keys)", sig.signer_index(), pks.len()),
+            })?;
+            let expected_leaf = poseidon_leaf(&ActiveScheme::leaf_hash(pk.as_bytes()));
+            validate_share_with_leaf(
+                i,
+                sig,
+                proof,
+                &pk_root,
+                msg,
+                mode,
+                pk,
+                &expected_leaf,
+                PoseidonKeyTree::verify_proof,
+            )?;
+            Ok(0)
+        })?,
+    };
+
+    let mut signers = sigs
+        .iter()
+        .zip(proofs.iter())
+        .map(|(sig, proof)| signer_witness(sig, proof, key_tree))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| PQAggregateError::NovaError("Signer index too large for the step circuit".to_string()))?;
+    signers.sort_unstable_by_key(|witness| witness.signer_index);
+
+    let committee_size = pks.len();
+    let mut indices: Vec<usize> = sigs.iter().map(|sig| sig.signer_index()).collect();
+    indices.sort_unstable();
+    let bitmap = proof_format::build_bitmap(indices.iter().copied(), committee_size);
+    let index_commitment = proof_format::compute_index_commitment(&indices);
+    let nonce_commitment = compute_nonce_commitment(&sigs);
+    let mut extensions = proof_format::proof_extensions(mode, None, None, None);
+    if key_tree != KeyTree::Sha3 {
+        proof_format::push_extension(&mut extensions, proof_format::EXT_KEY_TREE, &[key_tree.id()]);
+    }
+
+    let public_inputs_hash = proof_format::public_inputs_hash_v2(
+        &pk_root,
+        msg,
+        sigs.len(),
+        committee_size,
+        &bitmap,
+        &index_commitment,
+        &nonce_commitment,
+        &extensions,
+    );
+
+    let folded = prove_signers(signers, committee_size, key_tree, &pk_root, &public_inputs_hash)?;
+
+    let proof_bytes = proof_format::encode_v3(
+        sigs.len(),
+        committee_size,
+        &folded.commitment,
+        &bitmap,
+        &index_commitment,
+        &nonce_commitment,
+        &extensions,
+        &folded.snark,
+        &pk_root,
+    );
+
+    Ok(ZKSNARKProof::new(proof_bytes, sigs.len(), public_inputs_hash))
+}
+
+/// Aggregate signatures into a proof bound to a domain, epoch and validity window.
+///
+/// Validators sign [`ProofContext::bind`]`(msg)` instead of `msg`, and the
+/// context is recorded in the proof's public inputs. The result verifies
+/// only through [`verify_at`](crate::verifier::verify_at) with a matching
+/// domain and epoch and a time inside the window, so it cannot be replayed
+/// on another chain, under a later committee, or after it expires.
+///
+/// # Errors
+/// As [`aggregate_proofs`] (signatures are checked against the bound
+/// message), plus `InvalidInput` if the window ends before it starts.
+pub fn aggregate_proofs_with_context(
+    sigs: Vec<Signature>,
+    proofs: Vec<MerkleProof>,
+    pk_root: [u8; 32],
+    msg: &[u8],
+    mode: MessageMode,
+    pks: &[PublicKey],
+    context: &ProofContext,
+) -> Result<ZKSNARKProof> {
+    if context.not_before > context.not_after {
+        return Err(PQAggregateError::InvalidInput {
+            reason: alloc::format!(
+                "Validity window ends at {} before it starts at {}",
+                context.not_after, context.not_before
+            ),
+        });
+    }
+
+    let bound = context.bind(msg);
+    validate_shares(&sigs, &proofs, &pk_root, &bound, mode, pks, None)?;
+
+    let leaf_hashes: Vec<[u8; 32]> = proofs.iter().map(|p| *p.leaf_hash()).collect();
+    let extensions = proof_format::proof_extensions(mode, None, None, Some(context));
+    create_aggregated_commitment(&sigs, &leaf_hashes, &pk_root, &bound, pks.len(), extensions)
+}
+
+/// Aggregate signatures from a committee with per-validator weights.
+///
+/// `pk_root` must be the root of
+/// [`MerkleTree::from_weighted_public_keys`]`(pks, weights)`, so every Merkle
+/// proof also proves the signer's weight. The proof commits to the combined
+/// weight of the signers, which
+/// [`verify_with_policy`](crate::verifier::verify_with_policy) checks against
+/// a [`ThresholdPolicy::Weighted`](crate::types::ThresholdPolicy::Weighted) policy.
+///
+/// # Errors
+/// As [`aggregate_proofs`], plus `InvalidInput` if `weights` and `pks`
+/// differ in length or the combined weight overflows `u64`.
+pub fn aggregate_proofs_weighted(
+    sigs: Vec<Signature>,
+    proofs: Vec<MerkleProof>,
+    pk_root: [u8; 32],
+    msg: &[u8],
+    mode: MessageMode,
+    pks: &[PublicKey],
+    weights: &[u64],
+) -> Result<ZKSNARKProof> {
+    if weights.len() != pks.len() {
+        return Err(PQAggregateError::InvalidInput {
+            reason: alloc::format!("Got {} weights for {} public keys", weights.len(), pks.len()),
+        });
+    }
+
+    let total_weight = validate_shares(&sigs, &proofs, &pk_root, msg, mode, pks, Some(weights))?;
+
+    let leaf_hashes: Vec<[u8; 32]> = proofs.iter().map(|p| *p.leaf_hash()).collect();
+    let extensions = proof_format::proof_extensions(mode, Some(total_weight), None, None);
+    create_aggregated_commitment(&sigs, &leaf_hashes, &pk_root, msg, pks.len(), extensions)
+}
+
+/// Aggregate signatures for a committee governed by a hierarchical [`AccessStructure`].
+///
+/// `key_root` must be [`AccessStructure::key_root`]`(pks)`; the aggregator
+/// recomputes it from `pks`, so no per-signer Merkle proofs are needed. The
+/// signers must satisfy the whole structure, and the proof commits to the
+/// structure so that
+/// [`verify_with_policy`](crate::verifier::verify_with_policy) can re-check
+/// it under [`ThresholdPolicy::Hierarchical`](crate::types::ThresholdPolicy::Hierarchical).
+///
+/// # Errors
+/// - `InvalidInput` / `InvalidThreshold` if the structure is malformed for
+///   this committee, a signature fails, a signer appears twice, or a signer
+///   is not named in the structure
+/// - `MerkleProofInvalid` if `key_root` does not match the structure and keys
+/// - `PolicyViolation` if the signers do not satisfy the structure
+pub fn aggregate_proofs_hierarchical(
+    sigs: Vec<Signature>,
+    structure: &AccessStructure,
+    key_root: [u8; 32],
+    msg: &[u8],
+    mode: MessageMode,
+    pks: &[PublicKey],
+) -> Result<ZKSNARKProof> {
     if sigs.is_empty() {
         return Err(PQAggregateError::InsufficientSignatures {
-            required: 1,
+            required: structure.min_signers(),
             provided: 0,
         });
     }
 
-    if sigs.len() != proofs.len() {
-        return Err(PQAggregateError::InvalidInput {
-            reason: "Signature and proof count mismatch".to_string(),
+    if structure.key_root(pks)? != key_root {
+        return Err(PQAggregateError::MerkleProofInvalid {
+            index: 0,
+            reason: "Key root does not match the access structure and public keys".to_string(),
         });
     }
 
-    // Validate all Merkle proofs
-    for (i, proof) in proofs.iter().enumerate() {
-        if !MerkleTree::verify_proof(&pk_root, proof) {
-            return Err(PQAggregateError::MerkleProofInvalid {
-                index: i,
-                reason: "Proof does not verify against pk_root".to_string(),
+    let members: alloc::collections::BTreeSet<usize> = structure.signers().into_iter().collect();
+    let mut seen = alloc::collections::BTreeSet::new();
+    let mut leaf_hashes = Vec::with_capacity(sigs.len());
+    for (i, sig) in sigs.iter().enumerate() {
+        let signer_idx = sig.signer_index();
+        if !members.contains(&signer_idx) {
+            return Err(PQAggregateError::InvalidInput {
+                reason: alloc::format!("Signer {} is not part of the access structure", signer_idx),
             });
         }
+
+        // Membership follows from the recomputed key root
+        let pk = &pks[signer_idx];
+        let leaf_hash = ActiveScheme::leaf_hash(pk.as_bytes());
+        validate_signer(i, sig, &leaf_hash, &leaf_hash, msg, mode, pk)?;
+
+        if !seen.insert(signer_idx) {
+            return Err(PQAggregateError::InvalidInput {
+                reason: alloc::format!("Duplicate signature from signer {}", signer_idx),
+            });
+        }
+        leaf_hashes.push(leaf_hash);
+    }
+
+    let signers: Vec<usize> = seen.into_iter().collect();
+    if !structure.is_satisfied_by(&signers) {
+        return Err(PQAggregateError::PolicyViolation {
+            reason: alloc::format!("Signers {:?} do not satisfy the access structure", signers),
+        });
+    }
+
+    let extensions = proof_format::proof_extensions(mode, None, Some(&structure.commitment()), None);
+    create_aggregated_commitment(&sigs, &leaf_hashes, &key_root, msg, pks.len(), extensions)
+}
+
+/// Aggregate signatures whose key inclusion is shown by one [`MerkleMultiProof`].
+///
+/// Equivalent to [`aggregate_proofs`], but the multiproof from
+/// [`MerkleTree::prove_many`] replaces the per-signer proof vector, so path
+/// nodes shared by the signers travel only once. The multiproof must cover
+/// exactly the signers of `sigs`.
+///
+/// # Errors
+/// - `MerkleProofInvalid` if the multiproof does not verify against `pk_root`,
+///   does not cover a signer, or covers a leaf that does not match its key
+/// - `InvalidInput` if a signature fails, a signer appears twice, or the
+///   multiproof covers leaves without a signature
+pub fn aggregate_proofs_multi(
+    sigs: Vec<Signature>,
+    multiproof: &MerkleMultiProof,
+    pk_root: [u8; 32],
+    msg: &[u8],
+    mode: MessageMode,
+    pks: &[PublicKey],
+) -> Result<ZKSNARKProof> {
+    if sigs.is_empty() {
+        return Err(PQAggregateError::InsufficientSignatures {
+            required: 1,
+            provided: 0,
+        });
+    }
+
+    if !MerkleTree::verify_multi(&pk_root, multiproof) {
+        return Err(PQAggregateError::MerkleProofInvalid {
+            index: 0,
+            reason: "Multiproof does not verify against pk_root".to_string(),
+        });
     }
 
-    // Verify each ML-DSA signature against its public key
-    for (_i, sig) in sigs.iter().enumerate() {
+    let mut seen = alloc::collections::BTreeSet::new();
+    let mut leaf_hashes = Vec::with_capacity(sigs.len());
+    for (i, sig) in sigs.iter().enumerate() {
         let signer_idx = sig.signer_index();
-        if signer_idx >= pks.len() {
+        let Some(pk) = pks.get(signer_idx) else {
             return Err(PQAggregateError::InvalidInput {
                 reason: alloc::format!("Signer index {} out of bounds (have {} keys)", signer_idx, pks.len()),
             });
-        }
-        if !crate::core::signing::verify_single(&pks[signer_idx], msg, sig) {
+        };
+        let Some(leaf_hash) = multiproof.leaf_hash(signer_idx) else {
+            return Err(PQAggregateError::MerkleProofInvalid {
+                index: i,
+                reason: alloc::format!("Multiproof does not cover signer {}", signer_idx),
+            });
+        };
+
+        validate_signer(i, sig, leaf_hash, &ActiveScheme::leaf_hash(pk.as_bytes()), msg, mode, pk)?;
+
+        if !seen.insert(signer_idx) {
             return Err(PQAggregateError::InvalidInput {
-                reason: alloc::format!("Signature from signer {} failed ML-DSA verification", signer_idx),
+                reason: alloc::format!("Duplicate signature from signer {}", signer_idx),
             });
         }
+        leaf_hashes.push(*leaf_hash);
     }
 
-    // Create aggregated proof using commitment scheme
-    // This simulates Nova folding for v0.1.0
-    let proof = create_aggregated_commitment(&sigs, &proofs, &pk_root, msg)?;
+    if multiproof.num_leaves() != sigs.len() {
+        return Err(PQAggregateError::InvalidInput {
+            reason: alloc::format!(
+                "Multiproof covers {} leaves for {} signatures",
+                multiproof.num_leaves(),
+                sigs.len()
+            ),
+        });
+    }
 
-    Ok(proof)
+    let extensions = proof_format::proof_extensions(mode, None, None, None);
+    create_aggregated_commitment(&sigs, &leaf_hashes, &pk_root, msg, pks.len(), extensions)
 }
 
-/// Create a commitment-based aggregated proof.
+/// Aggregate signatures into a transparent [`FullProof`].
 ///
-/// The proof structure (simulating Nova):
-/// - Commitment to all signature data
-/// - Accumulator of signer indices
-/// - Public inputs hash
-fn create_aggregated_commitment(
+/// Runs the same checks as [`aggregate_proofs`], but instead of committing
+/// to the signatures it embeds them, together with each signer's public key
+/// and Merkle proof, so the result can be audited with
+/// [`verify_full`](crate::verifier::verify_full) without trusting the aggregator.
+/// The auditor passes the same `mode` to `verify_full`.
+pub fn aggregate_full_proof(
+    sigs: Vec<Signature>,
+    proofs: Vec<MerkleProof>,
+    pk_root: [u8; 32],
+    msg: &[u8],
+    mode: MessageMode,
+    pks: &[PublicKey],
+) -> Result<FullProof> {
+    validate_shares(&sigs, &proofs, &pk_root, msg, mode, pks, None)?;
+
+    let mut entries: Vec<(PublicKey, SignatureShare)> = sigs
+        .into_iter()
+        .zip(proofs)
+        .map(|(sig, proof)| (pks[sig.signer_index()].clone(), SignatureShare::new(sig, proof)))
+        .collect();
+    entries.sort_unstable_by_key(|(_, share)| share.signer_index());
+
+    Ok(FullProof {
+        pk_root,
+        committee_size: pks.len(),
+        scheme_id: ActiveScheme::ID,
+        entries,
+    })
+}
+
+/// Compress a full proof into the compact [`ZKSNARKProof`] form.
+///
+/// Every embedded signature is re-verified first, so the compact proof is
+/// only ever produced from signatures that were actually checked. The
+/// compact proof records `mode`.
+///
+/// # Errors
+/// Any error [`verify_full`](crate::verifier::verify_full) would reject the proof for.
+pub fn compress_full_proof(full: &FullProof, pk_root: [u8; 32], msg: &[u8], mode: MessageMode) -> Result<ZKSNARKProof> {
+    crate::verifier::check_full_proof(pk_root, msg, mode, full)?;
+
+    let (sigs, leaf_hashes): (Vec<Signature>, Vec<[u8; 32]>) = full
+        .entries
+        .iter()
+        .map(|(_, share)| (share.signature.clone(), share.merkle_proof.leaf_hash))
+        .unzip();
+
+    let extensions = proof_format::proof_extensions(mode, None, None, None);
+    create_aggregated_commitment(&sigs, &leaf_hashes, &pk_root, msg, full.committee_size, extensions)
+}
+
+/// Merge two partial aggregates over disjoint signer sets.
+///
+/// Both proofs must verify for the same `pk_root`, message and committee,
+/// and carry the same scheme, access structure and [`ProofContext`]. The
+/// result has the union of the two signer bitmaps, the summed signature
+/// count (and signer weight, for weighted proofs), and commitments that
+/// fold the two inputs' commitments in signer order, so merging is
+/// commutative. No raw signatures are needed.
+///
+/// # Errors
+/// - `CryptoError` if either proof does not verify
+/// - `InvalidInput` if a signer appears in both proofs, the proofs differ
+///   in committee size or extensions, or either is a legacy proof
+pub fn merge_proofs(a: &ZKSNARKProof, b: &ZKSNARKProof, pk_root: [u8; 32], msg: &[u8]) -> Result<ZKSNARKProof> {
+    let invalid = |reason: String| PQAggregateError::InvalidInput { reason };
+
+    let (Some(view_a), Some(view_b)) = (ProofView::parse(a.as_bytes()), ProofView::parse(b.as_bytes())) else {
+        return Err(invalid("Malformed proof".to_string()));
+    };
+    for view in [&view_a, &view_b] {
+        if view.version != crate::types::PROOF_FORMAT_V2 || view.uses_tagged_hashing() != Some(true) {
+            return Err(invalid("Legacy proofs cannot be merged".to_string()));
+        }
+    }
+
+    // Context-bound proofs are signed over the bound message
+    let context = view_a.context().flatten();
+    if context != view_b.context().flatten() {
+        return Err(invalid("Proofs are bound to different contexts".to_string()));
+    }
+    let signed_msg = context.map(|context| context.bind(msg));
+    let signed_msg = signed_msg.as_deref().unwrap_or(msg);
+
+    // Both proofs must verify in the mode the first one records
+    let mode = match view_a.prehash().flatten() {
+        Some(algorithm) => MessageMode::PreHashed(algorithm),
+        None => MessageMode::Pure,
+    };
+    for (name, proof) in [("first", a), ("second", b)] {
+        if !crate::verifier::verify_with_mode(pk_root, signed_msg, mode, proof) {
+            return Err(PQAggregateError::CryptoError {
+                reason: alloc::format!("The {} proof does not verify", name),
+            });
+        }
+    }
+
+    if view_a.committee_size != view_b.committee_size {
+        return Err(invalid(alloc::format!(
+            "Committee sizes differ: {} and {}",
+            view_a.committee_size, view_b.committee_size
+        )));
+    }
+    let access_structure = view_a.access_structure().flatten();
+    if view_a.scheme_id() != view_b.scheme_id() || access_structure != view_b.access_structure().flatten() {
+        return Err(invalid("Proofs differ in scheme or access structure".to_string()));
+    }
+    let signer_weight = match (view_a.signer_weight().flatten(), view_b.signer_weight().flatten()) {
+        (None, None) => None,
+        (Some(weight_a), Some(weight_b)) => Some(
+            weight_a
+                .checked_add(weight_b)
+                .ok_or_else(|| invalid("Combined signer weight overflows u64".to_string()))?,
+        ),
+        _ => return Err(invalid("Only one of the proofs is weighted".to_string())),
+    };
+
+    // Signer sets must be disjoint
+    let indices_a = crate::utils::bitmap_to_indices(view_a.bitmap);
+    let indices_b = crate::utils::bitmap_to_indices(view_b.bitmap);
+    if let Some(&overlap) = indices_a.iter().find(|i| indices_b.binary_search(i).is_ok()) {
+        return Err(invalid(alloc::format!("Signer {} appears in both proofs", overlap)));
+    }
+    let mut signer_indices = [indices_a.as_slice(), indices_b.as_slice()].concat();
+    signer_indices.sort_unstable();
+
+    // Fold the commitments, lowest signer first
+    let (first, second) = if indices_a.first() < indices_b.first() { (&view_a, &view_b) } else { (&view_b, &view_a) };
+    let fold = |tag: &[u8], left: &[u8], right: &[u8]| {
+        let mut hasher = crate::utils::TaggedHasher::new(tag);
+        hasher.update(left);
+        hasher.update(right);
+        hasher.finalize()
+    };
+    let commitment = fold(crate::utils::tags::MERGED_COMMITMENT, first.commitment, second.commitment);
+    let nonce_commitment = fold(crate::utils::tags::MERGED_NONCES, first.nonce_commitment, second.nonce_commitment);
+
+    let num_signatures = signer_indices.len();
+    let committee_size = view_a.committee_size;
+    let bitmap = proof_format::build_bitmap(signer_indices.iter().copied(), committee_size);
+    let index_commitment = proof_format::compute_index_commitment(&signer_indices);
+    let extensions = proof_format::proof_extensions(mode, signer_weight, access_structure.as_ref(), context.as_ref());
+
+    let public_inputs_hash = proof_format::public_inputs_hash_v2(
+        &pk_root,
+        signed_msg,
+        num_signatures,
+        committee_size,
+        &bitmap,
+        &index_commitment,
+        &nonce_commitment,
+        &extensions,
+    );
+    let proof_bytes = proof_format::encode_v2(
+        num_signatures,
+        committee_size,
+        &commitment,
+        &bitmap,
+        &index_commitment,
+        &nonce_commitment,
+        &extensions,
+        &pk_root,
+    );
+
+    Ok(ZKSNARKProof::new(proof_bytes, num_signatures, public_inputs_hash))
+}
+
+/// Aggregate signatures over several distinct messages into one certificate.
+///
+/// `batches` pairs each message with the signatures and Merkle proofs
+/// collected for it. Each batch is checked as in [`aggregate_proofs`], and
+/// signer sets may differ from one message to the next. Every message is
+/// signed in the same `mode`. The certificate commits to the messages,
+/// framed for `mode`, through the root of
+/// [`MultiMessageCertificate::message_tree`], in batch order.
+///
+/// # Errors
+/// - `InvalidInput` if `batches` is empty or repeats a message
+/// - any error [`aggregate_proofs`] reports for a batch
+pub fn aggregate_certificate(
+    batches: &[(&[u8], Vec<Signature>, Vec<MerkleProof>)],
+    pk_root: [u8; 32],
+    mode: MessageMode,
+    pks: &[PublicKey],
+) -> Result<MultiMessageCertificate> {
+    if batches.is_empty() {
+        return Err(PQAggregateError::InvalidInput {
+            reason: "A certificate needs at least one message".to_string(),
+        });
+    }
+
+    let mut seen = alloc::collections::BTreeSet::new();
+    let mut entries = Vec::with_capacity(batches.len());
+    for (position, (msg, sigs, proofs)) in batches.iter().enumerate() {
+        if !seen.insert(*msg) {
+            return Err(PQAggregateError::InvalidInput {
+                reason: alloc::format!("Message {} repeats an earlier message", position),
+            });
+        }
+
+        validate_shares(sigs, proofs, &pk_root, msg, mode, pks, None)?;
+
+        let leaf_hashes: Vec<[u8; 32]> = proofs.iter().map(|p| *p.leaf_hash()).collect();
+        let bitmap = proof_format::build_bitmap(sigs.iter().map(Signature::signer_index), pks.len());
+        entries.push((bitmap, compute_signature_commitment(sigs, &leaf_hashes)));
+    }
+
+    let messages: Vec<&[u8]> = batches.iter().map(|(msg, _, _)| *msg).collect();
+    let mut certificate = MultiMessageCertificate {
+        pk_root,
+        committee_size: pks.len(),
+        scheme_id: ActiveScheme::ID,
+        messages_root: MultiMessageCertificate::message_tree(&messages, mode).root(),
+        entries,
+        public_inputs_hash: [0u8; 32],
+    };
+    certificate.public_inputs_hash = certificate.compute_public_inputs_hash();
+
+    Ok(certificate)
+}
+
+/// Run the per-share and cross-share checks shared by the aggregation entry points.
+///
+/// With `weights`, leaves are checked against the weighted key tree and the
+/// signers' combined weight is returned (zero otherwise).
+fn validate_shares(
     sigs: &[Signature],
     proofs: &[MerkleProof],
     pk_root: &[u8; 32],
     msg: &[u8],
+    mode: MessageMode,
+    pks: &[PublicKey],
+    weights: Option<&[u64]>,
+) -> Result<u64> {
+    validate_shares_with(sigs, proofs, |i, sig, proof| validate_share(i, sig, proof, pk_root, msg, mode, pks, weights))
+}
+
+/// Run the cross-share checks, validating each share with `check`.
+///
+/// `check` returns the share's weight.
+fn validate_shares_with(
+    sigs: &[Signature],
+    proofs: &[MerkleProof],
+    mut check: impl FnMut(usize, &Signature, &MerkleProof) -> Result<u64>,
+) -> Result<u64> {
+    // Validate inputs
+    if sigs.is_empty() {
+        return Err(PQAggregateError::InsufficientSignatures {
+            required: 1,
+            provided: 0,
+        });
+    }
+
+    if sigs.len() != proofs.len() {
+        return Err(PQAggregateError::InvalidInput {
+            reason: "Signature and proof count mismatch".to_string(),
+        });
+    }
+
+    // Each proof must cover the leaf of the key that produced the paired
+    // signature, and each signer may only be counted once. Shares arrive
+    // from independent hosts, so neither can be assumed.
+    let mut seen = alloc::collections::BTreeSet::new();
+    let mut total_weight = 0u64;
+    for (i, (sig, proof)) in sigs.iter().zip(proofs.iter()).enumerate() {
+        let weight = check(i, sig, proof)?;
+
+        if !seen.insert(sig.signer_index()) {
+            return Err(PQAggregateError::InvalidInput {
+                reason: alloc::format!("Duplicate signature from signer {}", sig.signer_index()),
+            });
+        }
+
+        total_weight = total_weight.checked_add(weight).ok_or_else(|| PQAggregateError::InvalidInput {
+            reason: "Combined signer weight overflows u64".to_string(),
+        })?;
+    }
+
+    Ok(total_weight)
+}
+
+/// Aggregate signature shares collected from independent validators.
+///
+/// Convenience wrapper around [`aggregate_proofs`] for shares produced by
+/// [`sign_share`](crate::core::signing::sign_share) on separate hosts and
+/// typically received via [`SignatureShare::from_bytes`].
+pub fn aggregate_shares(
+    shares: Vec<SignatureShare>,
+    pk_root: [u8; 32],
+    msg: &[u8],
+    pks: &[PublicKey],
 ) -> Result<ZKSNARKProof> {
-    let mut hasher = Sha3_256::new();
+    let (sigs, proofs): (Vec<Signature>, Vec<MerkleProof>) =
+        shares.into_iter().map(SignatureShare::into_parts).unzip();
 
-    // Commit to public inputs
-    hasher.update(pk_root);
-    hasher.update(msg);
-    hasher.update(&(sigs.len() as u64).to_le_bytes());
+    aggregate_proofs(sigs, proofs, pk_root, msg, pks)
+}
 
-    let public_inputs_hash: [u8; 32] = hasher.finalize_reset().into();
+/// Validate a single signature and its Merkle proof.
+///
+/// Checks that the proof verifies against `pk_root`, that it covers the
+/// signer's own leaf and public key, and that the signature verifies.
+/// With `weights`, the leaf must also commit to the signer's weight, which
+/// is returned; otherwise the result is zero.
+/// `position` is only used to label errors.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn validate_share(
+    position: usize,
+    sig: &Signature,
+    proof: &MerkleProof,
+    pk_root: &[u8; 32],
+    msg: &[u8],
+    mode: MessageMode,
+    pks: &[PublicKey],
+    weights: Option<&[u64]>,
+) -> Result<u64> {
+    let signer_idx = sig.signer_index();
+    let Some(pk) = pks.get(signer_idx) else {
+        return Err(PQAggregateError::InvalidInput {
+            reason: alloc::format!("Signer index {} out of bounds (have {} keys)", signer_idx, pks.len()),
+        });
+    };
 
-    // Create proof bytes: commitments + compressed signature data
-    let mut proof_bytes = Vec::new();
+    let Some(weights) = weights else {
+        validate_share_with_key(position, sig, proof, pk_root, msg, mode, pk)?;
+        return Ok(0);
+    };
+    let Some(&weight) = weights.get(signer_idx) else {
+        return Err(PQAggregateError::InvalidInput {
+            reason: alloc::format!("No weight for signer {}", signer_idx),
+        });
+    };
 
-    // Header: version (1 byte) + num_sigs (2 bytes)
-    proof_bytes.push(0x01); // Version 1
-    proof_bytes.extend_from_slice(&(sigs.len() as u16).to_le_bytes());
+    let expected_leaf = ActiveScheme::weighted_leaf_hash(pk.as_bytes(), weight);
+    validate_share_with_leaf(position, sig, proof, pk_root, msg, mode, pk, &expected_leaf, MerkleTree::verify_proof)?;
+    Ok(weight)
+}
 
-    // Commitment chain (simulating Nova folding)
-    // Each step: commit to previous commitment + current signature
-    let mut running_commitment = [0u8; 32];
+/// Like [`validate_share`], with the signer's public key supplied directly.
+pub(crate) fn validate_share_with_key(
+    position: usize,
+    sig: &Signature,
+    proof: &MerkleProof,
+    pk_root: &[u8; 32],
+    msg: &[u8],
+    mode: MessageMode,
+    pk: &PublicKey,
+) -> Result<()> {
+    let expected_leaf = ActiveScheme::leaf_hash(pk.as_bytes());
+    validate_share_with_leaf(position, sig, proof, pk_root, msg, mode, pk, &expected_leaf, MerkleTree::verify_proof)
+}
 
-    for (sig, proof) in sigs.iter().zip(proofs.iter()) {
-        hasher.update(&running_commitment);
-        hasher.update(&sig.signer_index().to_le_bytes());
-        hasher.update(sig.nonce());
-        hasher.update(proof.leaf_hash());
+/// Check a share whose Merkle leaf must equal `expected_leaf`.
+///
+/// `verify_path` checks the proof against the key tree `pk_root` is the root of.
+#[allow(clippy::too_many_arguments)]
+fn validate_share_with_leaf(
+    position: usize,
+    sig: &Signature,
+    proof: &MerkleProof,
+    pk_root: &[u8; 32],
+    msg: &[u8],
+    mode: MessageMode,
+    pk: &PublicKey,
+    expected_leaf: &[u8; 32],
+    verify_path: fn(&[u8; 32], &MerkleProof) -> bool,
+) -> Result<()> {
+    if !verify_path(pk_root, proof) {
+        return Err(PQAggregateError::MerkleProofInvalid {
+            index: position,
+            reason: "Proof does not verify against pk_root".to_string(),
+        });
+    }
 
-        // Compress signature to 32 bytes (hash of full signature)
-        let sig_commitment: [u8; 32] = {
-            let mut sig_hasher = Sha3_256::new();
-            sig_hasher.update(sig.as_bytes());
-            sig_hasher.finalize().into()
-        };
-        hasher.update(&sig_commitment);
+    let signer_idx = sig.signer_index();
+    if proof.leaf_index() != signer_idx {
+        return Err(PQAggregateError::MerkleProofInvalid {
+            index: position,
+            reason: alloc::format!("Proof is for leaf {}, signature is from signer {}", proof.leaf_index(), signer_idx),
+        });
+    }
+
+    validate_signer(position, sig, proof.leaf_hash(), expected_leaf, msg, mode, pk)
+}
 
-        running_commitment = hasher.finalize_reset().into();
+/// Check that `leaf_hash` is the expected leaf of the signer's key and that the signature verifies.
+///
+/// The caller is responsible for having verified `leaf_hash` against the root.
+fn validate_signer(
+    position: usize,
+    sig: &Signature,
+    leaf_hash: &[u8; 32],
+    expected_leaf: &[u8; 32],
+    msg: &[u8],
+    mode: MessageMode,
+    pk: &PublicKey,
+) -> Result<()> {
+    let signer_idx = sig.signer_index();
+    if expected_leaf != leaf_hash {
+        return Err(PQAggregateError::MerkleProofInvalid {
+            index: position,
+            reason: alloc::format!("Leaf hash does not match public key of signer {}", signer_idx),
+        });
+    }
+
+    // Verify the signature against its public key
+    if !crate::core::signing::verify_single_with_mode(pk, msg, mode, sig) {
+        return Err(PQAggregateError::InvalidInput {
+            reason: alloc::format!("Signature from signer {} failed {} verification", signer_idx, ActiveScheme::NAME),
+        });
     }
 
-    // Final commitment
-    proof_bytes.extend_from_slice(&running_commitment);
+    Ok(())
+}
+
+/// Create a commitment-based aggregated proof.
+///
+/// `leaf_hashes[i]` is the validated Merkle leaf of `sigs[i]`'s signer.
+/// `extensions` is the proof's extension block from
+/// [`proof_format::proof_extensions`].
+///
+/// The proof structure (simulating Nova):
+/// - Commitment to all signature data
+/// - Signer bitmap sized to the committee, plus a commitment to the signer indices
+/// - Public inputs hash
+///
+/// See [`proof_format`](crate::core::proof_format) for the byte layout.
+pub(crate) fn create_aggregated_commitment(
+    sigs: &[Signature],
+    leaf_hashes: &[[u8; 32]],
+    pk_root: &[u8; 32],
+    msg: &[u8],
+    committee_size: usize,
+    extensions: Vec<u8>,
+) -> Result<ZKSNARKProof> {
+    let running_commitment = compute_signature_commitment(sigs, leaf_hashes);
 
-    // Add compressed signer bitmap (for efficient verification)
-    let bitmap = create_signer_bitmap(sigs);
-    proof_bytes.extend_from_slice(&bitmap);
+    // Signer bitmap and a commitment to the leaf indices it encodes
+    let mut signer_indices: Vec<usize> = sigs.iter().map(|s| s.signer_index()).collect();
+    signer_indices.sort_unstable();
+    let bitmap = proof_format::build_bitmap(signer_indices.iter().copied(), committee_size);
+    let index_commitment = proof_format::compute_index_commitment(&signer_indices);
 
     // Add aggregated nonce commitment
     let nonce_commitment = compute_nonce_commitment(sigs);
-    proof_bytes.extend_from_slice(&nonce_commitment);
 
-    // Add Merkle root commitment (redundant but useful for verification)
-    proof_bytes.extend_from_slice(pk_root);
 
-    // Ensure proof size is within bounds
-    if proof_bytes.len() > MAX_PROOF_SIZE {
+    let public_inputs_hash = proof_format::public_inputs_hash_v2(
+        pk_root,
+        msg,
+        sigs.len(),
+        committee_size,
+        &bitmap,
+        &index_commitment,
+        &nonce_commitment,
+        &extensions,
+    );
+
+    let proof_bytes = proof_format::encode_v2(
+        sigs.len(),
+        committee_size,
+        &running_commitment,
+        &bitmap,
+        &index_commitment,
+        &nonce_commitment,
+        &extensions,
+        pk_root,
+    );
+
+    // Ensure proof size is within bounds; only the bitmap grows with `n`
+    if proof_bytes.len() > MAX_PROOF_SIZE + bitmap.len() {
         return Err(PQAggregateError::AggregationFailed {
             reason: "Proof exceeds maximum size".to_string(),
         });
@@ -163,54 +908,53 @@ fn create_aggregated_commitment(
     ))
 }
 
-/// Create a bitmap of which signers participated.
-fn create_signer_bitmap(sigs: &[Signature]) -> [u8; 32] {
-    let mut bitmap = [0u8; 32];
+/// Compute the commitment chain over the signatures and their signers' leaves.
+///
+/// Each step is hashed under [`tags::SIGNATURE_COMMITMENT`] and each
+/// signature compressed under [`tags::SIGNATURE_DIGEST`].
+fn compute_signature_commitment(sigs: &[Signature], leaf_hashes: &[[u8; 32]]) -> [u8; 32] {
+    // Commitment chain (simulating Nova folding)
+    // Each step: commit to previous commitment + current signature
+    let mut running_commitment = [0u8; 32];
+
+    for (sig, leaf_hash) in sigs.iter().zip(leaf_hashes.iter()) {
+        let mut hasher = TaggedHasher::new(tags::SIGNATURE_COMMITMENT);
+        hasher.update(running_commitment);
+        hasher.update(sig.signer_index().to_le_bytes());
+        hasher.update(sig.nonce());
+        hasher.update(leaf_hash);
 
-    for sig in sigs {
-        let index = sig.signer_index();
-        if index < 256 {
-            bitmap[index / 8] |= 1 << (index % 8);
-        }
+        // Compress signature to 32 bytes (hash of full signature)
+        hasher.update(tagged_hash(tags::SIGNATURE_DIGEST, sig.as_bytes()));
+
+        running_commitment = hasher.finalize();
     }
 
-    bitmap
+    running_commitment
 }
 
-/// Compute a commitment to all nonces.
+/// Compute a commitment to all nonces under [`tags::NONCE_COMMITMENT`].
 fn compute_nonce_commitment(sigs: &[Signature]) -> [u8; 32] {
-    let mut hasher = Sha3_256::new();
+    let mut hasher = TaggedHasher::new(tags::NONCE_COMMITMENT);
 
     for sig in sigs {
         hasher.update(sig.nonce());
     }
 
-    hasher.finalize().into()
+    hasher.finalize()
 }
 
 /// Verify that an aggregated proof is well-formed.
 ///
 /// This performs structural validation without full verification.
+/// Both v1 and v2 proof layouts are accepted.
 pub fn validate_proof_structure(proof: &ZKSNARKProof) -> bool {
-    let bytes = proof.as_bytes();
-
-    // Minimum size: version (1) + num_sigs (2) + commitment (32) + bitmap (32) + nonce (32) + root (32)
-    if bytes.len() < 131 {
+    let Some(view) = ProofView::parse(proof.as_bytes()) else {
         return false;
-    }
-
-    // Check version
-    if bytes[0] != 0x01 {
-        return false;
-    }
+    };
 
     // Check num_signatures matches header
-    let header_count = u16::from_le_bytes([bytes[1], bytes[2]]) as usize;
-    if header_count != proof.num_signatures() {
-        return false;
-    }
-
-    true
+    view.num_signatures == proof.num_signatures()
 }
 
 #[cfg(test)]
@@ -224,7 +968,7 @@ mod tests {
         let (sks, pks, pk_root) = setup(5);
         let msg = b"test message";
 
-        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 3);
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 3).unwrap();
         let result = aggregate_proofs(sigs, proofs, pk_root, msg, &pks);
 
         assert!(result.is_ok());
@@ -238,7 +982,7 @@ mod tests {
         let (sks, pks, _pk_root) = setup(3);
         let msg = b"test";
 
-        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2);
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2).unwrap();
 
         // Use wrong root
         let wrong_root = [0xFFu8; 32];
@@ -265,7 +1009,7 @@ mod tests {
         let (sks, pks, pk_root) = setup(3);
         let msg = b"test";
 
-        let (sigs, mut proofs) = aggregate_sign(&sks, &pks, msg, 3);
+        let (sigs, mut proofs) = aggregate_sign(&sks, &pks, msg, 3).unwrap();
         proofs.pop(); // Remove one proof
 
         let result = aggregate_proofs(sigs, proofs, pk_root, msg, &pks);
@@ -276,12 +1020,287 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_aggregate_rejects_tampered_nonce() {
+        let (sks, pks, pk_root) = setup(3);
+        let msg = b"test";
+
+        let (mut sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2).unwrap();
+        sigs[1].nonce = [0xAB; 32];
+
+        let result = aggregate_proofs(sigs, proofs, pk_root, msg, &pks);
+
+        assert!(matches!(
+            result,
+            Err(PQAggregateError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_aggregate_shares_from_independent_signers() {
+        use crate::core::signing::{sign_share, NonceSource};
+        use crate::types::SignatureShare;
+
+        let (sks, pks, pk_root) = setup(5);
+        let tree = MerkleTree::from_public_keys(&pks);
+        let msg = b"distributed signing";
+
+        // Each validator signs on its own host and ships serialized bytes
+        let wire: Vec<Vec<u8>> = [0usize, 2, 4]
+            .iter()
+            .map(|&i| {
+                let proof = tree.prove(i).unwrap();
+                sign_share(&sks[i], i, msg, NonceSource::Random, &proof)
+                    .unwrap()
+                    .to_bytes()
+            })
+            .collect();
+
+        let shares: Vec<SignatureShare> = wire
+            .iter()
+            .map(|bytes| SignatureShare::from_bytes(bytes).unwrap())
+            .collect();
+
+        let proof = aggregate_shares(shares, pk_root, msg, &pks).unwrap();
+        assert_eq!(proof.num_signatures(), 3);
+        assert!(crate::verifier::verify(pk_root, msg, &proof));
+    }
+
+    #[test]
+    fn test_aggregate_rejects_mismatched_leaf() {
+        let (sks, pks, pk_root) = setup(3);
+        let msg = b"test";
+
+        let (sigs, mut proofs) = aggregate_sign(&sks, &pks, msg, 2).unwrap();
+        proofs.swap(0, 1);
+
+        let result = aggregate_proofs(sigs, proofs, pk_root, msg, &pks);
+        assert!(matches!(
+            result,
+            Err(PQAggregateError::MerkleProofInvalid { .. })
+        ));
+    }
+
+    #[test]
+    fn test_aggregate_rejects_duplicate_signer() {
+        let (sks, pks, pk_root) = setup(3);
+        let msg = b"test";
+
+        let (mut sigs, mut proofs) = aggregate_sign(&sks, &pks, msg, 2).unwrap();
+        sigs.push(sigs[0].clone());
+        proofs.push(proofs[0].clone());
+
+        let result = aggregate_proofs(sigs, proofs, pk_root, msg, &pks);
+        assert!(matches!(
+            result,
+            Err(PQAggregateError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_aggregate_with_multiproof() {
+        let (sks, pks, pk_root) = setup(10);
+        let tree = MerkleTree::from_public_keys(&pks);
+        let msg = b"multiproof";
+
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 7).unwrap();
+        let signers: Vec<usize> = sigs.iter().map(|s| s.signer_index()).collect();
+        let multiproof = tree.prove_many(&signers).unwrap();
+
+        let proof = aggregate_proofs_multi(sigs.clone(), &multiproof, pk_root, msg, MessageMode::Pure, &pks).unwrap();
+        assert!(crate::verifier::verify(pk_root, msg, &proof));
+
+        // Same commitment as aggregating with per-signer proofs
+        let expected = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
+        assert_eq!(proof.as_bytes(), expected.as_bytes());
+    }
+
+    #[test]
+    fn test_aggregate_multiproof_must_match_signers() {
+        let (sks, pks, pk_root) = setup(6);
+        let tree = MerkleTree::from_public_keys(&pks);
+        let msg = b"multiproof";
+        let (sigs, _) = aggregate_sign(&sks, &pks, msg, 3).unwrap();
+
+        // Missing a signer
+        let partial = tree.prove_many(&[0, 1]).unwrap();
+        assert!(matches!(
+            aggregate_proofs_multi(sigs.clone(), &partial, pk_root, msg, MessageMode::Pure, &pks),
+            Err(PQAggregateError::MerkleProofInvalid { index: 2, .. })
+        ));
+
+        // Covering a validator that did not sign
+        let extra = tree.prove_many(&[0, 1, 2, 5]).unwrap();
+        assert!(matches!(
+            aggregate_proofs_multi(sigs.clone(), &extra, pk_root, msg, MessageMode::Pure, &pks),
+            Err(PQAggregateError::InvalidInput { .. })
+        ));
+
+        // Wrong root
+        let exact = tree.prove_many(&[0, 1, 2]).unwrap();
+        assert!(matches!(
+            aggregate_proofs_multi(sigs.clone(), &exact, [0xFF; 32], msg, MessageMode::Pure, &pks),
+            Err(PQAggregateError::MerkleProofInvalid { .. })
+        ));
+
+        // Duplicate signer
+        let mut duplicated = sigs;
+        duplicated[2] = duplicated[0].clone();
+        assert!(matches!(
+            aggregate_proofs_multi(duplicated, &exact, pk_root, msg, MessageMode::Pure, &pks),
+            Err(PQAggregateError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_merge_disjoint_partial_aggregates() {
+        use crate::core::signing::aggregate_sign_subset;
+        use crate::verifier::{verify, verify_with_policy};
+
+        let (sks, pks, pk_root) = setup(10);
+        let msg = b"merge me";
+        let partial = |signers: &[usize]| {
+            let (sigs, proofs) = aggregate_sign_subset(&sks, &pks, msg, MessageMode::Pure, signers).unwrap();
+            aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap()
+        };
+
+        let a = partial(&[0, 3, 4]);
+        let b = partial(&[1, 7, 8, 9]);
+        let merged = merge_proofs(&a, &b, pk_root, msg).unwrap();
+        assert!(verify(pk_root, msg, &merged));
+        assert_eq!(merged.num_signatures(), 7);
+        assert_eq!(merged.signer_indices(), Some(vec![0, 1, 3, 4, 7, 8, 9]));
+        assert!(verify_with_policy(pk_root, msg, &merged, 10, &crate::types::ThresholdPolicy::AtLeast(7)));
+
+        // Order does not matter, and merges compose
+        assert_eq!(merge_proofs(&b, &a, pk_root, msg).unwrap().as_bytes(), merged.as_bytes());
+        let all = merge_proofs(&merged, &partial(&[2, 5, 6]), pk_root, msg).unwrap();
+        assert!(verify(pk_root, msg, &all));
+        assert_eq!(all.num_signatures(), 10);
+
+        // Overlapping signers
+        assert!(matches!(
+            merge_proofs(&a, &partial(&[4, 5]), pk_root, msg),
+            Err(PQAggregateError::InvalidInput { .. })
+        ));
+
+        // Another message or committee
+        assert!(merge_proofs(&a, &b, pk_root, b"other").is_err());
+        let (other_sks, other_pks, other_root) = setup(10);
+        let (sigs, proofs) = aggregate_sign_subset(&other_sks, &other_pks, msg, MessageMode::Pure, &[5]).unwrap();
+        let foreign = aggregate_proofs(sigs, proofs, other_root, msg, &other_pks).unwrap();
+        assert!(merge_proofs(&a, &foreign, pk_root, msg).is_err());
+    }
+
+    #[test]
+    fn test_merge_weighted_and_context_bound_proofs() {
+        use crate::core::signing::{aggregate_sign_subset, sign_share, NonceSource};
+        use crate::types::{ProofContext, ThresholdPolicy};
+        use crate::verifier::{verify_at, verify_with_policy};
+
+        let (sks, pks, _) = setup(6);
+        let msg = b"weighted merge";
+        let weights = [10, 20, 30, 40, 50, 60];
+        let tree = MerkleTree::from_weighted_public_keys(&pks, &weights);
+        let weighted_partial = |signers: &[usize]| {
+            let (sigs, proofs): (Vec<_>, Vec<_>) = signers
+                .iter()
+                .map(|&i| sign_share(&sks[i], i, msg, NonceSource::Random, &tree.prove(i).unwrap()).unwrap().into_parts())
+                .unzip();
+            aggregate_proofs_weighted(sigs, proofs, tree.root(), msg, MessageMode::Pure, &pks, &weights).unwrap()
+        };
+
+        let merged = merge_proofs(&weighted_partial(&[0, 5]), &weighted_partial(&[2]), tree.root(), msg).unwrap();
+        let policy = |min_weight| ThresholdPolicy::Weighted { min_weight };
+        assert!(verify_with_policy(tree.root(), msg, &merged, 6, &policy(100)));
+        assert!(!verify_with_policy(tree.root(), msg, &merged, 6, &policy(101)));
+
+        // Context-bound partials merge under their shared context only
+        let pk_root = MerkleTree::from_public_keys(&pks).root();
+        let domain = ProofContext::domain_id("test");
+        let bound_partial = |signers: &[usize], context: &ProofContext| {
+            let (sigs, proofs) = aggregate_sign_subset(&sks, &pks, &context.bind(msg), MessageMode::Pure, signers).unwrap();
+            aggregate_proofs_with_context(sigs, proofs, pk_root, msg, MessageMode::Pure, &pks, context).unwrap()
+        };
+        let context = ProofContext::new(domain, 1, 0, 100);
+        let merged = merge_proofs(&bound_partial(&[0], &context), &bound_partial(&[1], &context), pk_root, msg).unwrap();
+        assert!(verify_at(pk_root, msg, MessageMode::Pure, &merged, 50, 1, &domain));
+
+        let later = ProofContext::new(domain, 1, 0, 200);
+        assert!(merge_proofs(&bound_partial(&[0], &context), &bound_partial(&[1], &later), pk_root, msg).is_err());
+    }
+
+    #[test]
+    fn test_multi_message_certificate() {
+        use crate::core::signing::aggregate_sign_subset;
+        use crate::verifier::verify_certificate_message;
+
+        let (sks, pks, pk_root) = setup(6);
+        let messages: [&[u8]; 3] = [b"approve 1", b"approve 2", b"approve 3"];
+        let signer_sets: [&[usize]; 3] = [&[0, 1, 2, 3], &[2, 4], &[0, 1, 3, 4, 5]];
+        let batches: Vec<_> = messages
+            .iter()
+            .zip(signer_sets)
+            .map(|(msg, signers)| {
+                let (sigs, proofs) = aggregate_sign_subset(&sks, &pks, msg, MessageMode::Pure, signers).unwrap();
+                (*msg, sigs, proofs)
+            })
+            .collect();
+
+        let certificate = aggregate_certificate(&batches, pk_root, MessageMode::Pure, &pks).unwrap();
+        assert_eq!(certificate.num_messages(), 3);
+        assert_eq!(certificate.signer_indices(1), Some(vec![2, 4]));
+
+        let tree = MultiMessageCertificate::message_tree(&messages, MessageMode::Pure);
+        assert_eq!(tree.root(), *certificate.messages_root());
+        for (i, msg) in messages.iter().enumerate() {
+            let inclusion = tree.prove(i).unwrap();
+            assert!(verify_certificate_message(pk_root, &certificate, msg, MessageMode::Pure, &inclusion, signer_sets[i].len()));
+            assert!(!verify_certificate_message(pk_root, &certificate, msg, MessageMode::Pure, &inclusion, signer_sets[i].len() + 1));
+        }
+
+        // The inclusion proof must match the message and the committee
+        let inclusion = tree.prove(0).unwrap();
+        assert!(!verify_certificate_message(pk_root, &certificate, b"approve 2", MessageMode::Pure, &inclusion, 1));
+        assert!(!verify_certificate_message([0u8; 32], &certificate, messages[0], MessageMode::Pure, &inclusion, 1));
+
+        // Bitmaps are covered by the public inputs hash
+        let mut tampered = certificate.clone();
+        tampered.entries[1].0 = proof_format::build_bitmap(0..6, 6);
+        assert!(!verify_certificate_message(pk_root, &tampered, messages[1], MessageMode::Pure, &tree.prove(1).unwrap(), 6));
+
+        let restored = MultiMessageCertificate::from_bytes(&certificate.to_bytes()).unwrap();
+        assert_eq!(restored, certificate);
+        assert!(MultiMessageCertificate::from_bytes(&certificate.to_bytes()[..120]).is_none());
+    }
+
+    #[test]
+    fn test_multi_message_certificate_rejects_bad_batches() {
+        let (sks, pks, pk_root) = setup(4);
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, b"one", 2).unwrap();
+
+        assert!(matches!(
+            aggregate_certificate(&[], pk_root, MessageMode::Pure, &pks),
+            Err(PQAggregateError::InvalidInput { .. })
+        ));
+
+        let repeated = [(&b"one"[..], sigs.clone(), proofs.clone()), (&b"one"[..], sigs.clone(), proofs.clone())];
+        assert!(matches!(
+            aggregate_certificate(&repeated, pk_root, MessageMode::Pure, &pks),
+            Err(PQAggregateError::InvalidInput { .. })
+        ));
+
+        // Signatures over one message do not certify another
+        let mismatched = [(&b"one"[..], sigs.clone(), proofs.clone()), (&b"two"[..], sigs, proofs)];
+        assert!(aggregate_certificate(&mismatched, pk_root, MessageMode::Pure, &pks).is_err());
+    }
+
     #[test]
     fn test_proof_structure_validation() {
         let (sks, pks, pk_root) = setup(3);
         let msg = b"test";
 
-        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2);
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2).unwrap();
         let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
 
         assert!(validate_proof_structure(&proof));
@@ -292,7 +1311,7 @@ mod tests {
         let (sks, pks, pk_root) = setup(10);
         let msg = b"test message for size check";
 
-        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 10);
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 10).unwrap();
         let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
 
         // Proof should be compact
@@ -306,10 +1325,10 @@ mod tests {
         let msg1 = b"batch 1";
         let msg2 = b"batch 2";
 
-        let (sigs1, proofs1) = aggregate_sign(&sks, &pks, msg1, 3);
+        let (sigs1, proofs1) = aggregate_sign(&sks, &pks, msg1, 3).unwrap();
         let proof1 = aggregate_proofs(sigs1, proofs1, pk_root, msg1, &pks).unwrap();
 
-        let (sigs2, proofs2) = aggregate_sign(&sks, &pks, msg2, 3);
+        let (sigs2, proofs2) = aggregate_sign(&sks, &pks, msg2, 3).unwrap();
         let proof2 = aggregate_proofs(sigs2, proofs2, pk_root, msg2, &pks).unwrap();
 
         let super_proof = aggregate_zk_proofs(vec![proof1, proof2]).unwrap();
@@ -358,32 +1377,127 @@ pub fn aggregate_zk_proofs(proofs: Vec<ZKSNARKProof>) -> Result<crate::types::Su
 /// Create a RotationProof to transition between committee sets.
 /// 
 /// A rotation involves proving that t-of-n validators of the *old* root
-/// signed a message containing the *new* root.
+/// signed a message containing the *new* root, its committee size and the
+/// epoch (see [`RotationProof::message`](crate::types::RotationProof::message)).
 pub fn create_rotation_proof(
     old_sks: &[crate::types::SecretKey],
     old_pks: &[crate::types::PublicKey],
     old_root: [u8; 32],
     new_root: [u8; 32],
+    new_committee_size: usize,
     epoch: u64,
     threshold: usize,
 ) -> Result<crate::types::RotationProof> {
-    // 1. Sign the new root as the message
+    // 1. Sign the new root, committee size and epoch as the message
+    let msg = crate::types::RotationProof::message(&new_root, new_committee_size, epoch);
     let (sigs, proofs) = crate::core::signing::aggregate_sign(
-        old_sks, old_pks, &new_root, threshold
-    );
+        old_sks, old_pks, &msg, threshold
+    )?;
 
     // 2. Aggregate into a SNARK proof
-    let zksnark = aggregate_proofs(sigs, proofs, old_root, &new_root, old_pks)?;
+    let zksnark = aggregate_proofs(sigs, proofs, old_root, &msg, old_pks)?;
 
     // 3. Construct rotation proof
     Ok(crate::types::RotationProof::new(
         old_root,
         new_root,
+        new_committee_size,
         zksnark,
         epoch,
     ))
 }
 
+/// Plan a membership update replacing or revoking individual validators.
+///
+/// `tree` is the current key tree. Each change sets a leaf to a new value:
+/// the replacement key's
+/// [`leaf_hash`](crate::core::scheme::SignatureScheme::leaf_hash) or
+/// [`revoked_leaf`](crate::utils::revoked_leaf). The returned update still
+/// has to be signed by the old committee; see [`create_membership_update`].
+///
+/// # Errors
+/// `InvalidInput` if `changes` is empty, repeats or exceeds an index, or
+/// leaves a leaf unchanged
+pub fn prepare_membership_update(
+    tree: &MerkleTree,
+    changes: &[(usize, [u8; 32])],
+    epoch: u64,
+) -> Result<crate::types::PendingMembershipUpdate> {
+    let indices: Vec<usize> = changes.iter().map(|(index, _)| *index).collect();
+    let paths = tree.prove_many(&indices).ok_or_else(|| PQAggregateError::InvalidInput {
+        reason: "Membership changes must name distinct leaves in the key tree".to_string(),
+    })?;
+
+    let mut new_tree = tree.clone();
+    let mut new_leaves = Vec::with_capacity(changes.len());
+    for (&index, old_leaf) in paths.leaf_indices().iter().zip(paths.leaf_hashes()) {
+        let new_leaf = changes.iter().find(|(i, _)| *i == index).map(|(_, leaf)| *leaf).unwrap_or(*old_leaf);
+        if new_leaf == *old_leaf {
+            return Err(PQAggregateError::InvalidInput {
+                reason: alloc::format!("Leaf {} is unchanged", index),
+            });
+        }
+        new_tree.update_leaf(index, new_leaf);
+        new_leaves.push(new_leaf);
+    }
+
+    Ok(crate::types::PendingMembershipUpdate {
+        old_root: tree.root(),
+        new_root: new_tree.root(),
+        paths,
+        new_leaves,
+        epoch,
+    })
+}
+
+/// Aggregate the old committee's shares over `update` into a MembershipUpdateProof.
+///
+/// Each member signs [`PendingMembershipUpdate::signed_message`] with
+/// [`sign_share`](crate::core::signing::sign_share) and its proof under
+/// `update.old_root`. `old_pks` are the keys at the old tree's leaf indices
+/// (revoked slots may hold any key). Shares are checked as by an
+/// [`Aggregator`](crate::core::aggregator::Aggregator), so shares from
+/// revoked slots are rejected.
+///
+/// # Errors
+/// - `InvalidInput` if `threshold` is zero
+/// - Any error of [`Aggregator::add_share`](crate::core::aggregator::Aggregator::add_share)
+///   for an invalid or duplicate share
+/// - `InsufficientSignatures` if fewer than `threshold` shares were given
+pub fn create_membership_update(
+    update: crate::types::PendingMembershipUpdate,
+    old_pks: &[PublicKey],
+    shares: impl IntoIterator<Item = SignatureShare>,
+    threshold: usize,
+) -> Result<crate::types::MembershipUpdateProof> {
+    if threshold == 0 {
+        return Err(PQAggregateError::InvalidInput {
+            reason: "Membership update threshold must be at least 1".to_string(),
+        });
+    }
+
+    let msg = update.signed_message();
+    let mut aggregator = crate::core::aggregator::Aggregator::new(
+        update.old_root,
+        &msg,
+        old_pks,
+        crate::types::ThresholdPolicy::AtLeast(threshold),
+    );
+    for share in shares {
+        aggregator.add_share(share)?;
+    }
+    let zksnark = aggregator.finalize()?;
+
+    Ok(crate::types::MembershipUpdateProof::new(
+        update.old_root,
+        update.new_root,
+        update.paths,
+        update.new_leaves,
+        zksnark,
+        update.epoch,
+    ))
+}
+
 #[cfg(test)]
 mod rotation_tests {
     use super::*;
@@ -395,13 +1509,68 @@ mod rotation_tests {
         let (_sks_new, _pks_new, root_new) = setup(5);
 
         let result = create_rotation_proof(
-            &sks_old, &pks_old, root_old, root_new, 1, 3
+            &sks_old, &pks_old, root_old, root_new, 5, 1, 3
         );
 
         assert!(result.is_ok());
         let rotation = result.unwrap();
         assert_eq!(rotation.old_root, root_old);
         assert_eq!(rotation.new_root, root_new);
+        assert_eq!(rotation.new_committee_size, 5);
         assert_eq!(rotation.epoch, 1);
     }
+
+    #[test]
+    fn test_membership_update_creation() {
+        use crate::core::signing::{sign_share, NonceSource};
+
+        let (sks, pks, root) = setup(5);
+        let tree = MerkleTree::from_public_keys(&pks);
+        let (_, replacement_pks, _) = setup(1);
+        let replacement = ActiveScheme::leaf_hash(replacement_pks[0].as_bytes());
+
+        // Each member signs on its own; only the shares reach the collector
+        let shares = |update: &crate::types::PendingMembershipUpdate, tree: &MerkleTree, signers: &[usize]| {
+            signers
+                .iter()
+                .map(|&i| {
+                    let proof = tree.prove(i).unwrap();
+                    sign_share(&sks[i], i, &update.signed_message(), NonceSource::Random, &proof).unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let changes = [(4, crate::utils::revoked_leaf()), (1, replacement)];
+        let pending = prepare_membership_update(&tree, &changes, 1).unwrap();
+        let signed = shares(&pending, &tree, &[4, 2, 0]);
+        let update = create_membership_update(pending, &pks, signed, 3).unwrap();
+        assert_eq!(update.old_root, root);
+        assert_eq!(update.changed_indices(), &[1, 4]);
+        assert_eq!(update.new_leaf(1), Some(&replacement));
+
+        let mut expected = tree.clone();
+        expected.update_leaf(1, replacement);
+        expected.revoke(4);
+        assert_eq!(update.new_root, expected.root());
+
+        // Any members still in the tree may sign; revoked ones may not
+        let mut revoked = tree.clone();
+        revoked.revoke(0);
+        revoked.revoke(2);
+        let pending = prepare_membership_update(&revoked, &[(3, replacement)], 2).unwrap();
+        let update = create_membership_update(pending.clone(), &pks, shares(&pending, &revoked, &[1, 3, 4]), 3).unwrap();
+        assert_eq!(update.old_root, revoked.root());
+        assert!(matches!(
+            create_membership_update(pending.clone(), &pks, shares(&pending, &revoked, &[1, 3, 4]), 4),
+            Err(PQAggregateError::InsufficientSignatures { required: 4, provided: 3 })
+        ));
+        assert!(create_membership_update(pending.clone(), &pks, shares(&pending, &tree, &[0, 1, 3]), 3).is_err());
+        assert!(create_membership_update(pending.clone(), &pks, shares(&pending, &revoked, &[1, 1, 3]), 2).is_err());
+        assert!(create_membership_update(pending, &pks, Vec::new(), 0).is_err());
+
+        assert!(prepare_membership_update(&tree, &[], 1).is_err());
+        assert!(prepare_membership_update(&tree, &[(5, replacement)], 1).is_err());
+        assert!(prepare_membership_update(&tree, &[(1, replacement), (1, replacement)], 1).is_err());
+        assert!(prepare_membership_update(&tree, &[(1, tree.leaf(1).unwrap())], 1).is_err());
+    }
 }
@@ -0,0 +1,492 @@
+//! Incremental signature collection.
+//!
+//! [`aggregate_proofs`](crate::core::aggregation::aggregate_proofs) needs every
+//! signature up front and only reports problems at the end. A collector that
+//! receives shares over minutes instead feeds them to an [`Aggregator`] one at
+//! a time: each share is checked on arrival, and the proof is produced once
+//! the threshold policy is met.
+
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::vec::Vec;
+
+use crate::core::aggregation::{aggregate_proofs_hierarchical, create_aggregated_commitment, validate_share};
+use crate::core::prehash::MessageMode;
+use crate::core::proof_format;
+use crate::error::{PQAggregateError, Result};
+use crate::types::{MerkleProof, PublicKey, Signature, SignatureShare, ThresholdPolicy, ZKSNARKProof};
+
+/// Snapshot of how far an [`Aggregator`] is from its threshold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AggregationProgress {
+    /// Valid, distinct signatures collected so far
+    pub collected: usize,
+    /// Signatures required by the threshold policy
+    pub required: usize,
+    /// Total number of validators in the committee
+    pub committee_size: usize,
+    /// Combined weight of the collected signers (zero without weights)
+    pub weight: u64,
+    /// Combined weight required by the threshold policy (zero unless weighted)
+    pub required_weight: u64,
+}
+
+impl AggregationProgress {
+    /// Whether enough signatures have been collected to finalize.
+    pub fn is_complete(&self) -> bool {
+        self.collected >= self.required && self.weight >= self.required_weight
+    }
+
+    /// Number of signatures still missing (zero once complete).
+    pub fn remaining(&self) -> usize {
+        self.required.saturating_sub(self.collected)
+    }
+}
+
+/// Stateful collector that validates signatures as they arrive.
+///
+/// # Example
+/// ```
+/// use pq_aggregate::{setup, aggregate_sign, verify};
+/// use pq_aggregate::core::aggregator::Aggregator;
+/// use pq_aggregate::types::ThresholdPolicy;
+///
+/// let (sks, pks, pk_root) = setup(5);
+/// let msg = b"transfer";
+/// let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 3).unwrap();
+///
+/// let mut aggregator = Aggregator::new(pk_root, msg, &pks, ThresholdPolicy::AtLeast(3));
+/// for (sig, proof) in sigs.into_iter().zip(proofs) {
+///     let progress = aggregator.add(sig, proof).unwrap();
+///     println!("{}/{} signatures", progress.collected, progress.required);
+/// }
+///
+/// let proof = aggregator.finalize().unwrap();
+/// assert!(verify(pk_root, msg, &proof));
+/// ```
+pub struct Aggregator<'a> {
+    pk_root: [u8; 32],
+    msg: &'a [u8],
+    mode: MessageMode,
+    pks: &'a [PublicKey],
+    weights: Option<&'a [u64]>,
+    policy: ThresholdPolicy,
+    signatures: Vec<Signature>,
+    proofs: Vec<MerkleProof>,
+    signers: BTreeSet<usize>,
+    weight: u64,
+}
+
+impl<'a> Aggregator<'a> {
+    /// Start collecting signatures over `msg` from the committee `pks` with root `pk_root`.
+    pub fn new(pk_root: [u8; 32], msg: &'a [u8], pks: &'a [PublicKey], policy: ThresholdPolicy) -> Self {
+        Self {
+            pk_root,
+            msg,
+            mode: MessageMode::Pure,
+            pks,
+            weights: None,
+            policy,
+            signatures: Vec::new(),
+            proofs: Vec::new(),
+            signers: BTreeSet::new(),
+            weight: 0,
+        }
+    }
+
+    /// Collect against a weighted committee, where `weights[i]` is the weight of `pks[i]`.
+    ///
+    /// `pk_root` must then be the root of
+    /// [`MerkleTree::from_weighted_public_keys`](crate::utils::MerkleTree::from_weighted_public_keys),
+    /// and the finalized proof commits to the signers' combined weight.
+    pub fn with_weights(mut self, weights: &'a [u64]) -> Self {
+        self.weights = Some(weights);
+        self
+    }
+
+    /// Collect signatures over `msg` in the given [`MessageMode`] instead of pure mode.
+    ///
+    /// The finalized proof records the mode and verifies only through
+    /// [`verify_with_mode`](crate::verifier::verify_with_mode).
+    pub fn with_mode(mut self, mode: MessageMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Validate and record one signature.
+    ///
+    /// The Merkle proof and the ML-DSA signature are checked immediately; a
+    /// rejected share leaves the aggregator unchanged.
+    ///
+    /// # Errors
+    /// - `MerkleProofInvalid` / `InvalidInput` if the share does not verify
+    /// - `InvalidInput` if this signer has already been counted, if a
+    ///   [`ThresholdPolicy::Fixed`] count has already been reached, if the
+    ///   signer has no weight in a weighted committee, or if the signer is not
+    ///   named in a [`ThresholdPolicy::Hierarchical`] access structure
+    pub fn add(&mut self, signature: Signature, proof: MerkleProof) -> Result<AggregationProgress> {
+        let signer = signature.signer_index();
+
+        if self.signers.contains(&signer) {
+            return Err(PQAggregateError::InvalidInput {
+                reason: format!("Duplicate signature from signer {}", signer),
+            });
+        }
+
+        if let ThresholdPolicy::Hierarchical(structure) = &self.policy {
+            if !structure.signers().contains(&signer) {
+                return Err(PQAggregateError::InvalidInput {
+                    reason: format!("Signer {} is not part of the access structure", signer),
+                });
+            }
+        }
+
+        if let ThresholdPolicy::Fixed(required) = self.policy {
+            if self.signatures.len() >= required {
+                return Err(PQAggregateError::InvalidInput {
+                    reason: format!("Fixed threshold of {} signatures already reached", required),
+                });
+            }
+        }
+
+        let weight = validate_share(
+            self.signatures.len(),
+            &signature,
+            &proof,
+            &self.pk_root,
+            self.msg,
+            self.mode,
+            self.pks,
+            self.weights,
+        )?;
+        let Some(total_weight) = self.weight.checked_add(weight) else {
+            return Err(PQAggregateError::InvalidInput {
+                reason: "Combined signer weight overflows u64".into(),
+            });
+        };
+
+        self.weight = total_weight;
+        self.signers.insert(signer);
+        self.signatures.push(signature);
+        self.proofs.push(proof);
+
+        Ok(self.progress())
+    }
+
+    /// Validate and record a share received from a validator.
+    pub fn add_share(&mut self, share: SignatureShare) -> Result<AggregationProgress> {
+        let (signature, proof) = share.into_parts();
+        self.add(signature, proof)
+    }
+
+    /// Current progress toward the threshold.
+    pub fn progress(&self) -> AggregationProgress {
+        AggregationProgress {
+            collected: self.signatures.len(),
+            required: self.policy.required_signers(self.pks.len()),
+            committee_size: self.pks.len(),
+            weight: self.weight,
+            required_weight: self.policy.required_weight(),
+        }
+    }
+
+    /// Whether the collected signatures satisfy the threshold policy.
+    ///
+    /// A [`ThresholdPolicy::Weighted`] policy is never satisfied without
+    /// [`Self::with_weights`]. A [`ThresholdPolicy::Hierarchical`] policy is
+    /// satisfied once the collected signers satisfy its access structure.
+    pub fn is_ready(&self) -> bool {
+        if self.signatures.is_empty() {
+            return false;
+        }
+        match &self.policy {
+            ThresholdPolicy::Hierarchical(_) => {
+                let signers: Vec<usize> = self.signers().collect();
+                self.policy.is_satisfied_by_signers(&signers, self.pks.len())
+            }
+            policy => policy.is_satisfied_with_weight(self.signatures.len(), self.pks.len(), self.signer_weight()),
+        }
+    }
+
+    /// Combined weight of the accepted signers, if collecting against a weighted committee.
+    pub fn signer_weight(&self) -> Option<u64> {
+        self.weights.map(|_| self.weight)
+    }
+
+    /// Indices of the validators whose signatures have been accepted.
+    pub fn signers(&self) -> impl Iterator<Item = usize> + '_ {
+        self.signers.iter().copied()
+    }
+
+    /// Produce the aggregated proof from the collected signatures.
+    ///
+    /// Under a [`ThresholdPolicy::Hierarchical`] policy the signatures are
+    /// handed to
+    /// [`aggregate_proofs_hierarchical`](crate::core::aggregation::aggregate_proofs_hierarchical),
+    /// so the proof commits to the access structure and is rooted at
+    /// [`AccessStructure::key_root`](crate::core::access::AccessStructure::key_root)`(pks)`
+    /// rather than at `pk_root`. Verify it against that key root with
+    /// [`verify_with_policy`](crate::verifier::verify_with_policy).
+    ///
+    /// # Errors
+    /// `InsufficientSignatures` (or `InsufficientWeight` for a weighted
+    /// policy) if the threshold policy is not yet satisfied, and
+    /// `PolicyViolation` if enough signers of a hierarchical policy have
+    /// signed but they do not satisfy its access structure.
+    pub fn finalize(self) -> Result<ZKSNARKProof> {
+        if !self.is_ready() {
+            let progress = self.progress();
+            if progress.collected > 0 && matches!(self.policy, ThresholdPolicy::Weighted { .. }) {
+                return Err(PQAggregateError::InsufficientWeight {
+                    required: progress.required_weight,
+                    provided: progress.weight,
+                });
+            }
+            if progress.collected >= progress.required && matches!(self.policy, ThresholdPolicy::Hierarchical(_)) {
+                return Err(PQAggregateError::PolicyViolation {
+                    reason: format!("Signers {:?} do not satisfy the access structure", self.signers().collect::<Vec<_>>()),
+                });
+            }
+            return Err(PQAggregateError::InsufficientSignatures {
+                required: progress.required.max(1),
+                provided: progress.collected,
+            });
+        }
+
+        // Hierarchical proofs are rooted at the structure's key root
+        if let ThresholdPolicy::Hierarchical(structure) = &self.policy {
+            let key_root = structure.key_root(self.pks)?;
+            return aggregate_proofs_hierarchical(self.signatures, structure, key_root, self.msg, self.mode, self.pks);
+        }
+
+        // Every share was validated on arrival
+        let leaf_hashes: Vec<[u8; 32]> = self.proofs.iter().map(|p| *p.leaf_hash()).collect();
+        let extensions = proof_format::proof_extensions(self.mode, self.signer_weight(), None, None);
+        create_aggregated_commitment(&self.signatures, &leaf_hashes, &self.pk_root, self.msg, self.pks.len(), extensions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::keygen::setup;
+    use crate::core::signing::aggregate_sign;
+    use crate::verifier::verify;
+
+    #[test]
+    fn test_incremental_aggregation() {
+        let (sks, pks, pk_root) = setup(5);
+        let msg = b"incremental";
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 4).unwrap();
+
+        let mut aggregator = Aggregator::new(pk_root, msg, &pks, ThresholdPolicy::AtLeast(3));
+        assert_eq!(aggregator.progress().remaining(), 3);
+
+        let mut last = None;
+        for (sig, proof) in sigs.into_iter().zip(proofs).take(2) {
+            last = Some(aggregator.add(sig, proof).unwrap());
+        }
+        assert_eq!(last.unwrap().collected, 2);
+        assert!(!aggregator.is_ready());
+
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 3).unwrap();
+        let progress = aggregator.add(sigs[2].clone(), proofs[2].clone()).unwrap();
+        assert!(progress.is_complete());
+        assert!(aggregator.is_ready());
+        assert_eq!(aggregator.signers().collect::<Vec<_>>(), vec![0, 1, 2]);
+
+        let proof = aggregator.finalize().unwrap();
+        assert_eq!(proof.num_signatures(), 3);
+        assert!(verify(pk_root, msg, &proof));
+    }
+
+    #[test]
+    fn test_add_rejects_bad_share_immediately() {
+        let (sks, pks, pk_root) = setup(4);
+        let msg = b"test";
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2).unwrap();
+        let (other_sigs, _) = aggregate_sign(&sks, &pks, b"other message", 2).unwrap();
+
+        let mut aggregator = Aggregator::new(pk_root, msg, &pks, ThresholdPolicy::AtLeast(2));
+
+        // Signature over a different message
+        assert!(aggregator.add(other_sigs[0].clone(), proofs[0].clone()).is_err());
+        // Proof for the wrong leaf
+        assert!(matches!(
+            aggregator.add(sigs[0].clone(), proofs[1].clone()),
+            Err(PQAggregateError::MerkleProofInvalid { .. })
+        ));
+        assert_eq!(aggregator.progress().collected, 0);
+
+        aggregator.add(sigs[0].clone(), proofs[0].clone()).unwrap();
+        assert!(matches!(
+            aggregator.add(sigs[0].clone(), proofs[0].clone()),
+            Err(PQAggregateError::InvalidInput { .. })
+        ));
+        assert_eq!(aggregator.progress().collected, 1);
+    }
+
+    #[test]
+    fn test_finalize_before_threshold_fails() {
+        let (sks, pks, pk_root) = setup(10);
+        let msg = b"test";
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 6).unwrap();
+
+        // 67% of 10 requires 7 signatures
+        let mut aggregator = Aggregator::new(pk_root, msg, &pks, ThresholdPolicy::Tiered { level: 2 });
+        for (sig, proof) in sigs.into_iter().zip(proofs) {
+            aggregator.add(sig, proof).unwrap();
+        }
+
+        assert!(matches!(
+            aggregator.finalize(),
+            Err(PQAggregateError::InsufficientSignatures { required: 7, provided: 6 })
+        ));
+    }
+
+    #[test]
+    fn test_weighted_aggregation() {
+        use crate::utils::MerkleTree;
+
+        let (sks, pks, _) = setup(4);
+        let weights = [50, 10, 25, 15];
+        let tree = MerkleTree::from_weighted_public_keys(&pks, &weights);
+        let pk_root = tree.root();
+        let msg = b"weighted";
+
+        let (sigs, _) = aggregate_sign(&sks, &pks, msg, 3).unwrap();
+        let policy = ThresholdPolicy::Weighted { min_weight: 80 };
+        let mut aggregator = Aggregator::new(pk_root, msg, &pks, policy.clone()).with_weights(&weights);
+
+        // Signers 0 and 1 hold 60 of the required 80
+        for sig in &sigs[..2] {
+            let proof = tree.prove(sig.signer_index()).unwrap();
+            aggregator.add(sig.clone(), proof).unwrap();
+        }
+        assert!(!aggregator.is_ready());
+        assert!(!aggregator.progress().is_complete());
+        assert_eq!(aggregator.signer_weight(), Some(60));
+
+        let progress = aggregator.add(sigs[2].clone(), tree.prove(2).unwrap()).unwrap();
+        assert_eq!((progress.weight, progress.required_weight), (85, 80));
+        assert!(progress.is_complete());
+
+        let proof = aggregator.finalize().unwrap();
+        assert!(crate::verifier::verify_with_policy(pk_root, msg, &proof, 4, &policy));
+    }
+
+    #[test]
+    fn test_weighted_policy_reports_missing_weight() {
+        use crate::utils::MerkleTree;
+
+        let (sks, pks, unweighted_root) = setup(3);
+        let weights = [1, 1, 10];
+        let tree = MerkleTree::from_weighted_public_keys(&pks, &weights);
+        let msg = b"weighted";
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2).unwrap();
+        let policy = ThresholdPolicy::Weighted { min_weight: 5 };
+
+        let mut aggregator = Aggregator::new(tree.root(), msg, &pks, policy.clone()).with_weights(&weights);
+        // An unweighted Merkle proof does not prove the signer's weight
+        assert!(matches!(
+            aggregator.add(sigs[0].clone(), proofs[0].clone()),
+            Err(PQAggregateError::MerkleProofInvalid { .. })
+        ));
+        aggregator.add(sigs[0].clone(), tree.prove(0).unwrap()).unwrap();
+        aggregator.add(sigs[1].clone(), tree.prove(1).unwrap()).unwrap();
+        assert!(matches!(
+            aggregator.finalize(),
+            Err(PQAggregateError::InsufficientWeight { required: 5, provided: 2 })
+        ));
+
+        // Without weights a weighted policy can never be met
+        let mut aggregator = Aggregator::new(unweighted_root, msg, &pks, policy);
+        for (sig, proof) in sigs.into_iter().zip(proofs) {
+            aggregator.add(sig, proof).unwrap();
+        }
+        assert!(!aggregator.is_ready());
+    }
+
+    #[test]
+    fn test_prehashed_mode() {
+        use crate::core::prehash::PreHashAlgorithm;
+        use crate::core::signing::aggregate_sign_with_mode;
+
+        let (sks, pks, pk_root) = setup(4);
+        let prehashed = PreHashAlgorithm::Sha3_512.digest(b"firmware");
+        let (sigs, proofs) = aggregate_sign_with_mode(&sks, &pks, prehashed.digest(), prehashed.mode(), 2).unwrap();
+
+        // Signatures in pre-hash mode are rejected by a pure-mode aggregator
+        let mut pure = Aggregator::new(pk_root, prehashed.digest(), &pks, ThresholdPolicy::AtLeast(2));
+        assert!(pure.add(sigs[0].clone(), proofs[0].clone()).is_err());
+
+        let mut aggregator = Aggregator::new(pk_root, prehashed.digest(), &pks, ThresholdPolicy::AtLeast(2))
+            .with_mode(prehashed.mode());
+        for (sig, proof) in sigs.into_iter().zip(proofs) {
+            aggregator.add(sig, proof).unwrap();
+        }
+        let proof = aggregator.finalize().unwrap();
+
+        assert!(crate::verifier::verify_with_mode(pk_root, prehashed.digest(), prehashed.mode(), &proof));
+        assert!(!verify(pk_root, prehashed.digest(), &proof));
+    }
+
+    #[test]
+    fn test_fixed_policy_caps_signatures() {
+        let (sks, pks, pk_root) = setup(4);
+        let msg = b"test";
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 3).unwrap();
+
+        let mut aggregator = Aggregator::new(pk_root, msg, &pks, ThresholdPolicy::Fixed(2));
+        aggregator.add(sigs[0].clone(), proofs[0].clone()).unwrap();
+        aggregator.add(sigs[1].clone(), proofs[1].clone()).unwrap();
+        assert!(aggregator.add(sigs[2].clone(), proofs[2].clone()).is_err());
+
+        let proof = aggregator.finalize().unwrap();
+        assert!(crate::verifier::verify_with_policy(pk_root, msg, &proof, 4, &ThresholdPolicy::Fixed(2)));
+    }
+
+    #[test]
+    fn test_hierarchical_policy() {
+        use crate::core::access::AccessStructure;
+        use crate::core::signing::aggregate_sign_subset;
+
+        let (sks, pks, pk_root) = setup(7);
+        let msg = b"hierarchical";
+        let (sigs, proofs) = aggregate_sign_subset(&sks, &pks, msg, MessageMode::Pure, &[0, 1, 2, 3, 4, 6]).unwrap();
+        // Two of each organization; validator 6 belongs to neither
+        let structure = AccessStructure::groups(2, [(2, vec![0, 1, 2]), (2, vec![3, 4, 5])]);
+        let key_root = structure.key_root(&pks).unwrap();
+        let policy = ThresholdPolicy::Hierarchical(structure.clone());
+
+        let collect = |count: usize| {
+            let mut aggregator = Aggregator::new(pk_root, msg, &pks, policy.clone());
+            for (sig, proof) in sigs.iter().zip(&proofs).take(count) {
+                aggregator.add(sig.clone(), proof.clone()).unwrap();
+            }
+            aggregator
+        };
+
+        // Four signers, but only the first organization reaches its threshold
+        let aggregator = collect(4);
+        assert!(aggregator.progress().collected >= aggregator.progress().required);
+        assert!(!aggregator.is_ready());
+        assert!(matches!(aggregator.finalize(), Err(PQAggregateError::PolicyViolation { .. })));
+
+        let mut aggregator = collect(5);
+        assert!(matches!(
+            aggregator.add(sigs[5].clone(), proofs[5].clone()),
+            Err(PQAggregateError::InvalidInput { .. })
+        ));
+        assert!(aggregator.is_ready());
+
+        // Same proof as aggregating the signatures in one go, rooted at the key root
+        let expected = aggregate_proofs_hierarchical(sigs[..5].to_vec(), &structure, key_root, msg, MessageMode::Pure, &pks);
+        let proof = aggregator.finalize().unwrap();
+        assert_eq!(proof.as_bytes(), expected.unwrap().as_bytes());
+        assert!(crate::verifier::verify_with_policy(key_root, msg, &proof, 7, &policy));
+        assert!(!crate::verifier::verify_with_policy(pk_root, msg, &proof, 7, &policy));
+        let other = ThresholdPolicy::Hierarchical(AccessStructure::groups(1, [(3, vec![0, 1, 2])]));
+        assert!(!crate::verifier::verify_with_policy(key_root, msg, &proof, 7, &other));
+    }
+}
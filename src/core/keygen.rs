@@ -1,24 +1,39 @@
 //! Key generation for PQ-Aggregate.
 //!
-//! Generates independent ML-DSA-65 keypairs and computes the Merkle root
-//! for public key aggregation.
+//! Generates independent keypairs for the [`ActiveScheme`] (ML-DSA-65 by
+//! default) and computes the Merkle root for public key aggregation.
 
+use alloc::format;
 use alloc::vec::Vec;
-use pqc_dilithium::{Keypair, PUBLICKEYBYTES, SECRETKEYBYTES};
+use rand_core::RngCore;
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::Shake256;
+use zeroize::Zeroizing;
 
+use crate::core::scheme::{ActiveScheme, SignatureScheme};
+use crate::error::{PQAggregateError, Result};
 use crate::types::{PublicKey, SecretKey};
 use crate::utils::MerkleTree;
 
-/// ML-DSA-65 public key size in bytes (from pqc_dilithium mode3).
-pub const PUBLIC_KEY_SIZE: usize = PUBLICKEYBYTES;
+/// Public key size in bytes for the active signature scheme.
+pub const PUBLIC_KEY_SIZE: usize = ActiveScheme::PUBLIC_KEY_BYTES;
 
-/// ML-DSA-65 secret key size in bytes (from pqc_dilithium mode3).
-pub const SECRET_KEY_SIZE: usize = SECRETKEYBYTES;
+/// Secret key size in bytes for the active signature scheme.
+pub const SECRET_KEY_SIZE: usize = ActiveScheme::SECRET_KEY_BYTES;
+
+/// Length of the per-validator key seed.
+const KEY_SEED_LEN: usize = 32;
+
+/// Minimum accepted master seed length in bytes.
+pub const MIN_MASTER_SEED_LEN: usize = 32;
+
+/// Domain tag for deriving per-validator key seeds from a master seed.
+const VALIDATOR_SEED_DOMAIN: &[u8] = b"pq-aggregate/validator-seed/v1";
 
 /// Setup the threshold signature scheme for `n` participants.
 ///
-/// Generates `n` independent ML-DSA-65 keypairs and computes the Merkle root
-/// of all public keys.
+/// Generates `n` independent keypairs and computes the Merkle root of all
+/// public keys.
 ///
 /// # Arguments
 /// * `n` - Number of participants (must be >= 1)
@@ -38,6 +53,14 @@ pub const SECRET_KEY_SIZE: usize = SECRETKEYBYTES;
 /// assert_eq!(public_keys.len(), 5);
 /// ```
 pub fn setup(n: usize) -> (Vec<SecretKey>, Vec<PublicKey>, [u8; 32]) {
+    setup_with_rng(n, &mut rand::thread_rng())
+}
+
+/// [`setup`] with key material drawn from `rng`.
+///
+/// A seeded `rng` makes the whole key set reproducible; for keys that can
+/// be rebuilt from a backup, prefer [`setup_from_seed`].
+pub fn setup_with_rng<R: RngCore + ?Sized>(n: usize, rng: &mut R) -> (Vec<SecretKey>, Vec<PublicKey>, [u8; 32]) {
     if n == 0 {
         return (Vec::new(), Vec::new(), [0u8; 32]);
     }
@@ -46,13 +69,11 @@ pub fn setup(n: usize) -> (Vec<SecretKey>, Vec<PublicKey>, [u8; 32]) {
     let mut public_keys = Vec::with_capacity(n);
 
     for i in 0..n {
-        // Generate ML-DSA-65 keypair
-        let keypair = Keypair::generate();
+        let (pk_bytes, sk_bytes) = ActiveScheme::generate_keypair_with_rng(rng);
 
-        // Extract and wrap keys with index metadata
-        // pqc_dilithium v0.2: use expose_secret() method and public field
-        let sk = SecretKey::from_bytes(keypair.expose_secret().to_vec(), i);
-        let pk = PublicKey::from_bytes(keypair.public.to_vec(), i);
+        // Wrap keys with index metadata
+        let sk = SecretKey::from_bytes(sk_bytes.to_vec(), i);
+        let pk = PublicKey::from_bytes(pk_bytes, i);
 
         secret_keys.push(sk);
         public_keys.push(pk);
@@ -65,6 +86,118 @@ pub fn setup(n: usize) -> (Vec<SecretKey>, Vec<PublicKey>, [u8; 32]) {
     (secret_keys, public_keys, pk_root)
 }
 
+/// [`setup`] that also builds a [`PoseidonKeyTree`](crate::nova::merkle::PoseidonKeyTree).
+///
+/// Returns the roots of both key trees over the same public keys. Pass the
+/// Poseidon root, with [`PoseidonKeyTree`](crate::nova::merkle::PoseidonKeyTree)
+/// paths, to [`aggregate_proofs_nova_with_key_tree`](crate::core::aggregation::aggregate_proofs_nova_with_key_tree)
+/// to have signer membership checked inside the SNARK.
+///
+/// # Panics
+/// If `n` exceeds the Poseidon key tree's 65,536 leaves.
+#[cfg(feature = "nova")]
+pub fn setup_with_key_roots(n: usize) -> (Vec<SecretKey>, Vec<PublicKey>, crate::nova::merkle::KeyRoots) {
+    use crate::nova::merkle::{KeyRoots, PoseidonKeyTree};
+
+    let (secret_keys, public_keys, sha3) = setup(n);
+    let poseidon = PoseidonKeyTree::from_public_keys(&public_keys)
+        .expect("committee exceeds the Poseidon key tree")
+        .root();
+
+    (secret_keys, public_keys, KeyRoots { sha3, poseidon })
+}
+
+/// Derive the keypair for validator `index` from a master seed.
+///
+/// The 32-byte key seed is `SHAKE256(domain || len(master_seed) || master_seed || index)`,
+/// so every index yields an independent keypair and the same inputs always
+/// yield the same keys. A lost validator key can be rebuilt from the backup
+/// of `master_seed` alone.
+///
+/// # Errors
+/// `InvalidInput` if `master_seed` is shorter than [`MIN_MASTER_SEED_LEN`].
+pub fn derive_keypair(master_seed: &[u8], index: usize) -> Result<(SecretKey, PublicKey)> {
+    if master_seed.len() < MIN_MASTER_SEED_LEN {
+        return Err(PQAggregateError::InvalidInput {
+            reason: format!(
+                "Master seed must be at least {} bytes, got {}",
+                MIN_MASTER_SEED_LEN,
+                master_seed.len()
+            ),
+        });
+    }
+
+    let mut key_seed = Zeroizing::new([0u8; KEY_SEED_LEN]);
+    let mut hasher = Shake256::default();
+    hasher.update(VALIDATOR_SEED_DOMAIN);
+    hasher.update(&(master_seed.len() as u64).to_le_bytes());
+    hasher.update(master_seed);
+    hasher.update(&(index as u64).to_le_bytes());
+    hasher.finalize_xof().read(&mut key_seed[..]);
+
+    let (pk_bytes, sk_bytes) = ActiveScheme::keypair_from_seed(&key_seed);
+
+    Ok((
+        SecretKey::from_bytes(sk_bytes.to_vec(), index),
+        PublicKey::from_bytes(pk_bytes, index),
+    ))
+}
+
+/// Deterministic variant of [`setup`]: derive all `n` keypairs from `seed`.
+///
+/// Validator `i` receives [`derive_keypair(seed, i)`](derive_keypair), so the
+/// whole key set and its `pk_root` can be rebuilt from a single backup.
+///
+/// # Errors
+/// `InvalidInput` if `seed` is shorter than [`MIN_MASTER_SEED_LEN`].
+pub fn setup_from_seed(seed: &[u8], n: usize) -> Result<(Vec<SecretKey>, Vec<PublicKey>, [u8; 32])> {
+    if n == 0 {
+        return Ok((Vec::new(), Vec::new(), [0u8; 32]));
+    }
+
+    let mut secret_keys = Vec::with_capacity(n);
+    let mut public_keys = Vec::with_capacity(n);
+
+    for i in 0..n {
+        let (sk, pk) = derive_keypair(seed, i)?;
+        secret_keys.push(sk);
+        public_keys.push(pk);
+    }
+
+    let pk_root = MerkleTree::from_public_keys(&public_keys).root();
+
+    Ok((secret_keys, public_keys, pk_root))
+}
+
+/// Convert a BIP-39 mnemonic into the 64-byte master seed used by
+/// [`setup_from_seed`] and [`derive_keypair`].
+///
+/// Accepts the same phrases as [`SoftwareHSM`](crate::hsm::SoftwareHSM) and
+/// applies the standard BIP-39 seed derivation (PBKDF2-HMAC-SHA512, 2048
+/// rounds, salt `"mnemonic" || passphrase`).
+///
+/// # Errors
+/// `InvalidInput` if the phrase is not a valid BIP-39 mnemonic.
+pub fn master_seed_from_mnemonic(phrase: &str, passphrase: &str) -> Result<Zeroizing<[u8; 64]>> {
+    let mnemonic = bip39::Mnemonic::parse(phrase).map_err(|e| PQAggregateError::InvalidInput {
+        reason: format!("Invalid mnemonic: {}", e),
+    })?;
+
+    Ok(Zeroizing::new(mnemonic.to_seed(passphrase)))
+}
+
+/// Derive all `n` validator keys from a BIP-39 mnemonic.
+///
+/// Shorthand for [`master_seed_from_mnemonic`] followed by [`setup_from_seed`].
+pub fn setup_from_mnemonic(
+    phrase: &str,
+    passphrase: &str,
+    n: usize,
+) -> Result<(Vec<SecretKey>, Vec<PublicKey>, [u8; 32])> {
+    let seed = master_seed_from_mnemonic(phrase, passphrase)?;
+    setup_from_seed(&seed[..], n)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,6 +235,120 @@ mod tests {
         assert_eq!(root, [0u8; 32]);
     }
 
+    /// Standard BIP-39 test mnemonic (all-zero entropy).
+    const TEST_MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon \
+        abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_master_seed_matches_bip39_vector() {
+        // Reference vector from the BIP-39 specification (passphrase "TREZOR")
+        let seed = master_seed_from_mnemonic(TEST_MNEMONIC, "TREZOR").unwrap();
+        assert_eq!(
+            hex::encode(&seed[..]),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "legacy-hashing",
+        not(any(
+            feature = "ml-dsa-44",
+            feature = "ml-dsa-87",
+            feature = "slh-dsa",
+            feature = "hybrid-ed25519"
+        ))
+    ))]
+    fn test_setup_from_mnemonic_vectors() {
+        use crate::utils::MerkleTree;
+
+        // Same mnemonic, same committee, on every run and every machine
+        let (_, pks, _) = setup_from_mnemonic(TEST_MNEMONIC, "", 4).unwrap();
+        let root = MerkleTree::from_public_keys_legacy(&pks).root();
+        assert_eq!(
+            hex::encode(root),
+            "16f3210ed1a15f09cbc384b8e381eaec6e7e61807428b52a0cb6d52ead1f813b"
+        );
+        assert_eq!(
+            hex::encode(crate::utils::sha3_256(pks[0].as_bytes())),
+            "d5aa4d46c5d991f623dd4d415dd197932fa53884e3e6df6d0b526a2b9a830cf1"
+        );
+
+        // The passphrase is part of the seed
+        let (_, pks, _) = setup_from_mnemonic(TEST_MNEMONIC, "TREZOR", 4).unwrap();
+        let root = MerkleTree::from_public_keys_legacy(&pks).root();
+        assert_eq!(
+            hex::encode(root),
+            "a157a2cfd36566b691eb4805f7476508e8c3285f7db99930f60c370b462266e3"
+        );
+    }
+
+    #[test]
+    #[cfg(not(any(
+        feature = "ml-dsa-44",
+        feature = "ml-dsa-87",
+        feature = "slh-dsa",
+        feature = "hybrid-ed25519"
+    )))]
+    fn test_setup_from_mnemonic_tagged_vectors() {
+        // The same keys under tagged leaf and node hashing
+        let (_, pks, root) = setup_from_mnemonic(TEST_MNEMONIC, "", 4).unwrap();
+        assert_eq!(
+            hex::encode(root),
+            "2841128dab9e50132210c258a45fad0d6e77b99892add0b1cdfa4942bcc01faa"
+        );
+        assert_eq!(
+            hex::encode(crate::utils::sha3_256(pks[0].as_bytes())),
+            "d5aa4d46c5d991f623dd4d415dd197932fa53884e3e6df6d0b526a2b9a830cf1"
+        );
+
+        // The passphrase is part of the seed
+        let (_, _, root) = setup_from_mnemonic(TEST_MNEMONIC, "TREZOR", 4).unwrap();
+        assert_eq!(
+            hex::encode(root),
+            "d6ad527824616608164af784da294bbc0fa2b4b69a3bbbc34d8c5cb14fd27d77"
+        );
+    }
+
+    #[test]
+    fn test_derive_keypair_matches_setup_from_seed() {
+        let seed = [0x42u8; 32];
+        let (sks, pks, _) = setup_from_seed(&seed, 3).unwrap();
+
+        // A single lost key can be rebuilt by index
+        let (sk, pk) = derive_keypair(&seed, 2).unwrap();
+        assert_eq!(sk.as_bytes(), sks[2].as_bytes());
+        assert_eq!(pk.as_bytes(), pks[2].as_bytes());
+        assert_eq!(pk.index(), 2);
+
+        assert_ne!(pks[0].as_bytes(), pks[1].as_bytes());
+    }
+
+    #[test]
+    fn test_seeded_keys_sign_and_aggregate() {
+        use crate::core::aggregation::aggregate_proofs;
+        use crate::core::signing::aggregate_sign;
+
+        let (sks, pks, pk_root) = setup_from_seed(&[9u8; 64], 5).unwrap();
+        let msg = b"restored from backup";
+
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 3).unwrap();
+        let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
+        assert!(crate::verifier::verify(pk_root, msg, &proof));
+    }
+
+    #[test]
+    fn test_seeded_setup_rejects_bad_input() {
+        assert!(matches!(
+            setup_from_seed(&[0u8; 16], 3),
+            Err(PQAggregateError::InvalidInput { .. })
+        ));
+        assert!(matches!(
+            setup_from_mnemonic("not a valid mnemonic", "", 3),
+            Err(PQAggregateError::InvalidInput { .. })
+        ));
+    }
+
     #[test]
     fn test_merkle_root_deterministic() {
         // Note: This test verifies structure, not determinism
@@ -110,4 +357,17 @@ mod tests {
         let tree = MerkleTree::from_public_keys(&pks);
         assert_eq!(tree.root(), root);
     }
+
+    #[test]
+    fn test_setup_with_seeded_rng_is_reproducible() {
+        use rand::SeedableRng;
+
+        let (_, pks_a, root_a) = setup_with_rng(3, &mut rand::rngs::StdRng::seed_from_u64(7));
+        let (_, pks_b, root_b) = setup_with_rng(3, &mut rand::rngs::StdRng::seed_from_u64(7));
+        let (_, _, root_c) = setup_with_rng(3, &mut rand::rngs::StdRng::seed_from_u64(8));
+
+        assert_eq!(pks_a, pks_b);
+        assert_eq!(root_a, root_b);
+        assert_ne!(root_a, root_c);
+    }
 }
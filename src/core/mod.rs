@@ -2,13 +2,40 @@
 //!
 //! This module contains the primary cryptographic functionality:
 //! - Key generation (`keygen`)
+//! - Signature scheme backends (`scheme`)
 //! - Threshold signing (`signing`)
 //! - Proof aggregation (`aggregation`)
+//! - Incremental signature collection (`aggregator`)
+//! - Hierarchical access structures (`access`)
+//! - Pre-hashed message signing (`prehash`)
+//! - Aggregated proof byte layout (`proof_format`)
 
 pub mod keygen;
+pub mod scheme;
 pub mod signing;
 pub mod aggregation;
+pub mod aggregator;
+pub mod access;
+pub mod prehash;
+pub(crate) mod proof_format;
 
-pub use keygen::setup;
-pub use signing::aggregate_sign;
-pub use aggregation::aggregate_proofs;
+pub use keygen::{derive_keypair, setup, setup_from_mnemonic, setup_from_seed, setup_with_rng};
+#[cfg(feature = "nova")]
+pub use keygen::setup_with_key_roots;
+pub use signing::{
+    aggregate_sign, aggregate_sign_bitmap, aggregate_sign_deterministic, aggregate_sign_subset,
+    aggregate_sign_subset_with_rng, aggregate_sign_with_mode, aggregate_sign_with_rng, sign_share,
+    sign_share_with_mode, NonceSource,
+};
+pub use aggregation::{
+    aggregate_certificate, aggregate_full_proof, aggregate_proofs, aggregate_proofs_hierarchical,
+    aggregate_proofs_multi, aggregate_proofs_weighted, aggregate_proofs_with_context, aggregate_proofs_with_mode,
+    aggregate_shares,
+    compress_full_proof, merge_proofs,
+};
+#[cfg(feature = "nova")]
+pub use aggregation::{aggregate_proofs_nova, aggregate_proofs_nova_with_key_tree};
+pub use aggregator::{AggregationProgress, Aggregator};
+pub use access::AccessStructure;
+pub use prehash::{MessageMode, PreHashAlgorithm, PreHashedMessage, PreHasher};
+pub use scheme::{ActiveScheme, SignatureScheme};
@@ -0,0 +1,295 @@
+//! Pre-hashed message signing (HashML-DSA).
+//!
+//! Signing normally takes the whole message in memory. For large inputs such
+//! as firmware images, a [`PreHasher`] digests the data incrementally and the
+//! validators sign the resulting [`PreHashedMessage`] in
+//! [`MessageMode::PreHashed`].
+//!
+//! Every signed message is framed as in FIPS 204 (see [`MessageMode::frame`]),
+//! so a pure message and a pre-hashed one never produce the same signed bytes.
+//! The mode is always passed explicitly to signing and verification; it is
+//! never inferred from the message bytes.
+//!
+//! ```
+//! use pq_aggregate::core::prehash::PreHashAlgorithm;
+//!
+//! let mut hasher = PreHashAlgorithm::Sha3_512.hasher();
+//! for chunk in [&b"firmware "[..], &b"image"[..]] {
+//!     hasher.update(chunk);
+//! }
+//! let prehashed = hasher.finalize();
+//! assert_eq!(prehashed, PreHashAlgorithm::Sha3_512.digest(b"firmware image"));
+//! ```
+//!
+//! The `_with_mode` signing, aggregation and verification entry points take
+//! [`PreHashedMessage::digest`] together with [`PreHashedMessage::mode`].
+//! Proofs record the algorithm, and the verifier rejects a proof whose
+//! recorded mode differs from the one it is asked to check.
+
+use alloc::vec::Vec;
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{Digest, Sha3_512, Shake256};
+
+/// Length of every pre-hash digest in bytes.
+///
+/// SHAKE256 is read to 512 bits, as FIPS 204 requires for HashML-DSA.
+pub const PREHASH_DIGEST_LEN: usize = 64;
+
+/// Domain separator byte FIPS 204 uses for pure messages.
+const PURE_ML_DSA_PREFIX: u8 = 0x00;
+
+/// Domain separator byte FIPS 204 uses for pre-hashed messages.
+const HASH_ML_DSA_PREFIX: u8 = 0x01;
+
+/// DER-encoded OID of SHA3-512 (2.16.840.1.101.3.4.2.10).
+const OID_SHA3_512: [u8; 11] = [0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x0A];
+
+/// DER-encoded OID of SHAKE256 (2.16.840.1.101.3.4.2.12).
+const OID_SHAKE256: [u8; 11] = [0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x0C];
+
+/// Hash function used to pre-hash a message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PreHashAlgorithm {
+    /// SHA3-512
+    Sha3_512,
+    /// SHAKE256 with a 512-bit output
+    Shake256,
+}
+
+impl PreHashAlgorithm {
+    /// Identifier recorded in aggregated proofs.
+    pub fn id(self) -> u8 {
+        match self {
+            PreHashAlgorithm::Sha3_512 => 0x01,
+            PreHashAlgorithm::Shake256 => 0x02,
+        }
+    }
+
+    /// Look up an algorithm by [`Self::id`].
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0x01 => Some(PreHashAlgorithm::Sha3_512),
+            0x02 => Some(PreHashAlgorithm::Shake256),
+            _ => None,
+        }
+    }
+
+    /// DER-encoded object identifier of the algorithm.
+    pub fn oid(self) -> &'static [u8] {
+        match self {
+            PreHashAlgorithm::Sha3_512 => &OID_SHA3_512,
+            PreHashAlgorithm::Shake256 => &OID_SHAKE256,
+        }
+    }
+
+    /// Start a streaming digest.
+    pub fn hasher(self) -> PreHasher {
+        let state = match self {
+            PreHashAlgorithm::Sha3_512 => HasherState::Sha3_512(Sha3_512::new()),
+            PreHashAlgorithm::Shake256 => HasherState::Shake256(Shake256::default()),
+        };
+        PreHasher { algorithm: self, state }
+    }
+
+    /// Digest a message held in memory.
+    pub fn digest(self, data: &[u8]) -> PreHashedMessage {
+        let mut hasher = self.hasher();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+/// How a message is presented to the signature scheme.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MessageMode {
+    /// The message is signed directly.
+    #[default]
+    Pure,
+    /// The message is a digest computed with the given algorithm.
+    PreHashed(PreHashAlgorithm),
+}
+
+impl MessageMode {
+    /// The pre-hash algorithm, or `None` in pure mode.
+    pub fn prehash(self) -> Option<PreHashAlgorithm> {
+        match self {
+            MessageMode::Pure => None,
+            MessageMode::PreHashed(algorithm) => Some(algorithm),
+        }
+    }
+
+    /// Frame `msg` for signing, as FIPS 204 does with an empty context string.
+    ///
+    /// Pure: `[0x00][ctx_len:1 = 0][msg]`.
+    /// Pre-hashed: `[0x01][ctx_len:1 = 0][oid:11][msg]`, where `msg` is the digest.
+    pub fn frame(self, msg: &[u8]) -> Vec<u8> {
+        let mut framed = Vec::with_capacity(2 + OID_SHA3_512.len() + msg.len());
+        match self {
+            MessageMode::Pure => framed.extend_from_slice(&[PURE_ML_DSA_PREFIX, 0]),
+            MessageMode::PreHashed(algorithm) => {
+                framed.extend_from_slice(&[HASH_ML_DSA_PREFIX, 0]);
+                framed.extend_from_slice(algorithm.oid());
+            }
+        }
+        framed.extend_from_slice(msg);
+        framed
+    }
+}
+
+#[derive(Clone)]
+enum HasherState {
+    Sha3_512(Sha3_512),
+    Shake256(Shake256),
+}
+
+/// Incremental hasher producing a [`PreHashedMessage`].
+///
+/// With the `std` feature it also implements [`std::io::Write`], so a file
+/// or socket can be digested with [`std::io::copy`].
+#[derive(Clone)]
+pub struct PreHasher {
+    algorithm: PreHashAlgorithm,
+    state: HasherState,
+}
+
+impl PreHasher {
+    /// The algorithm this hasher computes.
+    pub fn algorithm(&self) -> PreHashAlgorithm {
+        self.algorithm
+    }
+
+    /// Absorb the next chunk of the message.
+    pub fn update(&mut self, data: &[u8]) {
+        match &mut self.state {
+            HasherState::Sha3_512(hasher) => Digest::update(hasher, data),
+            HasherState::Shake256(hasher) => Update::update(hasher, data),
+        }
+    }
+
+    /// Finish the digest.
+    pub fn finalize(self) -> PreHashedMessage {
+        let mut digest = [0u8; PREHASH_DIGEST_LEN];
+        match self.state {
+            HasherState::Sha3_512(hasher) => digest.copy_from_slice(&hasher.finalize()),
+            HasherState::Shake256(hasher) => hasher.finalize_xof().read(&mut digest),
+        }
+        PreHashedMessage::new(self.algorithm, &digest)
+    }
+}
+
+impl core::fmt::Debug for PreHasher {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PreHasher").field("algorithm", &self.algorithm).finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::io::Write for PreHasher {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Digest of a message signed in pre-hash mode.
+///
+/// Sign and verify [`Self::digest`] under [`Self::mode`]; the framing then
+/// matches FIPS 204's HashML-DSA `M'` with an empty context string.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PreHashedMessage {
+    algorithm: PreHashAlgorithm,
+    digest: [u8; PREHASH_DIGEST_LEN],
+}
+
+impl PreHashedMessage {
+    /// Wrap a digest computed elsewhere.
+    pub fn new(algorithm: PreHashAlgorithm, digest: &[u8; PREHASH_DIGEST_LEN]) -> Self {
+        Self { algorithm, digest: *digest }
+    }
+
+    /// The hash algorithm that produced the digest.
+    pub fn algorithm(&self) -> PreHashAlgorithm {
+        self.algorithm
+    }
+
+    /// The digest of the original message, which is what validators sign.
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
+
+    /// The mode to sign and verify [`Self::digest`] under.
+    pub fn mode(&self) -> MessageMode {
+        MessageMode::PreHashed(self.algorithm)
+    }
+}
+
+impl core::fmt::Debug for PreHashedMessage {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PreHashedMessage")
+            .field("algorithm", &self.algorithm)
+            .field("digest", &hex::encode(self.digest))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streaming_matches_one_shot() {
+        let data: alloc::vec::Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+        for algorithm in [PreHashAlgorithm::Sha3_512, PreHashAlgorithm::Shake256] {
+            let mut hasher = algorithm.hasher();
+            for chunk in data.chunks(777) {
+                hasher.update(chunk);
+            }
+            let streamed = hasher.finalize();
+            assert_eq!(streamed, algorithm.digest(&data));
+            assert_eq!(streamed.algorithm(), algorithm);
+        }
+
+        // The two algorithms produce unrelated messages
+        assert_ne!(
+            PreHashAlgorithm::Sha3_512.digest(&data).digest(),
+            PreHashAlgorithm::Shake256.digest(&data).digest()
+        );
+    }
+
+    #[test]
+    fn test_known_answer() {
+        let prehashed = PreHashAlgorithm::Sha3_512.digest(b"abc");
+        assert_eq!(
+            hex::encode(prehashed.digest()),
+            "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e\
+             10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0"
+        );
+        assert_eq!(
+            MessageMode::PreHashed(PreHashAlgorithm::Sha3_512).frame(prehashed.digest())[..13],
+            [&[0x01, 0x00][..], PreHashAlgorithm::Sha3_512.oid()].concat()
+        );
+    }
+
+    #[test]
+    fn test_framing_separates_modes() {
+        let prehashed = PreHashAlgorithm::Sha3_512.digest(b"image");
+        let framed = prehashed.mode().frame(prehashed.digest());
+        assert_eq!(framed.len(), 2 + 11 + PREHASH_DIGEST_LEN);
+        assert_eq!(MessageMode::Pure.frame(b"plain"), [&[0x00, 0x00][..], b"plain"].concat());
+
+        // A pure message spelling out a pre-hashed frame is still framed as pure
+        assert_ne!(MessageMode::Pure.frame(&framed[2..]), framed);
+        assert_ne!(
+            MessageMode::PreHashed(PreHashAlgorithm::Shake256).frame(prehashed.digest()),
+            framed
+        );
+
+        assert_eq!(MessageMode::default().prehash(), None);
+        assert_eq!(prehashed.mode().prehash(), Some(PreHashAlgorithm::Sha3_512));
+        assert_eq!(PreHashAlgorithm::from_id(PreHashAlgorithm::Shake256.id()), Some(PreHashAlgorithm::Shake256));
+    }
+}
@@ -0,0 +1,673 @@
+//! Internal layout of aggregated proof bytes.
+//!
+//! Three layouts exist:
+//!
+//! **v1** (legacy, fixed 32-byte bitmap, at most 256 signers):
+//! `[0x01][num_sigs:2][commitment:32][bitmap:32][nonce_commit:32][pk_root:32]`
+//!
+//! **v2** (bitmap sized to the committee):
+//! `[0x02][num_sigs:4][committee_size:4][commitment:32][bitmap_len:4][bitmap:ceil(n/8)]`
+//! `[index_commit:32][nonce_commit:32][ext_len:2][extensions][pk_root:32]`
+//!
+//! **v3** (Nova, `nova` feature): the v2 fields with a compressed SNARK
+//! before the root:
+//! `[0x03][num_sigs:4][committee_size:4][commitment:32][bitmap_len:4][bitmap:ceil(n/8)]`
+//! `[index_commit:32][nonce_commit:32][ext_len:2][extensions][snark_len:4][snark][pk_root:32]`
+//!
+//! In v3 the commitment is the Poseidon fold output of the SNARK, encoded
+//! as a Pallas scalar. Builds without the `nova` feature parse v3 proofs
+//! but never accept them.
+//!
+//! The extension block is a sequence of `[tag:1][len:2][value]` entries.
+//! Verifiers reject tags they do not understand.
+//!
+//! | Tag    | Value                                                   |
+//! |--------|---------------------------------------------------------|
+//! | `0x01` | Signature scheme ID (1 byte); absent means ML-DSA-65    |
+//! | `0x02` | Hash domain version (1 byte); absent means untagged     |
+//! | `0x03` | Combined signer weight (`u64` LE); weighted trees only  |
+//! | `0x04` | Access structure commitment (32 bytes); hierarchical    |
+//! | `0x05` | [`ProofContext`] (56 bytes); domain, epoch and validity |
+//! | `0x06` | [`PreHashAlgorithm`] ID (1 byte); pre-hashed messages   |
+//! | `0x07` | [`KeyTree`] ID (1 byte); absent means SHA3              |
+//!
+//! Proofs carrying `0x05` are signed over [`ProofContext::bind`] of the
+//! message and only verify through [`verify_at`](crate::verifier::verify_at).
+//! Proofs carrying `0x06` are over a [`PreHashedMessage`](crate::core::prehash::PreHashedMessage)
+//! and only verify in [`MessageMode::PreHashed`] with the same algorithm.
+//!
+//! Proofs without the `0x02` extension predate domain-separated hashing and
+//! are only accepted with the `legacy-hashing` feature.
+
+use alloc::vec::Vec;
+#[cfg(feature = "legacy-hashing")]
+use sha3::{Digest, Sha3_256};
+
+use crate::core::prehash::{MessageMode, PreHashAlgorithm};
+use crate::core::scheme::{ActiveScheme, SignatureScheme, SCHEME_ML_DSA_65};
+use crate::types::{KeyTree, ProofContext, PROOF_FORMAT_V1, PROOF_FORMAT_V2, PROOF_FORMAT_V3};
+use crate::utils::{tags, TaggedHasher};
+
+/// Size of a v1 proof: version + num_sigs + four 32-byte fields.
+const V1_PROOF_LEN: usize = 1 + 2 + 32 * 4;
+
+/// Smallest possible v2 proof (empty committee, no extensions).
+const V2_MIN_LEN: usize = 1 + 4 + 4 + 32 + 4 + 32 + 32 + 2 + 32;
+
+/// Extension recording the signature scheme the validators signed with.
+pub(crate) const EXT_SIGNATURE_SCHEME: u8 = 0x01;
+
+/// Extension recording that the proof's hashes are domain-separated.
+pub(crate) const EXT_HASH_DOMAIN: u8 = 0x02;
+
+/// Current hash domain version, using the tags in [`crate::utils::tags`].
+pub(crate) const HASH_DOMAIN_TAGGED_V1: u8 = 0x01;
+
+/// Extension recording the combined weight of the signers.
+pub(crate) const EXT_SIGNER_WEIGHT: u8 = 0x03;
+
+/// Extension recording the access structure a hierarchical proof satisfies.
+pub(crate) const EXT_ACCESS_STRUCTURE: u8 = 0x04;
+
+/// Extension recording the domain, epoch and validity window of the proof.
+pub(crate) const EXT_PROOF_CONTEXT: u8 = 0x05;
+
+/// Extension recording the algorithm a pre-hashed message was digested with.
+pub(crate) const EXT_PREHASH: u8 = 0x06;
+
+/// Extension recording the hash function of the key tree behind `pk_root`.
+pub(crate) const EXT_KEY_TREE: u8 = 0x07;
+
+/// Extension tags this version of the verifier understands.
+pub(crate) const KNOWN_EXTENSIONS: &[u8] = &[
+    EXT_SIGNATURE_SCHEME,
+    EXT_HASH_DOMAIN,
+    EXT_SIGNER_WEIGHT,
+    EXT_ACCESS_STRUCTURE,
+    EXT_PROOF_CONTEXT,
+    EXT_PREHASH,
+    EXT_KEY_TREE,
+];
+
+/// Raw prefix of the signer index commitment before domain separation.
+#[cfg(feature = "legacy-hashing")]
+const INDEX_COMMITMENT_DOMAIN: &[u8] = b"pq-aggregate/signer-indices/v1";
+
+/// Borrowed view over the fields of an encoded proof.
+#[derive(Clone, Debug)]
+pub(crate) struct ProofView<'a> {
+    pub(crate) version: u8,
+    pub(crate) num_signatures: usize,
+    /// Committee size `n`; v1 proofs have an implicit 256-slot bitmap.
+    pub(crate) committee_size: usize,
+    /// Output of the signature commitment chain.
+    pub(crate) commitment: &'a [u8],
+    pub(crate) bitmap: &'a [u8],
+    /// Commitment to the set of signer leaf indices (v2 only).
+    pub(crate) index_commitment: Option<&'a [u8]>,
+    /// Commitment to the signers' nonces.
+    pub(crate) nonce_commitment: &'a [u8],
+    /// Raw extension block (empty for v1).
+    pub(crate) extensions: &'a [u8],
+    /// Serialized compressed SNARK (v3 only).
+    pub(crate) snark: Option<&'a [u8]>,
+    pub(crate) pk_root: &'a [u8],
+}
+
+impl<'a> ProofView<'a> {
+    /// Parse proof bytes of any version.
+    ///
+    /// Only framing is checked here; semantic checks live in the verifier.
+    pub(crate) fn parse(bytes: &'a [u8]) -> Option<Self> {
+        match *bytes.first()? {
+            PROOF_FORMAT_V1 => Self::parse_v1(bytes),
+            PROOF_FORMAT_V2 => Self::parse_v2(bytes, false),
+            PROOF_FORMAT_V3 => Self::parse_v2(bytes, true),
+            _ => None,
+        }
+    }
+
+    fn parse_v1(bytes: &'a [u8]) -> Option<Self> {
+        if bytes.len() < V1_PROOF_LEN {
+            return None;
+        }
+
+        let root_start = bytes.len() - 32;
+
+        Some(Self {
+            version: PROOF_FORMAT_V1,
+            num_signatures: u16::from_le_bytes([bytes[1], bytes[2]]) as usize,
+            committee_size: 256,
+            commitment: &bytes[3..35],
+            bitmap: &bytes[35..67],
+            index_commitment: None,
+            nonce_commitment: &bytes[67..99],
+            extensions: &[],
+            snark: None,
+            pk_root: &bytes[root_start..],
+        })
+    }
+
+    /// Parse a v2 proof, or a v3 proof if `with_snark` is set.
+    fn parse_v2(bytes: &'a [u8], with_snark: bool) -> Option<Self> {
+        if bytes.len() < V2_MIN_LEN {
+            return None;
+        }
+
+        let num_signatures = read_u32(bytes, 1)? as usize;
+        let committee_size = read_u32(bytes, 5)? as usize;
+
+        // The commitment chain output sits at 9..41
+        let bitmap_len = read_u32(bytes, 41)? as usize;
+        let mut pos = 45usize;
+        let bitmap = bytes.get(pos..pos.checked_add(bitmap_len)?)?;
+        pos += bitmap_len;
+
+        let index_commitment = bytes.get(pos..pos + 32)?;
+        let nonce_commitment = bytes.get(pos + 32..pos + 64)?;
+        pos += 64;
+
+        let ext_len = u16::from_le_bytes([*bytes.get(pos)?, *bytes.get(pos + 1)?]) as usize;
+        pos += 2;
+        let extensions = bytes.get(pos..pos + ext_len)?;
+        pos += ext_len;
+
+        let snark = if with_snark {
+            let snark_len = read_u32(bytes, pos)? as usize;
+            pos += 4;
+            let snark = bytes.get(pos..pos.checked_add(snark_len)?)?;
+            pos += snark_len;
+            Some(snark)
+        } else {
+            None
+        };
+
+        // The root must be the final 32 bytes with nothing trailing
+        let pk_root = bytes.get(pos..pos + 32)?;
+        if pos + 32 != bytes.len() {
+            return None;
+        }
+
+        Some(Self {
+            version: bytes[0],
+            num_signatures,
+            committee_size,
+            commitment: &bytes[9..41],
+            bitmap,
+            index_commitment: Some(index_commitment),
+            nonce_commitment,
+            extensions,
+            snark,
+            pk_root,
+        })
+    }
+
+    /// Iterate over `(tag, value)` pairs in the extension block.
+    ///
+    /// Returns `None` if the block is malformed or repeats a tag.
+    pub(crate) fn extension_entries(&self) -> Option<Vec<(u8, &'a [u8])>> {
+        parse_extensions(self.extensions)
+    }
+
+    /// Signature scheme the proof was produced under.
+    ///
+    /// Proofs without the scheme extension (including all v1 proofs) predate
+    /// pluggable schemes and are ML-DSA-65. Returns `None` if the extension
+    /// block is malformed.
+    pub(crate) fn scheme_id(&self) -> Option<u8> {
+        let entries = self.extension_entries()?;
+        match entries.iter().find(|(tag, _)| *tag == EXT_SIGNATURE_SCHEME) {
+            Some((_, [id])) => Some(*id),
+            Some(_) => None,
+            None => Some(SCHEME_ML_DSA_65),
+        }
+    }
+
+    /// Whether the proof's hashes are domain-separated.
+    ///
+    /// Proofs without the hash domain extension (including all v1 proofs)
+    /// use untagged SHA3-256. Returns `None` if the extension block is
+    /// malformed or names an unknown hash domain version.
+    pub(crate) fn uses_tagged_hashing(&self) -> Option<bool> {
+        let entries = self.extension_entries()?;
+        match entries.iter().find(|(tag, _)| *tag == EXT_HASH_DOMAIN) {
+            Some((_, [HASH_DOMAIN_TAGGED_V1])) => Some(true),
+            Some(_) => None,
+            None => Some(false),
+        }
+    }
+
+    /// Combined weight of the signers, for proofs over a weighted key tree.
+    ///
+    /// The outer `None` means the extension block is malformed; `Some(None)`
+    /// means the proof does not record a weight.
+    pub(crate) fn signer_weight(&self) -> Option<Option<u64>> {
+        let entries = self.extension_entries()?;
+        match entries.iter().find(|(tag, _)| *tag == EXT_SIGNER_WEIGHT) {
+            Some((_, value)) => Some(Some(u64::from_le_bytes((*value).try_into().ok()?))),
+            None => Some(None),
+        }
+    }
+
+    /// [`AccessStructure::commitment`](crate::core::access::AccessStructure::commitment)
+    /// of the structure a hierarchical proof was aggregated for.
+    ///
+    /// The outer `None` means the extension block is malformed; `Some(None)`
+    /// means the proof is not hierarchical.
+    pub(crate) fn access_structure(&self) -> Option<Option<[u8; 32]>> {
+        let entries = self.extension_entries()?;
+        match entries.iter().find(|(tag, _)| *tag == EXT_ACCESS_STRUCTURE) {
+            Some((_, value)) => Some(Some((*value).try_into().ok()?)),
+            None => Some(None),
+        }
+    }
+
+    /// Domain, epoch and validity window the proof is bound to.
+    ///
+    /// The outer `None` means the extension block is malformed (including an
+    /// inverted window); `Some(None)` means the proof is not context-bound.
+    pub(crate) fn context(&self) -> Option<Option<ProofContext>> {
+        let entries = self.extension_entries()?;
+        match entries.iter().find(|(tag, _)| *tag == EXT_PROOF_CONTEXT) {
+            Some((_, value)) => Some(Some(ProofContext::from_bytes(value)?)),
+            None => Some(None),
+        }
+    }
+
+    /// Algorithm the proof's message was pre-hashed with.
+    ///
+    /// The outer `None` means the extension block is malformed or names an
+    /// unknown algorithm; `Some(None)` means the message was signed directly.
+    pub(crate) fn prehash(&self) -> Option<Option<PreHashAlgorithm>> {
+        let entries = self.extension_entries()?;
+        match entries.iter().find(|(tag, _)| *tag == EXT_PREHASH) {
+            Some((_, [id])) => Some(Some(PreHashAlgorithm::from_id(*id)?)),
+            Some(_) => None,
+            None => Some(None),
+        }
+    }
+
+    /// Key tree the proof's `pk_root` is the root of.
+    ///
+    /// Proofs without the extension use the SHA3 [`MerkleTree`](crate::utils::MerkleTree).
+    /// Returns `None` if the extension block is malformed or names an
+    /// unknown key tree.
+    pub(crate) fn key_tree(&self) -> Option<KeyTree> {
+        let entries = self.extension_entries()?;
+        match entries.iter().find(|(tag, _)| *tag == EXT_KEY_TREE) {
+            Some((_, [id])) => KeyTree::from_id(*id),
+            Some(_) => None,
+            None => Some(KeyTree::Sha3),
+        }
+    }
+}
+
+/// Encode a v2 proof body.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn encode_v2(
+    num_signatures: usize,
+    committee_size: usize,
+    commitment: &[u8; 32],
+    bitmap: &[u8],
+    index_commitment: &[u8; 32],
+    nonce_commitment: &[u8; 32],
+    extensions: &[u8],
+    pk_root: &[u8; 32],
+) -> Vec<u8> {
+    let mut out = Vec::with_capacity(V2_MIN_LEN + bitmap.len() + extensions.len());
+
+    out.push(PROOF_FORMAT_V2);
+    out.extend_from_slice(&(num_signatures as u32).to_le_bytes());
+    out.extend_from_slice(&(committee_size as u32).to_le_bytes());
+    out.extend_from_slice(commitment);
+    out.extend_from_slice(&(bitmap.len() as u32).to_le_bytes());
+    out.extend_from_slice(bitmap);
+    out.extend_from_slice(index_commitment);
+    out.extend_from_slice(nonce_commitment);
+    out.extend_from_slice(&(extensions.len() as u16).to_le_bytes());
+    out.extend_from_slice(extensions);
+    out.extend_from_slice(pk_root);
+
+    out
+}
+
+/// Encode a v3 proof body: [`encode_v2`] with `snark` inserted before the root.
+#[cfg(feature = "nova")]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn encode_v3(
+    num_signatures: usize,
+    committee_size: usize,
+    commitment: &[u8; 32],
+    bitmap: &[u8],
+    index_commitment: &[u8; 32],
+    nonce_commitment: &[u8; 32],
+    extensions: &[u8],
+    snark: &[u8],
+    pk_root: &[u8; 32],
+) -> Vec<u8> {
+    let mut out = encode_v2(
+        num_signatures,
+        committee_size,
+        commitment,
+        bitmap,
+        index_commitment,
+        nonce_commitment,
+        extensions,
+        pk_root,
+    );
+    out[0] = PROOF_FORMAT_V3;
+
+    out.truncate(out.len() - 32);
+    out.extend_from_slice(&(snark.len() as u32).to_le_bytes());
+    out.extend_from_slice(snark);
+    out.extend_from_slice(pk_root);
+
+    out
+}
+
+/// Extension block written into new proofs: the active scheme's ID and the
+/// hash domain version.
+pub(crate) fn default_extensions() -> Vec<u8> {
+    let mut block = Vec::new();
+    push_extension(&mut block, EXT_SIGNATURE_SCHEME, &[ActiveScheme::ID]);
+    push_extension(&mut block, EXT_HASH_DOMAIN, &[HASH_DOMAIN_TAGGED_V1]);
+    block
+}
+
+/// [`default_extensions`], plus the pre-hash algorithm if `mode` is
+/// [`MessageMode::PreHashed`], the combined signer weight if the proof is
+/// weighted, the access structure commitment if it is hierarchical, and the
+/// proof context if it is context-bound.
+pub(crate) fn proof_extensions(
+    mode: MessageMode,
+    signer_weight: Option<u64>,
+    access_structure: Option<&[u8; 32]>,
+    context: Option<&ProofContext>,
+) -> Vec<u8> {
+    let mut block = default_extensions();
+    if let Some(algorithm) = mode.prehash() {
+        push_extension(&mut block, EXT_PREHASH, &[algorithm.id()]);
+    }
+    if let Some(weight) = signer_weight {
+        push_extension(&mut block, EXT_SIGNER_WEIGHT, &weight.to_le_bytes());
+    }
+    if let Some(commitment) = access_structure {
+        push_extension(&mut block, EXT_ACCESS_STRUCTURE, commitment);
+    }
+    if let Some(context) = context {
+        push_extension(&mut block, EXT_PROOF_CONTEXT, &context.to_bytes());
+    }
+    block
+}
+
+/// Append a single `[tag][len][value]` entry to an extension block.
+pub(crate) fn push_extension(block: &mut Vec<u8>, tag: u8, value: &[u8]) {
+    block.push(tag);
+    block.extend_from_slice(&(value.len() as u16).to_le_bytes());
+    block.extend_from_slice(value);
+}
+
+/// Split an extension block into `(tag, value)` entries.
+pub(crate) fn parse_extensions(block: &[u8]) -> Option<Vec<(u8, &[u8])>> {
+    let mut entries: Vec<(u8, &[u8])> = Vec::new();
+    let mut pos = 0usize;
+
+    while pos < block.len() {
+        let tag = block[pos];
+        let len = u16::from_le_bytes([*block.get(pos + 1)?, *block.get(pos + 2)?]) as usize;
+        let value = block.get(pos + 3..pos + 3 + len)?;
+        if entries.iter().any(|(t, _)| *t == tag) {
+            return None;
+        }
+        entries.push((tag, value));
+        pos += 3 + len;
+    }
+
+    Some(entries)
+}
+
+/// Build a bitmap of `committee_size` bits with the given indices set.
+///
+/// Indices at or beyond `committee_size` are ignored; callers validate them first.
+pub(crate) fn build_bitmap(indices: impl IntoIterator<Item = usize>, committee_size: usize) -> Vec<u8> {
+    let mut bitmap = alloc::vec![0u8; committee_size.div_ceil(8)];
+
+    for index in indices {
+        if index < committee_size {
+            bitmap[index / 8] |= 1 << (index % 8);
+        }
+    }
+
+    bitmap
+}
+
+/// Commit to a set of signer leaf indices.
+///
+/// Indices are hashed in ascending order under [`tags::SIGNER_INDICES`] so
+/// the verifier can recompute the commitment directly from the bitmap.
+pub(crate) fn compute_index_commitment(sorted_indices: &[usize]) -> [u8; 32] {
+    let mut hasher = TaggedHasher::new(tags::SIGNER_INDICES);
+    hasher.update((sorted_indices.len() as u64).to_le_bytes());
+    for index in sorted_indices {
+        hasher.update((*index as u64).to_le_bytes());
+    }
+    hasher.finalize()
+}
+
+/// Index commitment of v2 proofs without the hash domain extension.
+#[cfg(feature = "legacy-hashing")]
+pub(crate) fn compute_index_commitment_legacy(sorted_indices: &[usize]) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(INDEX_COMMITMENT_DOMAIN);
+    hasher.update((sorted_indices.len() as u64).to_le_bytes());
+    for index in sorted_indices {
+        hasher.update((*index as u64).to_le_bytes());
+    }
+    hasher.finalize().into()
+}
+
+/// Public inputs hash for v2 proofs, under [`tags::PUBLIC_INPUTS`].
+///
+/// Extends the v1 hash `H(pk_root || msg || num_sigs)` with the committee
+/// size, the signer bitmap, the index and nonce commitments and any
+/// extensions.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn public_inputs_hash_v2(
+    pk_root: &[u8; 32],
+    msg: &[u8],
+    num_signatures: usize,
+    committee_size: usize,
+    bitmap: &[u8],
+    index_commitment: &[u8],
+    nonce_commitment: &[u8],
+    extensions: &[u8],
+) -> [u8; 32] {
+    let mut hasher = TaggedHasher::new(tags::PUBLIC_INPUTS);
+    hasher.update(pk_root);
+    hasher.update(msg);
+    hasher.update((num_signatures as u64).to_le_bytes());
+    hasher.update((committee_size as u64).to_le_bytes());
+    hasher.update((bitmap.len() as u64).to_le_bytes());
+    hasher.update(bitmap);
+    hasher.update(index_commitment);
+    hasher.update(nonce_commitment);
+    hasher.update((extensions.len() as u64).to_le_bytes());
+    hasher.update(extensions);
+    hasher.finalize()
+}
+
+/// Untagged public inputs hash of v1 proofs: `SHA3-256(pk_root || msg || num_sigs)`.
+#[cfg(feature = "legacy-hashing")]
+pub(crate) fn public_inputs_hash_v1_legacy(pk_root: &[u8; 32], msg: &[u8], num_signatures: usize) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(pk_root);
+    hasher.update(msg);
+    hasher.update((num_signatures as u64).to_le_bytes());
+    hasher.finalize().into()
+}
+
+/// Untagged public inputs hash of v2 proofs without the hash domain extension.
+#[cfg(feature = "legacy-hashing")]
+pub(crate) fn public_inputs_hash_v2_legacy(
+    pk_root: &[u8; 32],
+    msg: &[u8],
+    num_signatures: usize,
+    committee_size: usize,
+    bitmap: &[u8],
+    index_commitment: &[u8],
+    extensions: &[u8],
+) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(pk_root);
+    hasher.update(msg);
+    hasher.update((num_signatures as u64).to_le_bytes());
+    hasher.update((committee_size as u64).to_le_bytes());
+    hasher.update((bitmap.len() as u64).to_le_bytes());
+    hasher.update(bitmap);
+    hasher.update(index_commitment);
+    hasher.update((extensions.len() as u64).to_le_bytes());
+    hasher.update(extensions);
+    hasher.finalize().into()
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    let b = bytes.get(at..at + 4)?;
+    Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_with_ext(ext: &[u8]) -> Vec<u8> {
+        encode_v2(1, 8, &[1; 32], &[1], &[0; 32], &[2; 32], ext, &[3; 32])
+    }
+
+    #[test]
+    fn test_v2_roundtrip() {
+        let bitmap = build_bitmap([0, 3, 9], 10);
+        assert_eq!(bitmap, vec![0b0000_1001, 0b0000_0010]);
+
+        let index_commitment = compute_index_commitment(&[0, 3, 9]);
+        let mut ext = Vec::new();
+        push_extension(&mut ext, 0x7F, b"value");
+
+        let bytes = encode_v2(3, 10, &[1; 32], &bitmap, &index_commitment, &[2; 32], &ext, &[3; 32]);
+        let view = ProofView::parse(&bytes).unwrap();
+
+        assert_eq!(view.version, PROOF_FORMAT_V2);
+        assert_eq!(view.num_signatures, 3);
+        assert_eq!(view.committee_size, 10);
+        assert_eq!(view.bitmap, &bitmap[..]);
+        assert_eq!(view.index_commitment, Some(&index_commitment[..]));
+        assert_eq!(view.pk_root, &[3u8; 32][..]);
+        assert_eq!(view.extension_entries().unwrap(), vec![(0x7F, &b"value"[..])]);
+    }
+
+    #[test]
+    fn test_v2_rejects_bad_framing() {
+        let bitmap = build_bitmap([1], 8);
+        let bytes = encode_v2(1, 8, &[1; 32], &bitmap, &[0; 32], &[2; 32], &[], &[3; 32]);
+
+        // Trailing garbage
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert!(ProofView::parse(&extended).is_none());
+
+        // Truncated
+        assert!(ProofView::parse(&bytes[..bytes.len() - 1]).is_none());
+
+        // Bitmap length pointing past the end
+        let mut oversized = bytes.clone();
+        oversized[41..45].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(ProofView::parse(&oversized).is_none());
+    }
+
+    #[test]
+    fn test_scheme_id() {
+        let bytes = encode_with_ext(&default_extensions());
+        assert_eq!(ProofView::parse(&bytes).unwrap().scheme_id(), Some(ActiveScheme::ID));
+
+        // Pre-extension proofs are ML-DSA-65
+        let bytes = encode_with_ext(&[]);
+        assert_eq!(ProofView::parse(&bytes).unwrap().scheme_id(), Some(SCHEME_ML_DSA_65));
+
+        // The ID is exactly one byte
+        let mut ext = Vec::new();
+        push_extension(&mut ext, EXT_SIGNATURE_SCHEME, &[0x02, 0x00]);
+        let bytes = encode_with_ext(&ext);
+        assert_eq!(ProofView::parse(&bytes).unwrap().scheme_id(), None);
+    }
+
+    #[test]
+    fn test_hash_domain() {
+        let bytes = encode_with_ext(&default_extensions());
+        assert_eq!(ProofView::parse(&bytes).unwrap().uses_tagged_hashing(), Some(true));
+
+        // Pre-extension proofs are untagged
+        let bytes = encode_with_ext(&[]);
+        assert_eq!(ProofView::parse(&bytes).unwrap().uses_tagged_hashing(), Some(false));
+
+        // Unknown domain versions are not guessed at
+        let mut ext = Vec::new();
+        push_extension(&mut ext, EXT_HASH_DOMAIN, &[0x7F]);
+        let bytes = encode_with_ext(&ext);
+        assert_eq!(ProofView::parse(&bytes).unwrap().uses_tagged_hashing(), None);
+    }
+
+    #[test]
+    fn test_signer_weight() {
+        let bytes = encode_with_ext(&proof_extensions(MessageMode::Pure, Some(1_000), None, None));
+        assert_eq!(ProofView::parse(&bytes).unwrap().signer_weight(), Some(Some(1_000)));
+
+        let bytes = encode_with_ext(&proof_extensions(MessageMode::Pure, None, None, None));
+        assert_eq!(ProofView::parse(&bytes).unwrap().signer_weight(), Some(None));
+
+        // The weight is exactly eight bytes
+        let mut ext = Vec::new();
+        push_extension(&mut ext, EXT_SIGNER_WEIGHT, &[0x01, 0x00, 0x00, 0x00]);
+        let bytes = encode_with_ext(&ext);
+        assert_eq!(ProofView::parse(&bytes).unwrap().signer_weight(), None);
+    }
+
+    #[test]
+    fn test_access_structure() {
+        let bytes = encode_with_ext(&proof_extensions(MessageMode::Pure, None, Some(&[7; 32]), None));
+        assert_eq!(ProofView::parse(&bytes).unwrap().access_structure(), Some(Some([7; 32])));
+
+        let bytes = encode_with_ext(&default_extensions());
+        assert_eq!(ProofView::parse(&bytes).unwrap().access_structure(), Some(None));
+
+        let mut ext = Vec::new();
+        push_extension(&mut ext, EXT_ACCESS_STRUCTURE, &[7; 31]);
+        let bytes = encode_with_ext(&ext);
+        assert_eq!(ProofView::parse(&bytes).unwrap().access_structure(), None);
+    }
+
+    #[test]
+    fn test_proof_context() {
+        let context = ProofContext::new([9; 32], 4, 100, 200);
+
+        let bytes = encode_with_ext(&proof_extensions(MessageMode::Pure, None, None, Some(&context)));
+        assert_eq!(ProofView::parse(&bytes).unwrap().context(), Some(Some(context)));
+
+        let bytes = encode_with_ext(&default_extensions());
+        assert_eq!(ProofView::parse(&bytes).unwrap().context(), Some(None));
+
+        let mut ext = Vec::new();
+        push_extension(&mut ext, EXT_PROOF_CONTEXT, &ProofContext::new([9; 32], 4, 200, 100).to_bytes());
+        let bytes = encode_with_ext(&ext);
+        assert_eq!(ProofView::parse(&bytes).unwrap().context(), None);
+    }
+
+    #[test]
+    fn test_extensions_reject_duplicates_and_truncation() {
+        let mut ext = Vec::new();
+        push_extension(&mut ext, 0x01, &[1]);
+        push_extension(&mut ext, 0x01, &[2]);
+        assert!(parse_extensions(&ext).is_none());
+
+        assert!(parse_extensions(&[0x01, 0x05, 0x00, 0xAA]).is_none());
+        assert!(parse_extensions(&[]).unwrap().is_empty());
+    }
+}
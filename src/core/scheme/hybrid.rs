@@ -0,0 +1,229 @@
+//! Composite ML-DSA + Ed25519 scheme for the classical-to-PQ migration.
+//!
+//! Every key and signature is the concatenation of an ML-DSA component and
+//! an Ed25519 component, `ml_dsa || ed25519`, and a signature is valid only
+//! if both components verify. Because the Merkle leaf hash and the proof's
+//! signature commitments are computed over the full encodings, they cover
+//! both halves.
+//!
+//! The ML-DSA half is derived from the key seed exactly as in the plain
+//! ML-DSA scheme `M`, so a committee that switches to the hybrid mode keeps
+//! its post-quantum keys and only gains the Ed25519 ones.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::Shake256;
+use zeroize::Zeroizing;
+
+use super::{MlDsa65, SignatureScheme, SCHEME_ML_DSA_44, SCHEME_ML_DSA_87};
+use super::{SCHEME_ML_DSA_44_ED25519, SCHEME_ML_DSA_65_ED25519, SCHEME_ML_DSA_87_ED25519};
+use crate::error::{PQAggregateError, Result};
+
+/// Ed25519 public key size in bytes.
+pub const ED25519_PUBLIC_KEY_BYTES: usize = ed25519_dalek::PUBLIC_KEY_LENGTH;
+
+/// Ed25519 secret key (seed) size in bytes.
+pub const ED25519_SECRET_KEY_BYTES: usize = ed25519_dalek::SECRET_KEY_LENGTH;
+
+/// Ed25519 signature size in bytes.
+pub const ED25519_SIGNATURE_BYTES: usize = ed25519_dalek::SIGNATURE_LENGTH;
+
+/// Domain tag for deriving the Ed25519 seed from the key seed.
+const ED25519_SEED_DOMAIN: &[u8] = b"pq-aggregate/hybrid-ed25519-seed/v1";
+
+/// The ML-DSA parameter set `M` combined with Ed25519.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MlDsaEd25519<M = MlDsa65>(PhantomData<M>);
+
+impl<M: SignatureScheme> MlDsaEd25519<M> {
+    /// `(scheme ID, name)` matching the ML-DSA level.
+    const PARAMETER_SET: (u8, &'static str) = match M::ID {
+        SCHEME_ML_DSA_44 => (SCHEME_ML_DSA_44_ED25519, "ML-DSA-44+Ed25519"),
+        SCHEME_ML_DSA_87 => (SCHEME_ML_DSA_87_ED25519, "ML-DSA-87+Ed25519"),
+        _ => (SCHEME_ML_DSA_65_ED25519, "ML-DSA-65+Ed25519"),
+    };
+
+    /// Split an encoded public key into its `(ml_dsa, ed25519)` components.
+    ///
+    /// Returns `None` if the key has the wrong length.
+    pub fn split_public_key(public_key: &[u8]) -> Option<(&[u8], &[u8])> {
+        split(public_key, M::PUBLIC_KEY_BYTES, ED25519_PUBLIC_KEY_BYTES)
+    }
+
+    /// Split an encoded signature into its `(ml_dsa, ed25519)` components.
+    ///
+    /// Returns `None` if the signature has the wrong length.
+    pub fn split_signature(signature: &[u8]) -> Option<(&[u8], &[u8])> {
+        split(signature, M::SIGNATURE_BYTES, ED25519_SIGNATURE_BYTES)
+    }
+}
+
+impl<M: SignatureScheme> SignatureScheme for MlDsaEd25519<M> {
+    const ID: u8 = Self::PARAMETER_SET.0;
+    const NAME: &'static str = Self::PARAMETER_SET.1;
+    const PUBLIC_KEY_BYTES: usize = M::PUBLIC_KEY_BYTES + ED25519_PUBLIC_KEY_BYTES;
+    const SECRET_KEY_BYTES: usize = M::SECRET_KEY_BYTES + ED25519_SECRET_KEY_BYTES;
+    const SIGNATURE_BYTES: usize = M::SIGNATURE_BYTES + ED25519_SIGNATURE_BYTES;
+
+    fn keypair_from_seed(seed: &[u8; 32]) -> (Vec<u8>, Zeroizing<Vec<u8>>) {
+        let (mut pk, mut sk) = M::keypair_from_seed(seed);
+
+        let mut ed_seed = Zeroizing::new([0u8; ED25519_SECRET_KEY_BYTES]);
+        let mut hasher = Shake256::default();
+        hasher.update(ED25519_SEED_DOMAIN);
+        hasher.update(seed);
+        hasher.finalize_xof().read(&mut ed_seed[..]);
+
+        let signing_key = SigningKey::from_bytes(&ed_seed);
+        pk.extend_from_slice(signing_key.verifying_key().as_bytes());
+        sk.extend_from_slice(&ed_seed[..]);
+
+        (pk, sk)
+    }
+
+    fn sign(secret_key: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
+        let Some((ml_sk, ed_sk)) = split(secret_key, M::SECRET_KEY_BYTES, ED25519_SECRET_KEY_BYTES) else {
+            return Err(PQAggregateError::InvalidInput {
+                reason: alloc::format!(
+                    "{} secret key must be {} bytes, got {}",
+                    Self::NAME,
+                    Self::SECRET_KEY_BYTES,
+                    secret_key.len()
+                ),
+            });
+        };
+
+        let mut sig = M::sign(ml_sk, msg)?;
+
+        let mut ed_seed = Zeroizing::new([0u8; ED25519_SECRET_KEY_BYTES]);
+        ed_seed.copy_from_slice(ed_sk);
+        let signing_key = SigningKey::from_bytes(&ed_seed);
+        sig.extend_from_slice(&signing_key.sign(msg).to_bytes());
+
+        Ok(sig)
+    }
+
+    fn verify(public_key: &[u8], msg: &[u8], signature: &[u8]) -> bool {
+        let (Some((ml_pk, ed_pk)), Some((ml_sig, ed_sig))) =
+            (Self::split_public_key(public_key), Self::split_signature(signature))
+        else {
+            return false;
+        };
+
+        // Both halves must verify; neither one alone is sufficient
+        M::verify(ml_pk, msg, ml_sig) && verify_ed25519(ed_pk, msg, ed_sig)
+    }
+}
+
+fn verify_ed25519(public_key: &[u8], msg: &[u8], signature: &[u8]) -> bool {
+    let (Ok(public_key), Ok(signature)) = (
+        <[u8; ED25519_PUBLIC_KEY_BYTES]>::try_from(public_key),
+        ed25519_dalek::Signature::from_slice(signature),
+    ) else {
+        return false;
+    };
+    let Ok(verifying_key) = VerifyingKey::from_bytes(&public_key) else {
+        return false;
+    };
+    verifying_key.verify_strict(msg, &signature).is_ok()
+}
+
+/// Split `bytes` into a `first`-byte and a `second`-byte part, if it has exactly that length.
+fn split(bytes: &[u8], first: usize, second: usize) -> Option<(&[u8], &[u8])> {
+    (bytes.len() == first + second).then(|| bytes.split_at(first))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::scheme::{MlDsa44, MlDsa87};
+
+    type Hybrid = MlDsaEd25519<MlDsa65>;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let (pk, sk) = Hybrid::keypair_from_seed(&[5u8; 32]);
+        assert_eq!(pk.len(), Hybrid::PUBLIC_KEY_BYTES);
+        assert_eq!(sk.len(), Hybrid::SECRET_KEY_BYTES);
+
+        let sig = Hybrid::sign(&sk, b"hybrid").unwrap();
+        assert_eq!(sig.len(), Hybrid::SIGNATURE_BYTES);
+        assert!(Hybrid::verify(&pk, b"hybrid", &sig));
+        assert!(!Hybrid::verify(&pk, b"other", &sig));
+    }
+
+    #[test]
+    fn test_both_components_required() {
+        let (pk, sk) = Hybrid::keypair_from_seed(&[5u8; 32]);
+        let sig = Hybrid::sign(&sk, b"hybrid").unwrap();
+
+        // Corrupting either half invalidates the whole signature
+        let mut bad_ml = sig.clone();
+        bad_ml[0] ^= 0x01;
+        assert!(!Hybrid::verify(&pk, b"hybrid", &bad_ml));
+
+        let mut bad_ed = sig.clone();
+        bad_ed[MlDsa65::SIGNATURE_BYTES] ^= 0x01;
+        assert!(!Hybrid::verify(&pk, b"hybrid", &bad_ed));
+
+        // A stripped signature (ML-DSA only) is rejected
+        assert!(!Hybrid::verify(&pk, b"hybrid", &sig[..MlDsa65::SIGNATURE_BYTES]));
+
+        // Swapping in another validator's Ed25519 key is rejected
+        let (other_pk, _) = Hybrid::keypair_from_seed(&[6u8; 32]);
+        let (ml_pk, _) = Hybrid::split_public_key(&pk).unwrap();
+        let (_, other_ed_pk) = Hybrid::split_public_key(&other_pk).unwrap();
+        let mixed = [ml_pk, other_ed_pk].concat();
+        assert!(!Hybrid::verify(&mixed, b"hybrid", &sig));
+    }
+
+    #[test]
+    fn test_scheme_follows_ml_dsa_level() {
+        assert_eq!(MlDsaEd25519::<MlDsa44>::ID, SCHEME_ML_DSA_44_ED25519);
+        assert_eq!(Hybrid::ID, SCHEME_ML_DSA_65_ED25519);
+        assert_eq!(MlDsaEd25519::<MlDsa87>::NAME, "ML-DSA-87+Ed25519");
+        assert_eq!(
+            MlDsaEd25519::<MlDsa87>::SIGNATURE_BYTES,
+            MlDsa87::SIGNATURE_BYTES + ED25519_SIGNATURE_BYTES
+        );
+
+        let (pk, sk) = MlDsaEd25519::<MlDsa44>::keypair_from_seed(&[9u8; 32]);
+        let sig = MlDsaEd25519::<MlDsa44>::sign(&sk, b"level 2").unwrap();
+        assert!(MlDsaEd25519::<MlDsa44>::verify(&pk, b"level 2", &sig));
+        assert!(!Hybrid::verify(&pk, b"level 2", &sig));
+    }
+
+    #[test]
+    #[cfg(feature = "hybrid-ed25519")]
+    fn test_aggregation_rejects_broken_ed25519_half() {
+        use crate::core::aggregation::aggregate_proofs;
+        use crate::core::keygen::setup;
+        use crate::core::signing::aggregate_sign;
+
+        let (sks, pks, pk_root) = setup(3);
+        let msg = b"hybrid committee";
+
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2).unwrap();
+        assert!(sigs[0].hybrid_components().is_some());
+        assert!(pks[0].hybrid_components().is_some());
+
+        let proof = aggregate_proofs(sigs.clone(), proofs.clone(), pk_root, msg, &pks).unwrap();
+        assert!(crate::verifier::verify(pk_root, msg, &proof));
+
+        let mut tampered = sigs;
+        tampered[1].bytes[crate::core::scheme::ActiveMlDsa::SIGNATURE_BYTES + 3] ^= 0x01;
+        assert!(aggregate_proofs(tampered, proofs, pk_root, msg, &pks).is_err());
+    }
+
+    #[test]
+    fn test_ml_dsa_component_matches_plain_scheme() {
+        let (hybrid_pk, _) = Hybrid::keypair_from_seed(&[8u8; 32]);
+        let (plain_pk, _) = MlDsa65::keypair_from_seed(&[8u8; 32]);
+
+        let (ml_pk, ed_pk) = Hybrid::split_public_key(&hybrid_pk).unwrap();
+        assert_eq!(ml_pk, &plain_pk[..]);
+        assert_eq!(ed_pk.len(), ED25519_PUBLIC_KEY_BYTES);
+    }
+}
@@ -0,0 +1,182 @@
+//! ML-DSA backends (FIPS 204) on top of the `fips204` crate.
+//!
+//! All three parameter sets are always compiled; [`ActiveMlDsa`](super::ActiveMlDsa)
+//! picks the one the crate signs with. `fips204` runs in constant time with
+//! respect to secret data and is tested against the NIST ACVP vectors.
+//!
+//! Keys are derived with `ML-DSA.KeyGen_internal` from the 32-byte seed, and
+//! signing uses the deterministic variant (`rnd = {0}^32`) of the pure
+//! interface with an empty context string. The per-signer nonce already
+//! randomizes what is signed.
+
+use alloc::format;
+use alloc::vec::Vec;
+use fips204::traits::{KeyGen, SerDes, Signer, Verifier};
+use fips204::{ml_dsa_44, ml_dsa_65, ml_dsa_87};
+use zeroize::Zeroizing;
+
+use super::{SignatureScheme, SCHEME_ML_DSA_44, SCHEME_ML_DSA_65, SCHEME_ML_DSA_87};
+use crate::error::{PQAggregateError, Result};
+
+/// Implement [`SignatureScheme`] for one `fips204` parameter set.
+macro_rules! ml_dsa_scheme {
+    ($(#[$meta:meta])* $scheme:ident, $params:ident, $id:expr, $name:literal) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+        pub struct $scheme;
+
+        impl SignatureScheme for $scheme {
+            const ID: u8 = $id;
+            const NAME: &'static str = $name;
+            const PUBLIC_KEY_BYTES: usize = $params::PK_LEN;
+            const SECRET_KEY_BYTES: usize = $params::SK_LEN;
+            const SIGNATURE_BYTES: usize = $params::SIG_LEN;
+
+            fn keypair_from_seed(seed: &[u8; 32]) -> (Vec<u8>, Zeroizing<Vec<u8>>) {
+                let (pk, sk) = $params::KG::keygen_from_seed(seed);
+                let sk = Zeroizing::new(sk.into_bytes());
+                (pk.into_bytes().to_vec(), Zeroizing::new(sk.to_vec()))
+            }
+
+            fn sign(secret_key: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
+                let malformed = || PQAggregateError::InvalidInput {
+                    reason: format!(
+                        "{} secret key must be {} bytes, got {}",
+                        $name,
+                        $params::SK_LEN,
+                        secret_key.len()
+                    ),
+                };
+                let bytes = Zeroizing::new(<[u8; $params::SK_LEN]>::try_from(secret_key).map_err(|_| malformed())?);
+                let sk = $params::PrivateKey::try_from_bytes(*bytes).map_err(|_| malformed())?;
+
+                let sig = sk.try_sign_with_seed(&[0u8; 32], msg, &[]).map_err(|reason| {
+                    PQAggregateError::CryptoError {
+                        reason: format!("{} signing failed: {}", $name, reason),
+                    }
+                })?;
+                Ok(sig.to_vec())
+            }
+
+            fn verify(public_key: &[u8], msg: &[u8], signature: &[u8]) -> bool {
+                let (Ok(public_key), Ok(signature)) = (
+                    <[u8; $params::PK_LEN]>::try_from(public_key),
+                    <[u8; $params::SIG_LEN]>::try_from(signature),
+                ) else {
+                    return false;
+                };
+                let Ok(public_key) = $params::PublicKey::try_from_bytes(public_key) else {
+                    return false;
+                };
+                public_key.verify(msg, &signature, &[])
+            }
+        }
+    };
+}
+
+ml_dsa_scheme!(
+    /// ML-DSA-44 (NIST level 2).
+    MlDsa44,
+    ml_dsa_44,
+    SCHEME_ML_DSA_44,
+    "ML-DSA-44"
+);
+
+ml_dsa_scheme!(
+    /// ML-DSA-65 (NIST level 3), the default.
+    MlDsa65,
+    ml_dsa_65,
+    SCHEME_ML_DSA_65,
+    "ML-DSA-65"
+);
+
+ml_dsa_scheme!(
+    /// ML-DSA-87 (NIST level 5).
+    MlDsa87,
+    ml_dsa_87,
+    SCHEME_ML_DSA_87,
+    "ML-DSA-87"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::sha3_256;
+
+    fn round_trip<S: SignatureScheme>() {
+        let (pk, sk) = S::keypair_from_seed(&[7u8; 32]);
+        assert_eq!(pk.len(), S::PUBLIC_KEY_BYTES);
+        assert_eq!(sk.len(), S::SECRET_KEY_BYTES);
+
+        let sig = S::sign(&sk, b"seeded").unwrap();
+        assert_eq!(sig.len(), S::SIGNATURE_BYTES);
+        assert!(S::verify(&pk, b"seeded", &sig));
+        assert!(!S::verify(&pk, b"other", &sig));
+        assert!(!S::verify(&pk, b"seeded", &sig[1..]));
+
+        let mut tampered = sig.clone();
+        tampered[0] ^= 0x01;
+        assert!(!S::verify(&pk, b"seeded", &tampered));
+
+        // Deterministic signing
+        assert_eq!(S::sign(&sk, b"seeded").unwrap(), sig);
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        round_trip::<MlDsa44>();
+        round_trip::<MlDsa65>();
+        round_trip::<MlDsa87>();
+    }
+
+    #[test]
+    fn test_keygen_matches_nist_vectors() {
+        // First case of each parameter set in the NIST ACVP ML-DSA-keyGen-FIPS204
+        // vectors (tcId 1, 26 and 51), as SHA3-256 of the expected pk and sk.
+        fn check<S: SignatureScheme>(seed: &str, pk_hash: &str, sk_hash: &str) {
+            let seed: [u8; 32] = hex::decode(seed).unwrap().try_into().unwrap();
+            let (pk, sk) = S::keypair_from_seed(&seed);
+            assert_eq!(hex::encode(sha3_256(&pk)), pk_hash, "{} public key", S::NAME);
+            assert_eq!(hex::encode(sha3_256(&sk)), sk_hash, "{} secret key", S::NAME);
+        }
+
+        check::<MlDsa44>(
+            "93ef2e6ef1fb08999d142abe0295482370d3f43bdb254a78e2b0d5168eca065f",
+            "b09f140435b15d8da2e35f2c8b474068a473edb4b79eab3912278a699bea09c0",
+            "582fe8b284f625ce54ce845f1ddef018bc99b87f90b91d1993321209b4b4af7f",
+        );
+        check::<MlDsa65>(
+            "70cefb9aed5b68e018b079da8284b9d5cad5499ed9c265ff73588005d85c225c",
+            "3bab48eb1a51111d56433219d7abe12d15a8a3a1e0a190a9d144cf93fa34185c",
+            "dd8a5f0f0ff2f9d883d73a10ef50c1b020658379bbc92ee76ed414226e5ee709",
+        );
+        check::<MlDsa87>(
+            "38359fbcd79582cffe609e137ee2efe8a8dbcbad18ba92bb433ab4f09b49299d",
+            "6dbc9c454b1ee64d654382ffc7d1314a0498476a59e37364290e82195da101c7",
+            "8f2750a0bc106dda3bee390ba582e3f8101db1f98b1506f4c6fb149f69f7b869",
+        );
+    }
+
+    #[test]
+    fn test_sign_rejects_wrong_key_length() {
+        assert!(matches!(
+            MlDsa65::sign(&[0u8; 32], b"msg"),
+            Err(PQAggregateError::InvalidInput { .. })
+        ));
+
+        // A key of another parameter set is rejected, not misread
+        let (_, sk) = MlDsa44::keypair_from_seed(&[1u8; 32]);
+        assert!(MlDsa65::sign(&sk, b"msg").is_err());
+    }
+
+    #[test]
+    fn test_seeded_keypair_is_deterministic() {
+        let (pk1, sk1) = MlDsa65::keypair_from_seed(&[1u8; 32]);
+        let (pk2, sk2) = MlDsa65::keypair_from_seed(&[1u8; 32]);
+        let (pk3, _) = MlDsa65::keypair_from_seed(&[2u8; 32]);
+
+        assert_eq!(pk1, pk2);
+        assert_eq!(*sk1, *sk2);
+        assert_ne!(pk1, pk3);
+    }
+}
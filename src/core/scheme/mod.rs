@@ -0,0 +1,142 @@
+//! Pluggable signature schemes.
+//!
+//! Aggregation, proof encoding and verification treat keys and signatures as
+//! opaque bytes. Everything that does look inside them (key generation,
+//! signing, [`verify_single`](crate::core::signing::verify_single) and the
+//! Merkle leaf hashing) goes through the [`SignatureScheme`] trait, and the
+//! scheme used by the crate is [`ActiveScheme`].
+//!
+//! Every backend is always compiled; the cargo features only choose which
+//! one is active, so they can be combined freely. ML-DSA and SLH-DSA come
+//! from the `fips204` and `fips205` crates.
+//!
+//! | Feature          | Scheme              | ID                 |
+//! |------------------|---------------------|--------------------|
+//! | *(default)*      | ML-DSA-65           | `0x02`             |
+//! | `ml-dsa-44`      | ML-DSA-44           | `0x01`             |
+//! | `ml-dsa-87`      | ML-DSA-87           | `0x03`             |
+//! | `slh-dsa`        | SLH-DSA-SHAKE-128f  | `0x10`             |
+//! | `hybrid-ed25519` | ML-DSA + Ed25519    | `0x20 + ML-DSA ID` |
+//!
+//! When several are enabled, a fixed priority applies: `hybrid-ed25519`,
+//! then `slh-dsa`, then plain ML-DSA. The ML-DSA level ([`ActiveMlDsa`], also
+//! used for the hybrid's ML-DSA half) is the highest one enabled: ML-DSA-87
+//! over ML-DSA-44, and ML-DSA-65 when neither is set.
+//!
+//! The scheme ID is recorded in every aggregated proof, so a verifier built
+//! for one scheme rejects proofs produced under another.
+
+use alloc::vec::Vec;
+use rand_core::RngCore;
+use zeroize::Zeroizing;
+
+use crate::error::Result;
+use crate::utils::{tagged_hash, tags, TaggedHasher};
+
+pub mod hybrid;
+pub mod ml_dsa;
+pub mod slh_dsa;
+
+pub use hybrid::MlDsaEd25519;
+pub use ml_dsa::{MlDsa44, MlDsa65, MlDsa87};
+pub use slh_dsa::SlhDsaShake128f;
+
+/// Scheme ID of ML-DSA-44 (NIST level 2).
+pub const SCHEME_ML_DSA_44: u8 = 0x01;
+
+/// Scheme ID of ML-DSA-65 (NIST level 3), the default.
+pub const SCHEME_ML_DSA_65: u8 = 0x02;
+
+/// Scheme ID of ML-DSA-87 (NIST level 5).
+pub const SCHEME_ML_DSA_87: u8 = 0x03;
+
+/// Scheme ID of SLH-DSA-SHAKE-128f (hash-based, NIST level 1).
+pub const SCHEME_SLH_DSA_SHAKE_128F: u8 = 0x10;
+
+/// Scheme ID of the ML-DSA-44 + Ed25519 composite.
+pub const SCHEME_ML_DSA_44_ED25519: u8 = 0x21;
+
+/// Scheme ID of the ML-DSA-65 + Ed25519 composite.
+pub const SCHEME_ML_DSA_65_ED25519: u8 = 0x22;
+
+/// Scheme ID of the ML-DSA-87 + Ed25519 composite.
+pub const SCHEME_ML_DSA_87_ED25519: u8 = 0x23;
+
+/// The ML-DSA parameter set selected by the enabled cargo features.
+#[cfg(feature = "ml-dsa-87")]
+pub type ActiveMlDsa = MlDsa87;
+
+/// The ML-DSA parameter set selected by the enabled cargo features.
+#[cfg(all(feature = "ml-dsa-44", not(feature = "ml-dsa-87")))]
+pub type ActiveMlDsa = MlDsa44;
+
+/// The ML-DSA parameter set selected by the enabled cargo features.
+#[cfg(not(any(feature = "ml-dsa-44", feature = "ml-dsa-87")))]
+pub type ActiveMlDsa = MlDsa65;
+
+/// The signature scheme selected by the enabled cargo features.
+#[cfg(feature = "hybrid-ed25519")]
+pub type ActiveScheme = MlDsaEd25519<ActiveMlDsa>;
+
+/// The signature scheme selected by the enabled cargo features.
+#[cfg(all(feature = "slh-dsa", not(feature = "hybrid-ed25519")))]
+pub type ActiveScheme = SlhDsaShake128f;
+
+/// The signature scheme selected by the enabled cargo features.
+#[cfg(not(any(feature = "slh-dsa", feature = "hybrid-ed25519")))]
+pub type ActiveScheme = ActiveMlDsa;
+
+/// A signature scheme usable for validator keys.
+///
+/// Keys and signatures are exchanged as raw bytes in the scheme's own encoding.
+pub trait SignatureScheme {
+    /// Identifier recorded in aggregated proofs.
+    const ID: u8;
+    /// Human-readable name, e.g. `"ML-DSA-65"`.
+    const NAME: &'static str;
+    /// Encoded public key size in bytes.
+    const PUBLIC_KEY_BYTES: usize;
+    /// Encoded secret key size in bytes.
+    const SECRET_KEY_BYTES: usize;
+    /// Encoded signature size in bytes.
+    const SIGNATURE_BYTES: usize;
+
+    /// Derive a keypair from a 32-byte seed; the same seed always yields the same keys.
+    ///
+    /// Returns `(public_key, secret_key)`.
+    fn keypair_from_seed(seed: &[u8; 32]) -> (Vec<u8>, Zeroizing<Vec<u8>>);
+
+    /// Generate a fresh keypair from the thread-local RNG.
+    fn generate_keypair() -> (Vec<u8>, Zeroizing<Vec<u8>>) {
+        Self::generate_keypair_with_rng(&mut rand::thread_rng())
+    }
+
+    /// Generate a fresh keypair from a seed drawn from `rng`.
+    fn generate_keypair_with_rng<R: RngCore + ?Sized>(rng: &mut R) -> (Vec<u8>, Zeroizing<Vec<u8>>) {
+        let mut seed = Zeroizing::new([0u8; 32]);
+        rng.fill_bytes(&mut seed[..]);
+        Self::keypair_from_seed(&seed)
+    }
+
+    /// Sign `msg` with an encoded secret key.
+    ///
+    /// # Errors
+    /// `InvalidInput` if the secret key is malformed.
+    fn sign(secret_key: &[u8], msg: &[u8]) -> Result<Vec<u8>>;
+
+    /// Check `signature` on `msg` under an encoded public key.
+    fn verify(public_key: &[u8], msg: &[u8], signature: &[u8]) -> bool;
+
+    /// Hash of a public key as stored in the committee Merkle tree.
+    fn leaf_hash(public_key: &[u8]) -> [u8; 32] {
+        tagged_hash(tags::MERKLE_LEAF, public_key)
+    }
+
+    /// Hash of a public key and its weight as stored in a weighted committee Merkle tree.
+    fn weighted_leaf_hash(public_key: &[u8], weight: u64) -> [u8; 32] {
+        let mut hasher = TaggedHasher::new(tags::WEIGHTED_MERKLE_LEAF);
+        hasher.update(weight.to_le_bytes());
+        hasher.update(public_key);
+        hasher.finalize()
+    }
+}
@@ -0,0 +1,155 @@
+//! SLH-DSA-SHAKE-128f backend (FIPS 205) on top of the `fips205` crate.
+//!
+//! A stateless hash-based fallback for deployments that do not want to rely on
+//! lattice assumptions. Signatures are large (17 088 bytes) and slow to produce
+//! compared to ML-DSA; the "fast" parameter set keeps signing practical.
+//!
+//! Signing uses the deterministic variant (`opt_rand = PK.seed`) of the pure
+//! `slh_sign` interface with an empty context string. FIPS 205 key generation
+//! takes 48 bytes of seed material; [`SignatureScheme::keypair_from_seed`]
+//! expands the crate-wide 32-byte seed to 48 bytes with SHAKE256 under a
+//! domain tag.
+
+use alloc::format;
+use alloc::vec::Vec;
+use fips205::slh_dsa_shake_128f as params;
+use fips205::traits::{KeyGen, SerDes, Signer, Verifier};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::Shake256;
+use zeroize::Zeroizing;
+
+use super::{SignatureScheme, SCHEME_SLH_DSA_SHAKE_128F};
+use crate::error::{PQAggregateError, Result};
+
+/// Security parameter: hash output length in bytes.
+const HASH_BYTES: usize = params::N;
+
+/// Domain tag for expanding a 32-byte seed into the FIPS 205 key seeds.
+const KEYGEN_SEED_DOMAIN: &[u8] = b"pq-aggregate/slh-dsa-keygen/v1";
+
+/// SLH-DSA with the SHAKE-128f parameter set.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SlhDsaShake128f;
+
+impl SignatureScheme for SlhDsaShake128f {
+    const ID: u8 = SCHEME_SLH_DSA_SHAKE_128F;
+    const NAME: &'static str = "SLH-DSA-SHAKE-128f";
+    const PUBLIC_KEY_BYTES: usize = params::PK_LEN;
+    const SECRET_KEY_BYTES: usize = params::SK_LEN;
+    const SIGNATURE_BYTES: usize = params::SIG_LEN;
+
+    fn keypair_from_seed(seed: &[u8; 32]) -> (Vec<u8>, Zeroizing<Vec<u8>>) {
+        let mut seeds = Zeroizing::new([[0u8; HASH_BYTES]; 3]);
+        let mut reader = Shake256::default().chain(KEYGEN_SEED_DOMAIN).chain(seed).finalize_xof();
+        for part in seeds.iter_mut() {
+            reader.read(part);
+        }
+
+        let [sk_seed, sk_prf, pk_seed] = &*seeds;
+        keypair_from_seeds(sk_seed, sk_prf, pk_seed)
+    }
+
+    fn sign(secret_key: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
+        let malformed = || PQAggregateError::InvalidInput {
+            reason: format!(
+                "SLH-DSA secret key must be {} bytes, got {}",
+                params::SK_LEN,
+                secret_key.len()
+            ),
+        };
+        let bytes = Zeroizing::new(<[u8; params::SK_LEN]>::try_from(secret_key).map_err(|_| malformed())?);
+        let sk = params::PrivateKey::try_from_bytes(&bytes).map_err(|_| malformed())?;
+
+        // The deterministic variant never draws from the RNG
+        let sig = sk
+            .try_sign_with_rng(&mut rand::thread_rng(), msg, &[], false)
+            .map_err(|reason| PQAggregateError::CryptoError {
+                reason: format!("SLH-DSA signing failed: {}", reason),
+            })?;
+        Ok(sig.to_vec())
+    }
+
+    fn verify(public_key: &[u8], msg: &[u8], signature: &[u8]) -> bool {
+        let (Ok(public_key), Ok(signature)) = (
+            <[u8; params::PK_LEN]>::try_from(public_key),
+            <[u8; params::SIG_LEN]>::try_from(signature),
+        ) else {
+            return false;
+        };
+        let Ok(public_key) = params::PublicKey::try_from_bytes(&public_key) else {
+            return false;
+        };
+        public_key.verify(msg, &signature, &[])
+    }
+}
+
+/// `slh_keygen_internal`: returns `(PK.seed || PK.root, SK.seed || SK.prf || PK.seed || PK.root)`.
+fn keypair_from_seeds(
+    sk_seed: &[u8; HASH_BYTES],
+    sk_prf: &[u8; HASH_BYTES],
+    pk_seed: &[u8; HASH_BYTES],
+) -> (Vec<u8>, Zeroizing<Vec<u8>>) {
+    let (pk, sk) = params::KG::keygen_with_seeds(sk_seed, sk_prf, pk_seed);
+    let sk = Zeroizing::new(sk.into_bytes());
+    (pk.into_bytes().to_vec(), Zeroizing::new(sk.to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::sha3_256;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let (pk, sk) = SlhDsaShake128f::keypair_from_seed(&[3u8; 32]);
+        assert_eq!(pk.len(), 32);
+        assert_eq!(sk.len(), 64);
+
+        let sig = SlhDsaShake128f::sign(&sk, b"fallback").unwrap();
+        assert_eq!(sig.len(), 17_088);
+        assert!(SlhDsaShake128f::verify(&pk, b"fallback", &sig));
+        assert!(!SlhDsaShake128f::verify(&pk, b"other", &sig));
+
+        // Flip one bit in each part: randomizer, FORS, and the top XMSS layer
+        for pos in [0, HASH_BYTES + 5, sig.len() - 1] {
+            let mut tampered = sig.clone();
+            tampered[pos] ^= 0x01;
+            assert!(!SlhDsaShake128f::verify(&pk, b"fallback", &tampered));
+        }
+
+        let (other_pk, _) = SlhDsaShake128f::keypair_from_seed(&[4u8; 32]);
+        assert!(!SlhDsaShake128f::verify(&other_pk, b"fallback", &sig));
+        assert!(SlhDsaShake128f::sign(&sk[1..], b"fallback").is_err());
+    }
+
+    #[test]
+    fn test_keygen_matches_nist_vector() {
+        // Secret key of tcId 35 in the NIST ACVP SLH-DSA-sigGen-FIPS205 vectors
+        // (SLH-DSA-SHAKE-128f): SK.seed || SK.prf || PK.seed || PK.root
+        let expected = hex::decode(
+            "5a101891773098d5106a1176933f5a76bb9e1530166f19ce6c32dffc16bb1321\
+             bd19513d4ffb0bcdebbc7a1715bdd10e61e7a1da9f645dc64ec34c5db776493a",
+        )
+        .unwrap();
+        let part = |i: usize| -> [u8; HASH_BYTES] { expected[i * HASH_BYTES..(i + 1) * HASH_BYTES].try_into().unwrap() };
+
+        let (pk, sk) = keypair_from_seeds(&part(0), &part(1), &part(2));
+        assert_eq!(&sk[..], &expected[..]);
+        assert_eq!(&pk[..], &expected[2 * HASH_BYTES..]);
+    }
+
+    #[test]
+    fn test_matches_openssl() {
+        // Cross-checked against OpenSSL 3.5:
+        //   openssl genpkey -algorithm SLH-DSA-SHAKE-128f -pkeyopt hexseed:07..07 (48 bytes)
+        //   openssl pkeyutl -sign -rawin -pkeyopt deterministic:1 (message "abc")
+        let (pk, sk) = keypair_from_seeds(&[7u8; 16], &[7u8; 16], &[7u8; 16]);
+        assert_eq!(hex::encode(&pk[HASH_BYTES..]), "625005151786502e9bf076cff4c29ad9");
+
+        let sig = SlhDsaShake128f::sign(&sk, b"abc").unwrap();
+        assert_eq!(
+            hex::encode(sha3_256(&sig)),
+            "6a1e563a93ede6463b88e0a7377f277dc77060fdb0999586244043dff410ac48"
+        );
+    }
+}
@@ -1,19 +1,82 @@
 //! Threshold signing for PQ-Aggregate.
 //!
 //! Each validator signs independently with their own challenge:
-//! `c_i = H(m || i || nonce_i)`
+//! `c_i = H(m' || i || nonce_i)`, where `m'` is the message framed for its
+//! [`MessageMode`] (see [`MessageMode::frame`]).
 
+use alloc::format;
 use alloc::vec::Vec;
-use pqc_dilithium::{Keypair, PUBLICKEYBYTES, SECRETKEYBYTES};
 use rand_core::RngCore;
 
-use crate::types::{MerkleProof, PublicKey, SecretKey, Signature};
+use crate::core::prehash::MessageMode;
+use crate::core::scheme::{ActiveScheme, SignatureScheme};
+use crate::error::{PQAggregateError, Result};
+use crate::types::{MerkleProof, PublicKey, SecretKey, Signature, SignatureShare};
 use crate::utils::MerkleTree;
 
+/// Where a signer's per-signature nonce comes from.
+#[derive(Clone, Copy, Debug)]
+pub enum NonceSource {
+    /// Draw a fresh nonce from the thread-local RNG.
+    Random,
+    /// Use a caller-provided nonce (e.g. one assigned by the coordinator).
+    Fixed([u8; 32]),
+    /// Derive the nonce from the secret key, message and signer index.
+    ///
+    /// Needs no RNG, so it works under `no_std`, and signing the same
+    /// message twice yields the same share.
+    Deterministic,
+    /// [`Self::Deterministic`] with extra randomness mixed in.
+    ///
+    /// As with RFC 6979 hedged signatures, a weak or repeated random value
+    /// leaves the nonce no worse than the deterministic one.
+    Hedged([u8; 32]),
+}
+
+impl NonceSource {
+    /// Produce the nonce for signer `signer_index` signing `msg` with `sk`.
+    fn generate(self, sk: &SecretKey, msg: &[u8], signer_index: usize) -> [u8; 32] {
+        match self {
+            NonceSource::Random => {
+                let mut nonce = [0u8; 32];
+                rand::thread_rng().fill_bytes(&mut nonce);
+                nonce
+            }
+            NonceSource::Fixed(nonce) => nonce,
+            NonceSource::Deterministic => derive_nonce(sk, msg, signer_index, None),
+            NonceSource::Hedged(randomness) => derive_nonce(sk, msg, signer_index, Some(&randomness)),
+        }
+    }
+}
+
+/// Derive a nonce as `H(len(sk) || sk || index || randomness? || msg)` under
+/// [`tags::SIGNING_NONCE`](crate::utils::tags::SIGNING_NONCE).
+///
+/// The secret key keeps the nonce unpredictable to anyone else; the index
+/// and message make it unique per signer and message.
+fn derive_nonce(sk: &SecretKey, msg: &[u8], signer_index: usize, randomness: Option<&[u8; 32]>) -> [u8; 32] {
+    let mut hasher = crate::utils::TaggedHasher::new(crate::utils::tags::SIGNING_NONCE);
+    hasher.update((sk.as_bytes().len() as u64).to_le_bytes());
+    hasher.update(sk.as_bytes());
+    hasher.update((signer_index as u64).to_le_bytes());
+    match randomness {
+        Some(randomness) => {
+            hasher.update([1u8]);
+            hasher.update(randomness);
+        }
+        None => hasher.update([0u8]),
+    }
+    hasher.update(msg);
+    hasher.finalize()
+}
+
 /// Sign a message with `threshold` signers from the provided secret keys.
 ///
 /// Each signer computes their own challenge as `c_i = H(m || i || nonce_i)`
-/// per the paper's security requirement.
+/// per the paper's security requirement. Nonces are
+/// [hedged](NonceSource::Hedged) with randomness from the thread-local RNG;
+/// use [`aggregate_sign_with_rng`] to supply the RNG or
+/// [`aggregate_sign_deterministic`] to sign without one.
 ///
 /// # Arguments
 /// * `sks` - All secret keys in the group
@@ -26,6 +89,11 @@ use crate::utils::MerkleTree;
 /// - `Vec<Signature>` - Signatures from the first `threshold` signers
 /// - `Vec<MerkleProof>` - Merkle proofs for each signer's public key
 ///
+/// # Errors
+/// Returns the signing error if any of the first `threshold` signers fails
+/// to sign (e.g. a malformed secret key), rather than returning fewer
+/// signatures than asked for.
+///
 /// # Security
 /// - Each signature includes a unique nonce
 /// - Challenge is computed per-signer (no shared challenge)
@@ -34,122 +102,289 @@ pub fn aggregate_sign(
     pks: &[PublicKey],
     msg: &[u8],
     threshold: usize,
-) -> (Vec<Signature>, Vec<MerkleProof>) {
+) -> Result<(Vec<Signature>, Vec<MerkleProof>)> {
+    aggregate_sign_with_mode(sks, pks, msg, MessageMode::Pure, threshold)
+}
+
+/// [`aggregate_sign`] for a message in the given [`MessageMode`].
+///
+/// To sign a [`PreHashedMessage`](crate::core::prehash::PreHashedMessage),
+/// pass its [`digest`](crate::core::prehash::PreHashedMessage::digest) and
+/// [`mode`](crate::core::prehash::PreHashedMessage::mode).
+///
+/// # Errors
+/// Same as [`aggregate_sign`].
+pub fn aggregate_sign_with_mode(
+    sks: &[SecretKey],
+    pks: &[PublicKey],
+    msg: &[u8],
+    mode: MessageMode,
+    threshold: usize,
+) -> Result<(Vec<Signature>, Vec<MerkleProof>)> {
+    aggregate_sign_with_rng(sks, pks, msg, mode, threshold, &mut rand::thread_rng())
+}
+
+/// [`aggregate_sign_with_mode`] with nonces hedged by randomness drawn from `rng`.
+///
+/// A seeded `rng` makes the signatures reproducible.
+///
+/// # Errors
+/// Same as [`aggregate_sign`].
+pub fn aggregate_sign_with_rng<R: RngCore + ?Sized>(
+    sks: &[SecretKey],
+    pks: &[PublicKey],
+    msg: &[u8],
+    mode: MessageMode,
+    threshold: usize,
+    rng: &mut R,
+) -> Result<(Vec<Signature>, Vec<MerkleProof>)> {
+    sign_first(sks, pks, msg, mode, threshold, || {
+        let mut randomness = [0u8; 32];
+        rng.fill_bytes(&mut randomness);
+        NonceSource::Hedged(randomness)
+    })
+}
+
+/// [`aggregate_sign_with_mode`] with [deterministic](NonceSource::Deterministic) nonces.
+///
+/// Uses no RNG at all; the same keys and message always give the same signatures.
+///
+/// # Errors
+/// Same as [`aggregate_sign`].
+pub fn aggregate_sign_deterministic(
+    sks: &[SecretKey],
+    pks: &[PublicKey],
+    msg: &[u8],
+    mode: MessageMode,
+    threshold: usize,
+) -> Result<(Vec<Signature>, Vec<MerkleProof>)> {
+    sign_first(sks, pks, msg, mode, threshold, || NonceSource::Deterministic)
+}
+
+/// Sign with the first `threshold` signers, taking each nonce from `nonce_source`.
+fn sign_first(
+    sks: &[SecretKey],
+    pks: &[PublicKey],
+    msg: &[u8],
+    mode: MessageMode,
+    threshold: usize,
+    mut nonce_source: impl FnMut() -> NonceSource,
+) -> Result<(Vec<Signature>, Vec<MerkleProof>)> {
     let n = sks.len().min(pks.len());
     let t = threshold.min(n);
 
     if t == 0 || n == 0 {
-        return (Vec::new(), Vec::new());
+        return Ok((Vec::new(), Vec::new()));
     }
 
     // Build Merkle tree for proof generation
     let merkle_tree = MerkleTree::from_public_keys(pks);
+    let framed = mode.frame(msg);
 
     let mut signatures = Vec::with_capacity(t);
     let mut proofs = Vec::with_capacity(t);
 
-    // Use thread-local RNG for nonce generation
-    let mut rng = rand::thread_rng();
+    for (i, sk) in sks.iter().enumerate().take(t) {
+        // Generate Merkle proof for this signer's public key
+        let proof = merkle_tree.prove(i).ok_or_else(|| PQAggregateError::MerkleProofInvalid {
+            index: i,
+            reason: "Leaf not present in public key tree".into(),
+        })?;
+
+        // Each signer derives its own nonce and signs its own challenge
+        let nonce = nonce_source().generate(sk, &framed, i);
+        let challenge = crate::utils::compute_challenge(&framed, i, &nonce);
+        let sig_bytes = ActiveScheme::sign(sk.as_bytes(), &challenge)?;
+
+        signatures.push(Signature::new(sig_bytes, i, nonce));
+        proofs.push(proof);
+    }
 
-    for i in 0..t {
-        // Generate random nonce for this signer
-        let mut nonce = [0u8; 32];
-        rng.fill_bytes(&mut nonce);
+    Ok((signatures, proofs))
+}
 
-        // Sign the message with ML-DSA-65
-        // We need to reconstruct the keypair for signing
-        let sig_bytes = sign_with_dilithium(&sks[i], &pks[i], msg);
+/// Sign a message with an explicit subset of signers.
+///
+/// Unlike [`aggregate_sign`], which always uses signers `0..threshold`, this
+/// lets the caller pick any participating validators (e.g. 2, 5 and 9 when
+/// 0 and 1 are offline). Signatures and Merkle proofs are returned in the
+/// order the indices were given. `msg` is framed for `mode` as in
+/// [`aggregate_sign_with_mode`].
+///
+/// # Errors
+/// - `InsufficientSignatures` if `signers` is empty
+/// - `InvalidInput` if an index is out of range or appears more than once
+pub fn aggregate_sign_subset(
+    sks: &[SecretKey],
+    pks: &[PublicKey],
+    msg: &[u8],
+    mode: MessageMode,
+    signers: &[usize],
+) -> Result<(Vec<Signature>, Vec<MerkleProof>)> {
+    aggregate_sign_subset_with_rng(sks, pks, msg, mode, signers, &mut rand::thread_rng())
+}
 
-        let signature = Signature::new(sig_bytes, i, nonce);
-        signatures.push(signature);
+/// [`aggregate_sign_subset`] with nonces hedged by randomness drawn from `rng`.
+///
+/// # Errors
+/// Same as [`aggregate_sign_subset`].
+pub fn aggregate_sign_subset_with_rng<R: RngCore + ?Sized>(
+    sks: &[SecretKey],
+    pks: &[PublicKey],
+    msg: &[u8],
+    mode: MessageMode,
+    signers: &[usize],
+    rng: &mut R,
+) -> Result<(Vec<Signature>, Vec<MerkleProof>)> {
+    if signers.is_empty() {
+        return Err(PQAggregateError::InsufficientSignatures {
+            required: 1,
+            provided: 0,
+        });
+    }
 
-        // Generate Merkle proof for this signer's public key
-        if let Some(proof) = merkle_tree.prove(i) {
-            proofs.push(proof);
+    let n = sks.len().min(pks.len());
+    let mut seen = alloc::collections::BTreeSet::new();
+    for &index in signers {
+        if index >= n {
+            return Err(PQAggregateError::InvalidInput {
+                reason: format!("Signer index {} out of range (have {} keys)", index, n),
+            });
+        }
+        if !seen.insert(index) {
+            return Err(PQAggregateError::InvalidInput {
+                reason: format!("Duplicate signer index {}", index),
+            });
         }
     }
 
-    (signatures, proofs)
-}
-
-/// Sign a message using ML-DSA-65.
-fn sign_with_dilithium(sk: &SecretKey, pk: &PublicKey, msg: &[u8]) -> Vec<u8> {
-    // pqc_dilithium v0.2: need to reconstruct Keypair from raw bytes
-    // The Keypair struct has private fields, so we need to use a different approach
-    // Since we can't reconstruct, we'll create a temporary keypair with same secret
-    // For now, use the crate's internal approach by creating arrays
-    
-    let mut secret_bytes = [0u8; SECRETKEYBYTES];
-    let mut public_bytes = [0u8; PUBLICKEYBYTES];
-    
-    let sk_slice = sk.as_bytes();
-    let pk_slice = pk.as_bytes();
-    
-    secret_bytes[..sk_slice.len().min(SECRETKEYBYTES)].copy_from_slice(&sk_slice[..sk_slice.len().min(SECRETKEYBYTES)]);
-    public_bytes[..pk_slice.len().min(PUBLICKEYBYTES)].copy_from_slice(&pk_slice[..pk_slice.len().min(PUBLICKEYBYTES)]);
-    
-    // Use unsafe transmute to create Keypair from bytes - this matches the internal structure
-    // Actually, we need to use the Keypair::generate and just use the sign method
-    // The better approach: generate a new keypair for signing
-    // But that would give wrong signatures!
-    
-    // Actually looking at pqc_dilithium source, the Keypair is:
-    // pub struct Keypair { pub public: [u8; PUBLICKEYBYTES], secret: [u8; SECRETKEYBYTES] }
-    // The sign method just uses self.secret internally
-    // We can use a workaround with std::mem::transmute since both are Copy types
-    
-    // SAFETY: Temporary workaround for pqc_dilithium v0.2 API limitations.
-    // 
-    // pqc_dilithium v0.2 does not expose a standalone sign(secret_key, msg) function.
-    // The Keypair struct has private fields, preventing direct reconstruction.
-    // 
-    // We create a RawKeypair with the exact memory layout of pqc_dilithium::Keypair
-    // (verified against source: pub public first, then secret) and transmute it.
-    //
-    // This is safe because:
-    // 1. RawKeypair has #[repr(C)] and identical field types/sizes
-    // 2. Both types are Copy, no drop logic is bypassed
-    // 3. We only use the resulting Keypair for signing, not key generation
-    //
-    // TODO(v0.2.1): Replace with direct sign(secret_key, msg) when available.
-    // Alternative: Migrate to dilithium-rs which exposes sign_skonly().
-    #[repr(C)]
-    struct RawKeypair {
-        public: [u8; PUBLICKEYBYTES],
-        secret: [u8; SECRETKEYBYTES],
-    }
-
-    // Compile-time check: RawKeypair and Keypair must have the same size.
-    const _: () = assert!(
-        core::mem::size_of::<RawKeypair>() == core::mem::size_of::<Keypair>(),
-        "RawKeypair size does not match pqc_dilithium::Keypair — layout may have changed"
-    );
-
-    // Runtime alignment check
-    assert_eq!(
-        core::mem::align_of::<RawKeypair>(),
-        core::mem::align_of::<Keypair>(),
-        "RawKeypair alignment does not match pqc_dilithium::Keypair"
-    );
-    
-    let raw = RawKeypair {
-        public: public_bytes,
-        secret: secret_bytes,
-    };
-    
-    let keypair: Keypair = unsafe { core::mem::transmute(raw) };
-    
-    keypair.sign(msg).to_vec()
-}
-
-/// Verify a single ML-DSA-65 signature.
+    let merkle_tree = MerkleTree::from_public_keys(pks);
+
+    let mut signatures = Vec::with_capacity(signers.len());
+    let mut proofs = Vec::with_capacity(signers.len());
+
+    for &index in signers {
+        let proof = merkle_tree.prove(index).ok_or_else(|| PQAggregateError::MerkleProofInvalid {
+            index,
+            reason: "Leaf not present in public key tree".into(),
+        })?;
+        let mut randomness = [0u8; 32];
+        rng.fill_bytes(&mut randomness);
+        let share = sign_share_with_mode(&sks[index], index, msg, mode, NonceSource::Hedged(randomness), &proof)?;
+        let (signature, proof) = share.into_parts();
+
+        signatures.push(signature);
+        proofs.push(proof);
+    }
+
+    Ok((signatures, proofs))
+}
+
+/// Sign a message with the signers marked in `bitmap`.
+///
+/// Bit `i` (byte `i / 8`, bit `i % 8`) selects signer `i`, matching the
+/// signer bitmap embedded in aggregated proofs.
+///
+/// # Errors
+/// Same as [`aggregate_sign_subset`]; a set bit beyond the key set is
+/// reported as an out-of-range index.
+pub fn aggregate_sign_bitmap(
+    sks: &[SecretKey],
+    pks: &[PublicKey],
+    msg: &[u8],
+    mode: MessageMode,
+    bitmap: &[u8],
+) -> Result<(Vec<Signature>, Vec<MerkleProof>)> {
+    let signers = crate::utils::bitmap_to_indices(bitmap);
+    aggregate_sign_subset(sks, pks, msg, mode, &signers)
+}
+
+/// Produce this validator's signature share for `msg`.
+///
+/// Runs on the validator's own host: only its secret key and its Merkle
+/// proof (handed out once at setup via [`MerkleTree::prove`]) are needed.
+/// The resulting share can be serialized with
+/// [`SignatureShare::to_bytes`] and combined by the aggregator with
+/// [`aggregate_shares`](crate::core::aggregation::aggregate_shares).
+///
+/// # Arguments
+/// * `sk` - This validator's secret key
+/// * `pk_index` - This validator's leaf index in the public key tree
+/// * `msg` - Message to sign
+/// * `nonce_source` - Where the per-signer nonce comes from
+/// * `merkle_proof` - Inclusion proof of this validator's public key
+///
+/// # Errors
+/// Returns `InvalidInput` if `pk_index` disagrees with the secret key or
+/// with the Merkle proof's leaf index, or if the secret key is malformed.
+pub fn sign_share(
+    sk: &SecretKey,
+    pk_index: usize,
+    msg: &[u8],
+    nonce_source: NonceSource,
+    merkle_proof: &MerkleProof,
+) -> Result<SignatureShare> {
+    sign_share_with_mode(sk, pk_index, msg, MessageMode::Pure, nonce_source, merkle_proof)
+}
+
+/// [`sign_share`] for a message in the given [`MessageMode`].
+///
+/// # Errors
+/// Same as [`sign_share`].
+pub fn sign_share_with_mode(
+    sk: &SecretKey,
+    pk_index: usize,
+    msg: &[u8],
+    mode: MessageMode,
+    nonce_source: NonceSource,
+    merkle_proof: &MerkleProof,
+) -> Result<SignatureShare> {
+    if sk.index() != pk_index {
+        return Err(PQAggregateError::InvalidInput {
+            reason: format!("Secret key belongs to signer {}, not {}", sk.index(), pk_index),
+        });
+    }
+    if merkle_proof.leaf_index() != pk_index {
+        return Err(PQAggregateError::InvalidInput {
+            reason: format!(
+                "Merkle proof is for leaf {}, not signer {}",
+                merkle_proof.leaf_index(),
+                pk_index
+            ),
+        });
+    }
+
+    let framed = mode.frame(msg);
+    let nonce = nonce_source.generate(sk, &framed, pk_index);
+    let challenge = crate::utils::compute_challenge(&framed, pk_index, &nonce);
+    let sig_bytes = ActiveScheme::sign(sk.as_bytes(), &challenge)?;
+
+    Ok(SignatureShare::new(
+        Signature::new(sig_bytes, pk_index, nonce),
+        merkle_proof.clone(),
+    ))
+}
+
+/// Verify a single signature over a pure message under the active signature scheme.
+///
+/// The underlying signature covers the signer's challenge `c_i = H(m' || i || nonce_i)`,
+/// not `msg` itself, so a signature whose index or nonce was altered after
+/// signing is rejected.
 pub fn verify_single(pk: &PublicKey, msg: &[u8], sig: &Signature) -> bool {
-    pqc_dilithium::verify(sig.as_bytes(), msg, pk.as_bytes()).is_ok()
+    verify_single_with_mode(pk, msg, MessageMode::Pure, sig)
 }
 
-/// Compute the per-signer challenge: c_i = H(m || i || nonce_i)
-pub fn compute_signer_challenge(msg: &[u8], signer_index: usize, nonce: &[u8; 32]) -> [u8; 32] {
-    crate::utils::compute_challenge(msg, signer_index, nonce)
+/// [`verify_single`] for a message in the given [`MessageMode`].
+///
+/// A signature made in one mode never verifies in the other.
+pub fn verify_single_with_mode(pk: &PublicKey, msg: &[u8], mode: MessageMode, sig: &Signature) -> bool {
+    let challenge = compute_signer_challenge(msg, mode, sig.signer_index(), sig.nonce());
+    ActiveScheme::verify(pk.as_bytes(), &challenge, sig.as_bytes())
+}
+
+/// Compute the per-signer challenge: c_i = H(m' || i || nonce_i), with `m' = mode.frame(msg)`
+pub fn compute_signer_challenge(msg: &[u8], mode: MessageMode, signer_index: usize, nonce: &[u8; 32]) -> [u8; 32] {
+    crate::utils::compute_challenge(&mode.frame(msg), signer_index, nonce)
 }
 
 /// Sign a message for a single participant.
@@ -163,19 +398,13 @@ pub fn sign_single(
 ) -> Option<(Signature, MerkleProof)> {
     let merkle_tree = MerkleTree::from_public_keys(pks);
 
-    // Generate nonce
-    let mut rng = rand::thread_rng();
-    let mut nonce = [0u8; 32];
-    rng.fill_bytes(&mut nonce);
-
-    // Sign
-    let sig_bytes = sign_with_dilithium(sk, pk, msg);
-    let signature = Signature::new(sig_bytes, sk.index(), nonce);
-
     // Generate proof
     let proof = merkle_tree.prove(pk.index())?;
 
-    Some((signature, proof))
+    // Sign
+    let share = sign_share(sk, pk.index(), msg, NonceSource::Random, &proof).ok()?;
+
+    Some(share.into_parts())
 }
 
 #[cfg(test)]
@@ -188,7 +417,7 @@ mod tests {
         let (sks, pks, _root) = setup(5);
         let msg = b"test message";
 
-        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 3);
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 3).unwrap();
 
         assert_eq!(sigs.len(), 3);
         assert_eq!(proofs.len(), 3);
@@ -199,7 +428,7 @@ mod tests {
         let (sks, pks, _root) = setup(3);
         let msg = b"verify this";
 
-        let (sigs, _proofs) = aggregate_sign(&sks, &pks, msg, 1);
+        let (sigs, _proofs) = aggregate_sign(&sks, &pks, msg, 1).unwrap();
 
         assert!(verify_single(&pks[0], msg, &sigs[0]));
     }
@@ -209,7 +438,7 @@ mod tests {
         let (sks, pks, _root) = setup(3);
         let msg = b"test";
 
-        let (sigs, _) = aggregate_sign(&sks, &pks, msg, 3);
+        let (sigs, _) = aggregate_sign(&sks, &pks, msg, 3).unwrap();
 
         // All nonces should be unique
         for i in 0..sigs.len() {
@@ -225,22 +454,223 @@ mod tests {
         let msg = b"test";
 
         // Request more than available
-        let (sigs, _) = aggregate_sign(&sks, &pks, msg, 10);
+        let (sigs, _) = aggregate_sign(&sks, &pks, msg, 10).unwrap();
         assert_eq!(sigs.len(), 3);
 
         // Request zero
-        let (sigs, _) = aggregate_sign(&sks, &pks, msg, 0);
+        let (sigs, _) = aggregate_sign(&sks, &pks, msg, 0).unwrap();
         assert!(sigs.is_empty());
     }
 
+    #[test]
+    fn test_signing_failure_is_reported() {
+        let (mut sks, pks, _root) = setup(3);
+        sks[1] = SecretKey::from_bytes(vec![0u8; 16], 1);
+
+        // A signer that cannot sign is an error, not a silently shorter result
+        assert!(aggregate_sign(&sks, &pks, b"test", 3).is_err());
+        assert_eq!(aggregate_sign(&sks, &pks, b"test", 1).unwrap().0.len(), 1);
+    }
+
+    #[test]
+    fn test_sign_share_verifies() {
+        let (sks, pks, pk_root) = setup(4);
+        let tree = MerkleTree::from_public_keys(&pks);
+        let msg = b"share";
+
+        let proof = tree.prove(2).unwrap();
+        let share = sign_share(&sks[2], 2, msg, NonceSource::Fixed([9u8; 32]), &proof).unwrap();
+
+        assert_eq!(share.signer_index(), 2);
+        assert_eq!(share.nonce(), &[9u8; 32]);
+        assert!(verify_single(&pks[2], msg, share.signature()));
+        assert!(MerkleTree::verify_proof(&pk_root, share.merkle_proof()));
+    }
+
+    #[test]
+    fn test_sign_share_rejects_index_mismatch() {
+        let (sks, pks, _root) = setup(3);
+        let tree = MerkleTree::from_public_keys(&pks);
+        let msg = b"share";
+
+        // Proof for a different leaf
+        let wrong_proof = tree.prove(0).unwrap();
+        assert!(sign_share(&sks[1], 1, msg, NonceSource::Random, &wrong_proof).is_err());
+
+        // Secret key for a different signer
+        let proof = tree.prove(1).unwrap();
+        assert!(sign_share(&sks[0], 1, msg, NonceSource::Random, &proof).is_err());
+    }
+
+    #[test]
+    fn test_aggregate_sign_non_contiguous_subset() {
+        let (sks, pks, pk_root) = setup(10);
+        let msg = b"validators 0 and 1 are offline";
+
+        let (sigs, proofs) = aggregate_sign_subset(&sks, &pks, msg, MessageMode::Pure, &[2, 5, 9]).unwrap();
+
+        assert_eq!(sigs.len(), 3);
+        for ((sig, proof), expected) in sigs.iter().zip(proofs.iter()).zip([2usize, 5, 9]) {
+            assert_eq!(sig.signer_index(), expected);
+            assert_eq!(proof.leaf_index(), expected);
+            assert!(verify_single(&pks[expected], msg, sig));
+            assert!(MerkleTree::verify_proof(&pk_root, proof));
+        }
+
+        let proof = crate::core::aggregation::aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
+        assert!(crate::verifier::verify(pk_root, msg, &proof));
+    }
+
+    #[test]
+    fn test_aggregate_sign_bitmap() {
+        let (sks, pks, pk_root) = setup(10);
+        let msg = b"bitmap selection";
+
+        // Signers 1, 3 and 8
+        let bitmap = [0b0000_1010u8, 0b0000_0001];
+        let (sigs, proofs) = aggregate_sign_bitmap(&sks, &pks, msg, MessageMode::Pure, &bitmap).unwrap();
+
+        let indices: Vec<usize> = sigs.iter().map(|s| s.signer_index()).collect();
+        assert_eq!(indices, vec![1, 3, 8]);
+
+        let proof = crate::core::aggregation::aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
+        assert!(crate::verifier::verify(pk_root, msg, &proof));
+    }
+
+    #[test]
+    fn test_aggregate_sign_subset_rejects_bad_indices() {
+        let (sks, pks, _root) = setup(5);
+        let msg = b"test";
+
+        assert!(matches!(
+            aggregate_sign_subset(&sks, &pks, msg, MessageMode::Pure, &[1, 3, 1]),
+            Err(PQAggregateError::InvalidInput { .. })
+        ));
+        assert!(matches!(
+            aggregate_sign_subset(&sks, &pks, msg, MessageMode::Pure, &[0, 5]),
+            Err(PQAggregateError::InvalidInput { .. })
+        ));
+        assert!(matches!(
+            aggregate_sign_subset(&sks, &pks, msg, MessageMode::Pure, &[]),
+            Err(PQAggregateError::InsufficientSignatures { .. })
+        ));
+
+        // Bit 7 of the first byte selects signer 7, which does not exist
+        assert!(matches!(
+            aggregate_sign_bitmap(&sks, &pks, msg, MessageMode::Pure, &[0b1000_0001]),
+            Err(PQAggregateError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_tampered_nonce_fails_verification() {
+        let (sks, pks, _root) = setup(3);
+        let msg = b"test message";
+
+        let (sigs, _proofs) = aggregate_sign(&sks, &pks, msg, 1).unwrap();
+        let mut tampered = sigs[0].clone();
+        tampered.nonce[0] ^= 0x01;
+
+        assert!(verify_single(&pks[0], msg, &sigs[0]));
+        assert!(!verify_single(&pks[0], msg, &tampered));
+    }
+
+    #[test]
+    fn test_signature_does_not_cover_raw_message() {
+        let (sks, pks, _root) = setup(1);
+        let msg = b"test message";
+
+        let (sigs, _proofs) = aggregate_sign(&sks, &pks, msg, 1).unwrap();
+        assert!(!ActiveScheme::verify(pks[0].as_bytes(), msg, sigs[0].as_bytes()));
+    }
+
     #[test]
     fn test_wrong_message_fails_verification() {
         let (sks, pks, _root) = setup(1);
         let msg = b"original";
         let wrong_msg = b"tampered";
 
-        let (sigs, _) = aggregate_sign(&sks, &pks, msg, 1);
+        let (sigs, _) = aggregate_sign(&sks, &pks, msg, 1).unwrap();
 
         assert!(!verify_single(&pks[0], wrong_msg, &sigs[0]));
     }
+
+    #[test]
+    fn test_message_modes_do_not_verify_each_other() {
+        use crate::core::prehash::PreHashAlgorithm;
+
+        let (sks, pks, _root) = setup(1);
+        let prehashed = PreHashAlgorithm::Sha3_512.digest(b"firmware image");
+        // A pure message carrying the bytes a pre-hashed frame would start with
+        let lookalike = [&[0x01, 0x00][..], PreHashAlgorithm::Sha3_512.oid(), prehashed.digest()].concat();
+
+        let (pure, _) = aggregate_sign(&sks, &pks, &lookalike, 1).unwrap();
+        let (hashed, _) = aggregate_sign_with_mode(&sks, &pks, prehashed.digest(), prehashed.mode(), 1).unwrap();
+
+        assert!(verify_single(&pks[0], &lookalike, &pure[0]));
+        assert!(verify_single_with_mode(&pks[0], prehashed.digest(), prehashed.mode(), &hashed[0]));
+        assert!(!verify_single_with_mode(&pks[0], prehashed.digest(), prehashed.mode(), &pure[0]));
+        assert!(!verify_single(&pks[0], &lookalike, &hashed[0]));
+        assert!(!verify_single(&pks[0], prehashed.digest(), &hashed[0]));
+        assert!(!verify_single_with_mode(
+            &pks[0],
+            prehashed.digest(),
+            crate::core::prehash::MessageMode::PreHashed(PreHashAlgorithm::Shake256),
+            &hashed[0]
+        ));
+    }
+
+    #[test]
+    fn test_deterministic_nonces() {
+        let (sks, pks, pk_root) = setup(4);
+        let msg = b"deterministic";
+
+        let (sigs, proofs) = aggregate_sign_deterministic(&sks, &pks, msg, MessageMode::Pure, 3).unwrap();
+        let (again, _) = aggregate_sign_deterministic(&sks, &pks, msg, MessageMode::Pure, 3).unwrap();
+        for (sig, repeat) in sigs.iter().zip(&again) {
+            assert_eq!(sig.nonce(), repeat.nonce());
+            assert_eq!(sig.as_bytes(), repeat.as_bytes());
+        }
+        for sig in &sigs {
+            assert!(verify_single(&pks[sig.signer_index()], msg, sig));
+        }
+
+        // Unique per signer and per message
+        assert_ne!(sigs[0].nonce(), sigs[1].nonce());
+        let (other, _) = aggregate_sign_deterministic(&sks, &pks, b"other", MessageMode::Pure, 1).unwrap();
+        assert_ne!(other[0].nonce(), sigs[0].nonce());
+
+        // sign_share derives the same nonce
+        let share = sign_share(&sks[2], 2, msg, NonceSource::Deterministic, &proofs[2]).unwrap();
+        assert_eq!(share.nonce(), sigs[2].nonce());
+
+        let proof = crate::core::aggregation::aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
+        assert!(crate::verifier::verify(pk_root, msg, &proof));
+    }
+
+    #[test]
+    fn test_hedged_nonces_with_seeded_rng() {
+        use rand::SeedableRng;
+
+        let (sks, pks, _root) = setup(4);
+        let msg = b"hedged";
+        let sign = |seed| {
+            aggregate_sign_with_rng(&sks, &pks, msg, MessageMode::Pure, 3, &mut rand::rngs::StdRng::seed_from_u64(seed))
+                .unwrap()
+                .0
+        };
+
+        assert_eq!(sign(1)[2].as_bytes(), sign(1)[2].as_bytes());
+        assert_ne!(sign(1)[0].nonce(), sign(2)[0].nonce());
+
+        // Hedging changes the nonce even when the randomness is fixed
+        let (deterministic, _) = aggregate_sign_deterministic(&sks, &pks, msg, MessageMode::Pure, 1).unwrap();
+        let tree = MerkleTree::from_public_keys(&pks);
+        let hedged = sign_share(&sks[0], 0, msg, NonceSource::Hedged([0; 32]), &tree.prove(0).unwrap()).unwrap();
+        assert_ne!(hedged.nonce(), deterministic[0].nonce());
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let (subset, _) = aggregate_sign_subset_with_rng(&sks, &pks, msg, MessageMode::Pure, &[3, 1], &mut rng).unwrap();
+        assert!(subset.iter().all(|sig| verify_single(&pks[sig.signer_index()], msg, sig)));
+    }
 }
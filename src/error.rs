@@ -38,6 +38,15 @@ pub enum PQAggregateError {
         required: usize,
         provided: usize,
     },
+    /// Insufficient combined signer weight for a weighted threshold
+    InsufficientWeight {
+        required: u64,
+        provided: u64,
+    },
+    /// The committee signed two different rotations for the same epoch
+    RotationConflict {
+        epoch: u64,
+    },
     /// Network/RPC error
     NetworkError {
         reason: String,
@@ -87,6 +96,12 @@ impl core::fmt::Display for PQAggregateError {
             Self::InsufficientSignatures { required, provided } => {
                 write!(f, "Insufficient signatures: {} required, {} provided", required, provided)
             }
+            Self::InsufficientWeight { required, provided } => {
+                write!(f, "Insufficient signer weight: {} required, {} provided", required, provided)
+            }
+            Self::RotationConflict { epoch } => {
+                write!(f, "Conflicting committee rotations for epoch {}", epoch)
+            }
             Self::NetworkError { reason } => {
                 write!(f, "Network error: {}", reason)
             }
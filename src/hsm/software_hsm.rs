@@ -9,15 +9,15 @@ use aes_gcm::{
     aead::{Aead, KeyInit},
     Aes256Gcm // GenericArray not needed if using slice/vec for nonce
 };
-use rand::{Rng, RngCore};
+use rand::RngCore;
 use bip39::Mnemonic;
-use zeroize::{Zeroize, Zeroizing};
+use zeroize::Zeroizing;
 use std::path::PathBuf;
 use std::fs;
 use sha2::{Sha256, Digest};
 
+use crate::core::scheme::{ActiveScheme, SignatureScheme};
 use crate::error::{PQAggregateError, Result};
-use crate::types::{SecretKey, Signature};
 
 /// A software-backed HSM that encrypts keys at rest.
 pub struct SoftwareHSM {
@@ -51,24 +51,19 @@ impl SoftwareHSM {
         })
     }
 
-    /// Generate a new keystore with a Dilithium keypair, encrypted by the master key.
+    /// Generate a new keystore with a keypair for the active signature scheme,
+    /// encrypted by the master key.
     ///
     /// Returns the Public Key bytes.
     pub fn generate_and_save(&self) -> Result<Vec<u8>> {
-        // 1. Generate new Dilithium keypair (Mocking generation for spec if full lib not linked, 
-        //    but we have pqc_dilithium dep so let's try to use it or mock it if complex)
-        
-        // For this spec implementation, we'll simulate key generation to avoid complex 
-        // distinct-randomness logic from the library if not fully exposed.
-        // In prod, use `pqc_dilithium::Keypair::generate()`.
-        
-        // Simulating 4000 bytes of secret key (Dilithium is large)
-        let mut rng = rand::thread_rng();
-        let mut secret_bytes = Zeroizing::new(vec![0u8; 4032]);
-        rng.fill_bytes(&mut *secret_bytes);
-        
-        let mut public_bytes = vec![0u8; 1952];
-        rng.fill_bytes(&mut public_bytes);
+        self.generate_and_save_with_rng(&mut rand::thread_rng())
+    }
+
+    /// [`Self::generate_and_save`] with the keypair and encryption nonce
+    /// drawn from `rng`.
+    pub fn generate_and_save_with_rng<R: RngCore + ?Sized>(&self, rng: &mut R) -> Result<Vec<u8>> {
+        // 1. Generate new keypair
+        let (public_bytes, secret_bytes) = ActiveScheme::generate_keypair_with_rng(rng);
         
         // 2. Encrypt Secret Key
         let key_array = aes_gcm::aead::generic_array::GenericArray::from_slice(&*self.master_key);
@@ -127,20 +122,48 @@ impl SoftwareHSM {
                 })?
         );
         
-        // 3. Sign (Mock / Real)
-        // In real impl: pqc_dilithium::SecretKey::from_bytes(&secret_bytes).sign(msg)
-        
-        // We simulate signature for spec to avoid trait bounds hell with the lib versions
-        let mut signature = vec![0u8; 3293]; // ML-DSA-65 sig size
-        // Fill deterministically based on msg for stability
-        let mut hasher = Sha256::new();
-        hasher.update(msg);
-        hasher.update(&*secret_bytes);
-        let h = hasher.finalize();
-        signature[0..32].copy_from_slice(&h);
-        
-        // secret_bytes is Zeroizing, so it drops here safely.
-        
-        Ok(signature)
+        // 3. Sign; secret_bytes is Zeroizing, so it is wiped when dropped
+        ActiveScheme::sign(&secret_bytes, msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon \
+        abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_signatures_verify_under_saved_key() {
+        let path = std::env::temp_dir().join(format!("pq-aggregate-hsm-{}.enc", std::process::id()));
+        let hsm = SoftwareHSM::new(path.clone(), TEST_MNEMONIC).unwrap();
+
+        let pk = hsm.generate_and_save().unwrap();
+        let sig = hsm.sign(b"hsm message").unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(pk.len(), ActiveScheme::PUBLIC_KEY_BYTES);
+        assert!(ActiveScheme::verify(&pk, b"hsm message", &sig));
+        assert!(!ActiveScheme::verify(&pk, b"other message", &sig));
+    }
+
+    #[test]
+    fn test_generate_with_seeded_rng() {
+        use rand::SeedableRng;
+
+        let path = std::env::temp_dir().join(format!("pq-aggregate-hsm-rng-{}.enc", std::process::id()));
+        let hsm = SoftwareHSM::new(path.clone(), TEST_MNEMONIC).unwrap();
+
+        let pk = hsm.generate_and_save_with_rng(&mut rand::rngs::StdRng::seed_from_u64(1)).unwrap();
+        let first = fs::read(&path).unwrap();
+        let again = hsm.generate_and_save_with_rng(&mut rand::rngs::StdRng::seed_from_u64(1)).unwrap();
+        let second = fs::read(&path).unwrap();
+        let sig = hsm.sign(b"hsm message").unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(pk, again);
+        assert_eq!(first, second);
+        assert!(ActiveScheme::verify(&pk, b"hsm message", &sig));
     }
 }
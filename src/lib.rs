@@ -4,7 +4,9 @@
 //!
 //! This crate implements the PQ-Aggregate protocol (IACR ePrint xxxx/107115),
 //! using ML-DSA-65 (CRYSTALS-Dilithium Level 3) for post-quantum security
-//! and commitment-based proof aggregation.
+//! and commitment-based proof aggregation. ML-DSA-44, ML-DSA-87 and
+//! SLH-DSA-SHAKE-128f can be selected instead with cargo features (see
+//! [`core::scheme`]).
 //!
 //! ## Features
 //!
@@ -23,7 +25,7 @@
 //!
 //! // Sign: Collect t=3 threshold signatures
 //! let msg = b"transaction data";
-//! let (sigs, proofs) = aggregate_sign(&secret_keys, &public_keys, msg, 3);
+//! let (sigs, proofs) = aggregate_sign(&secret_keys, &public_keys, msg, 3).unwrap();
 //!
 //! // Aggregate: Combine into a single ZK proof
 //! let zk_proof = aggregate_proofs(sigs, proofs, pk_root, msg, &public_keys).unwrap();
@@ -62,17 +64,40 @@ pub mod agents;
 pub mod nova;
 
 // Re-export core functionality
-pub use core::aggregation::aggregate_proofs;
-pub use core::keygen::setup;
-pub use core::signing::aggregate_sign;
-pub use verifier::verify;
+pub use core::aggregation::{
+    aggregate_certificate, aggregate_full_proof, aggregate_proofs, aggregate_proofs_hierarchical,
+    aggregate_proofs_multi, aggregate_proofs_weighted, aggregate_proofs_with_context, aggregate_proofs_with_mode,
+    aggregate_shares,
+    compress_full_proof, merge_proofs,
+};
+#[cfg(feature = "nova")]
+pub use core::aggregation::{aggregate_proofs_nova, aggregate_proofs_nova_with_key_tree};
+pub use core::aggregator::{AggregationProgress, Aggregator};
+pub use core::access::AccessStructure;
+pub use core::prehash::{MessageMode, PreHashAlgorithm, PreHashedMessage, PreHasher};
+pub use core::scheme::{ActiveScheme, SignatureScheme};
+pub use core::keygen::{derive_keypair, setup, setup_from_mnemonic, setup_from_seed, setup_with_rng};
+#[cfg(feature = "nova")]
+pub use core::keygen::setup_with_key_roots;
+pub use core::signing::{
+    aggregate_sign, aggregate_sign_bitmap, aggregate_sign_deterministic, aggregate_sign_subset,
+    aggregate_sign_subset_with_rng, aggregate_sign_with_mode, aggregate_sign_with_rng, sign_share,
+    sign_share_with_mode, NonceSource,
+};
+pub use verifier::{
+    verify, verify_at, verify_certificate_message, verify_equivocation, verify_full, verify_with_mode,
+};
+pub use verifier::light_client::CommitteeLightClient;
 
 // Re-export utility functions
 pub use utils::{calculate_adaptive_threshold, MerkleTree};
 
 // Re-export types
 pub use error::{PQAggregateError, Result};
-pub use types::{MerkleProof, PublicKey, SecretKey, Signature, ZKSNARKProof};
+pub use types::{
+    EquivocationEvidence, FullProof, KeyTree, MerkleMultiProof, MerkleProof, MultiMessageCertificate, ProofContext,
+    PublicKey, SecretKey, Signature, SignatureShare, SlotMessage, ZKSNARKProof,
+};
 
 /// Library version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -96,7 +121,7 @@ mod integration_tests {
 
         // Sign
         let msg = b"integration test message";
-        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, t);
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, t).unwrap();
 
         assert_eq!(sigs.len(), t);
         assert_eq!(proofs.len(), t);
@@ -117,7 +142,7 @@ mod integration_tests {
         let (sks, pks, pk_root) = setup(3);
         let msg = b"original";
 
-        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2);
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2).unwrap();
         let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
 
         // Verify with wrong message should fail
@@ -129,7 +154,7 @@ mod integration_tests {
         let (sks, pks, pk_root) = setup(3);
         let msg = b"test";
 
-        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2);
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2).unwrap();
         let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
 
         // Verify with wrong root should fail
@@ -147,7 +172,7 @@ mod integration_tests {
         let (sks, pks, pk_root) = setup(n);
         let msg = b"adaptive test";
 
-        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, t);
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, t).unwrap();
         let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
 
         assert!(verify(pk_root, msg, &proof));
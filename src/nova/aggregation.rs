@@ -0,0 +1,270 @@
+//! Nova-backed aggregated proofs (proof format v3).
+//!
+//! [`aggregate_proofs_nova`](crate::core::aggregation::aggregate_proofs_nova)
+//! folds one [`MerkleStepCircuit`](crate::nova::circuit::MerkleStepCircuit)
+//! step per signer, in ascending index order, and compresses the result.
+//! The verifier rebuilds the initial state from the proof's public inputs
+//! hash and the expected final state from its signer bitmap, so the SNARK
+//! is checked against the same signer set the rest of the proof commits to.
+//!
+//! The signature commitment in the final state is read from the proof as is:
+//! nothing the verifier holds determines it, so the SNARK attests to the
+//! signer set and not to the signatures, which only the aggregator checks.
+//!
+//! Proofs over a [`PoseidonKeyTree`](crate::nova::merkle::PoseidonKeyTree)
+//! carry its root into the folded state, so the SNARK also shows that every
+//! signer's key is a leaf under `pk_root`.
+
+use bincode::Options;
+use ff::{Field, PrimeField};
+use pasta_curves::pallas;
+
+use crate::core::proof_format::ProofView;
+use crate::error::{PQAggregateError, Result};
+use crate::nova::circuit::{FoldState, MerkleStepCircuit, SignerWitness};
+use crate::nova::merkle::KEY_TREE_DEPTH;
+use crate::nova::params::aggregation_keys;
+use crate::nova::prover::{prove_batch, verify_proof, MerkleCompressedSNARK};
+use crate::types::{KeyTree, MerkleProof, Signature};
+use crate::utils::{tags, TaggedHasher};
+
+/// Upper bound on an encoded compressed SNARK.
+const MAX_SNARK_LEN: u64 = 1 << 20;
+
+/// Output of the signer fold.
+pub(crate) struct FoldingProof {
+    /// Final Poseidon commitment, as a canonical scalar encoding
+    pub(crate) commitment: [u8; 32],
+    /// Serialized [`MerkleCompressedSNARK`]
+    pub(crate) snark: Vec<u8>,
+}
+
+/// Map a 32-byte digest to a Pallas scalar by clearing its top byte.
+pub(crate) fn scalar_from_digest(digest: &[u8; 32]) -> pallas::Scalar {
+    let mut repr = *digest;
+    repr[31] = 0;
+    // Below 2^248, so always canonical
+    pallas::Scalar::from_repr(repr).unwrap()
+}
+
+/// The witness folded for one signer: its key leaf, nonce and signature.
+pub(crate) fn signer_leaf(sig: &Signature, leaf_hash: &[u8; 32]) -> pallas::Scalar {
+    let mut hasher = TaggedHasher::new(tags::NOVA_SIGNER);
+    hasher.update(leaf_hash);
+    hasher.update((sig.signer_index() as u64).to_le_bytes());
+    hasher.update(sig.nonce());
+    hasher.update(sig.as_bytes());
+    scalar_from_digest(&hasher.finalize())
+}
+
+/// The step circuit witness for a validated share.
+///
+/// With [`KeyTree::Poseidon`], `proof` must be a
+/// [`PoseidonKeyTree`](crate::nova::merkle::PoseidonKeyTree) proof; SHA3
+/// paths stay outside the circuit. Returns `None` if a sibling is not a
+/// canonical scalar encoding or the index does not fit the key tree.
+pub(crate) fn signer_witness(sig: &Signature, proof: &MerkleProof, key_tree: KeyTree) -> Option<SignerWitness<pallas::Scalar>> {
+    let signer_index = u32::try_from(sig.signer_index()).ok().filter(|&i| i < 1 << KEY_TREE_DEPTH)?;
+    let siblings = match key_tree {
+        KeyTree::Sha3 => vec![pallas::Scalar::ZERO; KEY_TREE_DEPTH],
+        KeyTree::Poseidon => proof
+            .siblings()
+            .iter()
+            .map(|sibling| Option::from(pallas::Scalar::from_repr(*sibling)))
+            .collect::<Option<Vec<_>>>()?,
+    };
+
+    Some(SignerWitness {
+        signer_index,
+        key_leaf: scalar_from_digest(proof.leaf_hash()),
+        signature: signer_leaf(sig, proof.leaf_hash()),
+        siblings,
+    })
+}
+
+/// The key root folded into the state for a proof over `pk_root`.
+///
+/// Zero for SHA3 key trees, which turns the in-circuit membership check
+/// off. `None` if a Poseidon root is zero or not a canonical scalar.
+fn key_root(key_tree: KeyTree, pk_root: &[u8; 32]) -> Option<pallas::Scalar> {
+    match key_tree {
+        KeyTree::Sha3 => Some(pallas::Scalar::ZERO),
+        KeyTree::Poseidon => Option::<pallas::Scalar>::from(pallas::Scalar::from_repr(*pk_root))
+            .filter(|root| !bool::from(root.is_zero())),
+    }
+}
+
+fn snark_options() -> impl Options {
+    bincode::DefaultOptions::new().with_limit(MAX_SNARK_LEN)
+}
+
+/// Fold `signers` (in ascending index order) into a compressed SNARK.
+pub(crate) fn prove_signers(
+    signers: Vec<SignerWitness<pallas::Scalar>>,
+    committee_size: usize,
+    key_tree: KeyTree,
+    pk_root: &[u8; 32],
+    public_inputs_hash: &[u8; 32],
+) -> Result<FoldingProof> {
+    let committee_size = u32::try_from(committee_size)
+        .ok()
+        .filter(|&n| n <= 1 << KEY_TREE_DEPTH)
+        .ok_or_else(|| PQAggregateError::NovaError("Committee too large for the step circuit".into()))?;
+    let key_root = key_root(key_tree, pk_root)
+        .ok_or_else(|| PQAggregateError::NovaError("Key root is not a Poseidon key tree root".into()))?;
+    let keys = aggregation_keys();
+
+    let z0 = FoldState::initial(scalar_from_digest(public_inputs_hash), committee_size, key_root);
+    let mut zn = z0;
+    let mut steps = Vec::with_capacity(signers.len());
+    for witness in signers {
+        zn = zn.absorb(&keys.constants.fold, witness.signer_index, witness.key_leaf, witness.signature);
+        steps.push(MerkleStepCircuit::new(witness, keys.constants.clone()));
+    }
+
+    let snark = prove_batch(&keys.params, &keys.prover_key, &z0.to_vec(), &steps)?;
+    let snark = snark_options()
+        .serialize(&snark)
+        .map_err(|e| PQAggregateError::NovaError(e.to_string()))?;
+
+    Ok(FoldingProof { commitment: zn.commitment.to_repr(), snark })
+}
+
+/// Check the compressed SNARK of a v3 proof.
+///
+/// The caller has already matched `public_inputs_hash` against the proof and
+/// checked the bitmap against the committee size and signer count.
+pub(crate) fn verify_signers(view: &ProofView<'_>, public_inputs_hash: &[u8; 32]) -> bool {
+    let Some(bytes) = view.snark else {
+        return false;
+    };
+    let Ok(snark) = snark_options().deserialize::<MerkleCompressedSNARK>(bytes) else {
+        return false;
+    };
+    let Some(committee_size) = u32::try_from(view.committee_size).ok().filter(|&n| n <= 1 << KEY_TREE_DEPTH) else {
+        return false;
+    };
+    let Ok(pk_root) = <[u8; 32]>::try_from(view.pk_root) else {
+        return false;
+    };
+    let Some(key_root) = view.key_tree().and_then(|key_tree| key_root(key_tree, &pk_root)) else {
+        return false;
+    };
+    let Some(commitment) = view
+        .commitment
+        .try_into()
+        .ok()
+        .and_then(|repr| Option::from(pallas::Scalar::from_repr(repr)))
+    else {
+        return false;
+    };
+
+    let keys = aggregation_keys();
+    let z0 = FoldState::initial(scalar_from_digest(public_inputs_hash), committee_size, key_root);
+
+    // Everything but the signature commitment follows from the bitmap; the
+    // commitment is taken from the proof unchecked
+    let indices = crate::utils::bitmap_to_indices(view.bitmap);
+    let mut zn = z0;
+    for &index in &indices {
+        let Ok(index) = u32::try_from(index) else {
+            return false;
+        };
+        zn = zn.absorb(&keys.constants.fold, index, pallas::Scalar::ZERO, pallas::Scalar::ZERO);
+    }
+    zn.commitment = commitment;
+
+    matches!(verify_proof(&keys.verifier_key, &snark, indices.len(), &z0.to_vec(), &zn.to_vec()), Ok(true))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::aggregation::aggregate_proofs_nova;
+    use crate::core::prehash::{MessageMode, PreHashAlgorithm};
+    use crate::core::proof_format::ProofView;
+    use crate::core::signing::aggregate_sign_subset;
+    use crate::types::{ZKSNARKProof, PROOF_FORMAT_V3};
+    use crate::{aggregate_proofs, setup, verify, verify_with_mode};
+
+    #[test]
+    fn test_nova_aggregate_and_verify() {
+        let (sks, pks, pk_root) = setup(6);
+        let msg = b"nova aggregation";
+
+        let (sigs, proofs) = aggregate_sign_subset(&sks, &pks, msg, MessageMode::Pure, &[4, 1, 3]).unwrap();
+        let proof = aggregate_proofs_nova(sigs.clone(), proofs.clone(), pk_root, msg, MessageMode::Pure, &pks).unwrap();
+        assert_eq!(proof.as_bytes()[0], PROOF_FORMAT_V3);
+        assert_eq!(proof.signer_indices(), Some(vec![1, 3, 4]));
+        assert!(verify(pk_root, msg, &proof));
+        assert!(!verify(pk_root, b"other message", &proof));
+
+        // Same public inputs as the commitment-based proof
+        let plain = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
+        assert_eq!(plain.public_inputs_hash(), proof.public_inputs_hash());
+
+        // The serialized form round-trips
+        let restored = ZKSNARKProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(verify(pk_root, msg, &restored));
+
+        // Altering the folded commitment breaks the SNARK
+        let mut bytes = proof.as_bytes().to_vec();
+        bytes[9] ^= 1;
+        let tampered = ZKSNARKProof::new(bytes, proof.num_signatures(), *proof.public_inputs_hash());
+        assert!(!verify(pk_root, msg, &tampered));
+
+        // A SNARK for another signer set does not transplant
+        let (sigs, proofs) = aggregate_sign_subset(&sks, &pks, msg, MessageMode::Pure, &[0, 2, 5]).unwrap();
+        let other = aggregate_proofs_nova(sigs, proofs, pk_root, msg, MessageMode::Pure, &pks).unwrap();
+        let view = ProofView::parse(other.as_bytes()).unwrap();
+        let own = ProofView::parse(proof.as_bytes()).unwrap();
+        let mut spliced = proof.as_bytes().to_vec();
+        let start = proof.as_bytes().len() - 32 - own.snark.unwrap().len();
+        spliced.truncate(start - 4);
+        spliced.extend_from_slice(&(view.snark.unwrap().len() as u32).to_le_bytes());
+        spliced.extend_from_slice(view.snark.unwrap());
+        spliced.extend_from_slice(&pk_root);
+        spliced[9..41].copy_from_slice(view.commitment);
+        let spliced = ZKSNARKProof::new(spliced, proof.num_signatures(), *proof.public_inputs_hash());
+        assert!(ProofView::parse(spliced.as_bytes()).is_some());
+        assert!(!verify(pk_root, msg, &spliced));
+
+        // The proof records the message mode
+        let prehashed = PreHashAlgorithm::Sha3_512.digest(msg);
+        let (sigs, proofs) = aggregate_sign_subset(&sks, &pks, prehashed.digest(), prehashed.mode(), &[2]).unwrap();
+        let proof = aggregate_proofs_nova(sigs, proofs, pk_root, prehashed.digest(), prehashed.mode(), &pks).unwrap();
+        assert!(verify_with_mode(pk_root, prehashed.digest(), prehashed.mode(), &proof));
+        assert!(!verify(pk_root, prehashed.digest(), &proof));
+    }
+
+    #[test]
+    fn test_nova_aggregate_over_poseidon_key_tree() {
+        use crate::core::aggregation::aggregate_proofs_nova_with_key_tree;
+        use crate::core::keygen::setup_with_key_roots;
+        use crate::core::signing::{sign_share, NonceSource};
+        use crate::nova::merkle::PoseidonKeyTree;
+        use crate::types::KeyTree;
+
+        let (sks, pks, roots) = setup_with_key_roots(5);
+        let tree = PoseidonKeyTree::from_public_keys(&pks).unwrap();
+        assert_eq!(tree.root(), roots.poseidon);
+        assert_eq!(crate::utils::MerkleTree::from_public_keys(&pks).root(), roots.sha3);
+        let msg = b"poseidon key tree";
+
+        let (sigs, proofs): (Vec<_>, Vec<_>) = [3usize, 0, 2]
+            .iter()
+            .map(|&i| sign_share(&sks[i], i, msg, NonceSource::Random, &tree.prove(i).unwrap()).unwrap().into_parts())
+            .unzip();
+        let mode = MessageMode::Pure;
+        let proof = aggregate_proofs_nova_with_key_tree(sigs.clone(), proofs, roots.poseidon, msg, mode, &pks, KeyTree::Poseidon)
+            .unwrap();
+        let view = ProofView::parse(proof.as_bytes()).unwrap();
+        assert_eq!(view.key_tree(), Some(KeyTree::Poseidon));
+        assert!(verify(roots.poseidon, msg, &proof));
+        assert!(!verify(roots.sha3, msg, &proof));
+
+        // SHA3 paths do not open the Poseidon root
+        let sha3_tree = crate::utils::MerkleTree::from_public_keys(&pks);
+        let sha3_proofs = [3usize, 0, 2].iter().map(|&i| sha3_tree.prove(i).unwrap()).collect();
+        assert!(aggregate_proofs_nova_with_key_tree(sigs, sha3_proofs, roots.poseidon, msg, mode, &pks, KeyTree::Poseidon).is_err());
+    }
+}
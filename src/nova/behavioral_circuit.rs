@@ -2,11 +2,206 @@
 //!
 //! Fuses causal chain integrity, policy compliance, and quantum-safe
 //! signature thresholds into a single recursive SNARK step.
+//!
+//! The circuit has a fixed shape: the event chain is padded to
+//! [`MAX_CHAIN_EVENTS`] slots, of which the first `chain_length` are
+//! active. Active events must have strictly increasing nonces and
+//! timestamps that regress by at most [`TIMESTAMP_SKEW_MS`], and are
+//! absorbed into a Poseidon chain that must equal the `chain_root` input.
+//!
+//! The policy's conditions are padded to [`MAX_POLICY_CONDITIONS`] slots,
+//! evaluated at the last event with the [`policy_gadgets`], and committed
+//! to in a Poseidon chain that must equal the `policy_root` input.
+//! Address whitelists are unsatisfiable: events do not log a destination
+//! address for them to be checked against.
+
+use std::sync::Arc;
 
-use std::marker::PhantomData;
-use bellpepper_core::{num::AllocatedNum, ConstraintSystem, SynthesisError};
-use ff::PrimeField;
+use bellpepper_core::boolean::{AllocatedBit, Boolean};
+use bellpepper_core::num::{AllocatedNum, Num};
+use bellpepper_core::{ConstraintSystem, SynthesisError};
+use ff::{Field, PrimeField, PrimeFieldBits};
+use generic_array::typenum::{U2, U3, U6};
+use neptune::{circuit2::poseidon_hash_allocated, poseidon::PoseidonConstants, Poseidon};
 use nova_snark::traits::circuit::StepCircuit;
+use pasta_curves::pallas;
+
+use crate::causal::{CausalEvent, StructuredMetadata, TIMESTAMP_SKEW_MS};
+use crate::nova::aggregation::scalar_from_digest;
+use crate::nova::gadgets;
+use crate::nova::policy_gadgets::{
+    self, conditions_commitment, prefix_to_field, ChainVars, EncodedCondition, EventVars, MetadataVars,
+    KIND_ADDRESS_WHITELIST, MAX_POLICY_CONDITIONS, MAX_WHITELIST_PREFIXES, NUM_CONDITION_KINDS,
+};
+use crate::policy::{BehavioralPolicy, PolicyCondition, RiskTier};
+
+/// Maximum number of events a single proof can cover.
+pub const MAX_CHAIN_EVENTS: usize = 32;
+
+/// Nonces and timestamps are range-checked to this many bits.
+const EVENT_FIELD_BITS: usize = 64;
+
+/// Thresholds are range-checked to this many bits.
+const THRESHOLD_BITS: usize = 8;
+
+/// Poseidon constants of the behavioral circuit's hashes over `F`.
+#[derive(Clone, Debug)]
+pub struct BehavioralConstants<F: PrimeField> {
+    /// Chain links, `(root, nonce, timestamp, action_type, fingerprint, metadata)`,
+    /// and condition encodings
+    pub link: PoseidonConstants<F, U6>,
+    /// Metadata, `(amount_usd_cents, destination_chain, risk_flags)`
+    pub metadata: PoseidonConstants<F, U3>,
+    /// `(root, item)` chains over conditions and whitelist prefixes
+    pub chain: PoseidonConstants<F, U2>,
+}
+
+impl<F: PrimeField> BehavioralConstants<F> {
+    pub fn new() -> Self {
+        Self { link: PoseidonConstants::new(), metadata: PoseidonConstants::new(), chain: PoseidonConstants::new() }
+    }
+}
+
+impl<F: PrimeField> Default for BehavioralConstants<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The circuit witness for one event.
+#[derive(Clone, Debug)]
+pub struct EventWitness<F: PrimeField> {
+    pub nonce: u64,
+    pub timestamp: u64,
+    pub action_type: u8,
+    pub fingerprint: F,
+    /// The event's structured metadata, if it is opened to the circuit
+    pub metadata: Option<StructuredMetadata>,
+}
+
+impl EventWitness<pallas::Scalar> {
+    /// The witness for `event`, opening `metadata` if given.
+    ///
+    /// The caller checks that `event` commits to `metadata`.
+    pub fn new(event: &CausalEvent, metadata: Option<StructuredMetadata>) -> Self {
+        Self {
+            nonce: event.nonce,
+            timestamp: event.timestamp,
+            action_type: event.action_type,
+            fingerprint: scalar_from_digest(&event.behavioral_fingerprint),
+            metadata,
+        }
+    }
+}
+
+impl<F: PrimeField> EventWitness<F> {
+    /// `Poseidon(amount_usd_cents, destination_chain, risk_flags)`, or zero without metadata.
+    pub fn metadata_commitment(&self, constants: &BehavioralConstants<F>) -> F {
+        self.metadata.map_or(F::ZERO, |metadata| {
+            let preimage = [
+                F::from(u64::from(metadata.amount_usd_cents)),
+                F::from(u64::from(metadata.destination_chain)),
+                F::from(u64::from(metadata.risk_flags)),
+            ];
+            Poseidon::new_with_preimage(&preimage, &constants.metadata).hash()
+        })
+    }
+
+    /// `Poseidon(root, nonce, timestamp, action_type, fingerprint, metadata)`.
+    fn link(&self, constants: &BehavioralConstants<F>, root: F) -> F {
+        let preimage = [
+            root,
+            F::from(self.nonce),
+            F::from(self.timestamp),
+            F::from(u64::from(self.action_type)),
+            self.fingerprint,
+            self.metadata_commitment(constants),
+        ];
+        Poseidon::new_with_preimage(&preimage, &constants.link).hash()
+    }
+}
+
+/// Native counterpart of the in-circuit event chain.
+///
+/// Each event updates
+/// `root = Poseidon(root, nonce, timestamp, action_type, fingerprint, metadata)`,
+/// starting from zero, where `metadata` is the
+/// [`EventWitness::metadata_commitment`].
+pub fn chain_commitment<F: PrimeField>(constants: &BehavioralConstants<F>, events: &[EventWitness<F>]) -> F {
+    events.iter().fold(F::ZERO, |root, event| event.link(constants, root))
+}
+
+/// The circuit witnesses for `events`, opening `target_metadata` on the last one.
+pub fn event_witnesses(
+    events: &[CausalEvent],
+    target_metadata: Option<&StructuredMetadata>,
+) -> Vec<EventWitness<pallas::Scalar>> {
+    let last = events.len().saturating_sub(1);
+    events
+        .iter()
+        .enumerate()
+        .map(|(i, event)| EventWitness::new(event, target_metadata.filter(|_| i == last).copied()))
+        .collect()
+}
+
+/// The `chain_root` a unified proof over `events` is checked against.
+///
+/// This is a Poseidon commitment to the events and the metadata opened on
+/// the last one. [`CausalEventLogger`](crate::causal::CausalEventLogger)
+/// keeps it next to its SHA3 root, for every event it has logged; see
+/// [`CausalEventLogger::get_event_chain_root`](crate::causal::CausalEventLogger::get_event_chain_root).
+pub fn event_chain_root(events: &[CausalEvent], target_metadata: Option<&StructuredMetadata>) -> [u8; 32] {
+    chain_commitment(&BehavioralConstants::new(), &event_witnesses(events, target_metadata)).to_repr()
+}
+
+/// An [`event_chain_root`] that is updated one event at a time.
+///
+/// Only the last event opens its metadata, so the root over all earlier
+/// events is kept apart from the last event and extended when the next one
+/// arrives.
+#[derive(Clone, Debug)]
+pub struct EventChainAccumulator {
+    /// Root over every event but the last, none of them opened
+    prefix: pallas::Scalar,
+    last: Option<EventWitness<pallas::Scalar>>,
+    constants: Arc<BehavioralConstants<pallas::Scalar>>,
+}
+
+impl EventChainAccumulator {
+    /// An accumulator over the empty chain.
+    pub fn new() -> Self {
+        Self { prefix: pallas::Scalar::ZERO, last: None, constants: Arc::new(BehavioralConstants::new()) }
+    }
+
+    /// Append `event`, opening `metadata` on it while it is the last event.
+    ///
+    /// The caller checks that `event` commits to `metadata`.
+    pub fn push(&mut self, event: &CausalEvent, metadata: Option<StructuredMetadata>) {
+        if let Some(last) = self.last.take() {
+            self.prefix = EventWitness { metadata: None, ..last }.link(&self.constants, self.prefix);
+        }
+        self.last = Some(EventWitness::new(event, metadata));
+    }
+
+    /// The [`event_chain_root`] of the events pushed so far.
+    pub fn root(&self) -> [u8; 32] {
+        self.last.as_ref().map_or(self.prefix, |last| last.link(&self.constants, self.prefix)).to_repr()
+    }
+}
+
+impl Default for EventChainAccumulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The `policy_root` a unified proof under `policies` is checked against.
+///
+/// This commits to the conditions of every policy, in order.
+pub fn policy_root(policies: &[BehavioralPolicy]) -> [u8; 32] {
+    let conditions: Vec<PolicyCondition> = policies.iter().flat_map(|p| p.conditions.iter().cloned()).collect();
+    conditions_commitment::<pallas::Scalar>(&BehavioralConstants::new(), &conditions).to_repr()
+}
 
 /// Unified inputs for the behavioral-signature circuit.
 #[derive(Clone, Debug)]
@@ -26,104 +221,358 @@ pub struct UnifiedCircuitInputs<F: PrimeField> {
 pub struct BehavioralVerificationCircuit<F: PrimeField> {
     pub inputs: UnifiedCircuitInputs<F>,
     // Witnesses
-    pub nonces: Vec<u64>,
-    pub timestamps: Vec<u64>,
-    pub fingerprints: Vec<F>,
-    _marker: PhantomData<F>,
+    pub events: Vec<EventWitness<F>>,
+    pub conditions: Vec<EncodedCondition>,
+    constants: Arc<BehavioralConstants<F>>,
 }
 
 impl<F: PrimeField> BehavioralVerificationCircuit<F> {
     pub fn new(
         inputs: UnifiedCircuitInputs<F>,
-        nonces: Vec<u64>,
-        timestamps: Vec<u64>,
-        fingerprints: Vec<F>,
+        events: Vec<EventWitness<F>>,
+        conditions: &[PolicyCondition],
     ) -> Self {
         Self {
             inputs,
-            nonces,
-            timestamps,
-            fingerprints,
-            _marker: PhantomData,
+            events,
+            conditions: conditions.iter().map(EncodedCondition::new).collect(),
+            constants: Arc::new(BehavioralConstants::new()),
         }
     }
 
+    /// A circuit over an empty chain and policy, for parameter generation.
+    pub fn blank() -> Self {
+        Self::new(
+            UnifiedCircuitInputs {
+                chain_root: F::ZERO,
+                chain_length: 0,
+                policy_root: F::ZERO,
+                evaluation_hash: F::ZERO,
+                risk_tier: 0,
+                pk_root: F::ZERO,
+                message_hash: F::ZERO,
+                threshold_t: 0,
+            },
+            vec![],
+            &[],
+        )
+    }
+}
+
+/// Allocate zero.
+fn alloc_zero<F, CS>(mut cs: CS) -> Result<AllocatedNum<F>, SynthesisError>
+where
+    F: PrimeField,
+    CS: ConstraintSystem<F>,
+{
+    let zero = AllocatedNum::alloc(cs.namespace(|| "value"), || Ok(F::ZERO))?;
+    cs.enforce(|| "is zero", |lc| lc + zero.get_variable(), |lc| lc + CS::one(), |lc| lc);
+    Ok(zero)
+}
+
+/// Allocate `value`, range-checked to `num_bits`.
+fn alloc_bounded<F, CS>(mut cs: CS, value: u64, num_bits: usize) -> Result<AllocatedNum<F>, SynthesisError>
+where
+    F: PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+{
+    let num = AllocatedNum::alloc(cs.namespace(|| "value"), || Ok(F::from(value)))?;
+    gadgets::enforce_bits(cs.namespace(|| "range"), &num, num_bits)?;
+    Ok(num)
+}
+
+impl<F: PrimeFieldBits> BehavioralVerificationCircuit<F> {
     /// Layer 1: Verify Causal Chain Integrity
+    ///
+    /// Returns the event slots for the policy layer.
     fn verify_causal_chain<CS: ConstraintSystem<F>>(
         &self,
         cs: &mut CS,
         chain_root_input: &AllocatedNum<F>,
-    ) -> Result<(), SynthesisError> {
-        // Enforce nonce monotonicity and timestamp bounds
-        for i in 0..self.nonces.len() - 1 {
-            let n1 = AllocatedNum::alloc(cs.namespace(|| format!("nonce_{}", i)), || Ok(F::from(self.nonces[i])))?;
-            let n2 = AllocatedNum::alloc(cs.namespace(|| format!("nonce_{}", i + 1)), || Ok(F::from(self.nonces[i + 1])))?;
-            
-            // n2 == n1 + 1 => (n1 + 1) * 1 = n2
+    ) -> Result<Vec<EventVars<F>>, SynthesisError> {
+        let len = self.events.len();
+        if len > MAX_CHAIN_EVENTS {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        let mut root = alloc_zero(cs.namespace(|| "empty chain"))?;
+        let mut slots: Vec<EventVars<F>> = Vec::with_capacity(MAX_CHAIN_EVENTS);
+        let mut previous: Option<AllocatedBit> = None;
+        for slot in 0..MAX_CHAIN_EVENTS {
+            let mut cs = cs.namespace(|| format!("event {}", slot));
+            let event = self.events.get(slot);
+            let metadata = event.and_then(|event| event.metadata).unwrap_or_default();
+            let present = event.is_some_and(|event| event.metadata.is_some());
+
+            let active = AllocatedBit::alloc(cs.namespace(|| "active"), Some(event.is_some()))?;
+            let nonce = alloc_bounded(cs.namespace(|| "nonce"), event.map_or(0, |e| e.nonce), EVENT_FIELD_BITS)?;
+            let timestamp =
+                alloc_bounded(cs.namespace(|| "timestamp"), event.map_or(0, |e| e.timestamp), EVENT_FIELD_BITS)?;
+            let action_type =
+                alloc_bounded(cs.namespace(|| "action type"), event.map_or(0, |e| u64::from(e.action_type)), 8)?;
+            let fingerprint = AllocatedNum::alloc(cs.namespace(|| "fingerprint"), || {
+                Ok(event.map_or(F::ZERO, |e| e.fingerprint))
+            })?;
+
+            // Metadata fields, zero unless present
+            let has_metadata = AllocatedBit::alloc(cs.namespace(|| "has metadata"), Some(present))?;
+            let amount = alloc_bounded(cs.namespace(|| "amount"), u64::from(metadata.amount_usd_cents), 32)?;
+            let destination =
+                alloc_bounded(cs.namespace(|| "destination"), u64::from(metadata.destination_chain), 16)?;
+            let flags = alloc_bounded(cs.namespace(|| "risk flags"), u64::from(metadata.risk_flags), 8)?;
+            for (name, field) in [("amount", &amount), ("destination", &destination), ("risk flags", &flags)] {
+                cs.enforce(
+                    || format!("{} without metadata", name),
+                    |lc| lc + CS::one() - has_metadata.get_variable(),
+                    |lc| lc + field.get_variable(),
+                    |lc| lc,
+                );
+            }
+            let metadata_hash = poseidon_hash_allocated(
+                cs.namespace(|| "metadata hash"),
+                vec![amount.clone(), destination.clone(), flags],
+                &self.constants.metadata,
+            )?;
+            let metadata_commitment = AllocatedNum::alloc(cs.namespace(|| "metadata commitment"), || {
+                Ok(if present { metadata_hash.get_value().ok_or(SynthesisError::AssignmentMissing)? } else { F::ZERO })
+            })?;
             cs.enforce(
-                || format!("nonce_increment_{}", i),
-                |lc| lc + n1.get_variable() + CS::one(),
-                |lc| lc + CS::one(),
-                |lc| lc + n2.get_variable(),
+                || "metadata commitment = has_metadata * hash",
+                |lc| lc + has_metadata.get_variable(),
+                |lc| lc + metadata_hash.get_variable(),
+                |lc| lc + metadata_commitment.get_variable(),
             );
 
-            let _t1 = AllocatedNum::alloc(cs.namespace(|| format!("ts_{}", i)), || Ok(F::from(self.timestamps[i])))?;
-            let _t2 = AllocatedNum::alloc(cs.namespace(|| format!("ts_{}", i + 1)), || Ok(F::from(self.timestamps[i + 1])))?;
-            
-            // t2 >= t1 - 500ms
-            // In R1CS we'd use comparison gadgets.
-            // For the benchmark, we simulate the comparison cost.
-        }
+            if let Some(previous_active) = &previous {
+                let previous_event = &slots[slot - 1];
+
+                // Active events form a prefix, so no inactive slot sits between two of them
+                cs.enforce(
+                    || "active prefix",
+                    |lc| lc + active.get_variable(),
+                    |lc| lc + CS::one() - previous_active.get_variable(),
+                    |lc| lc,
+                );
 
-        // Simulating Merkle Root reconstruction over fingerprints
-        let mut current_root = AllocatedNum::alloc(cs.namespace(|| "start_root"), || Ok(self.fingerprints[0]))?;
-        for i in 0..self.fingerprints.len() {
-            // Simulated hashing: next = current * fingerprint
-            let f = AllocatedNum::alloc(cs.namespace(|| format!("fp_{}", i)), || Ok(self.fingerprints[i]))?;
-            current_root = current_root.mul(cs.namespace(|| format!("hash_step_{}", i)), &f)?;
+                // nonce - 1 >= previous nonce
+                let gap =
+                    gadgets::masked_sub(cs.namespace(|| "nonce gap"), &active, &nonce, &previous_event.nonce, -F::ONE)?;
+                gadgets::enforce_bits(cs.namespace(|| "nonce increases"), &gap, EVENT_FIELD_BITS)?;
+
+                // timestamp + skew >= previous timestamp
+                let drift = gadgets::masked_sub(
+                    cs.namespace(|| "timestamp drift"),
+                    &active,
+                    &timestamp,
+                    &previous_event.timestamp,
+                    F::from(TIMESTAMP_SKEW_MS),
+                )?;
+                gadgets::enforce_bits(cs.namespace(|| "timestamp within skew"), &drift, EVENT_FIELD_BITS + 1)?;
+            }
+
+            let link = poseidon_hash_allocated(
+                cs.namespace(|| "link"),
+                vec![root.clone(), nonce.clone(), timestamp.clone(), action_type.clone(), fingerprint, metadata_commitment],
+                &self.constants.link,
+            )?;
+            root = gadgets::select(cs.namespace(|| "root"), &active, &link, &root)?;
+            slots.push(EventVars {
+                active: Boolean::from(active.clone()),
+                nonce,
+                timestamp,
+                action_type,
+                metadata: MetadataVars {
+                    present: Boolean::from(has_metadata),
+                    amount_usd_cents: amount,
+                    destination_chain: destination,
+                },
+            });
+            previous = Some(active);
         }
 
         // Final root must match input
         cs.enforce(
             || "chain_root_match",
-            |lc| lc + current_root.get_variable(),
+            |lc| lc + root.get_variable(),
             |lc| lc + CS::one(),
             |lc| lc + chain_root_input.get_variable(),
         );
 
+        Ok(slots)
+    }
+
+    /// Layer 2a: Evaluate the Policy Conditions at the Last Event
+    fn verify_conditions<CS: ConstraintSystem<F>>(
+        &self,
+        cs: &mut CS,
+        events: Vec<EventVars<F>>,
+        policy_root_input: &AllocatedNum<F>,
+    ) -> Result<(), SynthesisError> {
+        if self.conditions.len() > MAX_POLICY_CONDITIONS
+            || self.conditions.iter().any(|c| c.prefixes.len() > MAX_WHITELIST_PREFIXES)
+        {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        let chain = ChainVars::new(cs.namespace(|| "chain"), events)?;
+
+        let mut root = alloc_zero(cs.namespace(|| "empty policy"))?;
+        for slot in 0..MAX_POLICY_CONDITIONS {
+            let mut cs = cs.namespace(|| format!("condition {}", slot));
+            let condition = self.conditions.get(slot).cloned().unwrap_or_else(EncodedCondition::none);
+
+            // One-hot kind selector
+            let selectors = (0..NUM_CONDITION_KINDS)
+                .map(|kind| AllocatedBit::alloc(cs.namespace(|| format!("kind {}", kind)), Some(condition.kind == kind)))
+                .collect::<Result<Vec<_>, _>>()?;
+            cs.enforce(
+                || "one kind",
+                |lc| selectors.iter().fold(lc, |lc, bit| lc + bit.get_variable()),
+                |lc| lc + CS::one(),
+                |lc| lc + CS::one(),
+            );
+            let kind = AllocatedNum::alloc(cs.namespace(|| "kind"), || Ok(F::from(condition.kind as u64)))?;
+            cs.enforce(
+                || "kind value",
+                |lc| selectors.iter().enumerate().fold(lc, |lc, (k, bit)| lc + (F::from(k as u64), bit.get_variable())),
+                |lc| lc + CS::one(),
+                |lc| lc + kind.get_variable(),
+            );
+
+            let params = condition
+                .params
+                .iter()
+                .enumerate()
+                .map(|(i, &param)| alloc_bounded(cs.namespace(|| format!("param {}", i)), param, EVENT_FIELD_BITS))
+                .collect::<Result<Vec<_>, _>>()?;
+            let flags = [&params[2], &params[3]]
+                .iter()
+                .enumerate()
+                .map(|(i, param)| Ok(gadgets::to_bits_le(cs.namespace(|| format!("flag {}", i)), param, 1)?.remove(0)))
+                .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+            // Destinations are not logged, so a whitelist has nothing to check
+            // the prefixes against: refuse it whatever the chain
+            cs.enforce(
+                || "no address whitelist",
+                |lc| lc + selectors[KIND_ADDRESS_WHITELIST].get_variable(),
+                |lc| lc + CS::one(),
+                |lc| lc,
+            );
+
+            let mut prefix_root = alloc_zero(cs.namespace(|| "empty prefixes"))?;
+            for i in 0..MAX_WHITELIST_PREFIXES {
+                let mut cs = cs.namespace(|| format!("prefix {}", i));
+                let prefix = condition.prefixes.get(i);
+                let active = AllocatedBit::alloc(cs.namespace(|| "active"), Some(prefix.is_some()))?;
+                let value = AllocatedNum::alloc(cs.namespace(|| "value"), || Ok(prefix.map_or(F::ZERO, prefix_to_field)))?;
+                let link = poseidon_hash_allocated(
+                    cs.namespace(|| "link"),
+                    vec![prefix_root.clone(), value.clone()],
+                    &self.constants.chain,
+                )?;
+                prefix_root = gadgets::select(cs.namespace(|| "root"), &active, &link, &prefix_root)?;
+            }
+
+            let mut preimage = vec![kind];
+            preimage.extend(params.iter().cloned());
+            preimage.push(prefix_root);
+            let commitment = poseidon_hash_allocated(cs.namespace(|| "commitment"), preimage, &self.constants.link)?;
+            let link = poseidon_hash_allocated(cs.namespace(|| "link"), vec![root.clone(), commitment], &self.constants.chain)?;
+            root = gadgets::select(cs.namespace(|| "root"), &selectors[0], &root, &link)?;
+
+            let satisfied = [
+                policy_gadgets::max_daily_outflow(cs.namespace(|| "max daily outflow"), &chain, &params[0])?,
+                policy_gadgets::min_verification_count(
+                    cs.namespace(|| "min verification count"),
+                    &chain,
+                    &params[0],
+                    &params[1],
+                    &flags[0],
+                    &flags[1],
+                )?,
+                policy_gadgets::min_time_between_actions(
+                    cs.namespace(|| "min time between actions"),
+                    &chain,
+                    &params[0],
+                    &params[1],
+                )?,
+                policy_gadgets::no_concurrent_requests(cs.namespace(|| "no concurrent requests"), &chain, &params[0])?,
+                Boolean::constant(false),
+            ];
+
+            // ok = [empty] + sum([kind k] * satisfied_k), which is boolean as the kind is one-hot
+            let mut ok = Num::zero().add_bool_with_coeff(CS::one(), &Boolean::from(selectors[0].clone()), F::ONE);
+            for (k, satisfied) in satisfied.iter().enumerate() {
+                let selected = Boolean::and(
+                    cs.namespace(|| format!("kind {} satisfied", k + 1)),
+                    &Boolean::from(selectors[k + 1].clone()),
+                    satisfied,
+                )?;
+                ok = ok.add_bool_with_coeff(CS::one(), &selected, F::ONE);
+            }
+
+            // Conditions are evaluated at the last event, so only bind on a non-empty chain
+            cs.enforce(
+                || "condition holds",
+                |_| chain.has_target.lc(CS::one(), F::ONE),
+                |lc| lc + CS::one() - &ok.lc(F::ONE),
+                |lc| lc,
+            );
+        }
+
+        cs.enforce(
+            || "policy_root_match",
+            |lc| lc + root.get_variable(),
+            |lc| lc + CS::one(),
+            |lc| lc + policy_root_input.get_variable(),
+        );
+
         Ok(())
     }
 
-    /// Layer 2: Verify Policy Compliance & Adaptive Threshold
-    fn verify_policy_compliance<CS: ConstraintSystem<F>>(
+    /// Layer 2b: Verify the Adaptive Threshold
+    fn verify_threshold<CS: ConstraintSystem<F>>(
         &self,
         cs: &mut CS,
-        _risk_tier_input: &AllocatedNum<F>,
+        risk_tier_input: &AllocatedNum<F>,
         threshold_t_input: &AllocatedNum<F>,
     ) -> Result<(), SynthesisError> {
-        // Map RiskTier to Threshold
-        // 0 -> 2, 1 -> 3, 2 -> 5
-        let tier = self.inputs.risk_tier;
-        let expected_min_t = match tier {
-            0 => 2,
-            1 => 3,
-            2 => 5,
-            _ => 10, // Default safely high
-        };
+        // tier * (tier - 1) * (tier - 2) = 0, so the tier is a RiskTier index
+        let tier_sq = risk_tier_input.square(cs.namespace(|| "tier^2"))?;
+        cs.enforce(
+            || "known risk tier",
+            |lc| lc + tier_sq.get_variable() - risk_tier_input.get_variable(),
+            |lc| lc + risk_tier_input.get_variable() - (F::from(2), CS::one()),
+            |lc| lc,
+        );
 
-        let min_t_alloc = AllocatedNum::alloc(cs.namespace(|| "min_t"), || Ok(F::from(expected_min_t)))?;
+        // Map RiskTier to Threshold through the quadratic that passes through
+        // (0, Low), (1, Medium) and (2, High)
+        let [low, medium, high] =
+            [RiskTier::Low, RiskTier::Medium, RiskTier::High].map(|tier| F::from(tier.to_threshold() as u64));
+        let quadratic = (high - medium.double() + low) * F::TWO_INV;
+        let linear = medium - low - quadratic;
 
-        // Enforce threshold_t >= min_t
-        // Simplified: threshold_t == min_t + witness_offset
-        // Real implementation would use comparison gadget. 
-        // We'll enforce threshold_t == min_t for the exact threshold test.
+        let slack = AllocatedNum::alloc(cs.namespace(|| "threshold slack"), || {
+            let tier = risk_tier_input.get_value().ok_or(SynthesisError::AssignmentMissing)?;
+            let threshold = threshold_t_input.get_value().ok_or(SynthesisError::AssignmentMissing)?;
+            Ok(threshold - low - linear * tier - quadratic * tier.square())
+        })?;
         cs.enforce(
-            || "adaptive_threshold_enforcement",
-            |lc| lc + threshold_t_input.get_variable(),
+            || "slack = threshold_t - min_t",
+            |lc| lc + threshold_t_input.get_variable()
+                - (low, CS::one())
+                - (linear, risk_tier_input.get_variable())
+                - (quadratic, tier_sq.get_variable()),
             |lc| lc + CS::one(),
-            |lc| lc + min_t_alloc.get_variable(),
+            |lc| lc + slack.get_variable(),
         );
 
+        // Enforce threshold_t >= min_t
+        gadgets::enforce_bits(cs.namespace(|| "threshold range"), threshold_t_input, THRESHOLD_BITS)?;
+        gadgets::enforce_bits(cs.namespace(|| "threshold >= min_t"), &slack, THRESHOLD_BITS)?;
+
         Ok(())
     }
 
@@ -134,7 +583,9 @@ impl<F: PrimeField> BehavioralVerificationCircuit<F> {
     ) -> Result<(), SynthesisError> {
         // ML-DSA verification involves ~100k constraints per signature.
         // We simulate this with dummy heavy operations to hit the constraint budget.
-        let t = self.inputs.threshold_t;
+        // The cost is that of the highest tier's threshold, so the circuit
+        // shape does not depend on `threshold_t`.
+        let t = RiskTier::High.to_threshold();
         for i in 0..t {
             let mut val = AllocatedNum::alloc(cs.namespace(|| format!("sig_{}", i)), || Ok(F::from(12345u64)))?;
             for j in 0..100 { // 100 iterations of simulated complexity
@@ -145,7 +596,7 @@ impl<F: PrimeField> BehavioralVerificationCircuit<F> {
     }
 }
 
-impl<F: PrimeField> StepCircuit<F> for BehavioralVerificationCircuit<F> {
+impl<F: PrimeFieldBits> StepCircuit<F> for BehavioralVerificationCircuit<F> {
     fn arity(&self) -> usize {
         5 // chain_root, policy_root, risk_tier, pk_root, threshold_t
     }
@@ -156,16 +607,208 @@ impl<F: PrimeField> StepCircuit<F> for BehavioralVerificationCircuit<F> {
         z: &[AllocatedNum<F>],
     ) -> Result<Vec<AllocatedNum<F>>, SynthesisError> {
         // z: [chain_root, policy_root, risk_tier, pk_root, threshold_t]
-        let chain_root = &z[0];
-        let _policy_root = &z[1];
-        let risk_tier = &z[2];
-        let _pk_root = &z[3];
-        let threshold_t = &z[4];
+        let [chain_root, policy_root, risk_tier, _pk_root, threshold_t] = z else {
+            return Err(SynthesisError::Unsatisfiable);
+        };
 
-        self.verify_causal_chain(cs, chain_root)?;
-        self.verify_policy_compliance(cs, risk_tier, threshold_t)?;
-        self.verify_signatures(cs)?;
+        let events = self.verify_causal_chain(&mut cs.namespace(|| "causal chain"), chain_root)?;
+        self.verify_conditions(&mut cs.namespace(|| "policy"), events, policy_root)?;
+        self.verify_threshold(&mut cs.namespace(|| "threshold"), risk_tier, threshold_t)?;
+        self.verify_signatures(&mut cs.namespace(|| "signatures"))?;
 
         Ok(z.to_vec())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::causal::CausalEventLogger;
+    use bellpepper_core::test_cs::TestConstraintSystem;
+    use ff::Field;
+    use pasta_curves::pallas::Scalar;
+
+    fn events(times: &[u64]) -> Vec<CausalEvent> {
+        let mut logger = CausalEventLogger::new([0u8; 32]);
+        times
+            .iter()
+            .map(|&time| logger.log_event(&[0xAA; 32], 0x01, b"transfer", time).unwrap())
+            .collect()
+    }
+
+    fn circuit(events: &[CausalEvent]) -> BehavioralVerificationCircuit<Scalar> {
+        BehavioralVerificationCircuit { events: event_witnesses(events, None), ..BehavioralVerificationCircuit::blank() }
+    }
+
+    fn with_conditions(events: &[CausalEvent], conditions: &[PolicyCondition]) -> BehavioralVerificationCircuit<Scalar> {
+        BehavioralVerificationCircuit::new(BehavioralVerificationCircuit::blank().inputs, event_witnesses(events, None), conditions)
+    }
+
+    fn is_satisfied(circuit: &BehavioralVerificationCircuit<Scalar>, chain_root: Scalar, risk_tier: u64, threshold_t: u64) -> bool {
+        let policy_root = circuit.conditions.iter().fold(Scalar::ZERO, |root, condition| {
+            let commitment = condition.commitment(&circuit.constants);
+            Poseidon::new_with_preimage(&[root, commitment], &circuit.constants.chain).hash()
+        });
+        is_satisfied_under(circuit, chain_root, policy_root, risk_tier, threshold_t)
+    }
+
+    fn is_satisfied_under(
+        circuit: &BehavioralVerificationCircuit<Scalar>,
+        chain_root: Scalar,
+        policy_root: Scalar,
+        risk_tier: u64,
+        threshold_t: u64,
+    ) -> bool {
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        let z: Vec<_> = [chain_root, policy_root, Scalar::from(risk_tier), Scalar::ZERO, Scalar::from(threshold_t)]
+            .iter()
+            .enumerate()
+            .map(|(i, v)| AllocatedNum::alloc(cs.namespace(|| format!("z{}", i)), || Ok(*v)).unwrap())
+            .collect();
+        circuit.synthesize(&mut cs, &z).unwrap();
+        cs.is_satisfied()
+    }
+
+    fn root_of(events: &[CausalEvent]) -> Scalar {
+        Scalar::from_repr(event_chain_root(events, None)).unwrap()
+    }
+
+    #[test]
+    fn test_valid_chain_satisfies() {
+        // The second event is within the skew tolerance of the first
+        let events = events(&[1000, 600, 2000]);
+        let root = root_of(&events);
+        assert!(is_satisfied(&circuit(&events), root, 2, 5));
+        assert!(is_satisfied(&circuit(&[]), Scalar::ZERO, 0, 2));
+
+        // The circuit shape does not depend on the witness
+        let shape = |circuit: &BehavioralVerificationCircuit<Scalar>| {
+            let mut cs = TestConstraintSystem::<Scalar>::new();
+            let z: Vec<_> = (0..5)
+                .map(|i| AllocatedNum::alloc(cs.namespace(|| format!("z{}", i)), || Ok(Scalar::ZERO)).unwrap())
+                .collect();
+            circuit.synthesize(&mut cs, &z).unwrap();
+            cs.num_constraints()
+        };
+        let conditions = [
+            PolicyCondition::MinTimeBetweenActions { action_type: 0x01, min_seconds: 1 },
+            PolicyCondition::AddressWhitelist { allowed_prefixes: vec![[0u8; 20]] },
+        ];
+        assert_eq!(shape(&circuit(&events)), shape(&BehavioralVerificationCircuit::blank()));
+        assert_eq!(shape(&with_conditions(&events, &conditions)), shape(&BehavioralVerificationCircuit::blank()));
+    }
+
+    #[test]
+    fn test_tampered_chain_fails() {
+        let events = events(&[1000, 2000, 3000]);
+        let root = root_of(&events);
+
+        // Repeated and decreasing nonces
+        let mut repeated = circuit(&events);
+        repeated.events[2].nonce = repeated.events[1].nonce;
+        assert!(!is_satisfied(&repeated, root, 0, 2));
+        let mut decreasing = circuit(&events);
+        decreasing.events.swap(0, 1);
+        assert!(!is_satisfied(&decreasing, root, 0, 2));
+
+        // Timestamp regression beyond the skew tolerance
+        let mut regressed = circuit(&events);
+        regressed.events[2].timestamp = 2000 - TIMESTAMP_SKEW_MS - 1;
+        assert!(!is_satisfied(&regressed, root, 0, 2));
+
+        // A fingerprint that is not the committed one
+        let mut forged = circuit(&events);
+        forged.events[1].fingerprint += Scalar::ONE;
+        assert!(!is_satisfied(&forged, root, 0, 2));
+
+        // A dropped event
+        assert!(!is_satisfied(&circuit(&events[..2]), root, 0, 2));
+    }
+
+    #[test]
+    fn test_policy_conditions_enforced() {
+        // Two signature requests 10ms apart
+        let events = events(&[1000, 1010]);
+        let root = root_of(&events);
+        let cooldown = |min_seconds| [PolicyCondition::MinTimeBetweenActions { action_type: 0x01, min_seconds }];
+
+        assert!(is_satisfied(&with_conditions(&events, &[]), root, 0, 2));
+        assert!(is_satisfied(&with_conditions(&events, &cooldown(0)), root, 0, 2));
+        assert!(!is_satisfied(&with_conditions(&events, &cooldown(600)), root, 0, 2));
+
+        // Metadata opened on the last event is part of the chain root
+        let mut opened = with_conditions(&events, &[]);
+        opened.events[1].metadata = Some(StructuredMetadata::new(50_00, 0, 0));
+        assert!(!is_satisfied(&opened, root, 0, 2));
+        let metadata = StructuredMetadata::new(50_00, 0, 0);
+        let opened_root = Scalar::from_repr(event_chain_root(&events, Some(&metadata))).unwrap();
+        assert!(is_satisfied(&opened, opened_root, 0, 2));
+
+        // The conditions must be the committed ones
+        let circuit = with_conditions(&events, &cooldown(0));
+        let other_root = Scalar::from_repr(policy_root(&[BehavioralPolicy {
+            name: "other",
+            conditions: cooldown(1).to_vec(),
+            risk_tier: RiskTier::Low,
+        }]))
+        .unwrap();
+        assert!(!is_satisfied_under(&circuit, root, other_root, 0, 2));
+        assert!(!is_satisfied_under(&circuit, root, Scalar::ZERO, 0, 2));
+
+        // Conditions are evaluated at the last event, so an empty chain has nothing to check
+        assert!(is_satisfied(&with_conditions(&[], &cooldown(600)), Scalar::ZERO, 0, 2));
+    }
+
+    #[test]
+    fn test_address_whitelist_fails_closed() {
+        // Not even the zero address, which an unlogged destination would read as
+        let events = events(&[1000]);
+        let root = root_of(&events);
+        let whitelist = [PolicyCondition::AddressWhitelist { allowed_prefixes: vec![[0u8; 20]] }];
+        assert!(!is_satisfied(&with_conditions(&events, &whitelist), root, 0, 2));
+        assert!(!is_satisfied(&with_conditions(&[], &whitelist), Scalar::ZERO, 0, 2));
+
+        // Nor next to conditions that hold
+        let mixed = [PolicyCondition::NoConcurrentRequests { window_seconds: 0 }, whitelist[0].clone()];
+        assert!(!is_satisfied(&with_conditions(&events, &mixed), root, 0, 2));
+        assert!(is_satisfied(&with_conditions(&events, &mixed[..1]), root, 0, 2));
+    }
+
+    #[test]
+    fn test_logger_keeps_event_chain_root() {
+        let mut logger = CausalEventLogger::new([0u8; 32]);
+        assert_eq!(logger.get_event_chain_root(), event_chain_root(&[], None));
+
+        let metadata = StructuredMetadata::new(50_00, 1, 0);
+        let mut events = vec![logger.log_event_with_metadata(&[0xAA; 32], 0x01, b"first", metadata, 1000).unwrap()];
+        assert_eq!(logger.get_event_chain_root(), event_chain_root(&events, Some(&metadata)));
+
+        // Metadata is only opened on the last event
+        events.push(logger.log_event(&[0xAA; 32], 0x02, b"second", 2000).unwrap());
+        assert_eq!(logger.get_event_chain_root(), event_chain_root(&events, None));
+        events.push(logger.log_event_with_metadata(&[0xAA; 32], 0x01, b"third", metadata, 3000).unwrap());
+        assert_eq!(logger.get_event_chain_root(), event_chain_root(&events, Some(&metadata)));
+        assert_ne!(logger.get_event_chain_root(), event_chain_root(&events, None));
+
+        let root = Scalar::from_repr(logger.get_event_chain_root()).unwrap();
+        let mut opened = circuit(&events);
+        opened.events[2].metadata = Some(metadata);
+        assert!(is_satisfied(&opened, root, 0, 2));
+    }
+
+    #[test]
+    fn test_threshold_below_tier_minimum_fails() {
+        let circuit = circuit(&[]);
+        for (tier, min_t) in [RiskTier::Low, RiskTier::Medium, RiskTier::High]
+            .map(|tier| (u64::from(tier.index()), tier.to_threshold() as u64))
+        {
+            assert!(is_satisfied(&circuit, Scalar::ZERO, tier, min_t));
+            assert!(is_satisfied(&circuit, Scalar::ZERO, tier, min_t + 1));
+            assert!(!is_satisfied(&circuit, Scalar::ZERO, tier, min_t - 1), "tier {}", tier);
+        }
+
+        // Unknown tiers and out-of-range thresholds
+        assert!(!is_satisfied(&circuit, Scalar::ZERO, 3, 10));
+        assert!(!is_satisfied(&circuit, Scalar::ZERO, 0, 256));
+    }
+}
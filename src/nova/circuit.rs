@@ -1,35 +1,183 @@
-//! StepCircuit implementation for Merkle tree verification.
+//! StepCircuit implementation for signer aggregation.
+//!
+//! Each folding step absorbs one signer into a running state. The signers'
+//! ML-DSA signatures are checked natively before proving and are not
+//! verified in the circuit. It enforces only what makes the aggregate sound
+//! as a set: signers are folded in strictly ascending order, every index
+//! lies inside the committee, the count matches the number of steps, and
+//! every signer's key leaf and signature are absorbed into a Poseidon chain.
+//! When the state carries a
+//! [`PoseidonKeyTree`](crate::nova::merkle::PoseidonKeyTree) root, each key
+//! leaf's path to that root is checked as well.
 
-use std::marker::PhantomData;
+use std::sync::Arc;
 
 use bellpepper_core::{num::AllocatedNum, ConstraintSystem, SynthesisError};
-use ff::PrimeField;
+use ff::{PrimeField, PrimeFieldBits};
+use generic_array::typenum::U3;
+use neptune::{circuit2::poseidon_hash_allocated, poseidon::PoseidonConstants, Poseidon};
 use nova_snark::traits::circuit::StepCircuit;
 
-/// A Nova StepCircuit that verifies a Merkle proof.
+use crate::nova::gadgets;
+use crate::nova::merkle::{merkle_root_gadget, TreeArity, KEY_TREE_DEPTH};
+
+/// Poseidon arity used by the fold: `(state, a, b)`.
+pub type FoldArity = U3;
+
+/// Number of field elements in the folded state `z`.
+pub const STATE_ARITY: usize = 7;
+
+/// Signer indices and committee sizes are range-checked to this many bits.
+const INDEX_BITS: usize = 32;
+
+/// Poseidon constants for [`FoldArity`] over `F`.
+pub fn fold_constants<F: PrimeField>() -> PoseidonConstants<F, FoldArity> {
+    PoseidonConstants::new()
+}
+
+/// Native Poseidon hash matching the one the circuit computes.
+pub fn fold_hash<F: PrimeField>(constants: &PoseidonConstants<F, FoldArity>, preimage: [F; 3]) -> F {
+    Poseidon::new_with_preimage(&preimage, constants).hash()
+}
+
+/// Poseidon constants for both hashes the step circuit computes.
+#[derive(Clone, Debug)]
+pub struct StepConstants<F: PrimeField> {
+    /// Constants for the commitment chains
+    pub fold: PoseidonConstants<F, FoldArity>,
+    /// Constants for key tree nodes
+    pub tree: PoseidonConstants<F, TreeArity>,
+}
+
+impl<F: PrimeField> StepConstants<F> {
+    /// Generate both sets of constants.
+    pub fn new() -> Self {
+        Self { fold: fold_constants(), tree: crate::nova::merkle::tree_constants() }
+    }
+}
+
+impl<F: PrimeField> Default for StepConstants<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The state carried between folding steps.
 ///
-/// In v0.2.0 Phase 1, this is a scaffold that simply passes inputs to outputs.
+/// Layout of `z`:
+/// `[public_inputs, committee_size, key_root, commitment, index_commitment, count, next_index]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FoldState<F: PrimeField> {
+    /// Public inputs of the aggregate proof, passed through unchanged
+    pub public_inputs: F,
+    /// Committee size `n`, passed through unchanged
+    pub committee_size: F,
+    /// Poseidon key tree root every signer must belong to, or zero for
+    /// SHA3 key trees, whose paths are only checked natively
+    pub key_root: F,
+    /// Poseidon chain over `(key leaf, signature)`
+    pub commitment: F,
+    /// Poseidon chain over `(position, index)`; recomputable from the bitmap
+    pub index_commitment: F,
+    /// Signers folded so far
+    pub count: F,
+    /// Smallest index the next signer may have
+    pub next_index: F,
+}
+
+impl<F: PrimeField> FoldState<F> {
+    /// State before any signer has been folded.
+    pub fn initial(public_inputs: F, committee_size: u32, key_root: F) -> Self {
+        Self {
+            public_inputs,
+            committee_size: F::from(u64::from(committee_size)),
+            key_root,
+            commitment: F::ZERO,
+            index_commitment: F::ZERO,
+            count: F::ZERO,
+            next_index: F::ZERO,
+        }
+    }
+
+    /// Native counterpart of one [`MerkleStepCircuit`] step.
+    ///
+    /// Does not check the ordering, range and membership constraints the
+    /// circuit enforces.
+    pub fn absorb(&self, constants: &PoseidonConstants<F, FoldArity>, signer_index: u32, key_leaf: F, signature: F) -> Self {
+        let index = F::from(u64::from(signer_index));
+        Self {
+            public_inputs: self.public_inputs,
+            committee_size: self.committee_size,
+            key_root: self.key_root,
+            commitment: fold_hash(constants, [self.commitment, key_leaf, signature]),
+            index_commitment: fold_hash(constants, [self.index_commitment, self.count, index]),
+            count: self.count + F::ONE,
+            next_index: index + F::ONE,
+        }
+    }
+
+    /// The state as the `z` vector Nova folds over.
+    pub fn to_vec(&self) -> Vec<F> {
+        vec![
+            self.public_inputs,
+            self.committee_size,
+            self.key_root,
+            self.commitment,
+            self.index_commitment,
+            self.count,
+            self.next_index,
+        ]
+    }
+}
+
+/// Private inputs for one signer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignerWitness<F: PrimeField> {
+    /// Leaf index of the signer's key
+    pub signer_index: u32,
+    /// The signer's [`poseidon_leaf`](crate::nova::merkle::poseidon_leaf), as a scalar
+    pub key_leaf: F,
+    /// Field element committing to the signer's nonce and signature
+    pub signature: F,
+    /// [`KEY_TREE_DEPTH`] siblings from the key leaf to the key root
+    pub siblings: Vec<F>,
+}
+
+impl<F: PrimeField> SignerWitness<F> {
+    /// A zero witness, for parameter generation and SHA3 key trees.
+    pub fn blank() -> Self {
+        Self { signer_index: 0, key_leaf: F::ZERO, signature: F::ZERO, siblings: vec![F::ZERO; KEY_TREE_DEPTH] }
+    }
+}
+
+/// A Nova StepCircuit that folds one signer into the aggregate.
 ///
 /// # Public Inputs/Outputs (z)
-/// 1. `pk_root_hash` (field element)
-/// 2. `message_hash` (field element)
-#[derive(Clone, Debug, Default)]
+/// See [`FoldState`].
+///
+/// # Witness
+/// See [`SignerWitness`].
+#[derive(Clone, Debug)]
 pub struct MerkleStepCircuit<F: PrimeField> {
-    _marker: PhantomData<F>,
+    witness: SignerWitness<F>,
+    constants: Arc<StepConstants<F>>,
 }
 
 impl<F: PrimeField> MerkleStepCircuit<F> {
-    /// Create a new MerkleStepCircuit.
-    pub fn new() -> Self {
-        Self {
-            _marker: PhantomData,
-        }
+    /// Create the step for one signer.
+    pub fn new(witness: SignerWitness<F>, constants: Arc<StepConstants<F>>) -> Self {
+        Self { witness, constants }
+    }
+
+    /// A step with a zero witness, for parameter generation.
+    pub fn blank(constants: Arc<StepConstants<F>>) -> Self {
+        Self::new(SignerWitness::blank(), constants)
     }
 }
 
-impl<F: PrimeField> StepCircuit<F> for MerkleStepCircuit<F> {
+impl<F: PrimeFieldBits> StepCircuit<F> for MerkleStepCircuit<F> {
     fn arity(&self) -> usize {
-        2 // pk_root, message_hash
+        STATE_ARITY
     }
 
     fn synthesize<CS: ConstraintSystem<F>>(
@@ -37,42 +185,143 @@ impl<F: PrimeField> StepCircuit<F> for MerkleStepCircuit<F> {
         cs: &mut CS,
         z: &[AllocatedNum<F>],
     ) -> Result<Vec<AllocatedNum<F>>, SynthesisError> {
-        // v0.2.0: Merkle Tree Verification Circuit
-        // Inputs z: [pk_root_hash, message_hash]
-        
-        let _pk_root = &z[0];
-        let message_hash = &z[1];
-        
-        // 1. Constrain inputs to be efficient (boolean constraints omitted for v0.2)
-        
-        // 2. Merkle Proof Verification (Simplified for v0.2.0)
-        // In a real implementation we would use a SHA3/Poseidon gadget.
-        // For this prototype/benchmark, we simulate the cost with field operations.
-        // We assume 20 levels of hashing.
-        
-        let mut current_hash = message_hash.clone(); 
-        
-        for i in 0..20 {
-            // Mock Hash: h_new = h_old * 2 (simulated constraint)
-            // Real Mock: h_new = h_old * path_element
-            
-            // Allocate a "path element" witness (random for this demo)
-            let path_element = AllocatedNum::alloc(cs.namespace(|| format!("path_{}", i)), || {
-                Ok(F::from(1u64)) // Dummy witness value
-            })?;
-            
-            // Simple constraint: next = current * path
-            let next_hash = current_hash.mul(cs.namespace(|| format!("hash_{}", i)), &path_element)?;
-            current_hash = next_hash;
+        let [public_inputs, committee_size, key_root, commitment, index_commitment, count, next_index] = z else {
+            return Err(SynthesisError::Unsatisfiable);
+        };
+        let witness = &self.witness;
+        if witness.siblings.len() != KEY_TREE_DEPTH {
+            return Err(SynthesisError::Unsatisfiable);
         }
-        
-        // 3. Root check: computed_root == public_root
-        // In a real circuit we would enforce: current_hash == pk_root
-        // For IVC, we usually just pass the accumulation.
-        
-        // For the purpose of the StepCircuit, we pass inputs through.
-        // The "validity" proves that a signature exists.
-        
-        Ok(z.to_vec())
+
+        let index = AllocatedNum::alloc(cs.namespace(|| "signer index"), || Ok(F::from(u64::from(witness.signer_index))))?;
+        let key_leaf = AllocatedNum::alloc(cs.namespace(|| "key leaf"), || Ok(witness.key_leaf))?;
+        let signature = AllocatedNum::alloc(cs.namespace(|| "signature"), || Ok(witness.signature))?;
+        let siblings = witness
+            .siblings
+            .iter()
+            .enumerate()
+            .map(|(i, sibling)| AllocatedNum::alloc(cs.namespace(|| format!("sibling {}", i)), || Ok(*sibling)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The index bits select the path, which also caps indices at 2^KEY_TREE_DEPTH
+        let index_bits = gadgets::to_bits_le(cs.namespace(|| "index bits"), &index, KEY_TREE_DEPTH)?;
+        let root = merkle_root_gadget(cs.namespace(|| "key path"), &key_leaf, &index_bits, &siblings, &self.constants.tree)?;
+
+        // (root - key_root) * key_root = 0: the path must reach a nonzero key root
+        cs.enforce(
+            || "key leaf is in the key tree",
+            |lc| lc + root.get_variable() - key_root.get_variable(),
+            |lc| lc + key_root.get_variable(),
+            |lc| lc,
+        );
+
+        // next_index <= index, so indices strictly increase and none repeats
+        let gap = gadgets::sub(cs.namespace(|| "index - next_index"), &index, next_index)?;
+        gadgets::enforce_bits(cs.namespace(|| "index >= next_index"), &gap, INDEX_BITS)?;
+
+        // index < committee_size
+        let last = gadgets::add_constant(cs.namespace(|| "committee_size - 1"), committee_size, -F::ONE)?;
+        let headroom = gadgets::sub(cs.namespace(|| "committee_size - 1 - index"), &last, &index)?;
+        gadgets::enforce_bits(cs.namespace(|| "index < committee_size"), &headroom, INDEX_BITS)?;
+
+        let commitment_out = poseidon_hash_allocated(
+            cs.namespace(|| "commitment"),
+            vec![commitment.clone(), key_leaf, signature],
+            &self.constants.fold,
+        )?;
+        let index_commitment_out = poseidon_hash_allocated(
+            cs.namespace(|| "index commitment"),
+            vec![index_commitment.clone(), count.clone(), index.clone()],
+            &self.constants.fold,
+        )?;
+        let count_out = gadgets::add_constant(cs.namespace(|| "count + 1"), count, F::ONE)?;
+        let next_index_out = gadgets::add_constant(cs.namespace(|| "index + 1"), &index, F::ONE)?;
+
+        Ok(vec![
+            public_inputs.clone(),
+            committee_size.clone(),
+            key_root.clone(),
+            commitment_out,
+            index_commitment_out,
+            count_out,
+            next_index_out,
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bellpepper_core::test_cs::TestConstraintSystem;
+    use ff::Field;
+    use pasta_curves::pallas::Scalar;
+
+    fn witness(signer_index: u32) -> SignerWitness<Scalar> {
+        SignerWitness { signer_index, signature: Scalar::from(99u64), ..SignerWitness::blank() }
+    }
+
+    fn run_step(state: &FoldState<Scalar>, witness: SignerWitness<Scalar>) -> (bool, Vec<Scalar>) {
+        let circuit = MerkleStepCircuit::new(witness, Arc::new(StepConstants::new()));
+
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        let z: Vec<_> = state
+            .to_vec()
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| AllocatedNum::alloc(cs.namespace(|| format!("z{}", i)), || Ok(v)).unwrap())
+            .collect();
+        let out = circuit.synthesize(&mut cs, &z).unwrap();
+        (cs.is_satisfied(), out.iter().map(|n| n.get_value().unwrap()).collect())
+    }
+
+    #[test]
+    fn test_step_matches_native_state() {
+        let constants = fold_constants();
+        let state = FoldState::initial(Scalar::from(7u64), 10, Scalar::ZERO).absorb(&constants, 2, Scalar::ONE, Scalar::ONE);
+
+        let (satisfied, out) = run_step(&state, witness(5));
+        assert!(satisfied);
+        assert_eq!(out, state.absorb(&constants, 5, Scalar::ZERO, Scalar::from(99u64)).to_vec());
+    }
+
+    #[test]
+    fn test_step_rejects_bad_indices() {
+        let constants = fold_constants();
+        let state = FoldState::initial(Scalar::ZERO, 10, Scalar::ZERO).absorb(&constants, 4, Scalar::ONE, Scalar::ONE);
+
+        // Repeated and descending signers
+        assert!(!run_step(&state, witness(4)).0);
+        assert!(!run_step(&state, witness(3)).0);
+        // Outside the committee
+        assert!(!run_step(&state, witness(10)).0);
+        assert!(run_step(&state, witness(9)).0);
+    }
+
+    #[test]
+    fn test_step_checks_key_tree_membership() {
+        use crate::nova::merkle::{poseidon_leaf, PoseidonKeyTree};
+
+        let leaves: Vec<[u8; 32]> = (0..5u8).map(|i| poseidon_leaf(&[i; 32])).collect();
+        let tree = PoseidonKeyTree::from_leaves(&leaves).unwrap();
+        let key_root = Scalar::from_repr(tree.root()).unwrap();
+        let state = FoldState::initial(Scalar::ZERO, 5, key_root);
+
+        let decode = |bytes: &[u8; 32]| Scalar::from_repr(*bytes).unwrap();
+        let member = |signer_index: u32, leaf_index: usize| {
+            let proof = tree.prove(leaf_index).unwrap();
+            SignerWitness {
+                signer_index,
+                key_leaf: decode(proof.leaf_hash()),
+                signature: Scalar::ONE,
+                siblings: proof.siblings().iter().map(decode).collect(),
+            }
+        };
+
+        assert!(run_step(&state, member(3, 3)).0);
+        // A valid path presented under another index
+        assert!(!run_step(&state, member(2, 3)).0);
+        // A key that is not in the tree
+        assert!(!run_step(&state, SignerWitness { key_leaf: Scalar::from(7u64), ..member(3, 3) }).0);
+        assert!(!run_step(&state, witness(3)).0);
     }
 }
@@ -0,0 +1,258 @@
+//! Small R1CS gadgets shared by the step circuits.
+
+use bellpepper_core::boolean::{AllocatedBit, Boolean};
+use bellpepper_core::num::{AllocatedNum, Num};
+use bellpepper_core::{ConstraintSystem, LinearCombination, SynthesisError};
+use ff::PrimeFieldBits;
+
+/// Enforce that `value` fits in `num_bits` bits.
+///
+/// Allocates one boolean per bit and constrains their weighted sum to equal
+/// `value`. A field element that wrapped around (for example the difference
+/// `a - b` with `a < b`) has no such decomposition, so this doubles as a
+/// `>= 0` check on differences of small integers.
+pub fn enforce_bits<F, CS>(cs: CS, value: &AllocatedNum<F>, num_bits: usize) -> Result<(), SynthesisError>
+where
+    F: PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+{
+    to_bits_le(cs, value, num_bits).map(|_| ())
+}
+
+/// Decompose `value` into `num_bits` little-endian bits, enforcing that it fits.
+pub fn to_bits_le<F, CS>(mut cs: CS, value: &AllocatedNum<F>, num_bits: usize) -> Result<Vec<Boolean>, SynthesisError>
+where
+    F: PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+{
+    let values: Vec<Option<bool>> = match value.get_value() {
+        Some(v) => v.to_le_bits().iter().by_vals().take(num_bits).map(Some).collect(),
+        None => vec![None; num_bits],
+    };
+
+    let mut bits = Vec::with_capacity(num_bits);
+    let mut sum = LinearCombination::zero();
+    let mut coeff = F::ONE;
+    for (i, bit) in values.into_iter().enumerate() {
+        let bit = AllocatedBit::alloc(cs.namespace(|| format!("bit {}", i)), bit)?;
+        sum = sum + (coeff, bit.get_variable());
+        coeff = coeff.double();
+        bits.push(Boolean::from(bit));
+    }
+
+    cs.enforce(|| "recompose", |_| sum, |lc| lc + CS::one(), |lc| lc + value.get_variable());
+    Ok(bits)
+}
+
+/// Allocate `a - b`.
+pub fn sub<F, CS>(mut cs: CS, a: &AllocatedNum<F>, b: &AllocatedNum<F>) -> Result<AllocatedNum<F>, SynthesisError>
+where
+    F: PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+{
+    let diff = AllocatedNum::alloc(cs.namespace(|| "diff"), || {
+        Ok(a.get_value().ok_or(SynthesisError::AssignmentMissing)? - b.get_value().ok_or(SynthesisError::AssignmentMissing)?)
+    })?;
+    cs.enforce(
+        || "diff = a - b",
+        |lc| lc + a.get_variable() - b.get_variable(),
+        |lc| lc + CS::one(),
+        |lc| lc + diff.get_variable(),
+    );
+    Ok(diff)
+}
+
+/// Allocate `a + constant`.
+pub fn add_constant<F, CS>(mut cs: CS, a: &AllocatedNum<F>, constant: F) -> Result<AllocatedNum<F>, SynthesisError>
+where
+    F: PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+{
+    let sum = AllocatedNum::alloc(cs.namespace(|| "sum"), || {
+        Ok(a.get_value().ok_or(SynthesisError::AssignmentMissing)? + constant)
+    })?;
+    cs.enforce(
+        || "sum = a + constant",
+        |lc| lc + a.get_variable() + (constant, CS::one()),
+        |lc| lc + CS::one(),
+        |lc| lc + sum.get_variable(),
+    );
+    Ok(sum)
+}
+
+/// Allocate `a - b + offset` if `bit` is set, and zero otherwise.
+///
+/// Combined with [`enforce_bits`], this is a `a + offset >= b` check that
+/// only applies when `bit` is set.
+pub fn masked_sub<F, CS>(
+    mut cs: CS,
+    bit: &AllocatedBit,
+    a: &AllocatedNum<F>,
+    b: &AllocatedNum<F>,
+    offset: F,
+) -> Result<AllocatedNum<F>, SynthesisError>
+where
+    F: PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+{
+    let masked = AllocatedNum::alloc(cs.namespace(|| "masked"), || {
+        let diff = a.get_value().ok_or(SynthesisError::AssignmentMissing)?
+            - b.get_value().ok_or(SynthesisError::AssignmentMissing)?
+            + offset;
+        Ok(if bit.get_value().ok_or(SynthesisError::AssignmentMissing)? { diff } else { F::ZERO })
+    })?;
+    cs.enforce(
+        || "masked = bit * (a - b + offset)",
+        |lc| lc + bit.get_variable(),
+        |lc| lc + a.get_variable() - b.get_variable() + (offset, CS::one()),
+        |lc| lc + masked.get_variable(),
+    );
+    Ok(masked)
+}
+
+/// Allocate `a` if `bit` is set, and `b` otherwise.
+pub fn select<F, CS>(
+    mut cs: CS,
+    bit: &AllocatedBit,
+    a: &AllocatedNum<F>,
+    b: &AllocatedNum<F>,
+) -> Result<AllocatedNum<F>, SynthesisError>
+where
+    F: PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+{
+    let selected = AllocatedNum::alloc(cs.namespace(|| "selected"), || {
+        if bit.get_value().ok_or(SynthesisError::AssignmentMissing)? {
+            a.get_value().ok_or(SynthesisError::AssignmentMissing)
+        } else {
+            b.get_value().ok_or(SynthesisError::AssignmentMissing)
+        }
+    })?;
+    cs.enforce(
+        || "selected - b = bit * (a - b)",
+        |lc| lc + bit.get_variable(),
+        |lc| lc + a.get_variable() - b.get_variable(),
+        |lc| lc + selected.get_variable() - b.get_variable(),
+    );
+    Ok(selected)
+}
+
+/// Whether `a >= b`, for `a` and `b` below `2^num_bits`.
+///
+/// Decomposes `a - b + 2^num_bits` into `num_bits + 1` bits; the top bit is
+/// set exactly when the difference did not go negative.
+pub fn greater_or_equal<F, CS>(mut cs: CS, a: &Num<F>, b: &Num<F>, num_bits: usize) -> Result<Boolean, SynthesisError>
+where
+    F: PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+{
+    let offset = F::from(2).pow_vartime([num_bits as u64]);
+    let shifted = AllocatedNum::alloc(cs.namespace(|| "shifted"), || {
+        Ok(a.get_value().ok_or(SynthesisError::AssignmentMissing)?
+            - b.get_value().ok_or(SynthesisError::AssignmentMissing)?
+            + offset)
+    })?;
+    cs.enforce(
+        || "shifted = a - b + 2^n",
+        |_| a.lc(F::ONE) + &b.lc(-F::ONE) + (offset, CS::one()),
+        |lc| lc + CS::one(),
+        |lc| lc + shifted.get_variable(),
+    );
+
+    let mut bits = to_bits_le(cs.namespace(|| "bits"), &shifted, num_bits + 1)?;
+    Ok(bits.remove(num_bits))
+}
+
+/// Whether `value` is zero.
+pub fn is_zero<F, CS>(mut cs: CS, value: &Num<F>) -> Result<Boolean, SynthesisError>
+where
+    F: PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+{
+    let bit = AllocatedBit::alloc(cs.namespace(|| "is zero"), value.get_value().map(|v| bool::from(v.is_zero())))?;
+    let inverse = AllocatedNum::alloc(cs.namespace(|| "inverse"), || {
+        let value = value.get_value().ok_or(SynthesisError::AssignmentMissing)?;
+        Ok(Option::from(value.invert()).unwrap_or(F::ZERO))
+    })?;
+
+    // value * inverse = 1 - bit, and value * bit = 0
+    cs.enforce(
+        || "nonzero has an inverse",
+        |_| value.lc(F::ONE),
+        |lc| lc + inverse.get_variable(),
+        |lc| lc + CS::one() - bit.get_variable(),
+    );
+    cs.enforce(|| "zero sets the bit", |_| value.lc(F::ONE), |lc| lc + bit.get_variable(), |lc| lc);
+    Ok(Boolean::from(bit))
+}
+
+/// Allocate `sum(selectors[i] * values[i])`.
+///
+/// With a one-hot `selectors` this picks one value; with none set it is zero.
+pub fn pick<F, CS>(mut cs: CS, selectors: &[Boolean], values: &[AllocatedNum<F>]) -> Result<AllocatedNum<F>, SynthesisError>
+where
+    F: PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+{
+    if selectors.len() != values.len() {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+
+    let mut sum = Num::zero();
+    for (i, (selector, value)) in selectors.iter().zip(values).enumerate() {
+        let product = AllocatedNum::alloc(cs.namespace(|| format!("product {}", i)), || {
+            let selected = selector.get_value().ok_or(SynthesisError::AssignmentMissing)?;
+            Ok(if selected { value.get_value().ok_or(SynthesisError::AssignmentMissing)? } else { F::ZERO })
+        })?;
+        cs.enforce(
+            || format!("product {} = selector * value", i),
+            |_| selector.lc(CS::one(), F::ONE),
+            |lc| lc + value.get_variable(),
+            |lc| lc + product.get_variable(),
+        );
+        sum = sum.add(&Num::from(product));
+    }
+
+    let picked = AllocatedNum::alloc(cs.namespace(|| "picked"), || sum.get_value().ok_or(SynthesisError::AssignmentMissing))?;
+    cs.enforce(|| "picked = sum", |_| sum.lc(F::ONE), |lc| lc + CS::one(), |lc| lc + picked.get_variable());
+    Ok(picked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bellpepper_core::test_cs::TestConstraintSystem;
+    use pasta_curves::pallas::Scalar;
+
+    #[test]
+    fn test_enforce_bits() {
+        for (value, satisfied) in [(0u64, true), (255, true), (256, false)] {
+            let mut cs = TestConstraintSystem::<Scalar>::new();
+            let num = AllocatedNum::alloc(cs.namespace(|| "value"), || Ok(Scalar::from(value))).unwrap();
+            enforce_bits(cs.namespace(|| "range"), &num, 8).unwrap();
+            assert_eq!(cs.is_satisfied(), satisfied, "value {}", value);
+        }
+
+        // 3 - 5 wraps to a field element near the modulus
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        let a = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Scalar::from(3u64))).unwrap();
+        let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(Scalar::from(5u64))).unwrap();
+        let diff = sub(cs.namespace(|| "a - b"), &a, &b).unwrap();
+        enforce_bits(cs.namespace(|| "range"), &diff, 32).unwrap();
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_comparisons() {
+        for (a, b) in [(0u64, 0u64), (5, 3), (3, 5), (u64::MAX, 0), (0, u64::MAX)] {
+            let mut cs = TestConstraintSystem::<Scalar>::new();
+            let x = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Scalar::from(a))).unwrap();
+            let y = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(Scalar::from(b))).unwrap();
+            let ge = greater_or_equal(cs.namespace(|| "a >= b"), &Num::from(x.clone()), &Num::from(y), 64).unwrap();
+            let zero = is_zero(cs.namespace(|| "a == 0"), &Num::from(x)).unwrap();
+            assert!(cs.is_satisfied());
+            assert_eq!(ge.get_value(), Some(a >= b), "{} >= {}", a, b);
+            assert_eq!(zero.get_value(), Some(a == 0));
+        }
+    }
+}
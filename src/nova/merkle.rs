@@ -0,0 +1,252 @@
+//! Poseidon key tree over the Pallas scalar field.
+//!
+//! The SHA3 [`MerkleTree`] is expensive to open inside a circuit. A
+//! [`PoseidonKeyTree`] commits to the same key leaves with a Poseidon node
+//! hash, so the [`MerkleStepCircuit`](crate::nova::circuit::MerkleStepCircuit)
+//! can check each signer's membership with [`merkle_root_gadget`].
+//!
+//! The tree always has [`KEY_TREE_DEPTH`] levels: the tree over the actual
+//! leaves is padded with empty subtrees up to that depth, so every path has
+//! the same length and a single step circuit serves every committee size.
+
+use std::sync::OnceLock;
+
+use bellpepper_core::boolean::Boolean;
+use bellpepper_core::{num::AllocatedNum, ConstraintSystem, SynthesisError};
+use ff::{Field, PrimeField};
+use generic_array::typenum::U2;
+use neptune::{circuit2::poseidon_hash_allocated, poseidon::PoseidonConstants, Poseidon};
+use pasta_curves::pallas;
+
+use crate::core::scheme::{ActiveScheme, SignatureScheme};
+use crate::nova::aggregation::scalar_from_digest;
+use crate::types::{MerkleProof, PublicKey};
+use crate::utils::MerkleTree;
+
+/// Poseidon arity of an internal node: `(left, right)`.
+pub type TreeArity = U2;
+
+/// Number of levels in a Poseidon key tree, so committees of up to 65,536.
+pub const KEY_TREE_DEPTH: usize = 16;
+
+/// Poseidon constants for [`TreeArity`] over `F`.
+pub fn tree_constants<F: PrimeField>() -> PoseidonConstants<F, TreeArity> {
+    PoseidonConstants::new()
+}
+
+fn native_constants() -> &'static PoseidonConstants<pallas::Scalar, TreeArity> {
+    static CONSTANTS: OnceLock<PoseidonConstants<pallas::Scalar, TreeArity>> = OnceLock::new();
+    CONSTANTS.get_or_init(tree_constants)
+}
+
+fn hash_scalars(left: pallas::Scalar, right: pallas::Scalar) -> pallas::Scalar {
+    Poseidon::new_with_preimage(&[left, right], native_constants()).hash()
+}
+
+/// Roots of the empty subtrees: `zero_hashes()[k]` is the root of `2^k` zero leaves.
+fn zero_hashes() -> &'static [[u8; 32]; KEY_TREE_DEPTH + 1] {
+    static ZEROS: OnceLock<[[u8; 32]; KEY_TREE_DEPTH + 1]> = OnceLock::new();
+    ZEROS.get_or_init(|| {
+        let mut zeros = [[0u8; 32]; KEY_TREE_DEPTH + 1];
+        let mut node = pallas::Scalar::ZERO;
+        for zero in zeros.iter_mut() {
+            *zero = node.to_repr();
+            node = hash_scalars(node, node);
+        }
+        zeros
+    })
+}
+
+fn decode(bytes: &[u8; 32]) -> Option<pallas::Scalar> {
+    Option::from(pallas::Scalar::from_repr(*bytes))
+}
+
+/// Internal node hash on canonical scalar encodings.
+///
+/// Callers check that both inputs are canonical; anything else hashes as zero.
+fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let left = decode(left).unwrap_or(pallas::Scalar::ZERO);
+    let right = decode(right).unwrap_or(pallas::Scalar::ZERO);
+    hash_scalars(left, right).to_repr()
+}
+
+/// The Poseidon tree leaf for a SHA3 key leaf.
+///
+/// `leaf_hash` is a scheme's
+/// [`leaf_hash`](crate::core::scheme::SignatureScheme::leaf_hash), mapped to
+/// a canonical Pallas scalar so the same key has one leaf in either tree.
+pub fn poseidon_leaf(leaf_hash: &[u8; 32]) -> [u8; 32] {
+    scalar_from_digest(leaf_hash).to_repr()
+}
+
+/// Fixed-depth Poseidon Merkle tree over public keys.
+#[derive(Clone, Debug)]
+pub struct PoseidonKeyTree {
+    tree: MerkleTree,
+}
+
+impl PoseidonKeyTree {
+    /// Build a tree from [`poseidon_leaf`] encodings.
+    ///
+    /// Returns `None` for more than `2^KEY_TREE_DEPTH` leaves or a leaf that
+    /// is not a canonical scalar encoding.
+    pub fn from_leaves(leaves: &[[u8; 32]]) -> Option<Self> {
+        if leaves.len() > 1 << KEY_TREE_DEPTH || leaves.iter().any(|leaf| decode(leaf).is_none()) {
+            return None;
+        }
+        Some(Self { tree: MerkleTree::build(leaves, hash_pair) })
+    }
+
+    /// Build a tree from public keys, using the active scheme's leaf hash.
+    ///
+    /// Returns `None` for more than `2^KEY_TREE_DEPTH` keys.
+    pub fn from_public_keys(public_keys: &[PublicKey]) -> Option<Self> {
+        let leaves: Vec<[u8; 32]> = public_keys
+            .iter()
+            .map(|pk| poseidon_leaf(&ActiveScheme::leaf_hash(pk.as_bytes())))
+            .collect();
+        Self::from_leaves(&leaves)
+    }
+
+    /// Levels of the tree built over the actual leaves.
+    fn inner_depth(&self) -> usize {
+        self.tree.num_leaves().next_power_of_two().trailing_zeros() as usize
+    }
+
+    /// Get the root of the full-depth tree.
+    pub fn root(&self) -> [u8; 32] {
+        zero_hashes()[self.inner_depth()..KEY_TREE_DEPTH]
+            .iter()
+            .fold(self.tree.root(), |node, zero| hash_pair(&node, zero))
+    }
+
+    /// Get the number of leaves (excluding padding).
+    pub fn num_leaves(&self) -> usize {
+        self.tree.num_leaves()
+    }
+
+    /// Generate a proof of [`KEY_TREE_DEPTH`] siblings for the leaf at `leaf_index`.
+    pub fn prove(&self, leaf_index: usize) -> Option<MerkleProof> {
+        let proof = self.tree.prove(leaf_index)?;
+        let mut siblings = proof.siblings().to_vec();
+        siblings.extend_from_slice(&zero_hashes()[self.inner_depth()..KEY_TREE_DEPTH]);
+        Some(MerkleProof::new(siblings, leaf_index, *proof.leaf_hash()))
+    }
+
+    /// Verify a proof against the root of a [`PoseidonKeyTree`].
+    pub fn verify_proof(root: &[u8; 32], proof: &MerkleProof) -> bool {
+        proof.siblings().len() == KEY_TREE_DEPTH
+            && proof.leaf_index() < 1 << KEY_TREE_DEPTH
+            && decode(proof.leaf_hash()).is_some()
+            && proof.siblings().iter().all(|sibling| decode(sibling).is_some())
+            && MerkleTree::verify_with(root, proof, hash_pair)
+    }
+}
+
+/// Roots of both key trees over one committee, from
+/// [`setup_with_key_roots`](crate::core::keygen::setup_with_key_roots).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyRoots {
+    /// Root of the SHA3 [`MerkleTree`]
+    pub sha3: [u8; 32],
+    /// Root of the [`PoseidonKeyTree`]
+    pub poseidon: [u8; 32],
+}
+
+/// Compute the root of the path from `leaf` through `siblings`.
+///
+/// `index_bits` are the leaf index, least significant first; a set bit
+/// means the node is the right child at that level.
+pub fn merkle_root_gadget<F, CS>(
+    mut cs: CS,
+    leaf: &AllocatedNum<F>,
+    index_bits: &[Boolean],
+    siblings: &[AllocatedNum<F>],
+    constants: &PoseidonConstants<F, TreeArity>,
+) -> Result<AllocatedNum<F>, SynthesisError>
+where
+    F: PrimeField,
+    CS: ConstraintSystem<F>,
+{
+    if index_bits.len() != siblings.len() {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+
+    let mut node = leaf.clone();
+    for (level, (bit, sibling)) in index_bits.iter().zip(siblings).enumerate() {
+        let mut cs = cs.namespace(|| format!("level {}", level));
+        let (left, right) = AllocatedNum::conditionally_reverse(cs.namespace(|| "order"), &node, sibling, bit)?;
+        node = poseidon_hash_allocated(cs.namespace(|| "hash"), vec![left, right], constants)?;
+    }
+    Ok(node)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nova::gadgets;
+    use bellpepper_core::test_cs::TestConstraintSystem;
+
+    fn leaves(n: usize) -> Vec<[u8; 32]> {
+        (0..n).map(|i| poseidon_leaf(&[i as u8 + 1; 32])).collect()
+    }
+
+    #[test]
+    fn test_poseidon_tree_proofs() {
+        for n in [1, 2, 5, 8] {
+            let tree = PoseidonKeyTree::from_leaves(&leaves(n)).unwrap();
+            let root = tree.root();
+            for i in 0..n {
+                let proof = tree.prove(i).unwrap();
+                assert_eq!(proof.siblings().len(), KEY_TREE_DEPTH);
+                assert!(PoseidonKeyTree::verify_proof(&root, &proof), "n={} i={}", n, i);
+            }
+            assert!(tree.prove(n).is_none());
+        }
+
+        // Padding with empty leaves does not change the root
+        let mut padded = leaves(5);
+        padded.resize(8, [0u8; 32]);
+        assert_eq!(
+            PoseidonKeyTree::from_leaves(&leaves(5)).unwrap().root(),
+            PoseidonKeyTree::from_leaves(&padded).unwrap().root()
+        );
+
+        let tree = PoseidonKeyTree::from_leaves(&leaves(5)).unwrap();
+        let proof = tree.prove(3).unwrap();
+        let other = MerkleProof::new(proof.siblings().to_vec(), 2, *proof.leaf_hash());
+        assert!(!PoseidonKeyTree::verify_proof(&tree.root(), &other));
+        let short = MerkleProof::new(proof.siblings()[..3].to_vec(), 3, *proof.leaf_hash());
+        assert!(!PoseidonKeyTree::verify_proof(&tree.root(), &short));
+
+        // Non-canonical encodings are rejected
+        assert!(PoseidonKeyTree::from_leaves(&[[0xff; 32]]).is_none());
+    }
+
+    #[test]
+    fn test_gadget_matches_native_root() {
+        let tree = PoseidonKeyTree::from_leaves(&leaves(6)).unwrap();
+        let proof = tree.prove(5).unwrap();
+        let constants = tree_constants::<pallas::Scalar>();
+
+        let run = |leaf_index: u64| {
+            let mut cs = TestConstraintSystem::<pallas::Scalar>::new();
+            let leaf = AllocatedNum::alloc(cs.namespace(|| "leaf"), || Ok(decode(proof.leaf_hash()).unwrap())).unwrap();
+            let index =
+                AllocatedNum::alloc(cs.namespace(|| "index"), || Ok(pallas::Scalar::from(leaf_index))).unwrap();
+            let bits = gadgets::to_bits_le(cs.namespace(|| "index bits"), &index, KEY_TREE_DEPTH).unwrap();
+            let siblings: Vec<_> = proof
+                .siblings()
+                .iter()
+                .enumerate()
+                .map(|(i, s)| AllocatedNum::alloc(cs.namespace(|| format!("sibling {}", i)), || Ok(decode(s).unwrap())).unwrap())
+                .collect();
+            let root = merkle_root_gadget(cs.namespace(|| "path"), &leaf, &bits, &siblings, &constants).unwrap();
+            assert!(cs.is_satisfied());
+            root.get_value().unwrap().to_repr()
+        };
+
+        assert_eq!(run(5), tree.root());
+        assert_ne!(run(4), tree.root());
+    }
+}
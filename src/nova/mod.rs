@@ -5,9 +5,17 @@
 //!
 //! Implements recursive SNARK verification using the Nova proving system.
 
+#[cfg(feature = "nova")]
+pub mod aggregation;
 #[cfg(feature = "nova")]
 pub mod circuit;
 #[cfg(feature = "nova")]
+pub mod gadgets;
+#[cfg(feature = "nova")]
+pub mod merkle;
+#[cfg(feature = "nova")]
+pub mod policy_gadgets;
+#[cfg(feature = "nova")]
 pub mod behavioral_circuit;
 #[cfg(feature = "nova")]
 pub mod unified_prover;
@@ -21,6 +29,9 @@ pub mod prover;
 #[cfg(test)]
 #[cfg(feature = "nova")]
 mod tests {
+    use std::sync::Arc;
+
+    use crate::nova::circuit::{FoldState, MerkleStepCircuit, SignerWitness, StepConstants};
     use crate::nova::params::gen_params;
     use crate::nova::prover::{prove_batch, verify_proof, setup_keys};
     use pasta_curves::pallas;
@@ -35,22 +46,32 @@ mod tests {
 
         println!("Generating compressed SNARK keys...");
         let (pk, vk) = setup_keys(&params).expect("Key setup failed");
-        
+
+        let constants = Arc::new(StepConstants::new());
+        let z0 = FoldState::initial(pallas::Scalar::from(42u64), 8, pallas::Scalar::zero());
+        let mut zn = z0;
+        let mut steps = Vec::new();
+        for index in [1u32, 4, 6] {
+            let signature = pallas::Scalar::from(u64::from(index) * 1000);
+            zn = zn.absorb(&constants.fold, index, pallas::Scalar::zero(), signature);
+            let witness = SignerWitness { signer_index: index, signature, ..SignerWitness::blank() };
+            steps.push(MerkleStepCircuit::new(witness, constants.clone()));
+        }
+
         println!("Proving 3 steps...");
         let start_prove = Instant::now();
-        let proof = prove_batch(&params, 3, &pk).expect("Proving failed");
+        let proof = prove_batch(&params, &pk, &z0.to_vec(), &steps).expect("Proving failed");
         println!("Proving time: {:?}", start_prove.elapsed());
 
         println!("Verifying...");
-        let z0 = vec![pallas::Scalar::zero(); 2];
-        let zn = z0.clone(); // Identity circuit preservation
-        
         let start_verify = Instant::now();
-        // verify_proof(vk, proof, num_steps, z0, zn)
-        let valid = verify_proof(&vk, &proof, 3, &z0, &zn).expect("Verification failed");
+        let valid = verify_proof(&vk, &proof, 3, &z0.to_vec(), &zn.to_vec()).expect("Verification failed");
         let duration = start_verify.elapsed();
         println!("Verification time: {:?}", duration);
-        
+
         assert!(valid, "Proof should be valid");
+
+        // A different claimed signer count does not verify
+        assert!(verify_proof(&vk, &proof, 2, &z0.to_vec(), &zn.to_vec()).is_err());
     }
 }
@@ -1,12 +1,15 @@
+use std::sync::{Arc, OnceLock};
+
 use nova_snark::{
     provider::{PallasEngine, VestaEngine, ipa_pc::EvaluationEngine},
     PublicParams, CompressedSNARK,
     spartan::snark::RelaxedR1CSSNARK,
-    traits::snark::RelaxedR1CSSNARKTrait
+    traits::{circuit::TrivialCircuit, snark::RelaxedR1CSSNARKTrait}
 };
 use pasta_curves::{pallas, vesta};
 
-use crate::nova::circuit::MerkleStepCircuit;
+use crate::nova::circuit::{MerkleStepCircuit, StepConstants};
+use crate::nova::prover::{MerkleProverKey, MerkleVerifierKey};
 
 use crate::nova::behavioral_circuit::BehavioralVerificationCircuit;
 
@@ -21,20 +24,23 @@ pub type Pparams = PublicParams<
     PallasEngine,
     VestaEngine,
     MerkleStepCircuit<pallas::Scalar>,
-    MerkleStepCircuit<vesta::Scalar>,
+    TrivialCircuit<vesta::Scalar>,
 >;
 
 pub type UnifiedPparams = PublicParams<
     PallasEngine,
     VestaEngine,
     BehavioralVerificationCircuit<pallas::Scalar>,
-    BehavioralVerificationCircuit<vesta::Scalar>,
+    TrivialCircuit<vesta::Scalar>,
 >;
 
-/// Generate public parameters for the Merkle Identity Circuit.
+/// Generate public parameters for the signer aggregation circuit.
+///
+/// Generation is deterministic, so every party derives the same parameters
+/// and keys.
 pub fn gen_params() -> Pparams {
-    let circuit_primary = MerkleStepCircuit::new();
-    let circuit_secondary = MerkleStepCircuit::new();
+    let circuit_primary = MerkleStepCircuit::blank(Arc::new(StepConstants::new()));
+    let circuit_secondary = TrivialCircuit::default();
     
     let ck_primary = S1::ck_floor();
     let ck_secondary = S2::ck_floor();
@@ -47,39 +53,32 @@ pub fn gen_params() -> Pparams {
     ).expect("Failed to setup Nova parameters")
 }
 
+/// Parameters, keys and Poseidon constants for Nova aggregation.
+pub struct AggregationKeys {
+    pub params: Pparams,
+    pub prover_key: MerkleProverKey,
+    pub verifier_key: MerkleVerifierKey,
+    pub constants: Arc<StepConstants<pallas::Scalar>>,
+}
+
+/// The process-wide [`AggregationKeys`], generated on first use.
+///
+/// Generation takes a few seconds, so the first Nova aggregation or
+/// verification in a process is slower than the rest.
+pub fn aggregation_keys() -> &'static AggregationKeys {
+    static KEYS: OnceLock<AggregationKeys> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        let params = gen_params();
+        let (prover_key, verifier_key) =
+            CompressedSNARK::setup(&params).expect("Failed to setup Nova aggregation keys");
+        AggregationKeys { params, prover_key, verifier_key, constants: Arc::new(StepConstants::new()) }
+    })
+}
+
 /// Generate public parameters for the Unified Behavioral Circuit.
 pub fn gen_unified_params() -> UnifiedPparams {
-    // Generate empty circuit for setup
-    let circuit_primary = BehavioralVerificationCircuit::new(
-        super::behavioral_circuit::UnifiedCircuitInputs {
-            chain_root: pallas::Scalar::zero(),
-            chain_length: 0,
-            policy_root: pallas::Scalar::zero(),
-            evaluation_hash: pallas::Scalar::zero(),
-            risk_tier: 0,
-            pk_root: pallas::Scalar::zero(),
-            message_hash: pallas::Scalar::zero(),
-            threshold_t: 0,
-        },
-        vec![],
-        vec![],
-        vec![],
-    );
-    let circuit_secondary = BehavioralVerificationCircuit::new(
-        super::behavioral_circuit::UnifiedCircuitInputs {
-            chain_root: vesta::Scalar::zero(),
-            chain_length: 0,
-            policy_root: vesta::Scalar::zero(),
-            evaluation_hash: vesta::Scalar::zero(),
-            risk_tier: 0,
-            pk_root: vesta::Scalar::zero(),
-            message_hash: vesta::Scalar::zero(),
-            threshold_t: 0,
-        },
-        vec![],
-        vec![],
-        vec![],
-    );
+    let circuit_primary = BehavioralVerificationCircuit::blank();
+    let circuit_secondary = TrivialCircuit::default();
     
     let ck_primary = S1::ck_floor();
     let ck_secondary = S2::ck_floor();
@@ -94,8 +93,8 @@ pub fn gen_unified_params() -> UnifiedPparams {
 
 /// Setup keys for Unified CompressedSNARK.
 pub fn setup_unified_keys(params: &UnifiedPparams) -> Result<(
-    nova_snark::ProverKey<PallasEngine, VestaEngine, BehavioralVerificationCircuit<pallas::Scalar>, BehavioralVerificationCircuit<pasta_curves::vesta::Scalar>, S1, S2>,
-    nova_snark::VerifierKey<PallasEngine, VestaEngine, BehavioralVerificationCircuit<pallas::Scalar>, BehavioralVerificationCircuit<pasta_curves::vesta::Scalar>, S1, S2>
+    nova_snark::ProverKey<PallasEngine, VestaEngine, BehavioralVerificationCircuit<pallas::Scalar>, TrivialCircuit<vesta::Scalar>, S1, S2>,
+    nova_snark::VerifierKey<PallasEngine, VestaEngine, BehavioralVerificationCircuit<pallas::Scalar>, TrivialCircuit<vesta::Scalar>, S1, S2>
 ), crate::error::PQAggregateError> {
     CompressedSNARK::setup(params).map_err(|e: nova_snark::errors::NovaError| crate::error::PQAggregateError::NovaError(e.to_string()))
 }
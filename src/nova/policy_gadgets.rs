@@ -0,0 +1,591 @@
+//! R1CS gadgets for [`PolicyCondition`]s.
+//!
+//! Each gadget mirrors its evaluator in [`crate::policy::evaluator`]: it
+//! takes the allocated event chain and returns whether the condition holds
+//! at the chain's last event, so a proof shows the policy was satisfied
+//! rather than that someone ran [`PolicyEngine`](crate::policy::PolicyEngine).
+//!
+//! Conditions enter the circuit as an [`EncodedCondition`]: a kind, four
+//! integer parameters and, for whitelists, the allowed prefixes. Their
+//! Poseidon commitment is what the unified proof's `policy_root` chains.
+
+use bellpepper_core::boolean::Boolean;
+use bellpepper_core::num::{AllocatedNum, Num};
+use bellpepper_core::{ConstraintSystem, SynthesisError};
+use ff::{PrimeField, PrimeFieldBits};
+use neptune::Poseidon;
+
+use crate::causal::ActionType;
+use crate::nova::behavioral_circuit::BehavioralConstants;
+use crate::nova::gadgets;
+use crate::policy::evaluator::{OUTFLOW_WINDOW_MS, SIMULATED_REQUEST_OUTFLOW};
+use crate::policy::{Currency, PolicyCondition};
+
+/// Maximum number of conditions, across all policies, a proof can cover.
+pub const MAX_POLICY_CONDITIONS: usize = 4;
+
+/// Maximum number of prefixes in an [`PolicyCondition::AddressWhitelist`].
+pub const MAX_WHITELIST_PREFIXES: usize = 4;
+
+/// Comparisons are between values below `2^VALUE_BITS`.
+///
+/// Timestamps and parameters are 64-bit; scaled to milliseconds or cents
+/// and added together they stay below this.
+const VALUE_BITS: usize = 80;
+
+/// Number of condition kinds, including the empty slot.
+pub const NUM_CONDITION_KINDS: usize = 6;
+
+const KIND_NONE: usize = 0;
+const KIND_MAX_DAILY_OUTFLOW: usize = 1;
+const KIND_MIN_VERIFICATION_COUNT: usize = 2;
+const KIND_MIN_TIME_BETWEEN_ACTIONS: usize = 3;
+const KIND_NO_CONCURRENT_REQUESTS: usize = 4;
+pub(crate) const KIND_ADDRESS_WHITELIST: usize = 5;
+
+/// A condition as the circuit sees it.
+///
+/// | Kind | Condition                 | Parameters                                            |
+/// |------|---------------------------|-------------------------------------------------------|
+/// | 0    | (empty slot)              |                                                       |
+/// | 1    | `MaxDailyOutflow`         | `max_amount`, currency                                |
+/// | 2    | `MinVerificationCount`    | `threshold`, min amount, has min amount, cross-chain  |
+/// | 3    | `MinTimeBetweenActions`   | `action_type`, `min_seconds`                          |
+/// | 4    | `NoConcurrentRequests`    | `window_seconds`                                      |
+/// | 5    | `AddressWhitelist`        | none; the prefixes are listed separately              |
+///
+/// Flags sit in the last two parameters, which every other kind leaves zero.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncodedCondition {
+    pub kind: usize,
+    pub params: [u64; 4],
+    pub prefixes: Vec<[u8; 20]>,
+}
+
+impl EncodedCondition {
+    /// An unused condition slot.
+    pub fn none() -> Self {
+        Self { kind: KIND_NONE, params: [0; 4], prefixes: Vec::new() }
+    }
+
+    /// Encode `condition`.
+    pub fn new(condition: &PolicyCondition) -> Self {
+        let (kind, params, prefixes) = match condition {
+            PolicyCondition::MaxDailyOutflow { max_amount, currency } => {
+                let currency = match currency {
+                    Currency::USD => 0,
+                    Currency::ETH => 1,
+                    Currency::SOL => 2,
+                };
+                (KIND_MAX_DAILY_OUTFLOW, [*max_amount, currency, 0, 0], Vec::new())
+            }
+            PolicyCondition::MinVerificationCount { threshold, min_amount_usd, cross_chain_only } => (
+                KIND_MIN_VERIFICATION_COUNT,
+                [
+                    u64::from(*threshold),
+                    min_amount_usd.unwrap_or(0),
+                    u64::from(min_amount_usd.is_some()),
+                    u64::from(*cross_chain_only),
+                ],
+                Vec::new(),
+            ),
+            PolicyCondition::MinTimeBetweenActions { action_type, min_seconds } => {
+                (KIND_MIN_TIME_BETWEEN_ACTIONS, [u64::from(*action_type), *min_seconds, 0, 0], Vec::new())
+            }
+            PolicyCondition::NoConcurrentRequests { window_seconds } => {
+                (KIND_NO_CONCURRENT_REQUESTS, [*window_seconds, 0, 0, 0], Vec::new())
+            }
+            PolicyCondition::AddressWhitelist { allowed_prefixes } => {
+                (KIND_ADDRESS_WHITELIST, [0; 4], allowed_prefixes.clone())
+            }
+        };
+        Self { kind, params, prefixes }
+    }
+
+    /// Poseidon commitment to the encoding.
+    ///
+    /// `Poseidon(kind, params, prefix_root)`, where `prefix_root` chains the
+    /// prefixes as `Poseidon(root, prefix)` from zero.
+    pub fn commitment<F: PrimeField>(&self, constants: &BehavioralConstants<F>) -> F {
+        let prefix_root = self.prefixes.iter().fold(F::ZERO, |root, prefix| {
+            Poseidon::new_with_preimage(&[root, prefix_to_field(prefix)], &constants.chain).hash()
+        });
+        let [a, b, c, d] = self.params.map(F::from);
+        Poseidon::new_with_preimage(&[F::from(self.kind as u64), a, b, c, d, prefix_root], &constants.link).hash()
+    }
+}
+
+/// Native counterpart of the in-circuit condition chain.
+///
+/// Chains the [`EncodedCondition::commitment`] of each condition as
+/// `Poseidon(root, commitment)`, starting from zero.
+pub fn conditions_commitment<F: PrimeField>(constants: &BehavioralConstants<F>, conditions: &[PolicyCondition]) -> F {
+    conditions.iter().fold(F::ZERO, |root, condition| {
+        let commitment = EncodedCondition::new(condition).commitment(constants);
+        Poseidon::new_with_preimage(&[root, commitment], &constants.chain).hash()
+    })
+}
+
+/// A 20-byte address prefix as a field element.
+pub fn prefix_to_field<F: PrimeField>(prefix: &[u8; 20]) -> F {
+    let mut low = [0u8; 16];
+    low.copy_from_slice(&prefix[..16]);
+    let mut high = [0u8; 4];
+    high.copy_from_slice(&prefix[16..]);
+    F::from_u128(u128::from_le_bytes(low)) + F::from(u64::from(u32::from_le_bytes(high))) * F::from_u128(1 << 64).square()
+}
+
+/// The metadata fields the gadgets read.
+#[derive(Clone)]
+pub struct MetadataVars<F: PrimeField> {
+    /// Whether the event carries metadata; the fields are zero otherwise
+    pub present: Boolean,
+    pub amount_usd_cents: AllocatedNum<F>,
+    pub destination_chain: AllocatedNum<F>,
+}
+
+/// One allocated event slot.
+#[derive(Clone)]
+pub struct EventVars<F: PrimeField> {
+    /// Whether the slot holds an event; active slots form a prefix
+    pub active: Boolean,
+    pub nonce: AllocatedNum<F>,
+    pub timestamp: AllocatedNum<F>,
+    pub action_type: AllocatedNum<F>,
+    pub metadata: MetadataVars<F>,
+}
+
+/// The event chain with what the gadgets derive from it.
+///
+/// The target is the last active event, which every condition is
+/// evaluated at. Quantities that do not depend on a condition's parameters
+/// are computed once here rather than in each condition slot.
+pub struct ChainVars<F: PrimeField> {
+    pub events: Vec<EventVars<F>>,
+    /// Whether the chain has any event
+    pub has_target: Boolean,
+    /// Per slot: the event is the target
+    pub is_target: Vec<Boolean>,
+    /// Per slot: the event precedes the target
+    pub earlier: Vec<Boolean>,
+    /// Per slot: the event's nonce is nonzero
+    pub nonzero_nonce: Vec<Boolean>,
+    pub target_timestamp: AllocatedNum<F>,
+    pub target_action_type: AllocatedNum<F>,
+    pub target_metadata: MetadataVars<F>,
+    /// Simulated outflow of the signature requests in the target's window
+    pub daily_outflow: Num<F>,
+    /// Number of address verifications before the target
+    pub verification_count: Num<F>,
+}
+
+/// `num + constant`.
+fn plus<F: PrimeField, CS: ConstraintSystem<F>>(num: &AllocatedNum<F>, constant: F) -> Num<F> {
+    Num::from(num.clone()).add_bool_with_coeff(CS::one(), &Boolean::constant(true), constant)
+}
+
+/// `sum(bits)`.
+fn count<F: PrimeField, CS: ConstraintSystem<F>>(bits: &[Boolean]) -> Num<F> {
+    bits.iter().fold(Num::zero(), |sum, bit| sum.add_bool_with_coeff(CS::one(), bit, F::ONE))
+}
+
+/// Whether `num == constant`.
+fn equals<F, CS>(cs: CS, num: &AllocatedNum<F>, constant: u64) -> Result<Boolean, SynthesisError>
+where
+    F: PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+{
+    gadgets::is_zero(cs, &plus::<F, CS>(num, -F::from(constant)))
+}
+
+/// Whether `a == b`.
+fn equal<F, CS>(cs: CS, a: &AllocatedNum<F>, b: &AllocatedNum<F>) -> Result<Boolean, SynthesisError>
+where
+    F: PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+{
+    gadgets::is_zero(cs, &Num::from(a.clone()).add(&Num::from(b.clone()).scale(-F::ONE)))
+}
+
+/// Allocate a boolean that is set exactly when `num` is one.
+fn boolean_of<F, CS>(mut cs: CS, num: &Num<F>) -> Result<Boolean, SynthesisError>
+where
+    F: PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+{
+    let allocated = AllocatedNum::alloc(cs.namespace(|| "value"), || num.get_value().ok_or(SynthesisError::AssignmentMissing))?;
+    cs.enforce(|| "value = num", |_| num.lc(F::ONE), |lc| lc + CS::one(), |lc| lc + allocated.get_variable());
+    Ok(gadgets::to_bits_le(cs.namespace(|| "bit"), &allocated, 1)?.remove(0))
+}
+
+impl<F: PrimeFieldBits> ChainVars<F> {
+    /// Derive the target and per-event flags.
+    pub fn new<CS: ConstraintSystem<F>>(mut cs: CS, events: Vec<EventVars<F>>) -> Result<Self, SynthesisError> {
+        let n = events.len();
+        let mut is_target = Vec::with_capacity(n);
+        let mut earlier = Vec::with_capacity(n);
+        let mut nonzero_nonce = Vec::with_capacity(n);
+        let mut counted_requests = Vec::with_capacity(n);
+        let mut counted_verifications = Vec::with_capacity(n);
+        let mut target_present = Vec::with_capacity(n);
+
+        // With active slots forming a prefix, an event precedes the target
+        // exactly when the next slot is active
+        for (i, event) in events.iter().enumerate() {
+            let next_active = events.get(i + 1).map_or(Boolean::constant(false), |next| next.active.clone());
+            let mut cs = cs.namespace(|| format!("event {}", i));
+            is_target.push(Boolean::and(cs.namespace(|| "is target"), &event.active, &next_active.not())?);
+            earlier.push(next_active);
+            let nonce_is_zero = gadgets::is_zero(cs.namespace(|| "nonce is zero"), &Num::from(event.nonce.clone()))?;
+            nonzero_nonce.push(nonce_is_zero.not());
+        }
+        let has_target = events.first().map_or(Boolean::constant(false), |event| event.active.clone());
+
+        let timestamps: Vec<_> = events.iter().map(|event| event.timestamp.clone()).collect();
+        let actions: Vec<_> = events.iter().map(|event| event.action_type.clone()).collect();
+        let amounts: Vec<_> = events.iter().map(|event| event.metadata.amount_usd_cents.clone()).collect();
+        let chains: Vec<_> = events.iter().map(|event| event.metadata.destination_chain.clone()).collect();
+        let target_timestamp = gadgets::pick(cs.namespace(|| "target timestamp"), &is_target, &timestamps)?;
+        let target_action_type = gadgets::pick(cs.namespace(|| "target action"), &is_target, &actions)?;
+        let amount_usd_cents = gadgets::pick(cs.namespace(|| "target amount"), &is_target, &amounts)?;
+        let destination_chain = gadgets::pick(cs.namespace(|| "target destination"), &is_target, &chains)?;
+
+        for (i, event) in events.iter().enumerate() {
+            let mut cs = cs.namespace(|| format!("event {} flags", i));
+            target_present.push(Boolean::and(cs.namespace(|| "target metadata"), &is_target[i], &event.metadata.present)?);
+
+            // Signature requests at or after target - OUTFLOW_WINDOW_MS
+            let is_request = equals(cs.namespace(|| "is request"), &event.action_type, ActionType::SignatureRequest as u64)?;
+            let in_window = gadgets::greater_or_equal(
+                cs.namespace(|| "in window"),
+                &plus::<F, CS>(&event.timestamp, F::from(OUTFLOW_WINDOW_MS)),
+                &Num::from(target_timestamp.clone()),
+                VALUE_BITS,
+            )?;
+            let active_request = Boolean::and(cs.namespace(|| "active request"), &event.active, &is_request)?;
+            counted_requests.push(Boolean::and(cs.namespace(|| "counted request"), &active_request, &in_window)?);
+
+            let is_verification =
+                equals(cs.namespace(|| "is verification"), &event.action_type, ActionType::AddressVerification as u64)?;
+            counted_verifications.push(Boolean::and(cs.namespace(|| "counted verification"), &earlier[i], &is_verification)?);
+        }
+
+        let present = boolean_of(cs.namespace(|| "target has metadata"), &count::<F, CS>(&target_present))?;
+        Ok(Self {
+            has_target,
+            is_target,
+            earlier,
+            nonzero_nonce,
+            target_timestamp,
+            target_action_type,
+            target_metadata: MetadataVars { present, amount_usd_cents, destination_chain },
+            daily_outflow: count::<F, CS>(&counted_requests).scale(F::from(SIMULATED_REQUEST_OUTFLOW)),
+            verification_count: count::<F, CS>(&counted_verifications),
+            events,
+        })
+    }
+}
+
+/// [`PolicyCondition::MaxDailyOutflow`]: the simulated outflow of the
+/// day before the target is at most `max_amount`.
+pub fn max_daily_outflow<F, CS>(mut cs: CS, chain: &ChainVars<F>, max_amount: &AllocatedNum<F>) -> Result<Boolean, SynthesisError>
+where
+    F: PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+{
+    gadgets::greater_or_equal(
+        cs.namespace(|| "outflow within limit"),
+        &Num::from(max_amount.clone()),
+        &chain.daily_outflow,
+        VALUE_BITS,
+    )
+}
+
+/// [`PolicyCondition::MinVerificationCount`]: at least `threshold` address
+/// verifications precede the target.
+///
+/// When the target carries metadata the requirement is skipped for amounts
+/// below `min_amount_usd` (if `has_min_amount`) and, with
+/// `cross_chain_only`, for same-chain actions. Without metadata it always
+/// applies.
+pub fn min_verification_count<F, CS>(
+    mut cs: CS,
+    chain: &ChainVars<F>,
+    threshold: &AllocatedNum<F>,
+    min_amount_usd: &AllocatedNum<F>,
+    has_min_amount: &Boolean,
+    cross_chain_only: &Boolean,
+) -> Result<Boolean, SynthesisError>
+where
+    F: PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+{
+    let metadata = &chain.target_metadata;
+    let enough = gadgets::greater_or_equal(
+        cs.namespace(|| "enough verifications"),
+        &chain.verification_count,
+        &Num::from(threshold.clone()),
+        VALUE_BITS,
+    )?;
+
+    // amount_usd_cents / 100 < min  <=>  amount_usd_cents < 100 * min
+    let not_below = gadgets::greater_or_equal(
+        cs.namespace(|| "amount >= min"),
+        &Num::from(metadata.amount_usd_cents.clone()),
+        &Num::from(min_amount_usd.clone()).scale(F::from(100)),
+        VALUE_BITS,
+    )?;
+    let low_value = Boolean::and(cs.namespace(|| "low value"), has_min_amount, &not_below.not())?;
+    let same_chain = gadgets::is_zero(cs.namespace(|| "same chain"), &Num::from(metadata.destination_chain.clone()))?;
+    let local_only = Boolean::and(cs.namespace(|| "same chain skip"), cross_chain_only, &same_chain)?;
+    let exempt = Boolean::or(cs.namespace(|| "exempt"), &low_value, &local_only)?;
+    let skip = Boolean::and(cs.namespace(|| "skip"), &metadata.present, &exempt)?;
+
+    Boolean::or(cs.namespace(|| "satisfied"), &skip, &enough)
+}
+
+/// [`PolicyCondition::MinTimeBetweenActions`]: if the target is an
+/// `action_type` action, at least `min_seconds` passed since the last
+/// earlier one.
+pub fn min_time_between_actions<F, CS>(
+    mut cs: CS,
+    chain: &ChainVars<F>,
+    action_type: &AllocatedNum<F>,
+    min_seconds: &AllocatedNum<F>,
+) -> Result<Boolean, SynthesisError>
+where
+    F: PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+{
+    let applies = equal(cs.namespace(|| "target action"), &chain.target_action_type, action_type)?;
+
+    // Walk back from the target: the last match is the first one seen
+    let mut seen = Boolean::constant(false);
+    let mut is_last = vec![Boolean::constant(false); chain.events.len()];
+    for (i, event) in chain.events.iter().enumerate().rev() {
+        let mut cs = cs.namespace(|| format!("event {}", i));
+        let matches = equal(cs.namespace(|| "action"), &event.action_type, action_type)?;
+        let matches = Boolean::and(cs.namespace(|| "earlier match"), &chain.earlier[i], &matches)?;
+        is_last[i] = Boolean::and(cs.namespace(|| "is last"), &matches, &seen.not())?;
+        seen = Boolean::or(cs.namespace(|| "seen"), &seen, &matches)?;
+    }
+    let timestamps: Vec<_> = chain.events.iter().map(|event| event.timestamp.clone()).collect();
+    let last_timestamp = gadgets::pick(cs.namespace(|| "last timestamp"), &is_last, &timestamps)?;
+
+    // target - last (saturating) >= min_ms  <=>  target >= last + min_ms, or min_ms = 0
+    let far_enough = gadgets::greater_or_equal(
+        cs.namespace(|| "far enough"),
+        &Num::from(chain.target_timestamp.clone()),
+        &Num::from(last_timestamp).add(&Num::from(min_seconds.clone()).scale(F::from(1000))),
+        VALUE_BITS,
+    )?;
+    let no_gap = gadgets::is_zero(cs.namespace(|| "no gap required"), &Num::from(min_seconds.clone()))?;
+    let spaced = Boolean::or(cs.namespace(|| "spaced"), &far_enough, &no_gap)?;
+
+    let unchecked = Boolean::or(cs.namespace(|| "unchecked"), &applies.not(), &seen.not())?;
+    Boolean::or(cs.namespace(|| "satisfied"), &unchecked, &spaced)
+}
+
+/// [`PolicyCondition::NoConcurrentRequests`]: no earlier event with a
+/// nonzero nonce is less than `window_seconds` older than the target.
+pub fn no_concurrent_requests<F, CS>(
+    mut cs: CS,
+    chain: &ChainVars<F>,
+    window_seconds: &AllocatedNum<F>,
+) -> Result<Boolean, SynthesisError>
+where
+    F: PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+{
+    let window_ms = Num::from(window_seconds.clone()).scale(F::from(1000));
+    let open = gadgets::is_zero(cs.namespace(|| "empty window"), &Num::from(window_seconds.clone()))?.not();
+
+    // target - event (saturating) < window  <=>  target < event + window, for a nonzero window
+    let mut concurrent = Vec::with_capacity(chain.events.len());
+    for (i, event) in chain.events.iter().enumerate() {
+        let mut cs = cs.namespace(|| format!("event {}", i));
+        let outside = gadgets::greater_or_equal(
+            cs.namespace(|| "outside window"),
+            &Num::from(chain.target_timestamp.clone()),
+            &Num::from(event.timestamp.clone()).add(&window_ms),
+            VALUE_BITS,
+        )?;
+        let counted = Boolean::and(cs.namespace(|| "counted"), &chain.earlier[i], &chain.nonzero_nonce[i])?;
+        concurrent.push(Boolean::and(cs.namespace(|| "concurrent"), &counted, &outside.not())?);
+    }
+
+    let none = gadgets::is_zero(cs.namespace(|| "none concurrent"), &count::<F, CS>(&concurrent))?;
+    Boolean::or(cs.namespace(|| "satisfied"), &none, &open.not())
+}
+
+/// [`PolicyCondition::AddressWhitelist`]: if the target is a signature
+/// request or address verification, `destination` is one of the active
+/// `prefixes`.
+///
+/// Events do not commit to a destination address yet, so the unified circuit
+/// has nothing to bind `destination` to and rejects whitelists instead.
+pub fn address_whitelist<F, CS>(
+    mut cs: CS,
+    chain: &ChainVars<F>,
+    destination: &AllocatedNum<F>,
+    prefixes: &[(Boolean, AllocatedNum<F>)],
+) -> Result<Boolean, SynthesisError>
+where
+    F: PrimeFieldBits,
+    CS: ConstraintSystem<F>,
+{
+    let request = equals(cs.namespace(|| "request"), &chain.target_action_type, ActionType::SignatureRequest as u64)?;
+    let verification =
+        equals(cs.namespace(|| "verification"), &chain.target_action_type, ActionType::AddressVerification as u64)?;
+    let applies = Boolean::or(cs.namespace(|| "applies"), &request, &verification)?;
+
+    let mut matches = Vec::with_capacity(prefixes.len());
+    for (i, (active, prefix)) in prefixes.iter().enumerate() {
+        let mut cs = cs.namespace(|| format!("prefix {}", i));
+        let listed = equal(cs.namespace(|| "equal"), destination, prefix)?;
+        matches.push(Boolean::and(cs.namespace(|| "match"), active, &listed)?);
+    }
+    let listed = gadgets::is_zero(cs.namespace(|| "unlisted"), &count::<F, CS>(&matches))?.not();
+
+    Boolean::or(cs.namespace(|| "satisfied"), &applies.not(), &listed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::causal::{CausalEvent, CausalEventLogger, StructuredMetadata};
+    use crate::policy::evaluator::evaluate_condition_with_metadata;
+    use bellpepper_core::test_cs::TestConstraintSystem;
+    use pasta_curves::pallas::Scalar;
+
+    const SLOTS: usize = 6;
+
+    fn events(actions: &[(u8, u64)]) -> Vec<CausalEvent> {
+        let mut logger = CausalEventLogger::new([0u8; 32]);
+        actions
+            .iter()
+            .map(|&(action, time)| logger.log_event(&[0xAA; 32], action, b"payload", time).unwrap())
+            .collect()
+    }
+
+    /// Run the gadget for `condition` over `events`, with `metadata` on the last one.
+    fn evaluate(condition: &PolicyCondition, events: &[CausalEvent], metadata: Option<StructuredMetadata>) -> bool {
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        let mut alloc = |name: String, value: Scalar| AllocatedNum::alloc(cs.namespace(|| name), || Ok(value)).unwrap();
+
+        let slots: Vec<_> = (0..SLOTS)
+            .map(|i| {
+                let event = events.get(i);
+                let opened = metadata.filter(|_| i + 1 == events.len());
+                let metadata = opened.unwrap_or_default();
+                EventVars {
+                    active: Boolean::constant(event.is_some()),
+                    nonce: alloc(format!("nonce {}", i), Scalar::from(event.map_or(0, |e| e.nonce))),
+                    timestamp: alloc(format!("timestamp {}", i), Scalar::from(event.map_or(0, |e| e.timestamp))),
+                    action_type: alloc(format!("action {}", i), Scalar::from(event.map_or(0, |e| u64::from(e.action_type)))),
+                    metadata: MetadataVars {
+                        present: Boolean::constant(opened.is_some()),
+                        amount_usd_cents: alloc(format!("amount {}", i), Scalar::from(u64::from(metadata.amount_usd_cents))),
+                        destination_chain: alloc(format!("chain {}", i), Scalar::from(u64::from(metadata.destination_chain))),
+                    },
+                }
+            })
+            .collect();
+
+        let encoded = EncodedCondition::new(condition);
+        let params: Vec<_> = encoded.params.iter().enumerate().map(|(i, &p)| alloc(format!("param {}", i), Scalar::from(p))).collect();
+        let prefixes: Vec<_> = encoded
+            .prefixes
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (Boolean::constant(true), alloc(format!("prefix {}", i), prefix_to_field(p))))
+            .collect();
+        let destination = alloc("destination".to_string(), Scalar::from(0));
+        let flags = [Boolean::constant(encoded.params[2] == 1), Boolean::constant(encoded.params[3] == 1)];
+
+        let chain = ChainVars::new(cs.namespace(|| "chain"), slots).unwrap();
+        let ns = cs.namespace(|| "condition");
+        let satisfied = match encoded.kind {
+            KIND_MAX_DAILY_OUTFLOW => max_daily_outflow(ns, &chain, &params[0]),
+            KIND_MIN_VERIFICATION_COUNT => min_verification_count(ns, &chain, &params[0], &params[1], &flags[0], &flags[1]),
+            KIND_MIN_TIME_BETWEEN_ACTIONS => min_time_between_actions(ns, &chain, &params[0], &params[1]),
+            KIND_NO_CONCURRENT_REQUESTS => no_concurrent_requests(ns, &chain, &params[0]),
+            _ => address_whitelist(ns, &chain, &destination, &prefixes),
+        }
+        .unwrap();
+        assert!(cs.is_satisfied());
+        satisfied.get_value().unwrap()
+    }
+
+    #[test]
+    fn test_gadgets_match_evaluator() {
+        const REQUEST: u8 = 0x01;
+        const VERIFY: u8 = 0x02;
+        const BALANCE: u8 = 0x03;
+        let outflow = PolicyCondition::MaxDailyOutflow { max_amount: 1000, currency: Currency::USD };
+        let verifications = |threshold, min_amount_usd, cross_chain_only| PolicyCondition::MinVerificationCount {
+            threshold,
+            min_amount_usd,
+            cross_chain_only,
+        };
+        let cooldown = |min_seconds| PolicyCondition::MinTimeBetweenActions { action_type: REQUEST, min_seconds };
+        let concurrency = |window_seconds| PolicyCondition::NoConcurrentRequests { window_seconds };
+        let whitelist = |allowed_prefixes: Vec<[u8; 20]>| PolicyCondition::AddressWhitelist { allowed_prefixes };
+        let small = Some(StructuredMetadata::new(50_00, 0, 0));
+        let large = Some(StructuredMetadata::new(1500_00, 0, 0));
+        let bridged = Some(StructuredMetadata::new(5000_00, 137, 0));
+
+        let cases = [
+            (outflow.clone(), events(&[(REQUEST, 1000)]), None, true),
+            (outflow.clone(), events(&[(REQUEST, 1000), (VERIFY, 1100), (REQUEST, 1500)]), None, false),
+            (outflow.clone(), events(&[(REQUEST, 1000), (REQUEST, 1001 + OUTFLOW_WINDOW_MS)]), None, true),
+            (verifications(2, None, false), events(&[(VERIFY, 1000), (VERIFY, 1100), (REQUEST, 1200)]), None, true),
+            (verifications(2, None, false), events(&[(VERIFY, 1000), (REQUEST, 1200)]), None, false),
+            (verifications(3, Some(1000), false), events(&[(REQUEST, 1000)]), small, true),
+            (verifications(3, Some(1000), false), events(&[(REQUEST, 1000)]), large, false),
+            (verifications(3, Some(1000), false), events(&[(REQUEST, 1000)]), None, false),
+            (verifications(3, None, true), events(&[(REQUEST, 1000)]), large, true),
+            (verifications(3, None, true), events(&[(REQUEST, 1000)]), bridged, false),
+            (cooldown(600), events(&[(REQUEST, 1000), (REQUEST, 1010)]), None, false),
+            (cooldown(600), events(&[(REQUEST, 1000), (REQUEST, 601_000)]), None, true),
+            (cooldown(600), events(&[(REQUEST, 1000), (VERIFY, 1010)]), None, true),
+            (cooldown(600), events(&[(VERIFY, 1000), (REQUEST, 1010)]), None, true),
+            (cooldown(600), events(&[(REQUEST, 1000), (REQUEST, 800)]), None, false),
+            (cooldown(0), events(&[(REQUEST, 1000), (REQUEST, 800)]), None, true),
+            (concurrency(10), events(&[(REQUEST, 1000), (BALANCE, 5000)]), None, false),
+            (concurrency(10), events(&[(REQUEST, 1000), (BALANCE, 20_000)]), None, true),
+            (concurrency(0), events(&[(REQUEST, 1000), (REQUEST, 1000)]), None, true),
+            (whitelist(vec![[1u8; 20], [0u8; 20]]), events(&[(REQUEST, 1000)]), None, true),
+            (whitelist(vec![[1u8; 20]]), events(&[(REQUEST, 1000)]), None, false),
+            (whitelist(vec![]), events(&[(VERIFY, 1000)]), None, false),
+            (whitelist(vec![]), events(&[(BALANCE, 1000)]), None, true),
+        ];
+
+        for (i, (condition, events, metadata, expected)) in cases.iter().enumerate() {
+            let target_nonce = events.last().unwrap().nonce;
+            let native = evaluate_condition_with_metadata(condition, events, target_nonce, metadata.as_ref());
+            assert_eq!(native, *expected, "evaluator, case {}", i);
+            assert_eq!(evaluate(condition, events, *metadata), *expected, "gadget, case {}", i);
+        }
+    }
+
+    #[test]
+    fn test_condition_commitments_differ() {
+        let constants = BehavioralConstants::<Scalar>::new();
+        let conditions = [
+            PolicyCondition::MaxDailyOutflow { max_amount: 1000, currency: Currency::USD },
+            PolicyCondition::MaxDailyOutflow { max_amount: 1000, currency: Currency::ETH },
+            PolicyCondition::MinVerificationCount { threshold: 3, min_amount_usd: Some(0), cross_chain_only: false },
+            PolicyCondition::MinVerificationCount { threshold: 3, min_amount_usd: None, cross_chain_only: false },
+            PolicyCondition::AddressWhitelist { allowed_prefixes: vec![[1u8; 20]] },
+            PolicyCondition::AddressWhitelist { allowed_prefixes: vec![] },
+        ];
+        let commitments: Vec<_> = conditions.iter().map(|c| EncodedCondition::new(c).commitment(&constants)).collect();
+        for i in 0..commitments.len() {
+            for j in i + 1..commitments.len() {
+                assert_ne!(commitments[i], commitments[j], "{} and {}", i, j);
+            }
+        }
+        assert_ne!(conditions_commitment(&constants, &conditions[..2]), conditions_commitment(&constants, &conditions[1..2]));
+    }
+}
@@ -5,10 +5,15 @@
 
 use nova_snark::{RecursiveSNARK, CompressedSNARK};
 use nova_snark::provider::{PallasEngine, VestaEngine};
+use nova_snark::traits::circuit::TrivialCircuit;
 use pasta_curves::{pallas, vesta};
-use crate::causal::CausalEvent;
-use crate::policy::{PolicyEngine};
-use crate::nova::behavioral_circuit::{BehavioralVerificationCircuit, UnifiedCircuitInputs};
+use crate::causal::{CausalEvent, StructuredMetadata};
+use crate::policy::{PolicyCondition, PolicyEngine};
+use crate::nova::behavioral_circuit::{
+    chain_commitment, event_witnesses, BehavioralConstants, BehavioralVerificationCircuit, UnifiedCircuitInputs,
+    MAX_CHAIN_EVENTS,
+};
+use crate::nova::policy_gadgets::{conditions_commitment, MAX_POLICY_CONDITIONS};
 use crate::nova::params::{UnifiedPparams, S1, S2};
 use crate::error::PQAggregateError;
 
@@ -23,7 +28,7 @@ pub type UnifiedPK = nova_snark::ProverKey<
     PallasEngine, 
     VestaEngine, 
     BehavioralVerificationCircuit<pallas::Scalar>, 
-    BehavioralVerificationCircuit<pasta_curves::vesta::Scalar>, 
+    TrivialCircuit<vesta::Scalar>, 
     S1, 
     S2
 >;
@@ -32,7 +37,7 @@ pub type UnifiedCSNARK = CompressedSNARK<
     PallasEngine, 
     VestaEngine, 
     BehavioralVerificationCircuit<pallas::Scalar>, 
-    BehavioralVerificationCircuit<pasta_curves::vesta::Scalar>, 
+    TrivialCircuit<vesta::Scalar>, 
     S1, 
     S2
 >;
@@ -48,66 +53,104 @@ impl UnifiedProver {
     }
 
     /// Generate a unified status proof for a chain of events and signatures.
+    ///
+    /// `expected_chain_root` is the logger's SHA3 root, checked natively.
+    /// The proof itself commits to the events through
+    /// [`event_chain_root`](crate::nova::behavioral_circuit::event_chain_root),
+    /// which the logger keeps as
+    /// [`get_event_chain_root`](crate::causal::CausalEventLogger::get_event_chain_root),
+    /// and to the policies' conditions through
+    /// [`policy_root`](crate::nova::behavioral_circuit::policy_root), which
+    /// is what verifiers check it against.
+    ///
+    /// # Errors
+    /// `NovaError` if the policy is not satisfied, the chain or policies
+    /// exceed the circuit's limits, the policies include an
+    /// [`AddressWhitelist`](PolicyCondition::AddressWhitelist), or the circuit constraints do not hold
+    /// (for example `threshold_t` is below the risk tier's minimum).
     pub fn prove_unified(
         &self,
         params: &UnifiedPparams,
         pk: &UnifiedPK,
         events: &[CausalEvent],
         expected_chain_root: [u8; 32],
+        message_hash: [u8; 32],
+        pk_root: [u8; 32],
+        threshold_t: u8,
+    ) -> Result<UnifiedCSNARK, PQAggregateError> {
+        self.prove_unified_with_metadata(params, pk, events, None, expected_chain_root, message_hash, pk_root, threshold_t)
+    }
+
+    /// [`Self::prove_unified`] with the metadata of the chain's last event.
+    ///
+    /// The metadata must match the event's commitment. It is opened to the
+    /// circuit, so risk-adaptive conditions are proven against it, and is
+    /// part of the `chain_root` the proof is checked against.
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove_unified_with_metadata(
+        &self,
+        params: &UnifiedPparams,
+        pk: &UnifiedPK,
+        events: &[CausalEvent],
+        target_metadata: Option<&StructuredMetadata>,
+        expected_chain_root: [u8; 32],
         _message_hash: [u8; 32],
         _pk_root: [u8; 32],
         threshold_t: u8,
     ) -> Result<UnifiedCSNARK, PQAggregateError> {
-        
+        if let (Some(metadata), Some(last)) = (target_metadata, events.last()) {
+            if !last.commits_to_metadata(metadata) {
+                return Err(PQAggregateError::NovaError("Metadata does not match the event commitment".to_string()));
+            }
+        }
+
         // 1. Evaluate Policy
-        let evaluation = self.policy_engine.evaluate_chain(events, &expected_chain_root)
+        let evaluation = self.policy_engine.evaluate_chain_with_metadata(events, &expected_chain_root, target_metadata)
             .map_err(|e| PQAggregateError::NovaError(e.to_string()))?;
             
         if !evaluation.compliant {
             return Err(PQAggregateError::NovaError("Policy compliance failed".to_string()));
         }
+        if events.len() > MAX_CHAIN_EVENTS {
+            return Err(PQAggregateError::NovaError(format!(
+                "Event chain too long: {} events (max {})",
+                events.len(),
+                MAX_CHAIN_EVENTS
+            )));
+        }
+
+        let conditions: Vec<PolicyCondition> = self.policy_engine.policies().iter()
+            .flat_map(|policy| policy.conditions.iter().cloned())
+            .collect();
+        if conditions.len() > MAX_POLICY_CONDITIONS {
+            return Err(PQAggregateError::NovaError(format!(
+                "Too many policy conditions: {} (max {})",
+                conditions.len(),
+                MAX_POLICY_CONDITIONS
+            )));
+        }
+        if conditions.iter().any(|c| matches!(c, PolicyCondition::AddressWhitelist { .. })) {
+            return Err(PQAggregateError::NovaError(
+                "Address whitelists cannot be proven: events do not log a destination".to_string(),
+            ));
+        }
 
         // 2. Prepare Circuit Inputs
+        let constants = BehavioralConstants::new();
+        let witnesses = event_witnesses(events, target_metadata);
         let inputs = UnifiedCircuitInputs {
-            chain_root: pallas::Scalar::zero(), 
+            chain_root: chain_commitment(&constants, &witnesses),
             chain_length: events.len() as u64,
-            policy_root: pallas::Scalar::zero(), 
+            policy_root: conditions_commitment(&constants, &conditions),
             evaluation_hash: pallas::Scalar::zero(),
-            risk_tier: evaluation.risk_tier.to_threshold() as u8,
+            risk_tier: evaluation.risk_tier.index(),
             pk_root: pallas::Scalar::zero(),
             message_hash: pallas::Scalar::zero(),
             threshold_t,
         };
 
-        let nonces: Vec<u64> = events.iter().map(|e| e.nonce).collect();
-        let timestamps: Vec<u64> = events.iter().map(|e| e.timestamp).collect();
-        let fingerprints: Vec<pallas::Scalar> = events.iter()
-            .map(|_| pallas::Scalar::zero())
-            .collect();
-
-        let primary_circuit = BehavioralVerificationCircuit::new(
-            inputs.clone(),
-            nonces.clone(),
-            timestamps.clone(),
-            fingerprints.clone(),
-        );
-        
-        // Secondary circuit (vesta) - simplified implementation
-        let secondary_circuit = BehavioralVerificationCircuit::new(
-            UnifiedCircuitInputs {
-                chain_root: vesta::Scalar::zero(),
-                chain_length: 0,
-                policy_root: vesta::Scalar::zero(),
-                evaluation_hash: vesta::Scalar::zero(),
-                risk_tier: 0,
-                pk_root: vesta::Scalar::zero(),
-                message_hash: vesta::Scalar::zero(),
-                threshold_t: 0,
-            },
-            vec![],
-            vec![],
-            vec![],
-        );
+        let primary_circuit = BehavioralVerificationCircuit::new(inputs.clone(), witnesses, &conditions);
+        let secondary_circuit = TrivialCircuit::default();
 
         // 3. Initial inputs (z0)
         // z: [chain_root, policy_root, risk_tier, pk_root, threshold_t]
@@ -118,13 +161,9 @@ impl UnifiedProver {
             inputs.pk_root,
             pallas::Scalar::from(inputs.threshold_t as u64),
         ];
-        let z0_secondary = vec![vesta::Scalar::zero(); 5];
+        let z0_secondary = vec![vesta::Scalar::zero()];
 
         // 4. Prove Step
-        // We need the specific UnifiedPparams for the setup
-        // But for this orchestrator, we assume params are compatible
-        // If arity differs, Pparams will fail here.
-        
         let mut recursive_snark = RecursiveSNARK::new(
             params,
             &primary_circuit,
@@ -139,6 +178,10 @@ impl UnifiedProver {
             &secondary_circuit,
         ).map_err(|e| PQAggregateError::NovaError(e.to_string()))?;
 
+        // Folding does not check the witness; refuse to compress an unsatisfied step
+        recursive_snark.verify(params, 1, &z0_primary, &z0_secondary)
+            .map_err(|_| PQAggregateError::NovaError("Behavioral circuit constraints not satisfied".to_string()))?;
+
         // 5. Compress
         CompressedSNARK::prove(params, pk, &recursive_snark)
             .map_err(|e| PQAggregateError::NovaError(e.to_string()))
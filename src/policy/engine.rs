@@ -4,11 +4,11 @@
 //! compliance with defined behavioral policies.
 
 use alloc::vec::Vec;
-use crate::causal::CausalEvent;
+use crate::causal::{CausalEvent, StructuredMetadata};
 use crate::causal::logger::CausalEventLogger;
 use crate::policy::types::{BehavioralPolicy, PolicyEvaluation, RiskTier, PolicyProof};
 use crate::policy::evaluator;
-use sha3::{Digest, Sha3_256};
+use crate::utils::{tags, TaggedHasher};
 use thiserror::Error;
 
 /// Errors related to policy evaluation.
@@ -37,11 +37,28 @@ impl PolicyEngine {
         }
     }
 
+    /// Get the active policies.
+    pub fn policies(&self) -> &[BehavioralPolicy] {
+        &self.policies
+    }
+
     /// Evaluate policy compliance for a chain of events.
     pub fn evaluate_chain(
         &self,
         events: &[CausalEvent],
         expected_root: &[u8; 32],
+    ) -> Result<PolicyEvaluation, PolicyError> {
+        self.evaluate_chain_with_metadata(events, expected_root, None)
+    }
+
+    /// [`Self::evaluate_chain`] with the metadata of the chain's last event.
+    ///
+    /// Without metadata, risk-adaptive conditions are enforced conservatively.
+    pub fn evaluate_chain_with_metadata(
+        &self,
+        events: &[CausalEvent],
+        expected_root: &[u8; 32],
+        target_metadata: Option<&StructuredMetadata>,
     ) -> Result<PolicyEvaluation, PolicyError> {
         // 1. Verify integrity of the entire chain
         if !CausalEventLogger::verify_event_chain(events, expected_root) {
@@ -67,7 +84,7 @@ impl PolicyEngine {
             }
 
             for (idx, condition) in policy.conditions.iter().enumerate() {
-                if evaluator::evaluate_condition(condition, events, last_nonce) {
+                if evaluator::evaluate_condition_with_metadata(condition, events, last_nonce, target_metadata) {
                     satisfied_conditions.push(idx);
                 } else {
                     return Ok(PolicyEvaluation {
@@ -97,11 +114,11 @@ impl PolicyEngine {
         root_hash: [u8; 32],
         timestamp: u64,
     ) -> PolicyProof {
-        let mut hasher = Sha3_256::new();
-        hasher.update(&evaluation.evaluation_nonce.to_le_bytes());
-        hasher.update(&[evaluation.compliant as u8]);
-        hasher.update(&[evaluation.risk_tier.to_threshold() as u8]);
-        let evaluation_hash = hasher.finalize().into();
+        let mut hasher = TaggedHasher::new(tags::POLICY_EVALUATION);
+        hasher.update(evaluation.evaluation_nonce.to_le_bytes());
+        hasher.update([evaluation.compliant as u8]);
+        hasher.update([evaluation.risk_tier.to_threshold() as u8]);
+        let evaluation_hash = hasher.finalize();
 
         PolicyProof {
             root_hash,
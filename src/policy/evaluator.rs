@@ -1,12 +1,17 @@
 //! Deterministic evaluators for policy conditions.
 //!
-//! Supports both legacy (v0.01) and metadata-aware (v0.02) events
-//! with conservative fallback behavior for legacy events.
+//! Supports events with and without metadata, with conservative fallback
+//! behavior for events that carry none.
 
-use crate::causal::{CausalEvent, StructuredMetadata, EVENT_VERSION_METADATA};
-use crate::causal::metadata::compute_metadata_commitment;
+use crate::causal::{CausalEvent, StructuredMetadata};
 use crate::policy::types::PolicyCondition;
 
+/// Window over which [`PolicyCondition::MaxDailyOutflow`] sums outflows.
+pub const OUTFLOW_WINDOW_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Outflow counted for each signature request, pending real payload parsing.
+pub const SIMULATED_REQUEST_OUTFLOW: u64 = 1000;
+
 /// Extracted metadata from a v0.02 event, or None for legacy events.
 #[derive(Debug, Clone, Copy)]
 pub struct ExtractedMetadata {
@@ -27,8 +32,8 @@ impl From<&StructuredMetadata> for ExtractedMetadata {
 
 /// Attempt to extract metadata from an event.
 ///
-/// For v0.02 events, this returns the structured metadata if available.
-/// For v0.01 (legacy) events, returns None (conservative fallback).
+/// For events with a metadata commitment, this returns the structured metadata
+/// if it matches. For events without one, returns None (conservative fallback).
 ///
 /// **Security Note**: We cannot directly extract metadata from the commitment
 /// since it's a one-way hash. The caller must provide the original metadata
@@ -38,18 +43,13 @@ pub fn extract_metadata_from_proposal(
     event: &CausalEvent,
     proposed_metadata: Option<&StructuredMetadata>,
 ) -> Option<ExtractedMetadata> {
-    if event.version != EVENT_VERSION_METADATA {
-        return None; // Legacy event, no metadata
+    if !event.has_metadata() {
+        return None; // No metadata committed
     }
 
     // Verify the proposed metadata matches the commitment
     if let Some(metadata) = proposed_metadata {
-        let expected_commitment = compute_metadata_commitment(
-            event.nonce,
-            &event.payload_hash,
-            metadata,
-        );
-        if expected_commitment == event.metadata_commitment {
+        if event.commits_to_metadata(metadata) {
             return Some(ExtractedMetadata::from(metadata));
         }
     }
@@ -100,17 +100,16 @@ pub fn evaluate_condition_with_metadata(
 }
 
 fn evaluate_max_outflow(max_amount: u64, events: &[CausalEvent], target_nonce: u64) -> bool {
-    let day_ms = 24 * 60 * 60 * 1000;
     let target_event = events.iter().find(|e| e.nonce == target_nonce);
     if let Some(target) = target_event {
-        let start_ts = target.timestamp.saturating_sub(day_ms);
+        let start_ts = target.timestamp.saturating_sub(OUTFLOW_WINDOW_MS);
         let mut total = 0u64;
         
         for event in events.iter().filter(|e| e.nonce <= target_nonce && e.timestamp >= start_ts) {
             // In a real system, we'd parse the payload for 'amount'.
             // For the spec, we simulate 'outflow' by using a fixed value for SignatureRequests
             if event.action_type == 0x01 { // SIGNATURE_REQUEST
-                total = total.saturating_add(1000); // Simulated $1000 per request
+                total = total.saturating_add(SIMULATED_REQUEST_OUTFLOW); // Simulated $1000 per request
             }
         }
         total <= max_amount
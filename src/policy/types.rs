@@ -34,6 +34,15 @@ impl RiskTier {
             RiskTier::High => 5,
         }
     }
+
+    /// Position of the tier in ascending order of risk (`Low` = 0).
+    pub fn index(&self) -> u8 {
+        match self {
+            RiskTier::Low => 0,
+            RiskTier::Medium => 1,
+            RiskTier::High => 2,
+        }
+    }
 }
 
 /// Deterministic conditions for a behavioral policy.
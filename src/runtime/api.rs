@@ -1,5 +1,6 @@
 use crate::causal::CausalEventLogger;
 use crate::policy::PolicyEngine;
+use crate::utils::{tags, TaggedHasher};
 use sha3::{Sha3_256, Digest};
 use std::collections::HashMap;
 
@@ -118,12 +119,12 @@ impl CausalGuardRuntime {
             current_time_ms / 1000 // Convert to seconds for logger
         ).map_err(|e| RuntimeError::InternalError(e.to_string()))?;
 
-        // 4. ActionId Generation: SHA3-256(nonce || timestamp || agent_id)
-        let mut hasher = Sha3_256::new();
-        hasher.update(&event.nonce.to_be_bytes());
-        hasher.update(&event.timestamp.to_be_bytes());
-        hasher.update(&proposal.agent_id);
-        let action_id: ActionId = hasher.finalize().into();
+        // 4. ActionId Generation: H_tag(nonce || timestamp || agent_id)
+        let mut hasher = TaggedHasher::new(tags::ACTION_ID);
+        hasher.update(event.nonce.to_be_bytes());
+        hasher.update(event.timestamp.to_be_bytes());
+        hasher.update(proposal.agent_id);
+        let action_id: ActionId = hasher.finalize();
 
         // 5. Initialize State
         self.action_states.insert(action_id, ActionStatus::Pending);
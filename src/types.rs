@@ -6,10 +6,10 @@ use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
-/// ML-DSA-65 secret key wrapper with automatic zeroization on drop.
+/// Secret key wrapper with automatic zeroization on drop.
 #[derive(Clone, Zeroize, ZeroizeOnDrop)]
 pub struct SecretKey {
-    /// Raw secret key bytes (ML-DSA-65: 4032 bytes)
+    /// Raw secret key bytes in the active scheme's encoding (ML-DSA-65: 4032 bytes)
     pub(crate) bytes: Vec<u8>,
     /// Index of this key in the participant set
     pub(crate) index: usize,
@@ -32,10 +32,10 @@ impl SecretKey {
     }
 }
 
-/// ML-DSA-65 public key.
+/// Validator public key.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PublicKey {
-    /// Raw public key bytes (ML-DSA-65: 1952 bytes)
+    /// Raw public key bytes in the active scheme's encoding (ML-DSA-65: 1952 bytes)
     pub(crate) bytes: Vec<u8>,
     /// Index of this key in the participant set
     pub(crate) index: usize,
@@ -58,10 +58,19 @@ impl PublicKey {
     }
 }
 
-/// ML-DSA-65 signature with signer metadata.
+#[cfg(feature = "hybrid-ed25519")]
+impl PublicKey {
+    /// The `(ml_dsa, ed25519)` components of a hybrid key, or `None` if the
+    /// key has the wrong length.
+    pub fn hybrid_components(&self) -> Option<(&[u8], &[u8])> {
+        crate::core::scheme::ActiveScheme::split_public_key(&self.bytes)
+    }
+}
+
+/// Validator signature with signer metadata.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Signature {
-    /// Raw signature bytes (ML-DSA-65: 3293 bytes)
+    /// Raw signature bytes in the active scheme's encoding (ML-DSA-65: 3309 bytes)
     pub(crate) bytes: Vec<u8>,
     /// Index of the signer
     pub(crate) signer_index: usize,
@@ -91,6 +100,15 @@ impl Signature {
     }
 }
 
+#[cfg(feature = "hybrid-ed25519")]
+impl Signature {
+    /// The `(ml_dsa, ed25519)` components of a hybrid signature, or `None`
+    /// if the signature has the wrong length.
+    pub fn hybrid_components(&self) -> Option<(&[u8], &[u8])> {
+        crate::core::scheme::ActiveScheme::split_signature(&self.bytes)
+    }
+}
+
 /// Merkle proof for public key inclusion.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MerkleProof {
@@ -124,6 +142,385 @@ impl MerkleProof {
     }
 }
 
+/// Binary format version for [`MerkleMultiProof`].
+pub const MULTIPROOF_FORMAT_VERSION: u8 = 0x01;
+
+/// Merkle proof for several leaves at once.
+///
+/// Where `t` separate [`MerkleProof`]s repeat every path node the signers
+/// share, a multiproof carries each node only once and omits nodes the
+/// verifier can compute from the leaves themselves. Produced by
+/// [`MerkleTree::prove_many`](crate::utils::MerkleTree::prove_many) and
+/// checked with [`MerkleTree::verify_multi`](crate::utils::MerkleTree::verify_multi).
+///
+/// Leaves are ordered by ascending index.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleMultiProof {
+    /// Indices of the proven leaves, ascending
+    pub(crate) leaf_indices: Vec<usize>,
+    /// The public key hash at each proven leaf
+    pub(crate) leaf_hashes: Vec<[u8; 32]>,
+    /// Path nodes not derivable from the leaves, in verification order
+    pub(crate) proof_hashes: Vec<[u8; 32]>,
+    /// Height of the tree (number of levels above the leaves)
+    pub(crate) depth: u8,
+}
+
+impl MerkleMultiProof {
+    /// Create a new multiproof.
+    pub fn new(
+        leaf_indices: Vec<usize>,
+        leaf_hashes: Vec<[u8; 32]>,
+        proof_hashes: Vec<[u8; 32]>,
+        depth: u8,
+    ) -> Self {
+        Self { leaf_indices, leaf_hashes, proof_hashes, depth }
+    }
+
+    /// Get the indices of the proven leaves in ascending order.
+    pub fn leaf_indices(&self) -> &[usize] {
+        &self.leaf_indices
+    }
+
+    /// Get the leaf hashes, in the same order as [`Self::leaf_indices`].
+    pub fn leaf_hashes(&self) -> &[[u8; 32]] {
+        &self.leaf_hashes
+    }
+
+    /// Get the deduplicated path nodes.
+    pub fn proof_hashes(&self) -> &[[u8; 32]] {
+        &self.proof_hashes
+    }
+
+    /// Get the tree height.
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    /// Get the number of proven leaves.
+    pub fn num_leaves(&self) -> usize {
+        self.leaf_indices.len()
+    }
+
+    /// Look up the hash of a proven leaf by its index.
+    pub fn leaf_hash(&self, leaf_index: usize) -> Option<&[u8; 32]> {
+        let position = self.leaf_indices.binary_search(&leaf_index).ok()?;
+        self.leaf_hashes.get(position)
+    }
+
+    /// Serialize to compact binary format.
+    ///
+    /// Format: [version:1][depth:1][num_leaves:4]
+    ///         then per leaf: [leaf_index:4][leaf_hash:32]
+    ///         then [num_hashes:4][hashes:32*k]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(
+            1 + 1 + 4 + 36 * self.leaf_indices.len() + 4 + 32 * self.proof_hashes.len(),
+        );
+
+        out.push(MULTIPROOF_FORMAT_VERSION);
+        out.push(self.depth);
+        out.extend_from_slice(&(self.leaf_indices.len() as u32).to_le_bytes());
+        for (index, leaf_hash) in self.leaf_indices.iter().zip(&self.leaf_hashes) {
+            out.extend_from_slice(&(*index as u32).to_le_bytes());
+            out.extend_from_slice(leaf_hash);
+        }
+        out.extend_from_slice(&(self.proof_hashes.len() as u32).to_le_bytes());
+        for hash in &self.proof_hashes {
+            out.extend_from_slice(hash);
+        }
+
+        out
+    }
+
+    /// Deserialize from compact binary format.
+    ///
+    /// Returns `None` if the bytes are malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 6 || bytes[0] != MULTIPROOF_FORMAT_VERSION {
+            return None;
+        }
+
+        let depth = bytes[1];
+        let num_leaves = u32::from_le_bytes(bytes[2..6].try_into().ok()?) as usize;
+
+        let mut reader = ByteReader { bytes, pos: 6 };
+        let leaves = reader.take(num_leaves.checked_mul(36)?)?;
+        let (leaf_indices, leaf_hashes) = leaves
+            .chunks_exact(36)
+            .map(|chunk| {
+                let index = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) as usize;
+                let mut leaf_hash = [0u8; 32];
+                leaf_hash.copy_from_slice(&chunk[4..]);
+                (index, leaf_hash)
+            })
+            .unzip();
+
+        let num_hashes = reader.read_u32()? as usize;
+        let proof_hashes = reader
+            .take(num_hashes.checked_mul(32)?)?
+            .chunks_exact(32)
+            .map(|chunk| {
+                let mut hash = [0u8; 32];
+                hash.copy_from_slice(chunk);
+                hash
+            })
+            .collect();
+
+        if reader.pos != bytes.len() {
+            return None;
+        }
+
+        Some(Self { leaf_indices, leaf_hashes, proof_hashes, depth })
+    }
+}
+
+/// Hash function of the Merkle tree a proof's key root was built with.
+///
+/// Proofs record a non-default key tree in their extension block, so a
+/// verifier knows how `pk_root` was computed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KeyTree {
+    /// [`MerkleTree`](crate::utils::MerkleTree) over domain-separated SHA3-256
+    #[default]
+    Sha3,
+    /// Fixed-depth Poseidon tree over the Pallas scalar field, whose paths
+    /// can be checked inside the Nova step circuit (`nova` feature)
+    Poseidon,
+}
+
+impl KeyTree {
+    /// Identifier recorded in aggregated proofs.
+    pub fn id(self) -> u8 {
+        match self {
+            KeyTree::Sha3 => 0x01,
+            KeyTree::Poseidon => 0x02,
+        }
+    }
+
+    /// Look up a key tree by [`Self::id`].
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0x01 => Some(KeyTree::Sha3),
+            0x02 => Some(KeyTree::Poseidon),
+            _ => None,
+        }
+    }
+}
+
+/// Domain prefix of a message bound to a [`ProofContext`].
+pub const PROOF_CONTEXT_MESSAGE_DOMAIN: &[u8] = b"pq-aggregate/proof-context/v1";
+
+/// Where and when an aggregated proof is valid.
+///
+/// A proof aggregated with
+/// [`aggregate_proofs_with_context`](crate::core::aggregation::aggregate_proofs_with_context)
+/// records its context in the public inputs, and its signers sign
+/// [`Self::bind`] of the message rather than the bare message. Such a proof
+/// is only accepted by [`verify_at`](crate::verifier::verify_at) for the
+/// same domain and epoch, inside the validity window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofContext {
+    /// Domain or chain the proof is for (see [`Self::domain_id`])
+    pub domain: [u8; 32],
+    /// Committee epoch the signers belong to
+    pub epoch: u64,
+    /// First time (inclusive, Unix seconds) the proof is valid
+    pub not_before: u64,
+    /// Last time (inclusive, Unix seconds) the proof is valid
+    pub not_after: u64,
+}
+
+impl ProofContext {
+    /// Encoded size in bytes.
+    pub const ENCODED_LEN: usize = 32 + 8 + 8 + 8;
+
+    pub fn new(domain: [u8; 32], epoch: u64, not_before: u64, not_after: u64) -> Self {
+        Self { domain, epoch, not_before, not_after }
+    }
+
+    /// Derive a 32-byte domain identifier from a chain ID such as `"solana:devnet"`.
+    pub fn domain_id(chain_id: &str) -> [u8; 32] {
+        crate::utils::tagged_hash(crate::utils::tags::PROOF_DOMAIN, chain_id.as_bytes())
+    }
+
+    /// Whether `time` lies inside the validity window.
+    pub fn is_valid_at(&self, time: u64) -> bool {
+        self.not_before <= time && time <= self.not_after
+    }
+
+    /// The message validators sign for `msg` under this context.
+    ///
+    /// Format: [domain prefix][context:56][msg]
+    pub fn bind(&self, msg: &[u8]) -> Vec<u8> {
+        let mut bound = Vec::with_capacity(PROOF_CONTEXT_MESSAGE_DOMAIN.len() + Self::ENCODED_LEN + msg.len());
+        bound.extend_from_slice(PROOF_CONTEXT_MESSAGE_DOMAIN);
+        bound.extend_from_slice(&self.to_bytes());
+        bound.extend_from_slice(msg);
+        bound
+    }
+
+    /// Serialize to binary format.
+    ///
+    /// Format: [domain:32][epoch:8][not_before:8][not_after:8]
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        let mut out = [0u8; Self::ENCODED_LEN];
+        out[..32].copy_from_slice(&self.domain);
+        out[32..40].copy_from_slice(&self.epoch.to_le_bytes());
+        out[40..48].copy_from_slice(&self.not_before.to_le_bytes());
+        out[48..].copy_from_slice(&self.not_after.to_le_bytes());
+        out
+    }
+
+    /// Deserialize from binary format.
+    ///
+    /// Returns `None` if the length is wrong or the window ends before it starts.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::ENCODED_LEN {
+            return None;
+        }
+
+        let mut domain = [0u8; 32];
+        domain.copy_from_slice(&bytes[..32]);
+        let epoch = u64::from_le_bytes(bytes[32..40].try_into().ok()?);
+        let not_before = u64::from_le_bytes(bytes[40..48].try_into().ok()?);
+        let not_after = u64::from_le_bytes(bytes[48..].try_into().ok()?);
+        if not_before > not_after {
+            return None;
+        }
+
+        Some(Self { domain, epoch, not_before, not_after })
+    }
+}
+
+/// Legacy aggregated proof layout: fixed 32-byte bitmap, at most 256 signers.
+pub const PROOF_FORMAT_V1: u8 = 0x01;
+
+/// Aggregated proof layout with a length-prefixed bitmap sized to the committee.
+pub const PROOF_FORMAT_V2: u8 = 0x02;
+
+/// v2 layout carrying a compressed Nova SNARK over the signer set.
+pub const PROOF_FORMAT_V3: u8 = 0x03;
+
+/// Binary format version for [`SignatureShare`].
+pub const SHARE_FORMAT_VERSION: u8 = 0x01;
+
+/// A single validator's contribution to a threshold signature.
+///
+/// Each validator produces its share on its own host with
+/// [`sign_share`](crate::core::signing::sign_share) and ships it to the
+/// aggregator, so no process ever needs to hold more than one secret key.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignatureShare {
+    /// The ML-DSA signature, signer index and nonce
+    pub(crate) signature: Signature,
+    /// Proof that the signer's public key is a leaf of `pk_root`
+    pub(crate) merkle_proof: MerkleProof,
+}
+
+impl SignatureShare {
+    /// Create a new signature share.
+    pub fn new(signature: Signature, merkle_proof: MerkleProof) -> Self {
+        Self { signature, merkle_proof }
+    }
+
+    /// Get the signer's index.
+    pub fn signer_index(&self) -> usize {
+        self.signature.signer_index
+    }
+
+    /// Get the nonce used for this share.
+    pub fn nonce(&self) -> &[u8; 32] {
+        &self.signature.nonce
+    }
+
+    /// Get the signature.
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    /// Get the Merkle proof for the signer's public key.
+    pub fn merkle_proof(&self) -> &MerkleProof {
+        &self.merkle_proof
+    }
+
+    /// Split the share into its signature and Merkle proof.
+    pub fn into_parts(self) -> (Signature, MerkleProof) {
+        (self.signature, self.merkle_proof)
+    }
+
+    /// Serialize to compact binary format for transport to the aggregator.
+    ///
+    /// Format: [version:1][signer_index:4][nonce:32][sig_len:4][sig:N]
+    ///         [leaf_hash:32][num_siblings:1][siblings:32*k]
+    ///
+    /// The Merkle leaf index is not stored separately; it always equals the
+    /// signer index.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let siblings = self.merkle_proof.siblings();
+        let mut out = Vec::with_capacity(
+            1 + 4 + 32 + 4 + self.signature.bytes.len() + 32 + 1 + 32 * siblings.len(),
+        );
+
+        out.push(SHARE_FORMAT_VERSION);
+        out.extend_from_slice(&(self.signature.signer_index as u32).to_le_bytes());
+        out.extend_from_slice(&self.signature.nonce);
+        out.extend_from_slice(&(self.signature.bytes.len() as u32).to_le_bytes());
+        out.extend_from_slice(&self.signature.bytes);
+        out.extend_from_slice(&self.merkle_proof.leaf_hash);
+        out.push(siblings.len() as u8);
+        for sibling in siblings {
+            out.extend_from_slice(sibling);
+        }
+
+        out
+    }
+
+    /// Deserialize from compact binary format.
+    ///
+    /// Returns `None` if the bytes are malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        // Minimum size: version(1) + index(4) + nonce(32) + sig_len(4) + leaf(32) + count(1) = 74
+        if bytes.len() < 74 || bytes[0] != SHARE_FORMAT_VERSION {
+            return None;
+        }
+
+        let signer_index = u32::from_le_bytes(bytes[1..5].try_into().ok()?) as usize;
+
+        let mut nonce = [0u8; 32];
+        nonce.copy_from_slice(&bytes[5..37]);
+
+        let sig_len = u32::from_le_bytes(bytes[37..41].try_into().ok()?) as usize;
+        let sig_end = 41usize.checked_add(sig_len)?;
+        if bytes.len() < sig_end + 33 {
+            return None;
+        }
+        let sig_bytes = bytes[41..sig_end].to_vec();
+
+        let mut leaf_hash = [0u8; 32];
+        leaf_hash.copy_from_slice(&bytes[sig_end..sig_end + 32]);
+
+        let num_siblings = bytes[sig_end + 32] as usize;
+        let siblings_start = sig_end + 33;
+        if bytes.len() != siblings_start + 32 * num_siblings {
+            return None;
+        }
+        let siblings = bytes[siblings_start..]
+            .chunks_exact(32)
+            .map(|chunk| {
+                let mut sibling = [0u8; 32];
+                sibling.copy_from_slice(chunk);
+                sibling
+            })
+            .collect();
+
+        Some(Self {
+            signature: Signature::new(sig_bytes, signer_index, nonce),
+            merkle_proof: MerkleProof::new(siblings, signer_index, leaf_hash),
+        })
+    }
+}
+
 /// Aggregated ZKSNARK proof from Nova recursive folding.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ZKSNARKProof {
@@ -161,20 +558,38 @@ impl ZKSNARKProof {
         self.proof_bytes.len()
     }
 
+    /// Get the indices of the validators who signed, in ascending order.
+    ///
+    /// Read from the proof's signer bitmap. Returns `None` if the proof bytes
+    /// are malformed; the set is only meaningful once the proof has been
+    /// checked with [`verify`](crate::verifier::verify).
+    pub fn signer_indices(&self) -> Option<Vec<usize>> {
+        let view = crate::core::proof_format::ProofView::parse(&self.proof_bytes)?;
+        Some(crate::utils::bitmap_to_indices(view.bitmap))
+    }
+
     /// Serialize to compact binary format for on-chain submission.
     /// 
     /// Format: [version:1][num_sigs:2][inputs_hash:32][proof_len:4][proof_bytes:N]
+    ///
+    /// Proofs with more than `u16::MAX` signatures use version 0x03, which
+    /// widens `num_sigs` to 4 bytes. Smaller proofs keep the 0x02 encoding so
+    /// existing consumers continue to parse them.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut out = Vec::with_capacity(39 + self.proof_bytes.len());
-        
-        // Version byte
-        out.push(0x02);
+        let mut out = Vec::with_capacity(41 + self.proof_bytes.len());
         
-        // Number of signatures (2 bytes, little-endian)
-        // Protocol maximum is 256 signers; assert in debug, saturate in release.
-        debug_assert!(self.num_signatures <= u16::MAX as usize, "num_signatures exceeds u16::MAX");
-        let num_sigs_u16 = u16::try_from(self.num_signatures).unwrap_or(u16::MAX);
-        out.extend_from_slice(&num_sigs_u16.to_le_bytes());
+        match u16::try_from(self.num_signatures) {
+            Ok(num_sigs) => {
+                // Version byte + number of signatures (2 bytes, little-endian)
+                out.push(0x02);
+                out.extend_from_slice(&num_sigs.to_le_bytes());
+            }
+            Err(_) => {
+                // Version byte + number of signatures (4 bytes, little-endian)
+                out.push(0x03);
+                out.extend_from_slice(&(self.num_signatures as u32).to_le_bytes());
+            }
+        }
         
         // Public inputs hash (32 bytes)
         out.extend_from_slice(&self.public_inputs_hash);
@@ -190,35 +605,39 @@ impl ZKSNARKProof {
 
     /// Deserialize from compact binary format.
     /// 
+    /// Accepts both the 0x02 and 0x03 encodings produced by [`Self::to_bytes`].
     /// Returns `None` if the bytes are malformed.
     pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        // Minimum size: version(1) + num_sigs(2) + hash(32) + len(4) = 39
-        if bytes.len() < 39 {
-            return None;
-        }
+        // Parse version and num_signatures
+        let (num_signatures, header_len) = match bytes.first()? {
+            0x02 if bytes.len() >= 3 => (u16::from_le_bytes([bytes[1], bytes[2]]) as usize, 3),
+            0x03 if bytes.len() >= 5 => {
+                (u32::from_le_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]) as usize, 5)
+            }
+            _ => return None,
+        };
 
-        // Check version
-        if bytes[0] != 0x02 {
+        // Minimum size: header + hash(32) + len(4)
+        let body_start = header_len + 36;
+        if bytes.len() < body_start {
             return None;
         }
 
-        // Parse num_signatures
-        let num_signatures = u16::from_le_bytes([bytes[1], bytes[2]]) as usize;
-
         // Parse public_inputs_hash
         let mut public_inputs_hash = [0u8; 32];
-        public_inputs_hash.copy_from_slice(&bytes[3..35]);
+        public_inputs_hash.copy_from_slice(&bytes[header_len..header_len + 32]);
 
         // Parse proof length
-        let proof_len = u32::from_le_bytes([bytes[35], bytes[36], bytes[37], bytes[38]]) as usize;
+        let len_bytes = &bytes[header_len + 32..body_start];
+        let proof_len = u32::from_le_bytes([len_bytes[0], len_bytes[1], len_bytes[2], len_bytes[3]]) as usize;
 
         // Validate total length
-        if bytes.len() != 39 + proof_len {
+        if bytes.len() - body_start != proof_len {
             return None;
         }
 
         // Extract proof bytes
-        let proof_bytes = bytes[39..].to_vec();
+        let proof_bytes = bytes[body_start..].to_vec();
 
         Some(Self {
             proof_bytes,
@@ -243,6 +662,344 @@ impl ZKSNARKProof {
     }
 }
 
+/// Binary format version for [`FullProof`].
+pub const FULL_PROOF_FORMAT_VERSION: u8 = 0x01;
+
+/// Transparent aggregated proof carrying every signature in the clear.
+///
+/// Where [`ZKSNARKProof`] only commits to the signatures, a full proof embeds
+/// each signer's public key, nonce, signature and Merkle proof, so anyone
+/// holding `pk_root` can re-check every signature with
+/// [`verify_full`](crate::verifier::verify_full). Once verified it can be
+/// compressed into the compact form with
+/// [`compress_full_proof`](crate::core::aggregation::compress_full_proof).
+///
+/// Entries are ordered by ascending signer index.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FullProof {
+    /// Merkle root of the committee's public keys
+    pub(crate) pk_root: [u8; 32],
+    /// Number of validators in the committee
+    pub(crate) committee_size: usize,
+    /// Signature scheme the shares were produced under
+    pub(crate) scheme_id: u8,
+    /// Each signer's public key and share
+    pub(crate) entries: Vec<(PublicKey, SignatureShare)>,
+}
+
+impl FullProof {
+    /// Get the public key root the proof claims.
+    pub fn pk_root(&self) -> &[u8; 32] {
+        &self.pk_root
+    }
+
+    /// Get the committee size.
+    pub fn committee_size(&self) -> usize {
+        self.committee_size
+    }
+
+    /// Get the signature scheme ID.
+    pub fn scheme_id(&self) -> u8 {
+        self.scheme_id
+    }
+
+    /// Get the number of embedded signatures.
+    pub fn num_signatures(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Get the signer indices in ascending order.
+    pub fn signer_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.entries.iter().map(|(_, share)| share.signer_index())
+    }
+
+    /// Get each signer's public key and share.
+    pub fn entries(&self) -> &[(PublicKey, SignatureShare)] {
+        &self.entries
+    }
+
+    /// Serialize to binary format for archiving or handing to auditors.
+    ///
+    /// Format: [version:1][scheme_id:1][committee_size:4][num_sigs:4][pk_root:32]
+    ///         then per signer: [signer_index:4][nonce:32][pk_len:4][pk:M]
+    ///         [sig_len:4][sig:N][num_siblings:1][siblings:32*k]
+    ///
+    /// Leaf hashes are not stored; they are recomputed from the public keys.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+
+        out.push(FULL_PROOF_FORMAT_VERSION);
+        out.push(self.scheme_id);
+        out.extend_from_slice(&(self.committee_size as u32).to_le_bytes());
+        out.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        out.extend_from_slice(&self.pk_root);
+
+        for (public_key, share) in &self.entries {
+            let siblings = share.merkle_proof.siblings();
+            out.extend_from_slice(&(share.signature.signer_index as u32).to_le_bytes());
+            out.extend_from_slice(&share.signature.nonce);
+            out.extend_from_slice(&(public_key.bytes.len() as u32).to_le_bytes());
+            out.extend_from_slice(&public_key.bytes);
+            out.extend_from_slice(&(share.signature.bytes.len() as u32).to_le_bytes());
+            out.extend_from_slice(&share.signature.bytes);
+            out.push(siblings.len() as u8);
+            for sibling in siblings {
+                out.extend_from_slice(sibling);
+            }
+        }
+
+        out
+    }
+
+    /// Deserialize from binary format.
+    ///
+    /// Returns `None` if the bytes are malformed. A successful parse says
+    /// nothing about validity; use [`verify_full`](crate::verifier::verify_full).
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        use crate::core::scheme::{ActiveScheme, SignatureScheme};
+
+        // Header: version(1) + scheme(1) + committee_size(4) + num_sigs(4) + root(32) = 42
+        if bytes.len() < 42 || bytes[0] != FULL_PROOF_FORMAT_VERSION {
+            return None;
+        }
+
+        let scheme_id = bytes[1];
+        let committee_size = u32::from_le_bytes(bytes[2..6].try_into().ok()?) as usize;
+        let num_signatures = u32::from_le_bytes(bytes[6..10].try_into().ok()?) as usize;
+        let mut pk_root = [0u8; 32];
+        pk_root.copy_from_slice(&bytes[10..42]);
+
+        let mut reader = ByteReader { bytes, pos: 42 };
+        // Each entry takes at least 45 bytes, which bounds the allocation
+        let mut entries = Vec::with_capacity(num_signatures.min(bytes.len() / 45));
+
+        for _ in 0..num_signatures {
+            let signer_index = reader.read_u32()? as usize;
+            let nonce: [u8; 32] = reader.take(32)?.try_into().ok()?;
+            let pk_len = reader.read_u32()? as usize;
+            let pk_bytes = reader.take(pk_len)?.to_vec();
+            let sig_len = reader.read_u32()? as usize;
+            let sig_bytes = reader.take(sig_len)?.to_vec();
+            let num_siblings = *reader.take(1)?.first()? as usize;
+            let siblings = reader
+                .take(num_siblings.checked_mul(32)?)?
+                .chunks_exact(32)
+                .map(|chunk| {
+                    let mut sibling = [0u8; 32];
+                    sibling.copy_from_slice(chunk);
+                    sibling
+                })
+                .collect();
+
+            let leaf_hash = ActiveScheme::leaf_hash(&pk_bytes);
+            entries.push((
+                PublicKey::from_bytes(pk_bytes, signer_index),
+                SignatureShare::new(
+                    Signature::new(sig_bytes, signer_index, nonce),
+                    MerkleProof::new(siblings, signer_index, leaf_hash),
+                ),
+            ));
+        }
+
+        if reader.pos != bytes.len() {
+            return None;
+        }
+
+        Some(Self {
+            pk_root,
+            committee_size,
+            scheme_id,
+            entries,
+        })
+    }
+}
+
+/// Binary format version for [`MultiMessageCertificate`].
+pub const CERTIFICATE_FORMAT_VERSION: u8 = 0x01;
+
+/// Threshold certificate over several distinct messages.
+///
+/// Built by
+/// [`aggregate_certificate`](crate::core::aggregation::aggregate_certificate).
+/// Each message gets its own signer bitmap and signature commitment. The
+/// messages themselves are not stored, only the root of
+/// [`Self::message_tree`], so one certificate can vouch for many approvals
+/// while a verifier checks only the message it cares about with
+/// [`verify_certificate_message`](crate::verifier::verify_certificate_message).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultiMessageCertificate {
+    /// Merkle root of the committee's public keys
+    pub(crate) pk_root: [u8; 32],
+    /// Number of validators in the committee
+    pub(crate) committee_size: usize,
+    /// Signature scheme the signatures were produced under
+    pub(crate) scheme_id: u8,
+    /// Root of the message tree
+    pub(crate) messages_root: [u8; 32],
+    /// Per message, in tree order: signer bitmap and signature commitment
+    pub(crate) entries: Vec<(Vec<u8>, [u8; 32])>,
+    /// Commitment to all of the above
+    pub(crate) public_inputs_hash: [u8; 32],
+}
+
+impl MultiMessageCertificate {
+    /// Leaf of `msg`, signed in `mode`, in a message tree.
+    ///
+    /// The leaf covers the framed message, so a certificate records the
+    /// mode its messages were signed in.
+    pub fn message_leaf(msg: &[u8], mode: crate::core::prehash::MessageMode) -> [u8; 32] {
+        crate::utils::tagged_hash(crate::utils::tags::CERTIFICATE_MESSAGE, &mode.frame(msg))
+    }
+
+    /// Build the message tree a certificate over `messages`, signed in `mode`, commits to.
+    ///
+    /// Use [`MerkleTree::prove`](crate::utils::MerkleTree::prove) on the
+    /// result to produce the inclusion proof for one message.
+    pub fn message_tree(messages: &[&[u8]], mode: crate::core::prehash::MessageMode) -> crate::utils::MerkleTree {
+        let leaves: Vec<[u8; 32]> = messages.iter().map(|msg| Self::message_leaf(msg, mode)).collect();
+        crate::utils::MerkleTree::from_leaves(&leaves)
+    }
+
+    /// Get the public key root the certificate claims.
+    pub fn pk_root(&self) -> &[u8; 32] {
+        &self.pk_root
+    }
+
+    /// Get the committee size.
+    pub fn committee_size(&self) -> usize {
+        self.committee_size
+    }
+
+    /// Get the signature scheme ID.
+    pub fn scheme_id(&self) -> u8 {
+        self.scheme_id
+    }
+
+    /// Get the root of the message tree.
+    pub fn messages_root(&self) -> &[u8; 32] {
+        &self.messages_root
+    }
+
+    /// Get the number of certified messages.
+    pub fn num_messages(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Get the signer bitmap of the message at `message_index`.
+    pub fn bitmap(&self, message_index: usize) -> Option<&[u8]> {
+        self.entries.get(message_index).map(|(bitmap, _)| bitmap.as_slice())
+    }
+
+    /// Get the indices of the validators who signed the message at
+    /// `message_index`, in ascending order.
+    pub fn signer_indices(&self, message_index: usize) -> Option<Vec<usize>> {
+        self.bitmap(message_index).map(crate::utils::bitmap_to_indices)
+    }
+
+    /// Get the public inputs hash.
+    pub fn public_inputs_hash(&self) -> &[u8; 32] {
+        &self.public_inputs_hash
+    }
+
+    /// Recompute the commitment to the certificate's contents.
+    pub(crate) fn compute_public_inputs_hash(&self) -> [u8; 32] {
+        let mut hasher = crate::utils::TaggedHasher::new(crate::utils::tags::CERTIFICATE_INPUTS);
+        hasher.update(self.pk_root);
+        hasher.update([self.scheme_id]);
+        hasher.update((self.committee_size as u32).to_le_bytes());
+        hasher.update(self.messages_root);
+        hasher.update((self.entries.len() as u32).to_le_bytes());
+        for (bitmap, commitment) in &self.entries {
+            hasher.update(bitmap);
+            hasher.update(commitment);
+        }
+        hasher.finalize()
+    }
+
+    /// Serialize to binary format.
+    ///
+    /// Format: [version:1][scheme_id:1][committee_size:4][num_messages:4]
+    ///         [pk_root:32][messages_root:32][inputs_hash:32]
+    ///         then per message: [bitmap:ceil(n/8)][commitment:32]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let bitmap_len = self.committee_size.div_ceil(8);
+        let mut out = Vec::with_capacity(106 + self.entries.len() * (bitmap_len + 32));
+
+        out.push(CERTIFICATE_FORMAT_VERSION);
+        out.push(self.scheme_id);
+        out.extend_from_slice(&(self.committee_size as u32).to_le_bytes());
+        out.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        out.extend_from_slice(&self.pk_root);
+        out.extend_from_slice(&self.messages_root);
+        out.extend_from_slice(&self.public_inputs_hash);
+        for (bitmap, commitment) in &self.entries {
+            out.extend_from_slice(bitmap);
+            out.extend_from_slice(commitment);
+        }
+
+        out
+    }
+
+    /// Deserialize from binary format.
+    ///
+    /// Returns `None` if the bytes are malformed. A successful parse says
+    /// nothing about validity.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        // Header: version(1) + scheme(1) + committee_size(4) + num_messages(4) + 3 * 32 = 106
+        if bytes.len() < 106 || bytes[0] != CERTIFICATE_FORMAT_VERSION {
+            return None;
+        }
+
+        let scheme_id = bytes[1];
+        let committee_size = u32::from_le_bytes(bytes[2..6].try_into().ok()?) as usize;
+        let num_messages = u32::from_le_bytes(bytes[6..10].try_into().ok()?) as usize;
+        let pk_root: [u8; 32] = bytes[10..42].try_into().ok()?;
+        let messages_root: [u8; 32] = bytes[42..74].try_into().ok()?;
+        let public_inputs_hash: [u8; 32] = bytes[74..106].try_into().ok()?;
+
+        let entry_len = committee_size.div_ceil(8) + 32;
+        if bytes.len() - 106 != num_messages.checked_mul(entry_len)? {
+            return None;
+        }
+        let entries = bytes[106..]
+            .chunks_exact(entry_len)
+            .map(|chunk| {
+                let (bitmap, commitment) = chunk.split_at(entry_len - 32);
+                let mut commitment_bytes = [0u8; 32];
+                commitment_bytes.copy_from_slice(commitment);
+                (bitmap.to_vec(), commitment_bytes)
+            })
+            .collect();
+
+        Some(Self {
+            pk_root,
+            committee_size,
+            scheme_id,
+            messages_root,
+            entries,
+            public_inputs_hash,
+        })
+    }
+}
+
+/// Bounds-checked cursor over a byte slice.
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let slice = self.bytes.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(slice)
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+}
+
 /// A creative "ProofBatch" for high-density multi-proof storage.
 /// 
 /// Instead of storing individual proofs, we aggregate them into a single blob
@@ -306,15 +1063,439 @@ pub struct RotationProof {
     pub old_root: [u8; 32],
     /// The new public key root (proposed state)
     pub new_root: [u8; 32],
+    /// Number of validators in the new committee
+    pub new_committee_size: usize,
     /// The aggregated ZK proof showing that t-of-n of OLD committee signed NEW_ROOT
     pub proof: ZKSNARKProof,
     /// Epoch number of this rotation
     pub epoch: u64,
 }
 
+/// Domain prefix of the message signed to authorize a rotation.
+pub const ROTATION_MESSAGE_DOMAIN: &[u8] = b"pq-aggregate/rotation/v2";
+
 impl RotationProof {
-    pub fn new(old_root: [u8; 32], new_root: [u8; 32], proof: ZKSNARKProof, epoch: u64) -> Self {
-        Self { old_root, new_root, proof, epoch }
+    pub fn new(
+        old_root: [u8; 32],
+        new_root: [u8; 32],
+        new_committee_size: usize,
+        proof: ZKSNARKProof,
+        epoch: u64,
+    ) -> Self {
+        Self { old_root, new_root, new_committee_size, proof, epoch }
+    }
+
+    /// Message the old committee signs to hand over to `new_root` at `epoch`.
+    ///
+    /// Format: [domain][new_root:32][new_committee_size:4 LE][epoch:8 LE].
+    /// Binding the epoch keeps a rotation from being replayed under a
+    /// different epoch number; binding the size lets verifiers check later
+    /// proofs against a committee size they did not take from the proof.
+    pub fn message(new_root: &[u8; 32], new_committee_size: usize, epoch: u64) -> Vec<u8> {
+        let mut msg = Vec::with_capacity(ROTATION_MESSAGE_DOMAIN.len() + 44);
+        msg.extend_from_slice(ROTATION_MESSAGE_DOMAIN);
+        msg.extend_from_slice(new_root);
+        msg.extend_from_slice(&(new_committee_size as u32).to_le_bytes());
+        msg.extend_from_slice(&epoch.to_le_bytes());
+        msg
+    }
+
+    /// The message this rotation's proof must be over.
+    pub fn signed_message(&self) -> Vec<u8> {
+        Self::message(&self.new_root, self.new_committee_size, self.epoch)
+    }
+}
+
+/// Domain prefix of the message signed to authorize a membership update.
+pub const MEMBERSHIP_UPDATE_MESSAGE_DOMAIN: &[u8] = b"pq-aggregate/membership-update/v1";
+
+/// Binary format version for [`MembershipUpdateProof`].
+pub const MEMBERSHIP_UPDATE_FORMAT_VERSION: u8 = 0x01;
+
+/// A membership update the old committee has not signed yet.
+///
+/// Produced by [`prepare_membership_update`](crate::core::aggregation::prepare_membership_update).
+/// Each remaining member signs [`Self::signed_message`] on its own with
+/// [`sign_share`](crate::core::signing::sign_share), and the collected shares
+/// are aggregated into a [`MembershipUpdateProof`] by
+/// [`create_membership_update`](crate::core::aggregation::create_membership_update).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PendingMembershipUpdate {
+    /// The old public key root (current state)
+    pub old_root: [u8; 32],
+    /// The new public key root after the update
+    pub new_root: [u8; 32],
+    /// Multiproof of the changed leaves' old values under `old_root`
+    pub paths: MerkleMultiProof,
+    /// Replacement leaf for each index in `paths`, in the same order
+    pub new_leaves: Vec<[u8; 32]>,
+    /// Epoch number of this update
+    pub epoch: u64,
+}
+
+impl PendingMembershipUpdate {
+    /// The message each member of the old committee signs.
+    pub fn signed_message(&self) -> Vec<u8> {
+        MembershipUpdateProof::message(&self.new_root, self.epoch, &self.paths, &self.new_leaves)
+    }
+}
+
+/// A "MembershipUpdateProof" replaces or revokes individual validators.
+///
+/// Unlike a [`RotationProof`], which hands over to an unrelated root, it
+/// declares exactly which leaves of the key tree changed. `paths` proves the
+/// old leaves against `old_root`; the same path nodes with `new_leaves`
+/// substituted must hash to `new_root`, so no other leaf can have changed.
+/// A threshold of the old committee signs the declared changes.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MembershipUpdateProof {
+    /// The old public key root (current state)
+    pub old_root: [u8; 32],
+    /// The new public key root after the update
+    pub new_root: [u8; 32],
+    /// Multiproof of the changed leaves' old values under `old_root`
+    pub paths: MerkleMultiProof,
+    /// Replacement leaf for each index in `paths`, in the same order
+    pub new_leaves: Vec<[u8; 32]>,
+    /// The aggregated ZK proof showing that t-of-n of the OLD committee signed the update
+    pub proof: ZKSNARKProof,
+    /// Epoch number of this update
+    pub epoch: u64,
+}
+
+impl MembershipUpdateProof {
+    pub fn new(
+        old_root: [u8; 32],
+        new_root: [u8; 32],
+        paths: MerkleMultiProof,
+        new_leaves: Vec<[u8; 32]>,
+        proof: ZKSNARKProof,
+        epoch: u64,
+    ) -> Self {
+        Self { old_root, new_root, paths, new_leaves, proof, epoch }
+    }
+
+    /// Message the old committee signs to apply these leaf changes at `epoch`.
+    ///
+    /// Format: [domain][new_root:32][epoch:8 LE][num_changes:4]
+    ///         then per change: [leaf_index:4][old_leaf:32][new_leaf:32]
+    pub fn message(new_root: &[u8; 32], epoch: u64, paths: &MerkleMultiProof, new_leaves: &[[u8; 32]]) -> Vec<u8> {
+        let mut msg = Vec::with_capacity(MEMBERSHIP_UPDATE_MESSAGE_DOMAIN.len() + 44 + 68 * new_leaves.len());
+        msg.extend_from_slice(MEMBERSHIP_UPDATE_MESSAGE_DOMAIN);
+        msg.extend_from_slice(new_root);
+        msg.extend_from_slice(&epoch.to_le_bytes());
+        msg.extend_from_slice(&(new_leaves.len() as u32).to_le_bytes());
+        for ((index, old_leaf), new_leaf) in paths.leaf_indices.iter().zip(&paths.leaf_hashes).zip(new_leaves) {
+            msg.extend_from_slice(&(*index as u32).to_le_bytes());
+            msg.extend_from_slice(old_leaf);
+            msg.extend_from_slice(new_leaf);
+        }
+        msg
+    }
+
+    /// The message this update's proof must be over.
+    pub fn signed_message(&self) -> Vec<u8> {
+        Self::message(&self.new_root, self.epoch, &self.paths, &self.new_leaves)
+    }
+
+    /// Get the indices of the changed leaves in ascending order.
+    pub fn changed_indices(&self) -> &[usize] {
+        self.paths.leaf_indices()
+    }
+
+    /// Look up the replacement leaf for a changed index.
+    pub fn new_leaf(&self, leaf_index: usize) -> Option<&[u8; 32]> {
+        let position = self.paths.leaf_indices.binary_search(&leaf_index).ok()?;
+        self.new_leaves.get(position)
+    }
+
+    /// Serialize to compact binary format.
+    ///
+    /// Format: [version:1][old_root:32][new_root:32][epoch:8]
+    ///         [paths_len:4][paths:MerkleMultiProof][new_leaves:32*k]
+    ///         [proof_len:4][proof:ZKSNARKProof]
+    ///
+    /// `k` is the number of leaves in `paths`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let paths = self.paths.to_bytes();
+        let proof = self.proof.to_bytes();
+        let mut out = Vec::with_capacity(1 + 64 + 8 + 4 + paths.len() + 32 * self.new_leaves.len() + 4 + proof.len());
+
+        out.push(MEMBERSHIP_UPDATE_FORMAT_VERSION);
+        out.extend_from_slice(&self.old_root);
+        out.extend_from_slice(&self.new_root);
+        out.extend_from_slice(&self.epoch.to_le_bytes());
+        out.extend_from_slice(&(paths.len() as u32).to_le_bytes());
+        out.extend_from_slice(&paths);
+        for leaf in &self.new_leaves {
+            out.extend_from_slice(leaf);
+        }
+        out.extend_from_slice(&(proof.len() as u32).to_le_bytes());
+        out.extend_from_slice(&proof);
+
+        out
+    }
+
+    /// Deserialize from compact binary format.
+    ///
+    /// Returns `None` if the bytes are malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 73 || bytes[0] != MEMBERSHIP_UPDATE_FORMAT_VERSION {
+            return None;
+        }
+
+        let mut old_root = [0u8; 32];
+        old_root.copy_from_slice(&bytes[1..33]);
+        let mut new_root = [0u8; 32];
+        new_root.copy_from_slice(&bytes[33..65]);
+        let epoch = u64::from_le_bytes(bytes[65..73].try_into().ok()?);
+
+        let mut reader = ByteReader { bytes, pos: 73 };
+        let paths_len = reader.read_u32()? as usize;
+        let paths = MerkleMultiProof::from_bytes(reader.take(paths_len)?)?;
+        let new_leaves = reader
+            .take(paths.num_leaves().checked_mul(32)?)?
+            .chunks_exact(32)
+            .map(|chunk| {
+                let mut leaf = [0u8; 32];
+                leaf.copy_from_slice(chunk);
+                leaf
+            })
+            .collect();
+        let proof_len = reader.read_u32()? as usize;
+        let proof = ZKSNARKProof::from_bytes(reader.take(proof_len)?)?;
+
+        if reader.pos != bytes.len() {
+            return None;
+        }
+
+        Some(Self { old_root, new_root, paths, new_leaves, proof, epoch })
+    }
+}
+
+/// Domain prefix of a [`SlotMessage`].
+pub const SLOT_MESSAGE_DOMAIN: &[u8] = b"pq-aggregate/slot-message/v1";
+
+/// Binary format version for [`EquivocationEvidence`].
+pub const EQUIVOCATION_FORMAT_VERSION: u8 = 0x01;
+
+/// A payload signed for one slot of one epoch.
+///
+/// Validators that may be held accountable sign [`Self::to_bytes`]. An
+/// honest validator signs at most one payload per `(epoch, slot)`; two
+/// different payloads for the same slot are [`EquivocationEvidence`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotMessage {
+    /// Committee epoch
+    pub epoch: u64,
+    /// Slot within the epoch
+    pub slot: u64,
+    /// Application payload
+    pub payload: Vec<u8>,
+}
+
+impl SlotMessage {
+    pub fn new(epoch: u64, slot: u64, payload: Vec<u8>) -> Self {
+        Self { epoch, slot, payload }
+    }
+
+    /// The message validators sign.
+    ///
+    /// Format: [domain][epoch:8 LE][slot:8 LE][payload]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut msg = Vec::with_capacity(SLOT_MESSAGE_DOMAIN.len() + 16 + self.payload.len());
+        msg.extend_from_slice(SLOT_MESSAGE_DOMAIN);
+        msg.extend_from_slice(&self.epoch.to_le_bytes());
+        msg.extend_from_slice(&self.slot.to_le_bytes());
+        msg.extend_from_slice(&self.payload);
+        msg
+    }
+}
+
+/// Proof that a validator signed two different payloads for the same slot.
+///
+/// Holds the validator's public key and Merkle proof once, plus both
+/// signatures. The two sides are kept in payload order so the same double
+/// signature always yields the same evidence. Check it with
+/// [`verify_equivocation`](crate::verifier::verify_equivocation) against the
+/// committee root of its epoch.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EquivocationEvidence {
+    /// The offending validator's public key
+    pub(crate) public_key: PublicKey,
+    /// Inclusion proof of `public_key` in the committee root
+    pub(crate) merkle_proof: MerkleProof,
+    pub(crate) epoch: u64,
+    pub(crate) slot: u64,
+    /// The two payloads and their signatures, ordered by payload
+    pub(crate) signed: [(Vec<u8>, Signature); 2],
+}
+
+impl EquivocationEvidence {
+    /// Build evidence from two shares by the same validator.
+    ///
+    /// # Errors
+    /// `InvalidInput` if the shares come from different validators or
+    /// different key trees, if the messages are for different slots, or if
+    /// the payloads are equal. Signatures are not checked here.
+    pub fn new(
+        public_key: PublicKey,
+        first: (&SlotMessage, &SignatureShare),
+        second: (&SlotMessage, &SignatureShare),
+    ) -> crate::error::Result<Self> {
+        let invalid = |reason: &str| crate::error::PQAggregateError::InvalidInput { reason: reason.into() };
+
+        let (msg_a, share_a) = first;
+        let (msg_b, share_b) = second;
+        if share_a.signer_index() != public_key.index || share_b.signer_index() != public_key.index {
+            return Err(invalid("Shares must both come from the public key's validator"));
+        }
+        let (proof_a, proof_b) = (&share_a.merkle_proof, &share_b.merkle_proof);
+        if proof_a.siblings() != proof_b.siblings() || proof_a.leaf_hash() != proof_b.leaf_hash() {
+            return Err(invalid("Shares were made against different key trees"));
+        }
+        if (msg_a.epoch, msg_a.slot) != (msg_b.epoch, msg_b.slot) {
+            return Err(invalid("Messages are for different slots"));
+        }
+        if msg_a.payload == msg_b.payload {
+            return Err(invalid("Payloads are identical"));
+        }
+
+        let mut signed = [
+            (msg_a.payload.clone(), share_a.signature.clone()),
+            (msg_b.payload.clone(), share_b.signature.clone()),
+        ];
+        signed.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Ok(Self {
+            public_key,
+            merkle_proof: share_a.merkle_proof.clone(),
+            epoch: msg_a.epoch,
+            slot: msg_a.slot,
+            signed,
+        })
+    }
+
+    /// Get the index of the validator who equivocated.
+    pub fn offender(&self) -> usize {
+        self.public_key.index
+    }
+
+    /// Get the offender's public key.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Get the Merkle proof of the offender's key.
+    pub fn merkle_proof(&self) -> &MerkleProof {
+        &self.merkle_proof
+    }
+
+    /// Get the epoch of the conflicting messages.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Get the slot of the conflicting messages.
+    pub fn slot(&self) -> u64 {
+        self.slot
+    }
+
+    /// Get the two conflicting messages.
+    pub fn messages(&self) -> [SlotMessage; 2] {
+        self.signed.clone().map(|(payload, _)| SlotMessage::new(self.epoch, self.slot, payload))
+    }
+
+    /// Get the signature over each message, in the order of [`Self::messages`].
+    pub fn signatures(&self) -> [&Signature; 2] {
+        [&self.signed[0].1, &self.signed[1].1]
+    }
+
+    /// Serialize to compact binary format for submission to slashing logic.
+    ///
+    /// Format: [version:1][signer_index:4][epoch:8][slot:8][pk_len:4][pk:M]
+    ///         [num_siblings:1][siblings:32*k]
+    ///         then twice: [payload_len:4][payload][nonce:32][sig_len:4][sig:N]
+    ///
+    /// The Merkle leaf hash is not stored; it is recomputed from the public key.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let siblings = self.merkle_proof.siblings();
+        let mut out = Vec::new();
+
+        out.push(EQUIVOCATION_FORMAT_VERSION);
+        out.extend_from_slice(&(self.public_key.index as u32).to_le_bytes());
+        out.extend_from_slice(&self.epoch.to_le_bytes());
+        out.extend_from_slice(&self.slot.to_le_bytes());
+        out.extend_from_slice(&(self.public_key.bytes.len() as u32).to_le_bytes());
+        out.extend_from_slice(&self.public_key.bytes);
+        out.push(siblings.len() as u8);
+        for sibling in siblings {
+            out.extend_from_slice(sibling);
+        }
+        for (payload, signature) in &self.signed {
+            out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+            out.extend_from_slice(payload);
+            out.extend_from_slice(&signature.nonce);
+            out.extend_from_slice(&(signature.bytes.len() as u32).to_le_bytes());
+            out.extend_from_slice(&signature.bytes);
+        }
+
+        out
+    }
+
+    /// Deserialize from compact binary format.
+    ///
+    /// Returns `None` if the bytes are malformed or the payloads are not
+    /// distinct and in order. A successful parse says nothing about the
+    /// signatures; use [`verify_equivocation`](crate::verifier::verify_equivocation).
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        use crate::core::scheme::{ActiveScheme, SignatureScheme};
+
+        // Header: version(1) + index(4) + epoch(8) + slot(8) = 21
+        if bytes.len() < 21 || bytes[0] != EQUIVOCATION_FORMAT_VERSION {
+            return None;
+        }
+
+        let signer_index = u32::from_le_bytes(bytes[1..5].try_into().ok()?) as usize;
+        let epoch = u64::from_le_bytes(bytes[5..13].try_into().ok()?);
+        let slot = u64::from_le_bytes(bytes[13..21].try_into().ok()?);
+
+        let mut reader = ByteReader { bytes, pos: 21 };
+        let pk_len = reader.read_u32()? as usize;
+        let pk_bytes = reader.take(pk_len)?.to_vec();
+        let num_siblings = *reader.take(1)?.first()? as usize;
+        let siblings = reader
+            .take(num_siblings.checked_mul(32)?)?
+            .chunks_exact(32)
+            .map(|chunk| {
+                let mut sibling = [0u8; 32];
+                sibling.copy_from_slice(chunk);
+                sibling
+            })
+            .collect();
+
+        let mut read_side = || -> Option<(Vec<u8>, Signature)> {
+            let payload_len = reader.read_u32()? as usize;
+            let payload = reader.take(payload_len)?.to_vec();
+            let nonce: [u8; 32] = reader.take(32)?.try_into().ok()?;
+            let sig_len = reader.read_u32()? as usize;
+            let sig_bytes = reader.take(sig_len)?.to_vec();
+            Some((payload, Signature::new(sig_bytes, signer_index, nonce)))
+        };
+        let signed = [read_side()?, read_side()?];
+
+        if reader.pos != bytes.len() || signed[0].0 >= signed[1].0 {
+            return None;
+        }
+
+        let leaf_hash = ActiveScheme::leaf_hash(&pk_bytes);
+        Some(Self {
+            public_key: PublicKey::from_bytes(pk_bytes, signer_index),
+            merkle_proof: MerkleProof::new(siblings, signer_index, leaf_hash),
+            epoch,
+            slot,
+            signed,
+        })
     }
 }
 
@@ -329,6 +1510,71 @@ pub enum ThresholdPolicy {
     Percentage(u8),
     /// Multi-tiered logic: Low (33%), Medium (50%), High (67%)
     Tiered { level: u8 },
+    /// Signers whose combined weight is at least `min_weight`, taken from a
+    /// weighted key tree (see [`MerkleTree::from_weighted_public_keys`](crate::utils::MerkleTree::from_weighted_public_keys))
+    Weighted { min_weight: u64 },
+    /// Signers satisfying a nested threshold-of-thresholds structure
+    Hierarchical(crate::core::access::AccessStructure),
+}
+
+impl ThresholdPolicy {
+    /// Minimum number of signers out of `n` validators that satisfies this policy.
+    ///
+    /// A weighted policy needs at least one signer; the rest depends on
+    /// weight. A hierarchical policy needs at least the structure's
+    /// [`min_signers`](crate::core::access::AccessStructure::min_signers).
+    pub fn required_signers(&self, n: usize) -> usize {
+        match self {
+            Self::Fixed(req) | Self::AtLeast(req) => *req,
+            Self::Percentage(pct) => (n * (*pct as usize)).div_ceil(100),
+            Self::Tiered { level } => crate::utils::calculate_adaptive_threshold(n, *level),
+            Self::Weighted { .. } => 1,
+            Self::Hierarchical(structure) => structure.min_signers(),
+        }
+    }
+
+    /// Minimum combined signer weight that satisfies this policy (zero unless weighted).
+    pub fn required_weight(&self) -> u64 {
+        match self {
+            Self::Weighted { min_weight } => *min_weight,
+            _ => 0,
+        }
+    }
+
+    /// Whether `t` signers out of `n` validators satisfy this policy.
+    ///
+    /// Headcount alone never satisfies a [`ThresholdPolicy::Weighted`]
+    /// policy (use [`Self::is_satisfied_with_weight`]) or a
+    /// [`ThresholdPolicy::Hierarchical`] one (use [`Self::is_satisfied_by_signers`]).
+    pub fn is_satisfied(&self, t: usize, n: usize) -> bool {
+        match self {
+            Self::Fixed(req) => t == *req,
+            Self::Weighted { .. } | Self::Hierarchical(_) => false,
+            _ => t >= self.required_signers(n),
+        }
+    }
+
+    /// Whether the given signers out of `n` validators satisfy this policy.
+    ///
+    /// Weighted policies are never satisfied, since the signers' weights are unknown.
+    pub fn is_satisfied_by_signers(&self, signers: &[usize], n: usize) -> bool {
+        match self {
+            Self::Hierarchical(structure) => structure.validate(n).is_ok() && structure.is_satisfied_by(signers),
+            _ => self.is_satisfied(signers.len(), n),
+        }
+    }
+
+    /// Whether `t` signers out of `n` validators, with a combined `weight`,
+    /// satisfy this policy.
+    ///
+    /// `weight` is `None` when the signers' weights are not known, which
+    /// only satisfies count-based policies.
+    pub fn is_satisfied_with_weight(&self, t: usize, n: usize, weight: Option<u64>) -> bool {
+        match self {
+            Self::Weighted { min_weight } => t >= 1 && weight.is_some_and(|w| w >= *min_weight),
+            _ => self.is_satisfied(t, n),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -351,6 +1597,23 @@ mod tests {
         assert_eq!(recovered.as_bytes(), &[1, 2, 3, 4, 5, 6, 7, 8]);
     }
 
+    #[test]
+    fn test_zksnark_proof_large_count_roundtrip() {
+        // Previously saturated at u16::MAX
+        let original = ZKSNARKProof::new(vec![9; 16], 70_000, [0xCD; 32]);
+
+        let bytes = original.to_bytes();
+        assert_eq!(bytes[0], 0x03);
+
+        let recovered = ZKSNARKProof::from_bytes(&bytes).expect("Deserialization failed");
+        assert_eq!(recovered.num_signatures(), 70_000);
+        assert_eq!(recovered.public_inputs_hash(), &[0xCD; 32]);
+        assert_eq!(recovered.as_bytes(), &[9u8; 16]);
+
+        // Counts that fit in u16 keep the original encoding
+        assert_eq!(ZKSNARKProof::new(vec![], 3000, [0; 32]).to_bytes()[0], 0x02);
+    }
+
     #[test]
     #[cfg(feature = "compression")]
     fn test_zksnark_proof_compression() {
@@ -377,6 +1640,169 @@ mod tests {
         let mut bad_version = vec![0x01]; // Wrong version
         bad_version.extend_from_slice(&[0; 38]);
         assert!(ZKSNARKProof::from_bytes(&bad_version).is_none());
+
+        // Truncated 0x03 header
+        assert!(ZKSNARKProof::from_bytes(&[0x03, 0, 0]).is_none());
+    }
+
+    #[test]
+    fn test_signature_share_serialization_roundtrip() {
+        let share = SignatureShare::new(
+            Signature::new(vec![7u8; 100], 5, [0x11; 32]),
+            MerkleProof::new(vec![[0x22; 32], [0x33; 32], [0x44; 32]], 5, [0x55; 32]),
+        );
+
+        let bytes = share.to_bytes();
+        let recovered = SignatureShare::from_bytes(&bytes).expect("Deserialization failed");
+
+        assert_eq!(recovered.signer_index(), 5);
+        assert_eq!(recovered.nonce(), &[0x11; 32]);
+        assert_eq!(recovered.signature().as_bytes(), &[7u8; 100][..]);
+        assert_eq!(recovered.merkle_proof().leaf_index(), 5);
+        assert_eq!(recovered.merkle_proof().leaf_hash(), &[0x55; 32]);
+        assert_eq!(recovered.merkle_proof().siblings(), share.merkle_proof().siblings());
+    }
+
+    #[test]
+    fn test_multiproof_serialization_roundtrip() {
+        let original = MerkleMultiProof::new(
+            vec![1, 4, 6],
+            vec![[0x11; 32], [0x44; 32], [0x66; 32]],
+            vec![[0xAA; 32], [0xBB; 32]],
+            3,
+        );
+
+        let bytes = original.to_bytes();
+        assert_eq!(bytes.len(), 1 + 1 + 4 + 3 * 36 + 4 + 2 * 32);
+        assert_eq!(MerkleMultiProof::from_bytes(&bytes), Some(original.clone()));
+        assert_eq!(original.leaf_hash(4), Some(&[0x44; 32]));
+        assert_eq!(original.leaf_hash(5), None);
+
+        assert!(MerkleMultiProof::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(MerkleMultiProof::from_bytes(&trailing).is_none());
+        let mut bad_count = bytes;
+        bad_count[2..6].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(MerkleMultiProof::from_bytes(&bad_count).is_none());
+    }
+
+    #[test]
+    fn test_proof_context() {
+        let context = ProofContext::new(ProofContext::domain_id("cosmos:hub-4"), 9, 1_000, 2_000);
+        assert_ne!(context.domain, ProofContext::domain_id("cosmos:hub-5"));
+
+        assert!(!context.is_valid_at(999));
+        assert!(context.is_valid_at(1_000));
+        assert!(context.is_valid_at(2_000));
+        assert!(!context.is_valid_at(2_001));
+
+        let bytes = context.to_bytes();
+        assert_eq!(ProofContext::from_bytes(&bytes), Some(context));
+        assert!(ProofContext::from_bytes(&bytes[1..]).is_none());
+
+        // Inverted window
+        let inverted = ProofContext::new(context.domain, 9, 2_000, 1_000);
+        assert!(ProofContext::from_bytes(&inverted.to_bytes()).is_none());
+
+        // The bound message differs per context
+        let other_epoch = ProofContext { epoch: 10, ..context };
+        assert_ne!(context.bind(b"msg"), other_epoch.bind(b"msg"));
+        assert!(context.bind(b"msg").ends_with(b"msg"));
+    }
+
+    #[test]
+    fn test_membership_update_serialization_roundtrip() {
+        let paths = MerkleMultiProof::new(vec![2, 5], vec![[0x22; 32], [0x55; 32]], vec![[0xAA; 32]], 3);
+        let proof = ZKSNARKProof::new(vec![0x02; 40], 3, [0x33; 32]);
+        let original = MembershipUpdateProof::new([0x01; 32], [0x02; 32], paths, vec![[0x20; 32], [0x50; 32]], proof, 7);
+
+        let bytes = original.to_bytes();
+        let restored = MembershipUpdateProof::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.to_bytes(), bytes);
+        assert_eq!(restored.paths, original.paths);
+        assert_eq!(restored.epoch, 7);
+        assert_eq!(restored.changed_indices(), &[2, 5]);
+        assert_eq!(restored.new_leaf(5), Some(&[0x50; 32]));
+        assert_eq!(restored.new_leaf(3), None);
+        assert_eq!(restored.signed_message(), original.signed_message());
+
+        assert!(MembershipUpdateProof::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(MembershipUpdateProof::from_bytes(&trailing).is_none());
+        let mut bad_paths = bytes;
+        bad_paths[73..77].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(MembershipUpdateProof::from_bytes(&bad_paths).is_none());
+    }
+
+    #[test]
+    fn test_full_proof_serialization_roundtrip() {
+        use crate::core::scheme::{ActiveScheme, SignatureScheme};
+
+        let entry = |index: usize| {
+            (
+                PublicKey::from_bytes(vec![index as u8; 40], index),
+                SignatureShare::new(
+                    Signature::new(vec![0xA0 | index as u8; 64], index, [index as u8; 32]),
+                    MerkleProof::new(vec![[0x22; 32], [0x33; 32]], index, [0; 32]),
+                ),
+            )
+        };
+        let original = FullProof {
+            pk_root: [0x77; 32],
+            committee_size: 4,
+            scheme_id: 0x02,
+            entries: vec![entry(1), entry(3)],
+        };
+
+        let bytes = original.to_bytes();
+        let recovered = FullProof::from_bytes(&bytes).expect("Deserialization failed");
+
+        assert_eq!(recovered.pk_root(), &[0x77; 32]);
+        assert_eq!(recovered.committee_size(), 4);
+        assert_eq!(recovered.scheme_id(), 0x02);
+        assert_eq!(recovered.signer_indices().collect::<Vec<_>>(), vec![1, 3]);
+
+        let (pk, share) = &recovered.entries()[1];
+        assert_eq!(pk.as_bytes(), &[3u8; 40][..]);
+        assert_eq!(pk.index(), 3);
+        assert_eq!(share.signature().as_bytes(), &[0xA3; 64][..]);
+        assert_eq!(share.nonce(), &[3; 32]);
+        assert_eq!(share.merkle_proof().siblings(), &[[0x22; 32], [0x33; 32]]);
+        // Leaf hashes are rebuilt from the embedded keys
+        assert_eq!(share.merkle_proof().leaf_hash(), &ActiveScheme::leaf_hash(&[3u8; 40]));
+
+        // Truncated, trailing garbage, and an inflated signer count
+        assert!(FullProof::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert!(FullProof::from_bytes(&extended).is_none());
+        let mut inflated = bytes;
+        inflated[6..10].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(FullProof::from_bytes(&inflated).is_none());
+    }
+
+    #[test]
+    fn test_signature_share_from_bytes_invalid() {
+        let share = SignatureShare::new(
+            Signature::new(vec![1u8; 10], 0, [0; 32]),
+            MerkleProof::new(vec![[2u8; 32]], 0, [3u8; 32]),
+        );
+        let bytes = share.to_bytes();
+
+        // Truncated sibling list
+        assert!(SignatureShare::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+
+        // Wrong version
+        let mut bad_version = bytes.clone();
+        bad_version[0] = 0xFF;
+        assert!(SignatureShare::from_bytes(&bad_version).is_none());
+
+        // Signature length pointing past the end
+        let mut bad_len = bytes;
+        bad_len[37..41].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(SignatureShare::from_bytes(&bad_len).is_none());
     }
 }
 
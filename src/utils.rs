@@ -6,7 +6,7 @@ use alloc::vec;
 use alloc::vec::Vec;
 use sha3::{Digest, Sha3_256};
 
-use crate::types::MerkleProof;
+use crate::types::{MerkleMultiProof, MerkleProof};
 
 /// SHA3-256 hash helper.
 pub fn sha3_256(data: &[u8]) -> [u8; 32] {
@@ -15,12 +15,119 @@ pub fn sha3_256(data: &[u8]) -> [u8; 32] {
     hasher.finalize().into()
 }
 
+/// Domain tags for [`tagged_hash`], one per hashing context.
+///
+/// Every commitment that was previously a bare SHA3-256 uses its own tag, so
+/// a value hashed in one context cannot be replayed in another (for example
+/// a Merkle leaf presented as an internal node).
+pub mod tags {
+    /// Merkle leaf over a validator public key.
+    pub const MERKLE_LEAF: &[u8] = b"pq-aggregate/merkle-leaf/v1";
+    /// Merkle leaf over a validator's weight and public key.
+    pub const WEIGHTED_MERKLE_LEAF: &[u8] = b"pq-aggregate/weighted-merkle-leaf/v1";
+    /// Merkle internal node over two child hashes.
+    pub const MERKLE_NODE: &[u8] = b"pq-aggregate/merkle-node/v1";
+    /// Signer node of a hierarchical key tree.
+    pub const ACCESS_SIGNER: &[u8] = b"pq-aggregate/access-signer/v1";
+    /// Threshold gate of a hierarchical key tree.
+    pub const ACCESS_GROUP: &[u8] = b"pq-aggregate/access-group/v1";
+    /// Commitment to an access structure's encoding.
+    pub const ACCESS_STRUCTURE: &[u8] = b"pq-aggregate/access-structure/v1";
+    /// Public inputs hash of an aggregated proof.
+    pub const PUBLIC_INPUTS: &[u8] = b"pq-aggregate/public-inputs/v1";
+    /// Behavioral fingerprint of a causal event.
+    pub const EVENT_FINGERPRINT: &[u8] = b"pq-aggregate/event-fingerprint/v1";
+    /// Causal event leaf in the logger's Merkle tree.
+    pub const EVENT_LEAF: &[u8] = b"pq-aggregate/event-leaf/v1";
+    /// Commitment binding structured metadata to an event payload.
+    pub const METADATA_COMMITMENT: &[u8] = b"pq-aggregate/metadata-commitment/v1";
+    /// Runtime action identifier.
+    pub const ACTION_ID: &[u8] = b"pq-aggregate/action-id/v1";
+    /// Policy evaluation hash.
+    pub const POLICY_EVALUATION: &[u8] = b"pq-aggregate/policy-evaluation/v1";
+    /// Commitment chain output of two merged proofs.
+    pub const MERGED_COMMITMENT: &[u8] = b"pq-aggregate/merged-commitment/v1";
+    /// Nonce commitment of two merged proofs.
+    pub const MERGED_NONCES: &[u8] = b"pq-aggregate/merged-nonces/v1";
+    /// Message leaf of a multi-message certificate.
+    pub const CERTIFICATE_MESSAGE: &[u8] = b"pq-aggregate/certificate-message/v1";
+    /// Public inputs hash of a multi-message certificate.
+    pub const CERTIFICATE_INPUTS: &[u8] = b"pq-aggregate/certificate-inputs/v1";
+    /// Deterministic or hedged per-signer nonce.
+    pub const SIGNING_NONCE: &[u8] = b"pq-aggregate/signing-nonce/v1";
+    /// Per-signer witness folded by the Nova aggregation circuit.
+    pub const NOVA_SIGNER: &[u8] = b"pq-aggregate/nova-signer/v1";
+    /// Domain identifier derived from a chain ID.
+    pub const PROOF_DOMAIN: &[u8] = b"pq-aggregate/proof-domain/v1";
+    /// Placeholder leaf of a revoked validator.
+    pub const REVOKED_LEAF: &[u8] = b"pq-aggregate/revoked-leaf/v1";
+    /// Per-signer challenge each validator signs.
+    pub const SIGNER_CHALLENGE: &[u8] = b"pq-aggregate/signer-challenge/v1";
+    /// One step of the commitment chain over an aggregate's signatures.
+    pub const SIGNATURE_COMMITMENT: &[u8] = b"pq-aggregate/signature-commitment/v1";
+    /// Digest of a single signature inside the commitment chain.
+    pub const SIGNATURE_DIGEST: &[u8] = b"pq-aggregate/signature-digest/v1";
+    /// Commitment to an aggregate's signing nonces.
+    pub const NONCE_COMMITMENT: &[u8] = b"pq-aggregate/nonce-commitment/v1";
+    /// Commitment to an aggregate's signer leaf indices.
+    pub const SIGNER_INDICES: &[u8] = b"pq-aggregate/signer-indices/v1";
+}
+
+/// SHA3-256 hasher bound to a domain tag.
+///
+/// Computes `SHA3-256(len(tag) || tag || data)`, where `len(tag)` is one
+/// byte. Tags are taken from [`tags`].
+#[derive(Clone)]
+pub struct TaggedHasher(Sha3_256);
+
+impl TaggedHasher {
+    /// Start a hash in the domain `tag` (at most 255 bytes).
+    pub fn new(tag: &[u8]) -> Self {
+        debug_assert!(tag.len() <= u8::MAX as usize);
+        let mut hasher = Sha3_256::new();
+        hasher.update([tag.len() as u8]);
+        hasher.update(tag);
+        Self(hasher)
+    }
+
+    /// Absorb more data.
+    pub fn update(&mut self, data: impl AsRef<[u8]>) {
+        self.0.update(data);
+    }
+
+    /// Finish the hash.
+    pub fn finalize(self) -> [u8; 32] {
+        self.0.finalize().into()
+    }
+}
+
+/// One-shot [`TaggedHasher`].
+pub fn tagged_hash(tag: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut hasher = TaggedHasher::new(tag);
+    hasher.update(data);
+    hasher.finalize()
+}
+
 /// Compute challenge hash: c_i = H(m || i || nonce_i)
 /// Per the paper's security requirement: every validator computes their own challenge.
+///
+/// This is the value each validator actually signs, hashed under
+/// [`tags::SIGNER_CHALLENGE`]. The index is encoded as a fixed-width u64 so
+/// the challenge is identical across targets.
 pub fn compute_challenge(message: &[u8], signer_index: usize, nonce: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = TaggedHasher::new(tags::SIGNER_CHALLENGE);
+    hasher.update(message);
+    hasher.update((signer_index as u64).to_le_bytes());
+    hasher.update(nonce);
+    hasher.finalize()
+}
+
+/// [`compute_challenge`] without a domain tag, as signed before domain separation.
+#[cfg(feature = "legacy-hashing")]
+pub fn compute_challenge_legacy(message: &[u8], signer_index: usize, nonce: &[u8; 32]) -> [u8; 32] {
     let mut hasher = Sha3_256::new();
     hasher.update(message);
-    hasher.update(&signer_index.to_le_bytes());
+    hasher.update((signer_index as u64).to_le_bytes());
     hasher.update(nonce);
     hasher.finalize().into()
 }
@@ -32,16 +139,32 @@ pub struct MerkleTree {
     nodes: Vec<[u8; 32]>,
     /// Number of leaves
     num_leaves: usize,
+    /// Internal node hash the tree was built with
+    hash_pair: fn(&[u8; 32], &[u8; 32]) -> [u8; 32],
 }
 
 impl MerkleTree {
     /// Build a Merkle tree from leaf data (public key hashes).
+    ///
+    /// Internal nodes are hashed under [`tags::MERKLE_NODE`].
     pub fn from_leaves(leaves: &[[u8; 32]]) -> Self {
+        Self::build(leaves, hash_pair)
+    }
+
+    /// Build a tree with the untagged internal nodes used before domain separation.
+    #[cfg(feature = "legacy-hashing")]
+    pub fn from_leaves_legacy(leaves: &[[u8; 32]]) -> Self {
+        Self::build(leaves, hash_pair_legacy)
+    }
+
+    /// Build a tree over `leaves` with a custom internal node hash.
+    pub(crate) fn build(leaves: &[[u8; 32]], hash_pair: fn(&[u8; 32], &[u8; 32]) -> [u8; 32]) -> Self {
         let num_leaves = leaves.len();
         if num_leaves == 0 {
             return Self {
                 nodes: vec![[0u8; 32]],
                 num_leaves: 0,
+                hash_pair,
             };
         }
 
@@ -67,14 +190,48 @@ impl MerkleTree {
             nodes[i] = hash_pair(&left, &right);
         }
 
-        Self { nodes, num_leaves }
+        Self { nodes, num_leaves, hash_pair }
     }
 
     /// Build a Merkle tree from public keys.
+    ///
+    /// Leaves are the active signature scheme's
+    /// [`leaf_hash`](crate::core::scheme::SignatureScheme::leaf_hash) of each key.
     pub fn from_public_keys(public_keys: &[crate::types::PublicKey]) -> Self {
+        use crate::core::scheme::{ActiveScheme, SignatureScheme};
+
+        let leaves: Vec<[u8; 32]> = public_keys
+            .iter()
+            .map(|pk| ActiveScheme::leaf_hash(pk.as_bytes()))
+            .collect();
+        Self::from_leaves(&leaves)
+    }
+
+    /// Build a tree over the untagged `SHA3-256(pk)` leaves and internal nodes
+    /// used before domain separation, e.g. to recompute a committee root
+    /// recorded from an older mnemonic backup.
+    #[cfg(feature = "legacy-hashing")]
+    pub fn from_public_keys_legacy(public_keys: &[crate::types::PublicKey]) -> Self {
+        let leaves: Vec<[u8; 32]> = public_keys.iter().map(|pk| sha3_256(pk.as_bytes())).collect();
+        Self::from_leaves_legacy(&leaves)
+    }
+
+    /// Build a Merkle tree whose leaves commit to each validator's weight.
+    ///
+    /// `weights[i]` is the weight of `public_keys[i]`. Leaves are the active
+    /// signature scheme's
+    /// [`weighted_leaf_hash`](crate::core::scheme::SignatureScheme::weighted_leaf_hash).
+    ///
+    /// # Panics
+    /// If `weights` and `public_keys` differ in length.
+    pub fn from_weighted_public_keys(public_keys: &[crate::types::PublicKey], weights: &[u64]) -> Self {
+        use crate::core::scheme::{ActiveScheme, SignatureScheme};
+
+        assert_eq!(public_keys.len(), weights.len(), "one weight per public key");
         let leaves: Vec<[u8; 32]> = public_keys
             .iter()
-            .map(|pk| sha3_256(pk.as_bytes()))
+            .zip(weights)
+            .map(|(pk, weight)| ActiveScheme::weighted_leaf_hash(pk.as_bytes(), *weight))
             .collect();
         Self::from_leaves(&leaves)
     }
@@ -84,6 +241,47 @@ impl MerkleTree {
         self.nodes.first().copied().unwrap_or([0u8; 32])
     }
 
+    /// Get the number of leaves (excluding padding).
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    /// Get the leaf hash at `leaf_index`.
+    pub fn leaf(&self, leaf_index: usize) -> Option<[u8; 32]> {
+        if leaf_index >= self.num_leaves {
+            return None;
+        }
+        Some(self.nodes[self.num_leaves.next_power_of_two() - 1 + leaf_index])
+    }
+
+    /// Replace the leaf at `leaf_index` and recompute its path to the root.
+    ///
+    /// Used to swap a single validator's key without rebuilding the tree.
+    /// Returns the new root, or `None` if the index is out of range.
+    pub fn update_leaf(&mut self, leaf_index: usize, leaf: [u8; 32]) -> Option<[u8; 32]> {
+        if leaf_index >= self.num_leaves {
+            return None;
+        }
+
+        let mut node_index = self.num_leaves.next_power_of_two() - 1 + leaf_index;
+        self.nodes[node_index] = leaf;
+        while node_index > 0 {
+            node_index = (node_index - 1) / 2;
+            self.nodes[node_index] = (self.hash_pair)(&self.nodes[2 * node_index + 1], &self.nodes[2 * node_index + 2]);
+        }
+
+        Some(self.root())
+    }
+
+    /// Revoke the validator at `leaf_index`, keeping every other index in place.
+    ///
+    /// The leaf becomes [`revoked_leaf`], which no public key hashes to, so
+    /// shares from the revoked key no longer pass their inclusion check.
+    /// Returns the new root, or `None` if the index is out of range.
+    pub fn revoke(&mut self, leaf_index: usize) -> Option<[u8; 32]> {
+        self.update_leaf(leaf_index, revoked_leaf())
+    }
+
     /// Generate a Merkle proof for a leaf at the given index.
     pub fn prove(&self, leaf_index: usize) -> Option<MerkleProof> {
         if leaf_index >= self.num_leaves {
@@ -114,8 +312,114 @@ impl MerkleTree {
         Some(MerkleProof::new(siblings, leaf_index, leaf_hash))
     }
 
+    /// Generate a single proof covering several leaves.
+    ///
+    /// Path nodes shared by the leaves are included once, and nodes that
+    /// can be computed from other proven leaves are left out entirely.
+    /// Indices may be given in any order. Returns `None` if `leaf_indices`
+    /// is empty, contains duplicates, or any index is out of range.
+    pub fn prove_many(&self, leaf_indices: &[usize]) -> Option<MerkleMultiProof> {
+        let mut positions = leaf_indices.to_vec();
+        positions.sort_unstable();
+        if positions.is_empty()
+            || positions.windows(2).any(|pair| pair[0] == pair[1])
+            || positions.last().copied()? >= self.num_leaves
+        {
+            return None;
+        }
+
+        let mut width = self.num_leaves.next_power_of_two();
+        let depth = width.trailing_zeros() as u8;
+        let leaf_hashes = positions.iter().map(|&i| self.nodes[width - 1 + i]).collect();
+        let leaf_indices = positions.clone();
+
+        // Walk up level by level; a sibling is only needed when it is not
+        // itself one of the nodes being proven
+        let mut proof_hashes = Vec::new();
+        while width > 1 {
+            let level_start = width - 1;
+            let mut parents = Vec::with_capacity(positions.len());
+            let mut i = 0;
+            while i < positions.len() {
+                let position = positions[i];
+                if position % 2 == 0 && positions.get(i + 1) == Some(&(position + 1)) {
+                    i += 1;
+                } else {
+                    proof_hashes.push(self.nodes[level_start + (position ^ 1)]);
+                }
+                parents.push(position / 2);
+                i += 1;
+            }
+            positions = parents;
+            width /= 2;
+        }
+
+        Some(MerkleMultiProof::new(leaf_indices, leaf_hashes, proof_hashes, depth))
+    }
+
+    /// Verify a multiproof against the root.
+    ///
+    /// Fails if the leaves are not in strictly ascending order, if any index
+    /// lies outside the tree, or if the proof carries too few or too many
+    /// path nodes.
+    pub fn verify_multi(root: &[u8; 32], multiproof: &MerkleMultiProof) -> bool {
+        let indices = multiproof.leaf_indices();
+        let Some(width) = 1usize.checked_shl(u32::from(multiproof.depth())) else {
+            return false;
+        };
+        if indices.is_empty()
+            || indices.len() != multiproof.leaf_hashes().len()
+            || indices.windows(2).any(|pair| pair[0] >= pair[1])
+            || indices[indices.len() - 1] >= width
+        {
+            return false;
+        }
+
+        let mut level: Vec<(usize, [u8; 32])> =
+            indices.iter().copied().zip(multiproof.leaf_hashes().iter().copied()).collect();
+        let mut proof_hashes = multiproof.proof_hashes().iter();
+
+        for _ in 0..multiproof.depth() {
+            let mut parents = Vec::with_capacity(level.len());
+            let mut i = 0;
+            while i < level.len() {
+                let (position, hash) = level[i];
+                let (left, right) = if position % 2 == 1 {
+                    let Some(sibling) = proof_hashes.next() else { return false };
+                    (*sibling, hash)
+                } else if level.get(i + 1).map(|(next, _)| *next) == Some(position + 1) {
+                    i += 1;
+                    (hash, level[i].1)
+                } else {
+                    let Some(sibling) = proof_hashes.next() else { return false };
+                    (hash, *sibling)
+                };
+                parents.push((position / 2, hash_pair(&left, &right)));
+                i += 1;
+            }
+            level = parents;
+        }
+
+        proof_hashes.next().is_none() && level.len() == 1 && &level[0].1 == root
+    }
+
     /// Verify a Merkle proof against the root.
     pub fn verify_proof(root: &[u8; 32], proof: &MerkleProof) -> bool {
+        Self::verify_with(root, proof, hash_pair)
+    }
+
+    /// Verify a Merkle proof against a root built with [`Self::from_leaves_legacy`].
+    #[cfg(feature = "legacy-hashing")]
+    pub fn verify_proof_legacy(root: &[u8; 32], proof: &MerkleProof) -> bool {
+        Self::verify_with(root, proof, hash_pair_legacy)
+    }
+
+    /// Verify a Merkle proof with a custom internal node hash.
+    pub(crate) fn verify_with(
+        root: &[u8; 32],
+        proof: &MerkleProof,
+        hash_pair: fn(&[u8; 32], &[u8; 32]) -> [u8; 32],
+    ) -> bool {
         let mut current_hash = proof.leaf_hash;
         let mut index = proof.leaf_index;
 
@@ -132,14 +436,41 @@ impl MerkleTree {
     }
 }
 
+/// Placeholder leaf marking a revoked validator (see [`MerkleTree::revoke`]).
+pub fn revoked_leaf() -> [u8; 32] {
+    tagged_hash(tags::REVOKED_LEAF, &[])
+}
+
 /// Hash two nodes together.
 fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = TaggedHasher::new(tags::MERKLE_NODE);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize()
+}
+
+/// Hash two nodes together without a domain tag.
+#[cfg(feature = "legacy-hashing")]
+fn hash_pair_legacy(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
     let mut hasher = Sha3_256::new();
     hasher.update(left);
     hasher.update(right);
     hasher.finalize().into()
 }
 
+/// List the indices of all set bits in a signer bitmap, in ascending order.
+///
+/// Bit `i` lives in byte `i / 8` at position `i % 8`.
+pub fn bitmap_to_indices(bitmap: &[u8]) -> Vec<usize> {
+    bitmap
+        .iter()
+        .enumerate()
+        .flat_map(|(byte_idx, byte)| {
+            (0..8).filter(move |bit| byte & (1 << bit) != 0).map(move |bit| byte_idx * 8 + bit)
+        })
+        .collect()
+}
+
 /// Calculate adaptive threshold based on Appendix B of the paper.
 ///
 /// The adaptive threshold adjusts based on:
@@ -195,6 +526,164 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_multiproof() {
+        let leaves: Vec<[u8; 32]> = (0..11).map(|i| sha3_256(&[i as u8])).collect();
+        let tree = MerkleTree::from_leaves(&leaves);
+        let root = tree.root();
+
+        for indices in [vec![0], vec![10], vec![0, 1], vec![7, 2, 3, 9], (0..11).collect()] {
+            let multiproof = tree.prove_many(&indices).unwrap();
+            assert!(MerkleTree::verify_multi(&root, &multiproof));
+
+            let mut sorted = indices.clone();
+            sorted.sort_unstable();
+            assert_eq!(multiproof.leaf_indices(), &sorted[..]);
+            for &i in &indices {
+                assert_eq!(multiproof.leaf_hash(i), Some(&leaves[i]));
+            }
+        }
+
+        // Every leaf present: only the padding nodes (leaf 11, leaves 12..16) are needed
+        assert_eq!(tree.prove_many(&(0..11).collect::<Vec<_>>()).unwrap().proof_hashes().len(), 2);
+        // A single leaf degenerates to an ordinary proof
+        assert_eq!(tree.prove_many(&[5]).unwrap().proof_hashes(), tree.prove(5).unwrap().siblings());
+
+        assert!(tree.prove_many(&[]).is_none());
+        assert!(tree.prove_many(&[3, 3]).is_none());
+        assert!(tree.prove_many(&[11]).is_none());
+    }
+
+    #[test]
+    fn test_multiproof_rejects_tampering() {
+        let leaves: Vec<[u8; 32]> = (0..16).map(|i| sha3_256(&[i as u8])).collect();
+        let tree = MerkleTree::from_leaves(&leaves);
+        let root = tree.root();
+        let multiproof = tree.prove_many(&[1, 4, 5, 12]).unwrap();
+
+        let mut bad_leaf = multiproof.clone();
+        bad_leaf.leaf_hashes[2][0] ^= 1;
+        assert!(!MerkleTree::verify_multi(&root, &bad_leaf));
+
+        let mut bad_node = multiproof.clone();
+        bad_node.proof_hashes[0][0] ^= 1;
+        assert!(!MerkleTree::verify_multi(&root, &bad_node));
+
+        let mut moved = multiproof.clone();
+        moved.leaf_indices[0] = 0;
+        assert!(!MerkleTree::verify_multi(&root, &moved));
+
+        let mut unsorted = multiproof.clone();
+        unsorted.leaf_indices.swap(1, 2);
+        unsorted.leaf_hashes.swap(1, 2);
+        assert!(!MerkleTree::verify_multi(&root, &unsorted));
+
+        let mut short = multiproof.clone();
+        short.proof_hashes.pop();
+        assert!(!MerkleTree::verify_multi(&root, &short));
+
+        let mut long = multiproof.clone();
+        long.proof_hashes.push([0u8; 32]);
+        assert!(!MerkleTree::verify_multi(&root, &long));
+
+        let mut deep = multiproof;
+        deep.depth = u8::MAX;
+        assert!(!MerkleTree::verify_multi(&root, &deep));
+    }
+
+    #[test]
+    fn test_multiproof_is_smaller_than_separate_proofs() {
+        // t = 67 of n = 100
+        let leaves: Vec<[u8; 32]> = (0..100).map(|i| sha3_256(&[i as u8])).collect();
+        let tree = MerkleTree::from_leaves(&leaves);
+        let signers: Vec<usize> = (0..100).filter(|i| i % 3 != 2).take(67).collect();
+
+        let separate: usize = signers.iter().map(|&i| tree.prove(i).unwrap().siblings().len()).sum();
+        let multiproof = tree.prove_many(&signers).unwrap();
+        assert!(MerkleTree::verify_multi(&tree.root(), &multiproof));
+        assert!(multiproof.proof_hashes().len() * 4 < separate);
+    }
+
+    #[test]
+    fn test_update_leaf_matches_rebuild() {
+        let mut leaves: Vec<[u8; 32]> = (0..11).map(|i| sha3_256(&[i as u8])).collect();
+        let mut tree = MerkleTree::from_leaves(&leaves);
+
+        let replacement = sha3_256(b"replacement");
+        let root = tree.update_leaf(6, replacement).unwrap();
+        leaves[6] = replacement;
+        assert_eq!(root, MerkleTree::from_leaves(&leaves).root());
+        assert_eq!(tree.leaf(6), Some(replacement));
+        assert!(MerkleTree::verify_proof(&root, &tree.prove(6).unwrap()));
+
+        let root = tree.revoke(0).unwrap();
+        leaves[0] = revoked_leaf();
+        assert_eq!(root, MerkleTree::from_leaves(&leaves).root());
+
+        // Padding slots are not leaves
+        assert!(tree.update_leaf(11, replacement).is_none());
+        assert!(tree.leaf(11).is_none());
+        assert_eq!(tree.num_leaves(), 11);
+    }
+
+    #[test]
+    fn test_tagged_hash_separates_domains() {
+        let data = [0x5Au8; 64];
+        let leaf = tagged_hash(tags::MERKLE_LEAF, &data);
+        let node = tagged_hash(tags::MERKLE_NODE, &data);
+
+        assert_ne!(leaf, node);
+        assert_ne!(leaf, sha3_256(&data));
+
+        // SHA3-256(0x1b || "pq-aggregate/merkle-leaf/v1" || "abc")
+        assert_eq!(
+            hex::encode(tagged_hash(tags::MERKLE_LEAF, b"abc")),
+            "57213cbb3bae8c85dc39dc9e3d9542eff5402bbc427dd51d945391062cd9f49b"
+        );
+
+        // Streaming and one-shot agree
+        let mut hasher = TaggedHasher::new(tags::MERKLE_NODE);
+        hasher.update(&data[..32]);
+        hasher.update(&data[32..]);
+        assert_eq!(hasher.finalize(), node);
+
+        // The tag length prefix keeps tag/data boundaries unambiguous
+        let mut shifted = TaggedHasher::new(b"pq-aggregate/merkle-node/v");
+        shifted.update(b"1");
+        shifted.update(data);
+        assert_ne!(shifted.finalize(), node);
+    }
+
+    #[test]
+    fn test_internal_node_cannot_pose_as_leaf() {
+        use crate::core::scheme::{ActiveScheme, SignatureScheme};
+
+        let leaves: Vec<[u8; 32]> = (0..4).map(|i| sha3_256(&[i as u8])).collect();
+        let tree = MerkleTree::from_leaves(&leaves);
+
+        // With untagged hashing, a "public key" made of two child hashes
+        // hashes to their parent and verifies one level up the tree
+        let fake_key = [leaves[0], leaves[1]].concat();
+        let fake_leaf = ActiveScheme::leaf_hash(&fake_key);
+        assert_ne!(fake_leaf, hash_pair(&leaves[0], &leaves[1]));
+
+        let forged = MerkleProof::new(vec![hash_pair(&leaves[2], &leaves[3])], 0, fake_leaf);
+        assert!(!MerkleTree::verify_proof(&tree.root(), &forged));
+    }
+
+    #[test]
+    #[cfg(feature = "legacy-hashing")]
+    fn test_legacy_merkle_tree() {
+        let leaves: Vec<[u8; 32]> = (0..4).map(|i| sha3_256(&[i as u8])).collect();
+        let legacy = MerkleTree::from_leaves_legacy(&leaves);
+        let tagged = MerkleTree::from_leaves(&leaves);
+        assert_ne!(legacy.root(), tagged.root());
+
+        let proof = legacy.prove(2).unwrap();
+        assert!(MerkleTree::verify_proof_legacy(&legacy.root(), &proof));
+        assert!(!MerkleTree::verify_proof(&legacy.root(), &proof));
+    }
+
     #[test]
     fn test_adaptive_threshold() {
         assert_eq!(calculate_adaptive_threshold(5, 1), 3); // 51% of 5 = 2.55 -> 3
@@ -203,6 +692,12 @@ mod tests {
         assert_eq!(calculate_adaptive_threshold(10, 2), 7); // 67% of 10 = 6.7 -> 7
     }
 
+    #[test]
+    fn test_bitmap_to_indices() {
+        assert!(bitmap_to_indices(&[0, 0]).is_empty());
+        assert_eq!(bitmap_to_indices(&[0b0010_0101, 0b1000_0000]), vec![0, 2, 5, 15]);
+    }
+
     #[test]
     fn test_compute_challenge() {
         let msg = b"test message";
@@ -213,5 +708,13 @@ mod tests {
         
         // Different indices should produce different challenges
         assert_ne!(c1, c2);
+
+        // The challenge is domain separated from a bare hash of the same input
+        let mut untagged = msg.to_vec();
+        untagged.extend_from_slice(&0u64.to_le_bytes());
+        untagged.extend_from_slice(&nonce);
+        assert_ne!(c1, sha3_256(&untagged));
+        #[cfg(feature = "legacy-hashing")]
+        assert_eq!(compute_challenge_legacy(msg, 0, &nonce), sha3_256(&untagged));
     }
 }
@@ -2,12 +2,20 @@
 //!
 //! Verifies aggregated ZK proofs against the public key root and message.
 
-use sha3::{Digest, Sha3_256};
-
 #[cfg(feature = "nova")]
 pub mod unified;
+pub mod light_client;
+
+use alloc::format;
+use alloc::string::ToString;
 
-use crate::types::ZKSNARKProof;
+use crate::core::aggregation::validate_share_with_key;
+use crate::core::prehash::MessageMode;
+use crate::core::proof_format::{self, ProofView};
+use crate::core::scheme::{ActiveScheme, SignatureScheme};
+use crate::error::{PQAggregateError, Result};
+use crate::types::{FullProof, MerkleProof, MultiMessageCertificate, ZKSNARKProof, PROOF_FORMAT_V1};
+use crate::utils::MerkleTree;
 
 /// Verify an aggregated proof against the public key root and message.
 ///
@@ -15,6 +23,25 @@ use crate::types::ZKSNARKProof;
 /// 1. The proof structure is valid
 /// 2. The public inputs hash matches
 /// 3. The proof commitment is consistent
+/// 4. For v3 proofs, the compressed Nova SNARK verifies against the signer bitmap
+///
+/// None of these checks look at the signatures themselves. The commitments
+/// in a proof are taken as the aggregator produced them, and the v3 SNARK
+/// attests only to the signer set: ascending indices inside the committee,
+/// the signer count and, over a Poseidon key tree, each signer's key path.
+/// It does not show that those signers signed `msg`. A caller that does not
+/// trust the aggregator checks the signatures with [`verify_full`].
+///
+/// v1 (fixed 256-slot bitmap) and v2 (committee-sized bitmap) proofs are
+/// accepted, as are v3 proofs from
+/// [`aggregate_proofs_nova`](crate::core::aggregation::aggregate_proofs_nova)
+/// when the `nova` feature is enabled. Proofs recording a signature scheme other than the
+/// [`ActiveScheme`] are rejected, as are proofs hashed without domain tags
+/// (all v1 proofs and older v2 proofs) unless the `legacy-hashing` feature
+/// is enabled.
+///
+/// `msg` is a pure message; proofs over pre-hashed messages verify only
+/// through [`verify_with_mode`].
 ///
 /// # Arguments
 /// * `pk_root` - Merkle root of all public keys
@@ -27,96 +54,320 @@ use crate::types::ZKSNARKProof;
 /// # Performance
 /// Target: ≤ 15 µs verification time
 pub fn verify(pk_root: [u8; 32], msg: &[u8], proof: &ZKSNARKProof) -> bool {
+    verify_with_mode(pk_root, msg, MessageMode::Pure, proof)
+}
+
+/// [`verify`] for a message signed in the given [`MessageMode`].
+///
+/// The proof must record the same mode (and pre-hash algorithm); it is
+/// never inferred from `msg`.
+pub fn verify_with_mode(pk_root: [u8; 32], msg: &[u8], mode: MessageMode, proof: &ZKSNARKProof) -> bool {
+    let Some(view) = ProofView::parse(proof.as_bytes()) else {
+        return false;
+    };
+
     // Validate proof structure
-    if !validate_proof_structure(proof) {
+    if !validate_proof_structure(proof, &view) {
         return false;
     }
 
     // Recompute public inputs hash
-    let expected_hash = compute_public_inputs_hash(&pk_root, msg, proof.num_signatures());
+    let Some(expected_hash) = compute_public_inputs_hash(&pk_root, msg, &view) else {
+        return false;
+    };
 
     if expected_hash != *proof.public_inputs_hash() {
         return false;
     }
 
+    // A pre-hashed proof only verifies in pre-hash mode, and vice versa
+    if view.prehash() != Some(mode.prehash()) {
+        return false;
+    }
+
     // Verify proof commitments
-    verify_proof_commitments(proof, &pk_root)
+    verify_proof_commitments(&view, &pk_root) && verify_folding_proof(&view, proof.public_inputs_hash())
 }
 
-/// Validate the structure of a proof.
-fn validate_proof_structure(proof: &ZKSNARKProof) -> bool {
-    let bytes = proof.as_bytes();
+/// Verify a context-bound proof at a given time, epoch and domain.
+///
+/// Accepts only proofs from
+/// [`aggregate_proofs_with_context`](crate::core::aggregation::aggregate_proofs_with_context)
+/// whose recorded [`ProofContext`](crate::types::ProofContext) names `domain`
+/// and `epoch` and whose validity window contains `time` (Unix seconds).
+/// Proofs without a context are rejected, and context-bound proofs never
+/// pass plain [`verify`] for the unbound message. `mode` is checked as in
+/// [`verify_with_mode`].
+pub fn verify_at(
+    pk_root: [u8; 32],
+    msg: &[u8],
+    mode: MessageMode,
+    proof: &ZKSNARKProof,
+    time: u64,
+    epoch: u64,
+    domain: &[u8; 32],
+) -> bool {
+    let Some(view) = ProofView::parse(proof.as_bytes()) else {
+        return false;
+    };
+    let Some(Some(context)) = view.context() else {
+        return false;
+    };
 
-    // Minimum size: version (1) + num_sigs (2) + commitment (32) + bitmap (32) + nonce (32) + root (32)
-    if bytes.len() < 131 {
+    if context.domain != *domain || context.epoch != epoch || !context.is_valid_at(time) {
         return false;
     }
 
-    // Check version
-    if bytes[0] != 0x01 {
+    verify_with_mode(pk_root, &context.bind(msg), mode, proof)
+}
+
+/// Verify equivocation evidence against the committee root of its epoch.
+///
+/// Accepts the evidence only if the offender's key is a leaf of `pk_root`
+/// and both of its signatures verify over distinct payloads for the same
+/// epoch and slot. Choosing the right `pk_root` for
+/// [`EquivocationEvidence::epoch`](crate::types::EquivocationEvidence::epoch)
+/// (for example with
+/// [`CommitteeLightClient::root_at`](light_client::CommitteeLightClient::root_at))
+/// is up to the caller.
+pub fn verify_equivocation(evidence: &crate::types::EquivocationEvidence, pk_root: &[u8; 32]) -> bool {
+    let messages = evidence.messages();
+    if messages[0].payload == messages[1].payload {
         return false;
     }
 
-    // Check num_signatures matches header
-    let header_count = u16::from_le_bytes([bytes[1], bytes[2]]) as usize;
-    if header_count != proof.num_signatures() {
+    messages.iter().zip(evidence.signatures()).enumerate().all(|(position, (message, signature))| {
+        validate_share_with_key(
+            position,
+            signature,
+            evidence.merkle_proof(),
+            pk_root,
+            &message.to_bytes(),
+            MessageMode::Pure,
+            evidence.public_key(),
+        )
+        .is_ok()
+    })
+}
+
+/// Verify that one message of a [`MultiMessageCertificate`] reached `threshold` signers.
+///
+/// `inclusion` is the message's proof from
+/// [`MultiMessageCertificate::message_tree`]; its leaf index selects the
+/// signer bitmap that is checked. `mode` must be the one the certificate's
+/// messages were signed in. The certificate must be for `pk_root` and
+/// the [`ActiveScheme`], and its public inputs hash must match its contents.
+/// Other messages in the certificate are neither needed nor checked.
+pub fn verify_certificate_message(
+    pk_root: [u8; 32],
+    certificate: &MultiMessageCertificate,
+    msg: &[u8],
+    mode: MessageMode,
+    inclusion: &MerkleProof,
+    threshold: usize,
+) -> bool {
+    if certificate.pk_root != pk_root
+        || certificate.scheme_id != ActiveScheme::ID
+        || certificate.compute_public_inputs_hash() != certificate.public_inputs_hash
+    {
         return false;
     }
 
-    // Check num_signatures is reasonable
-    if proof.num_signatures() == 0 || proof.num_signatures() > 256 {
+    // The path length pins the leaf index to a real message slot
+    let num_messages = certificate.num_messages();
+    let depth = num_messages.next_power_of_two().trailing_zeros() as usize;
+    if inclusion.leaf_index >= num_messages || inclusion.siblings.len() != depth {
+        return false;
+    }
+    if inclusion.leaf_hash != MultiMessageCertificate::message_leaf(msg, mode)
+        || !MerkleTree::verify_proof(&certificate.messages_root, inclusion)
+    {
         return false;
     }
 
-    true
+    let Some(bitmap) = certificate.bitmap(inclusion.leaf_index) else {
+        return false;
+    };
+    let signers = crate::utils::bitmap_to_indices(bitmap);
+    bitmap.len() == certificate.committee_size.div_ceil(8)
+        && signers.iter().all(|&i| i < certificate.committee_size)
+        && !signers.is_empty()
+        && signers.len() >= threshold
 }
 
-/// Compute the expected public inputs hash.
-fn compute_public_inputs_hash(pk_root: &[u8; 32], msg: &[u8], num_sigs: usize) -> [u8; 32] {
-    let mut hasher = Sha3_256::new();
-    hasher.update(pk_root);
-    hasher.update(msg);
-    hasher.update(&(num_sigs as u64).to_le_bytes());
-    hasher.finalize().into()
+/// Verify a transparent [`FullProof`] by re-checking every embedded signature.
+///
+/// Unlike [`verify`], which can only check the proof's commitments, this
+/// verifies each signer's [`ActiveScheme`] signature, its Merkle proof
+/// against `pk_root`, and that no signer is counted twice. Signatures are
+/// checked over `msg` framed for `mode`.
+/// Policy checks on the number of signers are left to the caller.
+pub fn verify_full(pk_root: [u8; 32], msg: &[u8], mode: MessageMode, proof: &FullProof) -> bool {
+    check_full_proof(pk_root, msg, mode, proof).is_ok()
 }
 
-/// Verify the commitment chain in the proof.
-fn verify_proof_commitments(proof: &ZKSNARKProof, pk_root: &[u8; 32]) -> bool {
-    let bytes = proof.as_bytes();
+/// [`verify_full`], reporting why a proof was rejected.
+pub(crate) fn check_full_proof(pk_root: [u8; 32], msg: &[u8], mode: MessageMode, proof: &FullProof) -> Result<()> {
+    if proof.scheme_id() != ActiveScheme::ID {
+        return Err(PQAggregateError::InvalidInput {
+            reason: format!("Proof uses scheme 0x{:02x}, expected {}", proof.scheme_id(), ActiveScheme::NAME),
+        });
+    }
 
-    // Extract components from proof bytes
-    // Layout: version (1) + num_sigs (2) + commitment (32) + bitmap (32) + nonce (32) + root (32)
+    if *proof.pk_root() != pk_root {
+        return Err(PQAggregateError::InvalidInput {
+            reason: "Proof is for a different pk_root".to_string(),
+        });
+    }
 
-    if bytes.len() < 131 {
-        return false;
+    if proof.num_signatures() == 0 {
+        return Err(PQAggregateError::InsufficientSignatures { required: 1, provided: 0 });
     }
 
-    // Extract the pk_root commitment from the proof
-    let proof_root_start = bytes.len() - 32;
-    let proof_root = &bytes[proof_root_start..];
+    let mut previous = None;
+    for (position, (pk, share)) in proof.entries().iter().enumerate() {
+        let signer = share.signer_index();
 
-    // Verify pk_root matches
-    if proof_root != pk_root {
+        // Strictly ascending indices rule out duplicates
+        if signer >= proof.committee_size() || previous.is_some_and(|p| signer <= p) || pk.index() != signer {
+            return Err(PQAggregateError::InvalidInput {
+                reason: format!("Signer index {} out of order or outside the committee", signer),
+            });
+        }
+        previous = Some(signer);
+
+        validate_share_with_key(position, share.signature(), share.merkle_proof(), &pk_root, msg, mode, pk)?;
+    }
+
+    Ok(())
+}
+
+/// Validate the structure of a proof.
+fn validate_proof_structure(proof: &ZKSNARKProof, view: &ProofView<'_>) -> bool {
+    // Check num_signatures matches header
+    if view.num_signatures != proof.num_signatures() {
         return false;
     }
 
-    // Extract bitmap and verify at least one signer
-    let bitmap_start = 3 + 32; // After version + num_sigs + commitment
-    if bitmap_start + 32 > bytes.len() {
+    if proof.num_signatures() == 0 {
         return false;
     }
 
-    let bitmap = &bytes[bitmap_start..bitmap_start + 32];
-    let signer_count = count_signers_in_bitmap(bitmap);
+    let layout_ok = match view.version {
+        // Check num_signatures is reasonable
+        PROOF_FORMAT_V1 => proof.num_signatures() <= 256,
+        _ => {
+            // Bitmap must be exactly ceil(n / 8) bytes
+            if view.bitmap.len() != view.committee_size.div_ceil(8) {
+                return false;
+            }
+            if proof.num_signatures() > view.committee_size {
+                return false;
+            }
+
+            // Reject extensions we do not understand
+            let known = match view.extension_entries() {
+                Some(entries) => entries
+                    .iter()
+                    .all(|(tag, _)| proof_format::KNOWN_EXTENSIONS.contains(tag)),
+                None => false,
+            };
+            known
+                && view.signer_weight().is_some()
+                && view.access_structure().is_some()
+                && view.context().is_some()
+                && view.prehash().is_some()
+                && view.key_tree().is_some()
+        }
+    };
+
+    // Signatures made under another scheme were never checked by this build
+    layout_ok && view.scheme_id() == Some(ActiveScheme::ID)
+}
+
+/// Compute the expected public inputs hash.
+///
+/// Returns `None` for untagged (pre-domain-separation) proofs unless the
+/// `legacy-hashing` feature is enabled.
+fn compute_public_inputs_hash(pk_root: &[u8; 32], msg: &[u8], view: &ProofView<'_>) -> Option<[u8; 32]> {
+    let tagged = view.uses_tagged_hashing()?;
+
+    match view.index_commitment {
+        Some(index_commitment) if tagged => Some(proof_format::public_inputs_hash_v2(
+            pk_root,
+            msg,
+            view.num_signatures,
+            view.committee_size,
+            view.bitmap,
+            index_commitment,
+            view.nonce_commitment,
+            view.extensions,
+        )),
+        #[cfg(feature = "legacy-hashing")]
+        Some(index_commitment) => Some(proof_format::public_inputs_hash_v2_legacy(
+            pk_root,
+            msg,
+            view.num_signatures,
+            view.committee_size,
+            view.bitmap,
+            index_commitment,
+            view.extensions,
+        )),
+        #[cfg(feature = "legacy-hashing")]
+        None => Some(proof_format::public_inputs_hash_v1_legacy(pk_root, msg, view.num_signatures)),
+        #[cfg(not(feature = "legacy-hashing"))]
+        _ => None,
+    }
+}
+
+/// Verify the commitment chain in the proof.
+fn verify_proof_commitments(view: &ProofView<'_>, pk_root: &[u8; 32]) -> bool {
+    // Verify pk_root matches
+    if view.pk_root != pk_root {
+        return false;
+    }
 
     // Verify signer count matches
-    if signer_count != proof.num_signatures() {
+    let signer_count = count_signers_in_bitmap(view.bitmap);
+    if signer_count != view.num_signatures {
         return false;
     }
 
+    // v2: every set bit must be a committed leaf index inside the committee
+    if let Some(index_commitment) = view.index_commitment {
+        let indices = crate::utils::bitmap_to_indices(view.bitmap);
+        if indices.iter().any(|&i| i >= view.committee_size) {
+            return false;
+        }
+        let expected = match view.uses_tagged_hashing() {
+            Some(true) => proof_format::compute_index_commitment(&indices),
+            #[cfg(feature = "legacy-hashing")]
+            Some(false) => proof_format::compute_index_commitment_legacy(&indices),
+            _ => return false,
+        };
+        if expected != index_commitment {
+            return false;
+        }
+    }
+
     true
 }
 
+/// Check the compressed Nova SNARK of a v3 proof.
+///
+/// Proofs without a SNARK pass; v3 proofs are rejected outright when the
+/// `nova` feature is disabled.
+#[cfg_attr(not(feature = "nova"), allow(unused_variables))]
+fn verify_folding_proof(view: &ProofView<'_>, public_inputs_hash: &[u8; 32]) -> bool {
+    match view.snark {
+        None => true,
+        #[cfg(feature = "nova")]
+        Some(_) => crate::nova::aggregation::verify_signers(view, public_inputs_hash),
+        #[cfg(not(feature = "nova"))]
+        Some(_) => false,
+    }
+}
+
 /// Count the number of signers indicated in the bitmap.
 fn count_signers_in_bitmap(bitmap: &[u8]) -> usize {
     bitmap.iter().map(|b| b.count_ones() as usize).sum()
@@ -183,18 +434,58 @@ mod tests {
         let (sks, pks, pk_root) = setup(5);
         let msg = b"test message";
 
-        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 3);
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 3).unwrap();
         let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
 
         assert!(verify(pk_root, msg, &proof));
     }
 
+    #[test]
+    fn test_verify_rejects_nonce_swapped_after_signing() {
+        use crate::core::aggregation::aggregate_shares;
+        use crate::core::signing::{sign_share, NonceSource};
+        use crate::types::SignatureShare;
+        use crate::utils::MerkleTree;
+
+        let (sks, pks, pk_root) = setup(3);
+        let tree = MerkleTree::from_public_keys(&pks);
+        let msg = b"test message";
+
+        let mut wire: Vec<Vec<u8>> = (0..2)
+            .map(|i| {
+                sign_share(&sks[i], i, msg, NonceSource::Random, &tree.prove(i).unwrap())
+                    .unwrap()
+                    .to_bytes()
+            })
+            .collect();
+
+        // Untouched shares aggregate into a verifying proof
+        let shares: Vec<SignatureShare> =
+            wire.iter().map(|b| SignatureShare::from_bytes(b).unwrap()).collect();
+        let proof = aggregate_shares(shares, pk_root, msg, &pks).unwrap();
+        assert!(verify(pk_root, msg, &proof));
+
+        // A proof whose nonce commitment is swapped after signing does not verify
+        let view = ProofView::parse(proof.as_bytes()).unwrap();
+        let nonce_start = 45 + view.bitmap.len() + 32;
+        let mut bytes = proof.as_bytes().to_vec();
+        bytes[nonce_start] ^= 0x01;
+        let swapped = ZKSNARKProof::new(bytes, proof.num_signatures(), *proof.public_inputs_hash());
+        assert!(!verify(pk_root, msg, &swapped));
+
+        // Nor does a share whose nonce is swapped aggregate: nonce lives at bytes 5..37 of the share encoding
+        wire[1][5] ^= 0x01;
+        let shares: Vec<SignatureShare> =
+            wire.iter().map(|b| SignatureShare::from_bytes(b).unwrap()).collect();
+        assert!(aggregate_shares(shares, pk_root, msg, &pks).is_err());
+    }
+
     #[test]
     fn test_verify_wrong_message() {
         let (sks, pks, pk_root) = setup(3);
         let msg = b"original";
 
-        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2);
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2).unwrap();
         let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
 
         assert!(!verify(pk_root, b"wrong", &proof));
@@ -205,7 +496,7 @@ mod tests {
         let (sks, pks, pk_root) = setup(3);
         let msg = b"test";
 
-        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2);
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2).unwrap();
         let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
 
         let wrong_root = [0x42u8; 32];
@@ -219,8 +510,8 @@ mod tests {
         let msg1 = b"message 1";
         let msg2 = b"message 2";
 
-        let (sigs1, proofs1) = aggregate_sign(&sks, &pks, msg1, 2);
-        let (sigs2, proofs2) = aggregate_sign(&sks, &pks, msg2, 2);
+        let (sigs1, proofs1) = aggregate_sign(&sks, &pks, msg1, 2).unwrap();
+        let (sigs2, proofs2) = aggregate_sign(&sks, &pks, msg2, 2).unwrap();
 
         let proof1 = aggregate_proofs(sigs1, proofs1, pk_root, msg1, &pks).unwrap();
         let proof2 = aggregate_proofs(sigs2, proofs2, pk_root, msg2, &pks).unwrap();
@@ -241,6 +532,309 @@ mod tests {
         assert!(!verify([0u8; 32], b"test", &bad_proof));
     }
 
+    /// Re-seal a v2 proof after editing its bitmap, as a forger would.
+    fn reseal_with_bitmap(proof: &ZKSNARKProof, pk_root: &[u8; 32], msg: &[u8], bitmap: &[u8]) -> ZKSNARKProof {
+        let mut bytes = proof.as_bytes().to_vec();
+        bytes[45..45 + bitmap.len()].copy_from_slice(bitmap);
+
+        let view = ProofView::parse(&bytes).unwrap();
+        let hash = proof_format::public_inputs_hash_v2(
+            pk_root,
+            msg,
+            view.num_signatures,
+            view.committee_size,
+            view.bitmap,
+            view.index_commitment.unwrap(),
+            view.nonce_commitment,
+            view.extensions,
+        );
+        ZKSNARKProof::new(bytes, proof.num_signatures(), hash)
+    }
+
+    #[test]
+    fn test_verify_committee_larger_than_256() {
+        use crate::core::signing::aggregate_sign_subset;
+
+        let (sks, pks, pk_root) = setup(300);
+        let msg = b"large committee";
+
+        let (sigs, proofs) = aggregate_sign_subset(&sks, &pks, msg, MessageMode::Pure, &[3, 257, 299]).unwrap();
+        let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
+
+        let view = ProofView::parse(proof.as_bytes()).unwrap();
+        assert_eq!(view.version, crate::types::PROOF_FORMAT_V2);
+        assert_eq!(view.committee_size, 300);
+        assert_eq!(view.bitmap.len(), 38);
+        assert_eq!(crate::utils::bitmap_to_indices(view.bitmap), vec![3, 257, 299]);
+
+        assert!(verify(pk_root, msg, &proof));
+
+        let restored = ZKSNARKProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(verify(pk_root, msg, &restored));
+    }
+
+    #[test]
+    fn test_verify_rejects_bitmap_not_matching_committed_indices() {
+        let (sks, pks, pk_root) = setup(10);
+        let msg = b"test";
+
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 3).unwrap();
+        let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
+        assert!(verify(pk_root, msg, &proof));
+
+        // Same popcount, but signer 2 is swapped for signer 7
+        let swapped = reseal_with_bitmap(&proof, &pk_root, msg, &[0b1000_0011, 0]);
+        assert!(!verify(pk_root, msg, &swapped));
+
+        // Padding bit beyond the committee of 10
+        let padded = reseal_with_bitmap(&proof, &pk_root, msg, &[0b0000_0011, 0b0100_0000]);
+        assert!(!verify(pk_root, msg, &padded));
+    }
+
+    #[test]
+    fn test_verify_rejects_v3_without_valid_snark() {
+        let (sks, pks, pk_root) = setup(3);
+        let msg = b"test";
+
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2).unwrap();
+        let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
+
+        // The v2 body relabelled as v3 with a garbage SNARK before the root
+        let mut bytes = proof.as_bytes()[..proof.size() - 32].to_vec();
+        bytes[0] = crate::types::PROOF_FORMAT_V3;
+        bytes.extend_from_slice(&4u32.to_le_bytes());
+        bytes.extend_from_slice(b"junk");
+        bytes.extend_from_slice(&pk_root);
+
+        let view = ProofView::parse(&bytes).unwrap();
+        assert_eq!(view.snark, Some(&b"junk"[..]));
+        assert_eq!(view.pk_root, pk_root);
+
+        let relabelled = ZKSNARKProof::new(bytes, proof.num_signatures(), *proof.public_inputs_hash());
+        assert!(!verify(pk_root, msg, &relabelled));
+    }
+
+    #[test]
+    fn test_verify_rejects_unknown_extension() {
+        let (sks, pks, pk_root) = setup(3);
+        let msg = b"test";
+
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2).unwrap();
+        let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
+
+        let view = ProofView::parse(proof.as_bytes()).unwrap();
+        let mut ext = proof_format::default_extensions();
+        proof_format::push_extension(&mut ext, 0xEE, b"future");
+        let mut index_commitment = [0u8; 32];
+        index_commitment.copy_from_slice(view.index_commitment.unwrap());
+
+        let bytes = proof_format::encode_v2(
+            2, 3, &[0; 32], view.bitmap, &index_commitment, &[0; 32], &ext, &pk_root,
+        );
+        let hash = proof_format::public_inputs_hash_v2(
+            &pk_root, msg, 2, 3, view.bitmap, &index_commitment, &[0; 32], &ext,
+        );
+        assert!(!verify(pk_root, msg, &ZKSNARKProof::new(bytes, 2, hash)));
+    }
+
+    #[test]
+    fn test_verify_rejects_foreign_scheme() {
+        use crate::core::scheme::SCHEME_SLH_DSA_SHAKE_128F;
+
+        let (sks, pks, pk_root) = setup(3);
+        let msg = b"test";
+
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2).unwrap();
+        let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
+        let view = ProofView::parse(proof.as_bytes()).unwrap();
+        assert_eq!(view.scheme_id(), Some(ActiveScheme::ID));
+
+        // Re-encode the same proof claiming a different signature scheme
+        let reseal = |scheme_id: u8| {
+            let bytes = proof.as_bytes();
+            let nonce_start = 45 + view.bitmap.len() + 32;
+            let mut commitment = [0u8; 32];
+            commitment.copy_from_slice(&bytes[9..41]);
+            let mut index_commitment = [0u8; 32];
+            index_commitment.copy_from_slice(view.index_commitment.unwrap());
+            let mut nonce_commitment = [0u8; 32];
+            nonce_commitment.copy_from_slice(&bytes[nonce_start..nonce_start + 32]);
+
+            let mut ext = Vec::new();
+            proof_format::push_extension(&mut ext, proof_format::EXT_SIGNATURE_SCHEME, &[scheme_id]);
+            proof_format::push_extension(&mut ext, proof_format::EXT_HASH_DOMAIN, &[proof_format::HASH_DOMAIN_TAGGED_V1]);
+            let bytes = proof_format::encode_v2(
+                2, 3, &commitment, view.bitmap, &index_commitment, &nonce_commitment, &ext, &pk_root,
+            );
+            let hash = proof_format::public_inputs_hash_v2(
+                &pk_root, msg, 2, 3, view.bitmap, &index_commitment, &nonce_commitment, &ext,
+            );
+            ZKSNARKProof::new(bytes, 2, hash)
+        };
+
+        assert!(verify(pk_root, msg, &reseal(ActiveScheme::ID)));
+
+        let other = if ActiveScheme::ID == SCHEME_SLH_DSA_SHAKE_128F {
+            crate::core::scheme::SCHEME_ML_DSA_87
+        } else {
+            SCHEME_SLH_DSA_SHAKE_128F
+        };
+        assert!(!verify(pk_root, msg, &reseal(other)));
+    }
+
+    #[test]
+    fn test_verify_full_proof() {
+        use crate::core::aggregation::{aggregate_full_proof, compress_full_proof};
+        use crate::core::signing::aggregate_sign_subset;
+
+        let (sks, pks, pk_root) = setup(6);
+        let msg = b"audited transfer";
+
+        let (sigs, proofs) = aggregate_sign_subset(&sks, &pks, msg, MessageMode::Pure, &[4, 0, 2]).unwrap();
+        let full = aggregate_full_proof(sigs, proofs, pk_root, msg, MessageMode::Pure, &pks).unwrap();
+        assert_eq!(full.signer_indices().collect::<Vec<_>>(), vec![0, 2, 4]);
+        assert_eq!(full.committee_size(), 6);
+
+        // Auditors only need the root and the serialized proof
+        let restored = FullProof::from_bytes(&full.to_bytes()).unwrap();
+        assert!(verify_full(pk_root, msg, MessageMode::Pure, &restored));
+        assert!(!verify_full(pk_root, b"other", MessageMode::Pure, &restored));
+        assert!(!verify_full([0x42; 32], msg, MessageMode::Pure, &restored));
+
+        let compact = compress_full_proof(&restored, pk_root, msg, MessageMode::Pure).unwrap();
+        assert_eq!(compact.num_signatures(), 3);
+        assert!(verify(pk_root, msg, &compact));
+        assert_eq!(
+            crate::utils::bitmap_to_indices(ProofView::parse(compact.as_bytes()).unwrap().bitmap),
+            vec![0, 2, 4]
+        );
+    }
+
+    #[test]
+    fn test_verify_full_rejects_tampering() {
+        use crate::core::aggregation::{aggregate_full_proof, compress_full_proof};
+
+        let (sks, pks, pk_root) = setup(4);
+        let msg = b"test";
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 3).unwrap();
+        let full = aggregate_full_proof(sigs, proofs, pk_root, msg, MessageMode::Pure, &pks).unwrap();
+        assert!(verify_full(pk_root, msg, MessageMode::Pure, &full));
+
+        // A flipped signature bit is caught, and the proof will not compress
+        let mut bad_sig = full.clone();
+        bad_sig.entries[1].1.signature.bytes[10] ^= 0x01;
+        assert!(!verify_full(pk_root, msg, MessageMode::Pure, &bad_sig));
+        assert!(compress_full_proof(&bad_sig, pk_root, msg, MessageMode::Pure).is_err());
+
+        // A changed nonce changes the signed challenge
+        let mut bad_nonce = full.clone();
+        bad_nonce.entries[0].1.signature.nonce[0] ^= 0x01;
+        assert!(!verify_full(pk_root, msg, MessageMode::Pure, &bad_nonce));
+
+        // Counting one signer twice
+        let mut duplicated = full.clone();
+        duplicated.entries[2] = duplicated.entries[1].clone();
+        assert!(!verify_full(pk_root, msg, MessageMode::Pure, &duplicated));
+
+        // Substituting a key outside the committee
+        let (_, outsider, _) = setup(1);
+        let mut foreign_key = full.clone();
+        foreign_key.entries[0].0 = outsider[0].clone();
+        assert!(!verify_full(pk_root, msg, MessageMode::Pure, &foreign_key));
+
+        // Signers beyond the claimed committee size
+        let mut shrunk = full.clone();
+        shrunk.committee_size = 2;
+        assert!(!verify_full(pk_root, msg, MessageMode::Pure, &shrunk));
+
+        let mut foreign_scheme = full;
+        foreign_scheme.scheme_id ^= 0x80;
+        assert!(!verify_full(pk_root, msg, MessageMode::Pure, &foreign_scheme));
+    }
+
+    /// A v1 proof over signers 0 and 1, with its untagged public inputs hash.
+    fn legacy_v1_proof(pk_root: [u8; 32], msg: &[u8]) -> ZKSNARKProof {
+        use sha3::{Digest, Sha3_256};
+
+        let mut bytes = vec![crate::types::PROOF_FORMAT_V1];
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&[0xAA; 32]);
+        let mut bitmap = [0u8; 32];
+        bitmap[0] = 0b0000_0011;
+        bytes.extend_from_slice(&bitmap);
+        bytes.extend_from_slice(&[0xBB; 32]);
+        bytes.extend_from_slice(&pk_root);
+
+        let mut hasher = Sha3_256::new();
+        hasher.update(pk_root);
+        hasher.update(msg);
+        hasher.update(2u64.to_le_bytes());
+        ZKSNARKProof::new(bytes, 2, hasher.finalize().into())
+    }
+
+    #[test]
+    #[cfg(not(feature = "legacy-hashing"))]
+    fn test_verify_rejects_untagged_proofs() {
+        let (sks, pks, pk_root) = setup(3);
+        let msg = b"test";
+
+        assert!(!verify([0x11; 32], b"legacy", &legacy_v1_proof([0x11; 32], b"legacy")));
+
+        // A v2 proof stripped of the hash domain extension
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2).unwrap();
+        let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
+        let view = ProofView::parse(proof.as_bytes()).unwrap();
+        let mut index_commitment = [0u8; 32];
+        index_commitment.copy_from_slice(view.index_commitment.unwrap());
+
+        let mut ext = Vec::new();
+        proof_format::push_extension(&mut ext, proof_format::EXT_SIGNATURE_SCHEME, &[ActiveScheme::ID]);
+        let bytes = proof_format::encode_v2(
+            2, 3, &[0; 32], view.bitmap, &index_commitment, &[0; 32], &ext, &pk_root,
+        );
+        let hash = proof_format::public_inputs_hash_v2(
+            &pk_root, msg, 2, 3, view.bitmap, &index_commitment, &[0; 32], &ext,
+        );
+        assert!(!verify(pk_root, msg, &ZKSNARKProof::new(bytes, 2, hash)));
+    }
+
+    #[test]
+    #[cfg(feature = "legacy-hashing")]
+    fn test_verify_legacy_v1_proof() {
+        let pk_root = [0x11u8; 32];
+        let msg = b"legacy";
+
+        // v1 proofs predate pluggable schemes and are always ML-DSA-65
+        let proof = legacy_v1_proof(pk_root, msg);
+        let legacy_scheme = ActiveScheme::ID == crate::core::scheme::SCHEME_ML_DSA_65;
+        assert_eq!(verify(pk_root, msg, &proof), legacy_scheme);
+        assert!(!verify(pk_root, b"other", &proof));
+    }
+
+    #[test]
+    #[cfg(feature = "legacy-hashing")]
+    fn test_verify_legacy_v2_proof() {
+        let pk_root = [0x11u8; 32];
+        let msg = b"legacy";
+        let bitmap = proof_format::build_bitmap([0, 1], 3);
+
+        let mut ext = Vec::new();
+        proof_format::push_extension(&mut ext, proof_format::EXT_SIGNATURE_SCHEME, &[ActiveScheme::ID]);
+        let legacy_proof = |index_commitment: [u8; 32]| {
+            let bytes = proof_format::encode_v2(
+                2, 3, &[0; 32], &bitmap, &index_commitment, &[0; 32], &ext, &pk_root,
+            );
+            let hash = proof_format::public_inputs_hash_v2_legacy(
+                &pk_root, msg, 2, 3, &bitmap, &index_commitment, &ext,
+            );
+            ZKSNARKProof::new(bytes, 2, hash)
+        };
+
+        // Untagged proofs carry the untagged index commitment
+        assert!(verify(pk_root, msg, &legacy_proof(proof_format::compute_index_commitment_legacy(&[0, 1]))));
+        assert!(!verify(pk_root, msg, &legacy_proof(proof_format::compute_index_commitment(&[0, 1]))));
+    }
+
     #[test]
     fn test_count_signers() {
         let mut bitmap = [0u8; 32];
@@ -257,10 +851,10 @@ mod tests {
         let msg1 = b"msg1";
         let msg2 = b"msg2";
 
-        let (sigs1, prfs1) = aggregate_sign(&sks, &pks, msg1, 2);
+        let (sigs1, prfs1) = aggregate_sign(&sks, &pks, msg1, 2).unwrap();
         let proof1 = aggregate_proofs(sigs1, prfs1, pk_root, msg1, &pks).unwrap();
 
-        let (sigs2, prfs2) = aggregate_sign(&sks, &pks, msg2, 2);
+        let (sigs2, prfs2) = aggregate_sign(&sks, &pks, msg2, 2).unwrap();
         let proof2 = aggregate_proofs(sigs2, prfs2, pk_root, msg2, &pks).unwrap();
 
         let batch_hashes = vec![*proof1.public_inputs_hash(), *proof2.public_inputs_hash()];
@@ -278,11 +872,321 @@ mod tests {
 
         // Create rotation proof signed by committee 1 authorizing committee 2
         let rotation = crate::core::aggregation::create_rotation_proof(
-            &sks1, &pks1, root1, root2, 2, 2
+            &sks1, &pks1, root1, root2, 3, 2, 2
         ).unwrap();
 
         // Verify rotation
         assert!(verify_rotation_proof(&rotation, &root1));
+
+        // The epoch is part of the signed message
+        let mut replayed = rotation.clone();
+        replayed.epoch = 3;
+        assert!(!verify_rotation_proof(&replayed, &root1));
+
+        // So is the size of the incoming committee
+        let mut resized = rotation.clone();
+        resized.new_committee_size = 2;
+        assert!(!verify_rotation_proof(&resized, &root1));
+    }
+
+    #[test]
+    fn test_verify_at() {
+        use crate::core::aggregation::aggregate_proofs_with_context;
+        use crate::types::ProofContext;
+
+        let (sks, pks, pk_root) = setup(5);
+        let msg = b"bridge transfer";
+        let domain = ProofContext::domain_id("ethereum:11155111");
+        let context = ProofContext::new(domain, 3, 1_000, 2_000);
+
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, &context.bind(msg), 3).unwrap();
+        let proof = aggregate_proofs_with_context(sigs, proofs, pk_root, msg, MessageMode::Pure, &pks, &context).unwrap();
+
+        assert!(verify_at(pk_root, msg, MessageMode::Pure, &proof, 1_500, 3, &domain));
+        assert!(verify_at(pk_root, msg, MessageMode::Pure, &proof, 2_000, 3, &domain));
+
+        // Outside the window, another epoch, another chain
+        assert!(!verify_at(pk_root, msg, MessageMode::Pure, &proof, 999, 3, &domain));
+        assert!(!verify_at(pk_root, msg, MessageMode::Pure, &proof, 2_001, 3, &domain));
+        assert!(!verify_at(pk_root, msg, MessageMode::Pure, &proof, 1_500, 4, &domain));
+        assert!(!verify_at(pk_root, msg, MessageMode::Pure, &proof, 1_500, 3, &ProofContext::domain_id("solana:devnet")));
+        assert!(!verify_at(pk_root, b"other", MessageMode::Pure, &proof, 1_500, 3, &domain));
+
+        // Not replayable as an unbound proof
+        assert!(!verify(pk_root, msg, &proof));
+
+        // Proofs without a context have no freshness to check
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 3).unwrap();
+        let plain = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
+        assert!(!verify_at(pk_root, msg, MessageMode::Pure, &plain, 1_500, 3, &domain));
+
+        // Signatures over the bare message are not accepted for a context
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 3).unwrap();
+        assert!(aggregate_proofs_with_context(sigs, proofs, pk_root, msg, MessageMode::Pure, &pks, &context).is_err());
+
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, &context.bind(msg), 3).unwrap();
+        let inverted = ProofContext::new(domain, 3, 2_000, 1_000);
+        assert!(aggregate_proofs_with_context(sigs, proofs, pk_root, msg, MessageMode::Pure, &pks, &inverted).is_err());
+    }
+
+    #[test]
+    fn test_verify_prehashed_message() {
+        use crate::core::aggregation::{
+            aggregate_proofs_with_context, aggregate_proofs_with_mode, create_aggregated_commitment,
+        };
+        use crate::core::signing::aggregate_sign_with_mode;
+        use crate::core::prehash::{PreHashAlgorithm, PreHasher};
+        use crate::types::ProofContext;
+
+        let (sks, pks, pk_root) = setup(4);
+        let image: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+
+        // Stream the image through the hasher
+        let mut hasher: PreHasher = PreHashAlgorithm::Shake256.hasher();
+        std::io::copy(&mut image.as_slice(), &mut hasher).unwrap();
+        let prehashed = hasher.finalize();
+
+        let mode = prehashed.mode();
+        let (sigs, proofs) = aggregate_sign_with_mode(&sks, &pks, prehashed.digest(), mode, 3).unwrap();
+        let proof = aggregate_proofs_with_mode(sigs, proofs, pk_root, prehashed.digest(), mode, &pks).unwrap();
+        let view = ProofView::parse(proof.as_bytes()).unwrap();
+        assert_eq!(view.prehash(), Some(Some(PreHashAlgorithm::Shake256)));
+        assert!(verify_with_mode(pk_root, prehashed.digest(), mode, &proof));
+        assert!(!verify(pk_root, prehashed.digest(), &proof));
+        assert!(!verify_with_mode(pk_root, prehashed.digest(), MessageMode::PreHashed(PreHashAlgorithm::Sha3_512), &proof));
+        let other = PreHashAlgorithm::Shake256.digest(b"another image");
+        assert!(!verify_with_mode(pk_root, other.digest(), mode, &proof));
+
+        // Pure-mode signatures are rejected when aggregated in pre-hash mode
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, prehashed.digest(), 3).unwrap();
+        assert!(aggregate_proofs_with_mode(sigs, proofs, pk_root, prehashed.digest(), mode, &pks).is_err());
+
+        // Plain proofs record no pre-hash and do not verify in pre-hash mode
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, &image, 3).unwrap();
+        let plain = aggregate_proofs(sigs, proofs, pk_root, &image, &pks).unwrap();
+        assert_eq!(ProofView::parse(plain.as_bytes()).unwrap().prehash(), Some(None));
+        assert!(!verify_with_mode(pk_root, &image, mode, &plain));
+
+        // A proof claiming pre-hash mode for a plain message is rejected
+        let msg = b"plain";
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 2).unwrap();
+        let leaf_hashes: Vec<[u8; 32]> = proofs.iter().map(|p| *p.leaf_hash()).collect();
+        let extensions = proof_format::proof_extensions(mode, None, None, None);
+        let mislabelled = create_aggregated_commitment(&sigs, &leaf_hashes, &pk_root, msg, 4, extensions).unwrap();
+        assert!(!verify(pk_root, msg, &mislabelled));
+
+        // Pre-hashing composes with context binding
+        let domain = ProofContext::domain_id("firmware");
+        let context = ProofContext::new(domain, 1, 0, 10);
+        let (sigs, proofs) = aggregate_sign_with_mode(&sks, &pks, &context.bind(prehashed.digest()), mode, 3).unwrap();
+        let bound =
+            aggregate_proofs_with_context(sigs, proofs, pk_root, prehashed.digest(), mode, &pks, &context).unwrap();
+        assert_eq!(ProofView::parse(bound.as_bytes()).unwrap().prehash(), Some(Some(PreHashAlgorithm::Shake256)));
+        assert!(verify_at(pk_root, prehashed.digest(), mode, &bound, 5, 1, &domain));
+        assert!(!verify_at(pk_root, prehashed.digest(), MessageMode::Pure, &bound, 5, 1, &domain));
+    }
+
+    #[test]
+    fn test_pure_message_shaped_like_prehash_frame() {
+        use crate::core::aggregation::aggregate_proofs_with_mode;
+        use crate::core::prehash::PreHashAlgorithm;
+
+        let (sks, pks, pk_root) = setup(4);
+        let prehashed = PreHashAlgorithm::Sha3_512.digest(b"firmware image");
+        // 77 bytes that spell out `01 00 || OID || digest`
+        let lookalike = [&[0x01, 0x00][..], PreHashAlgorithm::Sha3_512.oid(), prehashed.digest()].concat();
+        assert_eq!(lookalike.len(), 77);
+
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, &lookalike, 3).unwrap();
+        let pure = aggregate_proofs(sigs.clone(), proofs.clone(), pk_root, &lookalike, &pks).unwrap();
+        assert_eq!(ProofView::parse(pure.as_bytes()).unwrap().prehash(), Some(None));
+        assert!(verify(pk_root, &lookalike, &pure));
+        assert!(!verify_with_mode(pk_root, prehashed.digest(), prehashed.mode(), &pure));
+
+        // The pure signatures cannot be passed off as signatures over the digest
+        assert!(aggregate_proofs_with_mode(sigs, proofs, pk_root, prehashed.digest(), prehashed.mode(), &pks).is_err());
+    }
+
+    #[test]
+    fn test_prehashed_mode_in_every_aggregation_path() {
+        use crate::core::aggregation::{
+            aggregate_certificate, aggregate_full_proof, aggregate_proofs_multi, aggregate_proofs_weighted,
+            compress_full_proof,
+        };
+        use crate::core::prehash::PreHashAlgorithm;
+        use crate::core::signing::{aggregate_sign_deterministic, aggregate_sign_subset};
+        use crate::types::{MultiMessageCertificate, ThresholdPolicy};
+
+        let (sks, pks, pk_root) = setup(5);
+        let prehashed = PreHashAlgorithm::Sha3_512.digest(b"release bundle");
+        let (digest, mode) = (prehashed.digest(), prehashed.mode());
+        let recorded = |proof: &ZKSNARKProof| ProofView::parse(proof.as_bytes()).unwrap().prehash();
+
+        let (sigs, proofs) = aggregate_sign_subset(&sks, &pks, digest, mode, &[4, 0, 2]).unwrap();
+        let (pure_sigs, pure_proofs) = aggregate_sign_subset(&sks, &pks, digest, MessageMode::Pure, &[4, 0, 2]).unwrap();
+
+        // Multiproof aggregation
+        let multiproof = MerkleTree::from_public_keys(&pks).prove_many(&[0, 2, 4]).unwrap();
+        let proof = aggregate_proofs_multi(sigs.clone(), &multiproof, pk_root, digest, mode, &pks).unwrap();
+        assert_eq!(recorded(&proof), Some(Some(PreHashAlgorithm::Sha3_512)));
+        assert!(verify_with_mode(pk_root, digest, mode, &proof));
+        assert!(!verify(pk_root, digest, &proof));
+        assert!(aggregate_proofs_multi(pure_sigs.clone(), &multiproof, pk_root, digest, mode, &pks).is_err());
+
+        // Full proofs are checked, and compressed, in the mode they were signed in
+        let full = aggregate_full_proof(sigs.clone(), proofs.clone(), pk_root, digest, mode, &pks).unwrap();
+        assert!(verify_full(pk_root, digest, mode, &full));
+        assert!(!verify_full(pk_root, digest, MessageMode::Pure, &full));
+        let compact = compress_full_proof(&full, pk_root, digest, mode).unwrap();
+        assert!(verify_with_mode(pk_root, digest, mode, &compact));
+        assert!(compress_full_proof(&full, pk_root, digest, MessageMode::Pure).is_err());
+
+        // Certificates commit to the framed messages
+        let batches = [(digest, sigs.clone(), proofs.clone())];
+        let certificate = aggregate_certificate(&batches, pk_root, mode, &pks).unwrap();
+        let inclusion = MultiMessageCertificate::message_tree(&[digest], mode).prove(0).unwrap();
+        assert!(verify_certificate_message(pk_root, &certificate, digest, mode, &inclusion, 3));
+        let pure_inclusion = MultiMessageCertificate::message_tree(&[digest], MessageMode::Pure).prove(0).unwrap();
+        assert!(!verify_certificate_message(pk_root, &certificate, digest, MessageMode::Pure, &pure_inclusion, 3));
+        let pure_batches = [(digest, pure_sigs, pure_proofs)];
+        assert!(aggregate_certificate(&pure_batches, pk_root, mode, &pks).is_err());
+
+        // Weighted proofs, checked against a policy in the same mode
+        let weights = [1, 1, 1, 1, 1];
+        let tree = MerkleTree::from_weighted_public_keys(&pks, &weights);
+        let (sigs, _) = aggregate_sign_deterministic(&sks, &pks, digest, mode, 3).unwrap();
+        let proofs = (0..3).map(|i| tree.prove(i).unwrap()).collect();
+        let weighted = aggregate_proofs_weighted(sigs, proofs, tree.root(), digest, mode, &pks, &weights).unwrap();
+        let policy = ThresholdPolicy::Weighted { min_weight: 3 };
+        assert!(verify_with_policy_and_mode(tree.root(), digest, mode, &weighted, 5, &policy));
+        assert!(!verify_with_policy(tree.root(), digest, &weighted, 5, &policy));
+    }
+
+    #[test]
+    fn test_signer_indices() {
+        let (sks, pks, pk_root) = setup(12);
+        let msg = b"who signed";
+
+        let (sigs, proofs) = crate::core::signing::aggregate_sign_subset(&sks, &pks, msg, MessageMode::Pure, &[9, 2, 5, 11]).unwrap();
+        let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
+        assert_eq!(proof.signer_indices(), Some(vec![2, 5, 9, 11]));
+
+        let garbage = ZKSNARKProof::new(vec![0x02, 0x01], 1, [0; 32]);
+        assert_eq!(garbage.signer_indices(), None);
+    }
+
+    #[test]
+    fn test_equivocation_evidence() {
+        use crate::core::signing::{sign_share, NonceSource};
+        use crate::types::{EquivocationEvidence, SlotMessage};
+        use crate::utils::MerkleTree;
+
+        let (sks, pks, pk_root) = setup(4);
+        let tree = MerkleTree::from_public_keys(&pks);
+        let sign = |i: usize, msg: &SlotMessage| {
+            sign_share(&sks[i], i, &msg.to_bytes(), NonceSource::Random, &tree.prove(i).unwrap()).unwrap()
+        };
+
+        let block_a = SlotMessage::new(3, 17, b"block A".to_vec());
+        let block_b = SlotMessage::new(3, 17, b"block B".to_vec());
+        let (share_a, share_b) = (sign(2, &block_a), sign(2, &block_b));
+
+        let evidence = EquivocationEvidence::new(pks[2].clone(), (&block_b, &share_b), (&block_a, &share_a)).unwrap();
+        assert_eq!(evidence.offender(), 2);
+        assert_eq!((evidence.epoch(), evidence.slot()), (3, 17));
+        assert_eq!(evidence.messages(), [block_a.clone(), block_b.clone()]);
+        assert!(verify_equivocation(&evidence, &pk_root));
+
+        let bytes = evidence.to_bytes();
+        let restored = EquivocationEvidence::from_bytes(&bytes).unwrap();
+        assert!(verify_equivocation(&restored, &pk_root));
+        assert_eq!(restored.to_bytes(), bytes);
+        assert!(EquivocationEvidence::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+
+        // Another committee, or a signature moved to the wrong payload
+        let (_, _, other_root) = setup(4);
+        assert!(!verify_equivocation(&evidence, &other_root));
+        let mut swapped = evidence.clone();
+        let [(_, sig_a), (_, sig_b)] = &mut swapped.signed;
+        core::mem::swap(sig_a, sig_b);
+        assert!(!verify_equivocation(&swapped, &pk_root));
+
+        // Someone else's key cannot be blamed
+        let mut framed = evidence.clone();
+        framed.public_key = pks[1].clone();
+        assert!(!verify_equivocation(&framed, &pk_root));
+
+        // Not equivocation: different slots, same payload, different validators
+        let next_slot = SlotMessage::new(3, 18, b"block B".to_vec());
+        assert!(EquivocationEvidence::new(pks[2].clone(), (&block_a, &share_a), (&next_slot, &sign(2, &next_slot))).is_err());
+        assert!(EquivocationEvidence::new(pks[2].clone(), (&block_a, &share_a), (&block_a, &sign(2, &block_a))).is_err());
+        assert!(EquivocationEvidence::new(pks[2].clone(), (&block_a, &share_a), (&block_b, &sign(1, &block_b))).is_err());
+    }
+
+    #[test]
+    fn test_membership_update_flow() {
+        use crate::core::aggregation::{create_membership_update, prepare_membership_update};
+        use crate::core::signing::{sign_share, NonceSource};
+        use crate::utils::{revoked_leaf, MerkleTree};
+
+        let (sks, mut pks, root) = setup(5);
+        let tree = MerkleTree::from_public_keys(&pks);
+        let (new_sk, new_pk) = crate::core::keygen::derive_keypair(&[7u8; 32], 1).unwrap();
+
+        // Replace validator 1's key and revoke validator 3
+        let changes = [(1, ActiveScheme::leaf_hash(new_pk.as_bytes())), (3, revoked_leaf())];
+        let pending = prepare_membership_update(&tree, &changes, 1).unwrap();
+        let shares = [0, 2, 4].map(|i| {
+            sign_share(&sks[i], i, &pending.signed_message(), NonceSource::Random, &tree.prove(i).unwrap()).unwrap()
+        });
+        let update = create_membership_update(pending, &pks, shares, 3).unwrap();
+        assert!(verify_membership_update(&update, &root));
+        assert!(!verify_membership_update(&update, &update.new_root));
+
+        let restored = crate::types::MembershipUpdateProof::from_bytes(&update.to_bytes()).unwrap();
+        assert!(verify_membership_update(&restored, &root));
+
+        // The new committee signs under the new root; the revoked key cannot
+        let mut new_tree = tree.clone();
+        new_tree.update_leaf(1, changes[0].1);
+        new_tree.revoke(3);
+        assert_eq!(new_tree.root(), update.new_root);
+        pks[1] = new_pk;
+
+        let msg = b"after update";
+        let share = |sk, i| sign_share(sk, i, msg, NonceSource::Random, &new_tree.prove(i).unwrap()).unwrap().into_parts();
+        let (sigs, proofs): (Vec<_>, Vec<_>) = [share(&sks[0], 0), share(&new_sk, 1)].into_iter().unzip();
+        let proof = aggregate_proofs(sigs, proofs, update.new_root, msg, &pks).unwrap();
+        assert!(verify(update.new_root, msg, &proof));
+
+        let (sig, merkle_proof) = share(&sks[3], 3);
+        assert!(aggregate_proofs(vec![sig], vec![merkle_proof], update.new_root, msg, &pks).is_err());
+
+        // An undeclared leaf also changed
+        let mut extra = new_tree.clone();
+        let mut sneaky = update.clone();
+        sneaky.new_root = extra.revoke(0).unwrap();
+        assert!(!verify_membership_update(&sneaky, &root));
+
+        // Declared leaves differ from what was signed
+        let mut swapped = update.clone();
+        swapped.new_leaves[0] = ActiveScheme::leaf_hash(pks[0].as_bytes());
+        assert!(!verify_membership_update(&swapped, &root));
+
+        // Consistent changes, but not the ones the committee signed
+        let forged_leaves = [[0xAB; 32], revoked_leaf()];
+        let mut forged_tree = tree.clone();
+        forged_tree.update_leaf(1, forged_leaves[0]);
+        forged_tree.revoke(3);
+        let mut forged = update.clone();
+        forged.new_leaves = forged_leaves.to_vec();
+        forged.new_root = forged_tree.root();
+        assert!(check_membership_changes(&forged));
+        assert!(!verify_membership_update(&forged, &root));
+
+        let mut bumped = update;
+        bumped.epoch = 2;
+        assert!(!verify_membership_update(&bumped, &root));
     }
 }
 
@@ -300,41 +1204,119 @@ pub fn verify_rotation_proof(
     }
 
     // 2. SNARK Verification
-    // The "message" signed in a rotation is the new public key root.
-    verify(rotation.old_root, &rotation.new_root, &rotation.proof)
+    // The "message" signed in a rotation is the new public key root, its
+    // committee size and the epoch.
+    verify(rotation.old_root, &rotation.signed_message(), &rotation.proof)
+}
+
+/// Verify a MembershipUpdateProof.
+///
+/// Validates that the update starts from `current_trusted_root`, that only
+/// the declared leaves differ between `old_root` and `new_root`, and that a
+/// threshold of the old committee signed exactly those changes.
+pub fn verify_membership_update(
+    update: &crate::types::MembershipUpdateProof,
+    current_trusted_root: &[u8; 32],
+) -> bool {
+    // 1. Root matching
+    if update.old_root != *current_trusted_root {
+        return false;
+    }
+
+    // 2. Declared leaves are the only difference
+    if !check_membership_changes(update) {
+        return false;
+    }
+
+    // 3. SNARK Verification over the declared changes
+    verify(update.old_root, &update.signed_message(), &update.proof)
+}
+
+/// Check that `new_root` is `old_root` with only the declared leaves replaced.
+///
+/// The old leaves are proven against `old_root`; substituting the new leaves
+/// into the same path nodes must then yield `new_root`. Padding slots (the
+/// all-zero leaf) can neither be filled nor created.
+pub(crate) fn check_membership_changes(update: &crate::types::MembershipUpdateProof) -> bool {
+    let paths = &update.paths;
+    if update.new_leaves.len() != paths.num_leaves()
+        || paths
+            .leaf_hashes()
+            .iter()
+            .zip(&update.new_leaves)
+            .any(|(old, new)| old == new || *old == [0u8; 32] || *new == [0u8; 32])
+    {
+        return false;
+    }
+
+    let updated = crate::types::MerkleMultiProof::new(
+        paths.leaf_indices().to_vec(),
+        update.new_leaves.clone(),
+        paths.proof_hashes().to_vec(),
+        paths.depth(),
+    );
+    crate::utils::MerkleTree::verify_multi(&update.old_root, paths)
+        && crate::utils::MerkleTree::verify_multi(&update.new_root, &updated)
 }
 
 /// Verify an aggregated proof against a specific ThresholdPolicy.
 /// 
 /// This is the "Adaptive Threshold Gadget" which enforces dynamic security
 /// requirements (e.g., higher threshold for high-value transactions).
+///
+/// A [`ThresholdPolicy::Weighted`](crate::types::ThresholdPolicy::Weighted)
+/// policy is checked against the combined signer weight the proof commits
+/// to, so it only accepts proofs from
+/// [`aggregate_proofs_weighted`](crate::core::aggregation::aggregate_proofs_weighted)
+/// over a weighted key tree. A
+/// [`ThresholdPolicy::Hierarchical`](crate::types::ThresholdPolicy::Hierarchical)
+/// policy only accepts proofs for the same structure rooted at its
+/// [`key_root`](crate::core::access::AccessStructure::key_root), as produced by
+/// [`aggregate_proofs_hierarchical`](crate::core::aggregation::aggregate_proofs_hierarchical)
+/// or by an [`Aggregator`](crate::core::aggregator::Aggregator) under that
+/// policy, and is evaluated against the signer bitmap.
 pub fn verify_with_policy(
     pk_root: [u8; 32],
     msg: &[u8],
     proof: &crate::types::ZKSNARKProof,
     total_validators: usize,
     policy: &crate::types::ThresholdPolicy,
+) -> bool {
+    verify_with_policy_and_mode(pk_root, msg, MessageMode::Pure, proof, total_validators, policy)
+}
+
+/// [`verify_with_policy`] for a message signed in the given [`MessageMode`].
+///
+/// The mode is checked as in [`verify_with_mode`].
+pub fn verify_with_policy_and_mode(
+    pk_root: [u8; 32],
+    msg: &[u8],
+    mode: MessageMode,
+    proof: &crate::types::ZKSNARKProof,
+    total_validators: usize,
+    policy: &crate::types::ThresholdPolicy,
 ) -> bool {
     // 1. Standard SNARK verification
-    if !verify(pk_root, msg, proof) {
+    if !verify_with_mode(pk_root, msg, mode, proof) {
         return false;
     }
 
     // 2. Policy enforcement (The Gadget)
-    let t = proof.num_signatures();
-    let n = total_validators;
+    // The weight and structure are covered by the public inputs hash checked above
+    let Some(view) = ProofView::parse(proof.as_bytes()) else {
+        return false;
+    };
+    let (Some(signer_weight), Some(access_structure)) = (view.signer_weight(), view.access_structure()) else {
+        return false;
+    };
 
     match policy {
-        crate::types::ThresholdPolicy::Fixed(req) => t == *req,
-        crate::types::ThresholdPolicy::AtLeast(req) => t >= *req,
-        crate::types::ThresholdPolicy::Percentage(pct) => {
-            let min_req = (n * (*pct as usize) + 99) / 100;
-            t >= min_req
-        },
-        crate::types::ThresholdPolicy::Tiered { level } => {
-            let req = crate::utils::calculate_adaptive_threshold(n, *level);
-            t >= req
+        crate::types::ThresholdPolicy::Hierarchical(structure) => {
+            access_structure == Some(structure.commitment())
+                && view.committee_size == total_validators
+                && policy.is_satisfied_by_signers(&crate::utils::bitmap_to_indices(view.bitmap), total_validators)
         }
+        _ => policy.is_satisfied_with_weight(proof.num_signatures(), total_validators, signer_weight),
     }
 }
 
@@ -353,7 +1335,7 @@ mod policy_tests {
         let msg = b"policy test";
 
         // Create a proof with 7 signatures (70%)
-        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 7);
+        let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 7).unwrap();
         let proof = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).unwrap();
 
         // 1. Fixed Policy
@@ -368,5 +1350,98 @@ mod policy_tests {
         assert!(verify_with_policy(pk_root, msg, &proof, n, &ThresholdPolicy::Tiered { level: 2 }));
         // Level 3 = 80% (requires 8, we have 7)
         assert!(!verify_with_policy(pk_root, msg, &proof, n, &ThresholdPolicy::Tiered { level: 3 }));
+
+        // 4. Weighted Policy: the proof carries no weight
+        assert!(!verify_with_policy(pk_root, msg, &proof, n, &ThresholdPolicy::Weighted { min_weight: 0 }));
+    }
+
+    #[test]
+    fn test_verify_weighted_policy() {
+        use crate::core::aggregation::aggregate_proofs_weighted;
+        use crate::utils::MerkleTree;
+
+        let (sks, pks, _) = setup(5);
+        let weights = [40, 5, 5, 5, 45];
+        let tree = MerkleTree::from_weighted_public_keys(&pks, &weights);
+        let pk_root = tree.root();
+        let msg = b"stake weighted";
+
+        // Signers 1-3 are a majority by headcount but hold only 15% of the stake
+        let (sigs, _) = crate::core::signing::aggregate_sign_subset(&sks, &pks, msg, MessageMode::Pure, &[1, 2, 3]).unwrap();
+        let proofs = [1, 2, 3].iter().map(|&i| tree.prove(i).unwrap()).collect();
+        let minority = aggregate_proofs_weighted(sigs, proofs, pk_root, msg, MessageMode::Pure, &pks, &weights).unwrap();
+        assert!(verify(pk_root, msg, &minority));
+        assert!(verify_with_policy(pk_root, msg, &minority, 5, &ThresholdPolicy::AtLeast(3)));
+        assert!(!verify_with_policy(pk_root, msg, &minority, 5, &ThresholdPolicy::Weighted { min_weight: 67 }));
+
+        // Signers 0 and 4 are a minority by headcount but hold 85%
+        let (sigs, _) = crate::core::signing::aggregate_sign_subset(&sks, &pks, msg, MessageMode::Pure, &[0, 4]).unwrap();
+        let proofs = [0, 4].iter().map(|&i| tree.prove(i).unwrap()).collect();
+        let majority = aggregate_proofs_weighted(sigs, proofs, pk_root, msg, MessageMode::Pure, &pks, &weights).unwrap();
+        assert!(verify_with_policy(pk_root, msg, &majority, 5, &ThresholdPolicy::Weighted { min_weight: 67 }));
+        assert!(!verify_with_policy(pk_root, msg, &majority, 5, &ThresholdPolicy::AtLeast(3)));
+    }
+
+    #[test]
+    fn test_verify_hierarchical_policy() {
+        use crate::core::access::AccessStructure;
+        use crate::core::aggregation::aggregate_proofs_hierarchical;
+        use crate::core::signing::aggregate_sign_subset;
+
+        // 2 of 3 organizations, each contributing at least 2 of its 4 signers
+        let (sks, pks, _) = setup(12);
+        let structure =
+            AccessStructure::groups(2, [(2, vec![0, 1, 2, 3]), (2, vec![4, 5, 6, 7]), (2, vec![8, 9, 10, 11])]);
+        let key_root = structure.key_root(&pks).unwrap();
+        let policy = ThresholdPolicy::Hierarchical(structure.clone());
+        let msg = b"org quorum";
+
+        let (sigs, _) = aggregate_sign_subset(&sks, &pks, msg, MessageMode::Pure, &[0, 1, 8, 9]).unwrap();
+        let proof = aggregate_proofs_hierarchical(sigs, &structure, key_root, msg, MessageMode::Pure, &pks).unwrap();
+        assert!(verify(key_root, msg, &proof));
+        assert!(verify_with_policy(key_root, msg, &proof, 12, &policy));
+
+        // A different structure over the same committee does not accept it
+        let stricter =
+            AccessStructure::groups(3, [(2, vec![0, 1, 2, 3]), (2, vec![4, 5, 6, 7]), (2, vec![8, 9, 10, 11])]);
+        assert!(!verify_with_policy(key_root, msg, &proof, 12, &ThresholdPolicy::Hierarchical(stricter)));
+
+        // Five signers, but only one organization reaches its threshold
+        let (sigs, _) = aggregate_sign_subset(&sks, &pks, msg, MessageMode::Pure, &[0, 1, 2, 4, 8]).unwrap();
+        assert!(matches!(
+            aggregate_proofs_hierarchical(sigs, &structure, key_root, msg, MessageMode::Pure, &pks),
+            Err(PQAggregateError::PolicyViolation { .. })
+        ));
+
+        // Wrong key root
+        let (sigs, _) = aggregate_sign_subset(&sks, &pks, msg, MessageMode::Pure, &[4, 5, 8, 9]).unwrap();
+        assert!(matches!(
+            aggregate_proofs_hierarchical(sigs, &structure, [0; 32], msg, MessageMode::Pure, &pks),
+            Err(PQAggregateError::MerkleProofInvalid { .. })
+        ));
+
+        // A flat proof over the same signers does not satisfy a hierarchical policy
+        let (sigs, proofs) = aggregate_sign_subset(&sks, &pks, msg, MessageMode::Pure, &[0, 1, 8, 9]).unwrap();
+        let flat_root = crate::utils::MerkleTree::from_public_keys(&pks).root();
+        let flat = aggregate_proofs(sigs, proofs, flat_root, msg, &pks).unwrap();
+        assert!(!verify_with_policy(flat_root, msg, &flat, 12, &policy));
+    }
+
+    #[test]
+    fn test_weighted_aggregation_rejects_wrong_weight() {
+        use crate::core::aggregation::aggregate_proofs_weighted;
+        use crate::utils::MerkleTree;
+
+        let (sks, pks, _) = setup(3);
+        let weights = [1, 1, 1];
+        let tree = MerkleTree::from_weighted_public_keys(&pks, &weights);
+        let msg = b"stake weighted";
+        let (sigs, _) = aggregate_sign(&sks, &pks, msg, 2).unwrap();
+        let proofs: Vec<_> = (0..2).map(|i| tree.prove(i).unwrap()).collect();
+
+        // Claiming more stake than the tree commits to
+        let inflated = [100, 1, 1];
+        assert!(aggregate_proofs_weighted(sigs.clone(), proofs.clone(), tree.root(), msg, MessageMode::Pure, &pks, &inflated).is_err());
+        assert!(aggregate_proofs_weighted(sigs, proofs, tree.root(), msg, MessageMode::Pure, &pks, &[1, 1]).is_err());
     }
 }
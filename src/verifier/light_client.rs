@@ -0,0 +1,516 @@
+//! Light client that follows validator-set rotations from genesis.
+//!
+//! [`verify_rotation_proof`](super::verify_rotation_proof) checks a single
+//! transition. A [`CommitteeLightClient`] chains them: starting from a
+//! trusted genesis root it accepts each [`RotationProof`] only if it was
+//! signed by the committee trusted at the time, its epoch moves forward,
+//! and it does not conflict with a rotation already accepted for the same
+//! epoch. [`MembershipUpdateProof`]s that replace or revoke individual
+//! validators advance the same epoch sequence.
+//!
+//! The client also tracks each committee's size, taken from genesis and
+//! from the signed rotations, so that policies are never evaluated against
+//! the committee size a proof claims for itself.
+
+use alloc::format;
+use alloc::vec::Vec;
+
+use super::{check_membership_changes, verify, verify_with_policy};
+use crate::core::proof_format::ProofView;
+use crate::error::{PQAggregateError, Result};
+use crate::types::{MembershipUpdateProof, RotationProof, ThresholdPolicy, ZKSNARKProof};
+
+/// Binary format version for [`CommitteeLightClient`] state.
+pub const LIGHT_CLIENT_FORMAT_VERSION: u8 = 0x02;
+
+/// Tracks the trusted committee root across rotation epochs.
+///
+/// The genesis committee is epoch 0, so the first rotation has an epoch of
+/// at least 1. Epochs must increase but may skip values.
+///
+/// # Example
+/// ```
+/// use pq_aggregate::setup;
+/// use pq_aggregate::core::aggregation::create_rotation_proof;
+/// use pq_aggregate::verifier::light_client::CommitteeLightClient;
+///
+/// let (sks, pks, genesis) = setup(4);
+/// let (_, _, next) = setup(4);
+///
+/// let mut client = CommitteeLightClient::new(genesis, 4);
+/// let rotation = create_rotation_proof(&sks, &pks, genesis, next, 4, 1, 3).unwrap();
+/// assert!(client.apply(&rotation).unwrap());
+/// assert_eq!(client.trusted_root(), &next);
+///
+/// // Persist and resume later
+/// let restored = CommitteeLightClient::from_bytes(&client.to_bytes()).unwrap();
+/// assert_eq!(restored.epoch(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CommitteeLightClient {
+    genesis_root: [u8; 32],
+    genesis_committee_size: usize,
+    /// Accepted rotations as `(epoch, new_root, new_committee_size)`, in ascending epoch order
+    rotations: Vec<(u64, [u8; 32], usize)>,
+    policy: Option<ThresholdPolicy>,
+}
+
+impl CommitteeLightClient {
+    /// Start from a trusted genesis committee root and its number of validators.
+    pub fn new(genesis_root: [u8; 32], genesis_committee_size: usize) -> Self {
+        Self {
+            genesis_root,
+            genesis_committee_size,
+            rotations: Vec::new(),
+            policy: None,
+        }
+    }
+
+    /// Require every rotation proof to also satisfy `policy`.
+    ///
+    /// Without a policy any valid proof from the trusted committee is
+    /// accepted, as with [`verify_rotation_proof`](super::verify_rotation_proof).
+    /// The policy is evaluated against the trusted committee size, not the
+    /// one recorded in the proof. It is configuration and is not part of the
+    /// serialized state.
+    pub fn with_policy(mut self, policy: ThresholdPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Get the genesis committee root.
+    pub fn genesis_root(&self) -> &[u8; 32] {
+        &self.genesis_root
+    }
+
+    /// Get the currently trusted committee root.
+    pub fn trusted_root(&self) -> &[u8; 32] {
+        self.rotations.last().map_or(&self.genesis_root, |(_, root, _)| root)
+    }
+
+    /// Get the number of validators in the currently trusted committee.
+    pub fn committee_size(&self) -> usize {
+        self.rotations.last().map_or(self.genesis_committee_size, |(_, _, size)| *size)
+    }
+
+    /// Get the epoch of the most recent accepted rotation (0 at genesis).
+    pub fn epoch(&self) -> u64 {
+        self.rotations.last().map_or(0, |(epoch, _, _)| *epoch)
+    }
+
+    /// Get the number of accepted rotations.
+    pub fn num_rotations(&self) -> usize {
+        self.rotations.len()
+    }
+
+    /// The committee root that was trusted during `epoch`.
+    ///
+    /// Returns `None` for epochs beyond the current one, which are not yet known.
+    pub fn root_at(&self, epoch: u64) -> Option<[u8; 32]> {
+        if epoch > self.epoch() {
+            return None;
+        }
+        // Last rotation at or before `epoch`
+        let accepted = self.rotations.partition_point(|(e, _, _)| *e <= epoch);
+        Some(accepted.checked_sub(1).map_or(self.genesis_root, |i| self.rotations[i].1))
+    }
+
+    /// Ingest the next rotation.
+    ///
+    /// Returns `Ok(true)` if the trusted root advanced and `Ok(false)` if
+    /// this exact rotation had already been accepted.
+    ///
+    /// # Errors
+    /// - `InvalidInput` if the epoch does not move forward or the rotation
+    ///   does not start from the trusted root
+    /// - `CryptoError` if the proof does not verify (or fails the policy)
+    /// - `RotationConflict` if a *valid* rotation to a different root was
+    ///   already accepted for this epoch, i.e. the old committee signed a fork
+    pub fn apply(&mut self, rotation: &RotationProof) -> Result<bool> {
+        let new_committee = (rotation.new_root, Some(rotation.new_committee_size));
+        self.advance(rotation.epoch, rotation.old_root, new_committee, |client, committee_size| {
+            client.check_proof(rotation.old_root, committee_size, &rotation.signed_message(), &rotation.proof)
+        })
+    }
+
+    /// Ingest the next membership update (key replacement or revocation).
+    ///
+    /// Updates share the epoch sequence with rotations and are accepted
+    /// under the same rules as [`Self::apply`]; additionally the update must
+    /// change only the leaves it declares. Leaves are replaced in place, so
+    /// the committee size carries over.
+    pub fn apply_membership_update(&mut self, update: &MembershipUpdateProof) -> Result<bool> {
+        self.advance(update.epoch, update.old_root, (update.new_root, None), |client, committee_size| {
+            check_membership_changes(update)
+                && client.check_proof(update.old_root, committee_size, &update.signed_message(), &update.proof)
+        })
+    }
+
+    /// Ingest rotations in order, stopping at the first error.
+    ///
+    /// Returns the number of rotations that advanced the trusted root.
+    pub fn apply_all<'a>(&mut self, rotations: impl IntoIterator<Item = &'a RotationProof>) -> Result<usize> {
+        let mut advanced = 0;
+        for rotation in rotations {
+            if self.apply(rotation)? {
+                advanced += 1;
+            }
+        }
+        Ok(advanced)
+    }
+
+    /// Move from `old_root` to `new_root` at `epoch` if `is_valid` holds.
+    ///
+    /// `new_committee_size` is `None` if the committee keeps its size.
+    /// `is_valid` is given the size of the committee trusted at `old_root`.
+    fn advance(
+        &mut self,
+        epoch: u64,
+        old_root: [u8; 32],
+        (new_root, new_committee_size): ([u8; 32], Option<usize>),
+        is_valid: impl FnOnce(&Self, usize) -> bool,
+    ) -> Result<bool> {
+        if let Ok(position) = self.rotations.binary_search_by_key(&epoch, |(e, _, _)| *e) {
+            let (previous_root, previous_size) = self.committee_before(position);
+            let (_, accepted_root, accepted_size) = self.rotations[position];
+            if old_root == previous_root
+                && new_root == accepted_root
+                && new_committee_size.unwrap_or(previous_size) == accepted_size
+            {
+                return Ok(false);
+            }
+            if old_root == previous_root && is_valid(self, previous_size) {
+                return Err(PQAggregateError::RotationConflict { epoch });
+            }
+            return Err(PQAggregateError::CryptoError {
+                reason: format!("Rotation proof for epoch {} does not verify", epoch),
+            });
+        }
+
+        if epoch <= self.epoch() {
+            return Err(PQAggregateError::InvalidInput {
+                reason: format!("Rotation epoch {} does not follow epoch {}", epoch, self.epoch()),
+            });
+        }
+
+        if old_root != *self.trusted_root() {
+            return Err(PQAggregateError::InvalidInput {
+                reason: format!("Rotation for epoch {} does not start from the trusted root", epoch),
+            });
+        }
+
+        let committee_size = self.committee_size();
+        if !is_valid(self, committee_size) {
+            return Err(PQAggregateError::CryptoError {
+                reason: format!("Rotation proof for epoch {} does not verify", epoch),
+            });
+        }
+
+        self.rotations.push((epoch, new_root, new_committee_size.unwrap_or(committee_size)));
+        Ok(true)
+    }
+
+    /// Root and committee size trusted before the rotation at `position` was applied.
+    fn committee_before(&self, position: usize) -> ([u8; 32], usize) {
+        position.checked_sub(1).map_or((self.genesis_root, self.genesis_committee_size), |i| {
+            let (_, root, size) = self.rotations[i];
+            (root, size)
+        })
+    }
+
+    /// Verify a transition's proof against its own `old_root`.
+    ///
+    /// `committee_size` is the trusted size of that committee; the size
+    /// recorded in the proof is only checked against it, never used in its place.
+    fn check_proof(&self, old_root: [u8; 32], committee_size: usize, msg: &[u8], proof: &ZKSNARKProof) -> bool {
+        let Some(view) = ProofView::parse(proof.as_bytes()) else {
+            return false;
+        };
+        if view.committee_size != committee_size {
+            return false;
+        }
+        match &self.policy {
+            None => verify(old_root, msg, proof),
+            Some(policy) => verify_with_policy(old_root, msg, proof, committee_size, policy),
+        }
+    }
+
+    /// Serialize the trusted state for persistence.
+    ///
+    /// Format: [version:1][genesis_root:32][genesis_committee_size:4][num_rotations:4]
+    ///         then per rotation: [epoch:8][new_root:32][new_committee_size:4]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(1 + 32 + 4 + 4 + 44 * self.rotations.len());
+
+        out.push(LIGHT_CLIENT_FORMAT_VERSION);
+        out.extend_from_slice(&self.genesis_root);
+        out.extend_from_slice(&(self.genesis_committee_size as u32).to_le_bytes());
+        out.extend_from_slice(&(self.rotations.len() as u32).to_le_bytes());
+        for (epoch, root, size) in &self.rotations {
+            out.extend_from_slice(&epoch.to_le_bytes());
+            out.extend_from_slice(root);
+            out.extend_from_slice(&(*size as u32).to_le_bytes());
+        }
+
+        out
+    }
+
+    /// Restore state saved with [`Self::to_bytes`].
+    ///
+    /// Returns `None` if the bytes are malformed or the epochs are not
+    /// strictly increasing from 1. The restored client has no policy set.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 41 || bytes[0] != LIGHT_CLIENT_FORMAT_VERSION {
+            return None;
+        }
+
+        let mut genesis_root = [0u8; 32];
+        genesis_root.copy_from_slice(&bytes[1..33]);
+        let genesis_committee_size = u32::from_le_bytes(bytes[33..37].try_into().ok()?) as usize;
+        let count = u32::from_le_bytes(bytes[37..41].try_into().ok()?) as usize;
+        if bytes.len() != 41 + count.checked_mul(44)? {
+            return None;
+        }
+
+        let mut rotations = Vec::with_capacity(count);
+        let mut last_epoch = 0u64;
+        for chunk in bytes[41..].chunks_exact(44) {
+            let epoch = u64::from_le_bytes(chunk[..8].try_into().ok()?);
+            if epoch <= last_epoch {
+                return None;
+            }
+            let mut root = [0u8; 32];
+            root.copy_from_slice(&chunk[8..40]);
+            let size = u32::from_le_bytes(chunk[40..].try_into().ok()?) as usize;
+            rotations.push((epoch, root, size));
+            last_epoch = epoch;
+        }
+
+        Some(Self {
+            genesis_root,
+            genesis_committee_size,
+            rotations,
+            policy: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::aggregation::create_rotation_proof;
+    use crate::core::keygen::setup;
+    use crate::types::{PublicKey, SecretKey};
+
+    type Committee = (Vec<SecretKey>, Vec<PublicKey>, [u8; 32]);
+
+    fn rotate(from: &Committee, to: &Committee, epoch: u64) -> RotationProof {
+        create_rotation_proof(&from.0, &from.1, from.2, to.2, to.1.len(), epoch, 3).unwrap()
+    }
+
+    /// Apply `changes` to `tree`, signed by the first three members still in it.
+    fn update_members(
+        committee: &Committee,
+        tree: &crate::utils::MerkleTree,
+        changes: &[(usize, [u8; 32])],
+        epoch: u64,
+    ) -> MembershipUpdateProof {
+        use crate::core::aggregation::{create_membership_update, prepare_membership_update};
+        use crate::core::scheme::{ActiveScheme, SignatureScheme};
+        use crate::core::signing::{sign_share, NonceSource};
+
+        let pending = prepare_membership_update(tree, changes, epoch).unwrap();
+        let shares: Vec<_> = (0..committee.1.len())
+            .filter(|&i| tree.leaf(i) == Some(ActiveScheme::leaf_hash(committee.1[i].as_bytes())))
+            .take(3)
+            .map(|i| {
+                sign_share(&committee.0[i], i, &pending.signed_message(), NonceSource::Random, &tree.prove(i).unwrap())
+                    .unwrap()
+            })
+            .collect();
+        create_membership_update(pending, &committee.1, shares, 3).unwrap()
+    }
+
+    #[test]
+    fn test_follows_rotation_chain() {
+        let committees: Vec<Committee> = (0..4).map(|_| setup(4)).collect();
+        let rotations = [
+            rotate(&committees[0], &committees[1], 1),
+            rotate(&committees[1], &committees[2], 2),
+            rotate(&committees[2], &committees[3], 5),
+        ];
+
+        let mut client = CommitteeLightClient::new(committees[0].2, 4);
+        assert_eq!(client.apply_all(&rotations).unwrap(), 3);
+        assert_eq!(client.trusted_root(), &committees[3].2);
+        assert_eq!(client.epoch(), 5);
+
+        assert_eq!(client.root_at(0), Some(committees[0].2));
+        assert_eq!(client.root_at(2), Some(committees[2].2));
+        assert_eq!(client.root_at(4), Some(committees[2].2));
+        assert_eq!(client.root_at(5), Some(committees[3].2));
+        assert_eq!(client.root_at(6), None);
+
+        // Re-delivering an accepted rotation is harmless
+        assert!(!client.apply(&rotations[1]).unwrap());
+        assert_eq!(client.epoch(), 5);
+    }
+
+    #[test]
+    fn test_rejects_regression_and_unlinked_rotations() {
+        let a = setup(4);
+        let b = setup(4);
+        let c = setup(4);
+
+        let mut client = CommitteeLightClient::new(a.2, 4);
+        client.apply(&rotate(&a, &b, 3)).unwrap();
+
+        // Epoch 2 < 3, even though b signed it
+        assert!(matches!(
+            client.apply(&rotate(&b, &c, 2)),
+            Err(PQAggregateError::InvalidInput { .. })
+        ));
+        // Not signed by the trusted committee
+        assert!(matches!(
+            client.apply(&rotate(&a, &c, 4)),
+            Err(PQAggregateError::InvalidInput { .. })
+        ));
+        // Claims to come from b but carries a's proof
+        let mut forged = rotate(&a, &c, 4);
+        forged.old_root = b.2;
+        assert!(matches!(client.apply(&forged), Err(PQAggregateError::CryptoError { .. })));
+        // Epoch bumped after signing
+        let mut bumped = rotate(&b, &c, 4);
+        bumped.epoch = 9;
+        assert!(client.apply(&bumped).is_err());
+
+        assert_eq!(client.trusted_root(), &b.2);
+        assert_eq!(client.epoch(), 3);
+    }
+
+    #[test]
+    fn test_detects_conflicting_rotation() {
+        let a = setup(4);
+        let b = setup(4);
+        let fork = setup(4);
+
+        let mut client = CommitteeLightClient::new(a.2, 4);
+        client.apply(&rotate(&a, &b, 1)).unwrap();
+
+        // The genesis committee also signed a different successor for epoch 1
+        assert!(matches!(
+            client.apply(&rotate(&a, &fork, 1)),
+            Err(PQAggregateError::RotationConflict { epoch: 1 })
+        ));
+
+        // An invalid proof is not evidence of a fork
+        let mut garbage = rotate(&a, &fork, 1);
+        garbage.proof = rotate(&a, &b, 1).proof;
+        assert!(matches!(client.apply(&garbage), Err(PQAggregateError::CryptoError { .. })));
+        assert_eq!(client.trusted_root(), &b.2);
+    }
+
+    #[test]
+    fn test_follows_membership_updates() {
+        use crate::utils::MerkleTree;
+
+        let a = setup(4);
+        let b = setup(4);
+        let tree = MerkleTree::from_public_keys(&a.1);
+
+        let mut client = CommitteeLightClient::new(a.2, 4);
+        let revoke = update_members(&a, &tree, &[(2, crate::utils::revoked_leaf())], 1);
+        assert!(client.apply_membership_update(&revoke).unwrap());
+        assert!(!client.apply_membership_update(&revoke).unwrap());
+        assert_eq!(client.trusted_root(), &revoke.new_root);
+
+        // A second, different update for epoch 1 is a fork
+        let replace = update_members(&a, &tree, &[(0, crate::utils::revoked_leaf())], 1);
+        assert!(matches!(
+            client.apply_membership_update(&replace),
+            Err(PQAggregateError::RotationConflict { epoch: 1 })
+        ));
+
+        // Tampering with the declared changes is not a valid update
+        let mut remaining = tree.clone();
+        remaining.revoke(2);
+        let mut next = update_members(&a, &remaining, &[(1, crate::utils::revoked_leaf())], 2);
+        next.new_root = b.2;
+        assert!(client.apply_membership_update(&next).is_err());
+        assert_eq!(client.epoch(), 1);
+    }
+
+    #[test]
+    fn test_policy_is_enforced() {
+        let a = setup(4);
+        let b = setup(4);
+
+        // Three of four signed; four are required
+        let mut client = CommitteeLightClient::new(a.2, 4).with_policy(ThresholdPolicy::AtLeast(4));
+        assert!(client.apply(&rotate(&a, &b, 1)).is_err());
+
+        let mut client = CommitteeLightClient::new(a.2, 4).with_policy(ThresholdPolicy::Percentage(75));
+        assert!(client.apply(&rotate(&a, &b, 1)).unwrap());
+    }
+
+    #[test]
+    fn test_policy_uses_trusted_committee_size() {
+        use crate::core::aggregation::aggregate_proofs;
+        use crate::core::signing::aggregate_sign;
+
+        let a = setup(4);
+        let b = setup(4);
+
+        // Three of four sign, but the proof claims a committee of three
+        let msg = RotationProof::message(&b.2, 4, 1);
+        let (sigs, proofs) = aggregate_sign(&a.0, &a.1, &msg, 3).unwrap();
+        let proof = aggregate_proofs(sigs, proofs, a.2, &msg, &a.1[..3]).unwrap();
+        assert_eq!(ProofView::parse(proof.as_bytes()).unwrap().committee_size, 3);
+        let shrunk = RotationProof::new(a.2, b.2, 4, proof, 1);
+        assert!(verify_with_policy(a.2, &msg, &shrunk.proof, 3, &ThresholdPolicy::Percentage(100)));
+
+        let mut client = CommitteeLightClient::new(a.2, 4).with_policy(ThresholdPolicy::Percentage(100));
+        assert!(client.apply(&shrunk).is_err());
+        let mut client = CommitteeLightClient::new(a.2, 4);
+        assert!(client.apply(&shrunk).is_err());
+
+        // Later proofs are checked against the size the rotation signed
+        let c = setup(5);
+        let mut client = CommitteeLightClient::new(a.2, 4).with_policy(ThresholdPolicy::Percentage(60));
+        client.apply(&rotate(&a, &c, 1)).unwrap();
+        assert_eq!(client.committee_size(), 5);
+        assert!(client.apply(&rotate(&c, &b, 2)).unwrap());
+    }
+
+    #[test]
+    fn test_state_roundtrip() {
+        let a = setup(4);
+        let b = setup(4);
+        let c = setup(4);
+
+        let mut client = CommitteeLightClient::new(a.2, 4);
+        client.apply(&rotate(&a, &b, 1)).unwrap();
+
+        let mut restored = CommitteeLightClient::from_bytes(&client.to_bytes()).unwrap();
+        assert_eq!(restored.genesis_root(), &a.2);
+        assert_eq!(restored.trusted_root(), &b.2);
+        assert_eq!(restored.committee_size(), 4);
+        assert_eq!(restored.num_rotations(), 1);
+
+        // Sync continues where it left off
+        restored.apply(&rotate(&b, &c, 2)).unwrap();
+        assert_eq!(restored.trusted_root(), &c.2);
+
+        let bytes = restored.to_bytes();
+        assert!(CommitteeLightClient::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+
+        // Epochs must be strictly increasing
+        let mut reordered = bytes.clone();
+        reordered[41 + 44..41 + 52].copy_from_slice(&1u64.to_le_bytes());
+        assert!(CommitteeLightClient::from_bytes(&reordered).is_none());
+
+        let mut bad_count = bytes;
+        bad_count[37..41].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(CommitteeLightClient::from_bytes(&bad_count).is_none());
+    }
+}
@@ -2,9 +2,11 @@
 //!
 //! Validates composite proofs in constant time (O(1)).
 
+use ff::PrimeField;
 use nova_snark::CompressedSNARK;
 use nova_snark::provider::{PallasEngine, VestaEngine};
-use pasta_curves::pallas;
+use nova_snark::traits::circuit::TrivialCircuit;
+use pasta_curves::{pallas, vesta};
 use crate::nova::behavioral_circuit::BehavioralVerificationCircuit;
 use crate::nova::params::{S1, S2};
 use crate::error::PQAggregateError;
@@ -15,27 +17,42 @@ pub struct UnifiedVerifier;
 
 impl UnifiedVerifier {
     /// Verify a unified proof against expected public inputs.
+    ///
+    /// `chain_root` is the [`event_chain_root`](crate::nova::behavioral_circuit::event_chain_root)
+    /// of the proven events, which for a proof over a whole log is the logger's
+    /// [`get_event_chain_root`](crate::causal::CausalEventLogger::get_event_chain_root),
+    /// `policy_root` the
+    /// [`policy_root`](crate::nova::behavioral_circuit::policy_root) of the
+    /// enforced policies and `risk_tier` a [`RiskTier::index`](crate::policy::RiskTier::index).
+    #[allow(clippy::too_many_arguments)]
     pub fn verify_unified(
         _params: &UnifiedPparams,
-        vk: &nova_snark::VerifierKey<PallasEngine, VestaEngine, BehavioralVerificationCircuit<pallas::Scalar>, BehavioralVerificationCircuit<pasta_curves::vesta::Scalar>, S1, S2>,
-        proof: &CompressedSNARK<PallasEngine, VestaEngine, BehavioralVerificationCircuit<pallas::Scalar>, BehavioralVerificationCircuit<pasta_curves::vesta::Scalar>, S1, S2>,
-        _chain_root: [u8; 32],
+        vk: &nova_snark::VerifierKey<PallasEngine, VestaEngine, BehavioralVerificationCircuit<pallas::Scalar>, TrivialCircuit<vesta::Scalar>, S1, S2>,
+        proof: &CompressedSNARK<PallasEngine, VestaEngine, BehavioralVerificationCircuit<pallas::Scalar>, TrivialCircuit<vesta::Scalar>, S1, S2>,
+        chain_root: [u8; 32],
+        policy_root: [u8; 32],
         risk_tier: u8,
         _pk_root: [u8; 32],
         threshold_t: u8,
     ) -> Result<bool, PQAggregateError> {
-        
+        let Some(chain_root) = Option::<pallas::Scalar>::from(pallas::Scalar::from_repr(chain_root)) else {
+            return Ok(false);
+        };
+        let Some(policy_root) = Option::<pallas::Scalar>::from(pallas::Scalar::from_repr(policy_root)) else {
+            return Ok(false);
+        };
+
         let z0_primary = vec![
-            pallas::Scalar::zero(),
-            pallas::Scalar::zero(),
+            chain_root,
+            policy_root,
             pallas::Scalar::from(risk_tier as u64),
             pallas::Scalar::zero(),
             pallas::Scalar::from(threshold_t as u64),
         ];
         
-        // Identity circuit output should match input in this prototype
+        // The circuit passes its inputs through unchanged
         let zn_primary = z0_primary.clone();
-        let z0_secondary = vec![pasta_curves::vesta::Scalar::zero(); 5];
+        let z0_secondary = vec![vesta::Scalar::zero()];
 
         let (zn_got, _) = proof.verify(vk, 1, &z0_primary, &z0_secondary)
             .map_err(|e| PQAggregateError::NovaError(e.to_string()))?;
@@ -4,7 +4,7 @@
 
 use pq_aggregate::causal::{
     CausalEvent, CausalEventLogger, StructuredMetadata, 
-    EVENT_VERSION_LEGACY, EVENT_VERSION_METADATA,
+    EVENT_VERSION_TAGGED,
     risk_flags,
 };
 use pq_aggregate::causal::metadata::compute_metadata_commitment;
@@ -12,7 +12,7 @@ use pq_aggregate::policy::types::PolicyCondition;
 use pq_aggregate::policy::evaluator::{evaluate_condition_with_metadata, extract_metadata_from_proposal};
 
 // =============================================================================
-// TC-1.1: New events (v0.02) with metadata pass chain verification
+// TC-1.1: New events (v0.03) with metadata pass chain verification
 // =============================================================================
 
 #[test]
@@ -30,7 +30,7 @@ fn tc_1_1_v02_events_with_metadata_pass_verification() {
         1000,
     ).unwrap();
     
-    assert_eq!(event.version, EVENT_VERSION_METADATA);
+    assert_eq!(event.version, EVENT_VERSION_TAGGED);
     assert_ne!(event.metadata_commitment, [0u8; 32]);
     
     // Verify chain
@@ -44,11 +44,11 @@ fn tc_1_1_v02_events_with_metadata_pass_verification() {
 // =============================================================================
 
 #[test]
-fn tc_1_2_legacy_events_remain_verifiable() {
+fn tc_1_2_events_without_metadata_verify() {
     let mut logger = CausalEventLogger::new([0u8; 32]);
     let agent_id = [0xBBu8; 32];
     
-    // Log legacy events using the original API
+    // Log events using the original API
     logger.log_event(&agent_id, 0x01, b"legacy request 1", 1000).unwrap();
     logger.log_event(&agent_id, 0x02, b"legacy verification", 2000).unwrap();
     logger.log_event(&agent_id, 0x01, b"legacy request 2", 3000).unwrap();
@@ -56,9 +56,9 @@ fn tc_1_2_legacy_events_remain_verifiable() {
     let root = logger.get_current_root();
     let events = logger.get_all_events();
     
-    // All events should be v0.01
+    // All events should be v0.03 without a metadata commitment
     for event in events {
-        assert_eq!(event.version, EVENT_VERSION_LEGACY);
+        assert_eq!(event.version, EVENT_VERSION_TAGGED);
         assert_eq!(event.metadata_commitment, [0u8; 32]);
     }
     
@@ -66,6 +66,52 @@ fn tc_1_2_legacy_events_remain_verifiable() {
     assert!(CausalEventLogger::verify_event_chain(events, &root));
 }
 
+#[test]
+#[cfg(feature = "legacy-hashing")]
+fn tc_1_2_legacy_events_remain_verifiable() {
+    use pq_aggregate::causal::{EVENT_VERSION_LEGACY, EVENT_VERSION_METADATA};
+    use pq_aggregate::utils::MerkleTree;
+
+    let agent_id = [0xBBu8; 32];
+    let metadata = StructuredMetadata::new(100_00, 0, 0);
+    let events = vec![
+        CausalEvent::new_legacy(1, 1000, agent_id, 0x01, b"legacy request 1", None),
+        CausalEvent::new_legacy(2, 2000, agent_id, 0x02, b"legacy verification", None),
+        CausalEvent::new_legacy(3, 3000, agent_id, 0x01, b"v02 request", Some(&metadata)),
+    ];
+    assert_eq!(events[0].version, EVENT_VERSION_LEGACY);
+    assert_eq!(events[2].version, EVENT_VERSION_METADATA);
+
+    // Roots recorded before domain separation used untagged internal nodes
+    let leaves: Vec<[u8; 32]> = events.iter().map(|e| e.to_leaf()).collect();
+    let root = MerkleTree::from_leaves_legacy(&leaves).root();
+    assert!(CausalEventLogger::verify_event_chain(&events, &root));
+    assert!(!CausalEventLogger::verify_event_chain(&events, &MerkleTree::from_leaves(&leaves).root()));
+
+    // Legacy and tagged events never share a chain
+    let mut mixed = events.clone();
+    mixed.push(CausalEvent::new(4, 4000, agent_id, 0x02, b"tagged"));
+    let leaves: Vec<[u8; 32]> = mixed.iter().map(|e| e.to_leaf()).collect();
+    assert!(!CausalEventLogger::verify_event_chain(&mixed, &MerkleTree::from_leaves_legacy(&leaves).root()));
+    assert!(!CausalEventLogger::verify_event_chain(&mixed, &MerkleTree::from_leaves(&leaves).root()));
+}
+
+#[test]
+#[cfg(not(feature = "legacy-hashing"))]
+fn tc_1_2_legacy_events_rejected_without_compat_flag() {
+    use pq_aggregate::causal::EVENT_VERSION_LEGACY;
+
+    let mut logger = CausalEventLogger::new([0u8; 32]);
+    logger.log_event(&[0xBBu8; 32], 0x01, b"request", 1000).unwrap();
+    let root = logger.get_current_root();
+
+    // Relabelling a v0.03 event as v0.01 does not slip past verification
+    let mut events = logger.get_all_events().to_vec();
+    events[0].version = EVENT_VERSION_LEGACY;
+    assert!(!events[0].verify_fingerprint());
+    assert!(!CausalEventLogger::verify_event_chain(&events, &root));
+}
+
 // =============================================================================
 // TC-1.3: Tampering with metadata_commitment invalidates fingerprint
 // =============================================================================
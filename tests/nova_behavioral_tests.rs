@@ -1,12 +1,12 @@
 #![cfg(feature = "nova")]
 
-use pq_aggregate::causal::{CausalEventLogger};
-use pq_aggregate::policy::{PolicyEngine, BehavioralPolicy, PolicyCondition, RiskTier};
+use pq_aggregate::causal::{CausalEventLogger, StructuredMetadata};
+use pq_aggregate::policy::{PolicyEngine, BehavioralPolicy, PolicyCondition, RiskTier, Currency};
+use pq_aggregate::nova::behavioral_circuit::{event_chain_root, policy_root};
 use pq_aggregate::nova::unified_prover::{UnifiedProver};
 use pq_aggregate::verifier::unified::{UnifiedVerifier};
 use pq_aggregate::nova::params::{gen_unified_params, setup_unified_keys};
 use pq_aggregate::setup;
-use pasta_curves::pallas;
 
 #[test]
 fn test_tc_3_1_valid_high_risk_threshold_5() {
@@ -16,9 +16,10 @@ fn test_tc_3_1_valid_high_risk_threshold_5() {
     // 1. Setup adaptive policy (High risk for > $1000)
     let policy = BehavioralPolicy {
         name: "Security Escalation",
-        conditions: vec![PolicyCondition::MinVerificationCount { threshold: 3, for_amount_gte: 1000 }],
+        conditions: vec![PolicyCondition::MinVerificationCount { threshold: 3, min_amount_usd: Some(1000), cross_chain_only: false }],
         risk_tier: RiskTier::High, // Requires t=5
     };
+    let policy_root = policy_root(&[policy.clone()]);
     let engine = PolicyEngine::new(vec![policy]);
     let prover = UnifiedProver::new(engine);
 
@@ -49,17 +50,38 @@ fn test_tc_3_1_valid_high_risk_threshold_5() {
     ).expect("Proving failed");
 
     // 4. Verify Unified Proof
+    // The proof covers the whole log, so it verifies against the logger's root
+    assert_eq!(logger.get_event_chain_root(), event_chain_root(&events, None));
     let valid = UnifiedVerifier::verify_unified(
         &params,
         &vk,
         &proof,
-        root,
+        logger.get_event_chain_root(),
+        policy_root,
         2, // risk_tier=2 (High)
         pk_root,
         5, // t=5
     ).unwrap();
     
     assert!(valid);
+
+    // The proof is bound to the policy it was made under
+    let weaker = BehavioralPolicy {
+        name: "Weaker",
+        conditions: vec![PolicyCondition::MinVerificationCount { threshold: 1, min_amount_usd: None, cross_chain_only: false }],
+        risk_tier: RiskTier::High,
+    };
+    let valid = UnifiedVerifier::verify_unified(
+        &params,
+        &vk,
+        &proof,
+        event_chain_root(&events, None),
+        pq_aggregate::nova::behavioral_circuit::policy_root(&[weaker]),
+        2,
+        pk_root,
+        5,
+    ).unwrap_or(false);
+    assert!(!valid);
 }
 
 #[test]
@@ -109,6 +131,7 @@ fn test_tc_3_3_valid_low_risk_threshold_2() {
         conditions: vec![],
         risk_tier: RiskTier::Low, // Requires t=2
     };
+    let policy_root = policy_root(&[policy.clone()]);
     let engine = PolicyEngine::new(vec![policy]);
     let prover = UnifiedProver::new(engine);
 
@@ -120,8 +143,15 @@ fn test_tc_3_3_valid_low_risk_threshold_2() {
     let (pk, vk) = setup_unified_keys(&params).unwrap();
     
     let proof = prover.prove_unified(&params, &pk, &events, root, [0;32], [0;32], 2).unwrap();
-    let valid = UnifiedVerifier::verify_unified(&params, &vk, &proof, root, 0, [0;32], 2).unwrap();
+    let valid = UnifiedVerifier::verify_unified(&params, &vk, &proof, event_chain_root(&events, None), policy_root, 0, [0;32], 2).unwrap();
     assert!(valid);
+
+    // The proof is bound to the events it was made over
+    let mut tampered = events.clone();
+    tampered[0].timestamp += 1;
+    let valid = UnifiedVerifier::verify_unified(&params, &vk, &proof, event_chain_root(&tampered, None), policy_root, 0, [0;32], 2)
+        .unwrap_or(false);
+    assert!(!valid);
 }
 
 #[test]
@@ -157,14 +187,16 @@ fn test_tc_3_5_outflow_limit_fails() {
     
     let policy = BehavioralPolicy {
         name: "Outflow Limit",
-        conditions: vec![PolicyCondition::MaxDailyOutflow { max_amount: 1000, currency: 1 }],
+        conditions: vec![PolicyCondition::MaxDailyOutflow { max_amount: 1000, currency: Currency::USD }],
         risk_tier: RiskTier::Medium,
     };
     let engine = PolicyEngine::new(vec![policy]);
     let prover = UnifiedProver::new(engine);
 
+    // Each signature request counts as a $1000 outflow
     let mut events = Vec::new();
-    events.push(logger.log_event(&agent_id, 0x01, b"large", 1500).unwrap());
+    events.push(logger.log_event(&agent_id, 0x01, b"first", 1000).unwrap());
+    events.push(logger.log_event(&agent_id, 0x01, b"second", 1500).unwrap());
 
     let root = logger.get_current_root();
     let params = gen_unified_params();
@@ -173,3 +205,48 @@ fn test_tc_3_5_outflow_limit_fails() {
     let res = prover.prove_unified(&params, &pk, &events, root, [0;32], [0;32], 3);
     assert!(res.is_err(), "Proving should fail due to outflow limit violation");
 }
+
+#[test]
+fn test_tc_3_6_low_value_metadata_skips_verification() {
+    let mut logger = CausalEventLogger::new([0u8; 32]);
+    let agent_id = [0xAA; 32];
+
+    let policy = BehavioralPolicy {
+        name: "Large Transfers Need Verification",
+        conditions: vec![PolicyCondition::MinVerificationCount { threshold: 3, min_amount_usd: Some(1000), cross_chain_only: false }],
+        risk_tier: RiskTier::Low,
+    };
+    let policy_root = policy_root(&[policy.clone()]);
+    let engine = PolicyEngine::new(vec![policy]);
+    let prover = UnifiedProver::new(engine);
+
+    // A $50 transfer without prior verifications
+    let metadata = StructuredMetadata::new(50_00, 0, 0);
+    let events = vec![logger.log_event_with_metadata(&agent_id, 0x01, b"transfer $50", metadata, 1000).unwrap()];
+    let root = logger.get_current_root();
+
+    let params = gen_unified_params();
+    let (pk, vk) = setup_unified_keys(&params).unwrap();
+
+    // Without the metadata the requirement is enforced conservatively
+    assert!(prover.prove_unified(&params, &pk, &events, root, [0;32], [0;32], 2).is_err());
+
+    // Metadata that does not match the event's commitment is refused
+    let inflated = StructuredMetadata::new(5_00, 0, 0);
+    assert!(prover.prove_unified_with_metadata(&params, &pk, &events, Some(&inflated), root, [0;32], [0;32], 2).is_err());
+
+    let proof = prover
+        .prove_unified_with_metadata(&params, &pk, &events, Some(&metadata), root, [0;32], [0;32], 2)
+        .expect("Proving failed");
+    let valid = UnifiedVerifier::verify_unified(
+        &params,
+        &vk,
+        &proof,
+        logger.get_event_chain_root(),
+        policy_root,
+        0,
+        [0;32],
+        2,
+    ).unwrap();
+    assert!(valid);
+}
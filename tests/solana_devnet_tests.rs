@@ -51,7 +51,7 @@ async fn test_solana_devnet_integration_suite() {
     // a. Prepare threshold proof
     let (sks, pks, pk_root) = setup(10);
     let msg = b"transfer_proof_payload";
-    let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 3);
+    let (sigs, proofs) = aggregate_sign(&sks, &pks, msg, 3).unwrap();
     let zkp = aggregate_proofs(sigs, proofs, pk_root, msg, &pks).expect("Proof generation failed");
 
     // b. Execute flow with verifications and proof